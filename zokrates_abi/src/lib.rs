@@ -1,5 +1,7 @@
 #![feature(box_patterns, box_syntax)]
 
+pub mod merkle;
+
 pub enum Inputs<T> {
     Raw(Vec<T>),
     Abi(Values<T>),
@@ -24,6 +26,7 @@ pub enum Error {
     Json(String),
     Conversion(String),
     Type(String),
+    Execution(String),
 }
 
 impl fmt::Display for Error {
@@ -32,11 +35,12 @@ impl fmt::Display for Error {
             Error::Json(e) => write!(f, "Invalid JSON: {}", e),
             Error::Conversion(e) => write!(f, "Invalid ZoKrates values: {}", e),
             Error::Type(e) => write!(f, "Type error: {}", e),
+            Error::Execution(e) => write!(f, "Execution failed: {}", e),
         }
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Value<T> {
     U8(u8),
     U16(u16),
@@ -48,7 +52,7 @@ pub enum Value<T> {
     Struct(Vec<(String, Value<T>)>),
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct Values<T>(Vec<Value<T>>);
 
 impl<T: Field> fmt::Display for Value<T> {
@@ -0,0 +1,283 @@
+//! Merkle trees matching the inclusion proofs of the standard library (`merkle/*`), so that their
+//! inputs can be generated in the ABI format accepted by `compute-witness`
+
+use crate::{Decode, Encode, Error, Value, Values};
+use zokrates_core::compile::CompilationArtifacts;
+use zokrates_core::ir::{Interpreter, Prog, Statement};
+use zokrates_core::typed_absy::types::ConcreteType;
+use zokrates_field::Field;
+
+/// A hash function compressing two sibling nodes into their parent
+pub trait Hasher<T> {
+    fn hash(&self, left: &Value<T>, right: &Value<T>) -> Result<Value<T>, Error>;
+}
+
+impl<T, F: Fn(&Value<T>, &Value<T>) -> Value<T>> Hasher<T> for F {
+    fn hash(&self, left: &Value<T>, right: &Value<T>) -> Result<Value<T>, Error> {
+        Ok(self(left, right))
+    }
+}
+
+/// A hasher which executes a compiled program of signature `main(N left, N right) -> N`, for
+/// example a wrapper around `hashes/poseidon/poseidon` to build a tree for `merkle/poseidon`
+pub struct ProgramHasher<T> {
+    program: Prog<T>,
+    node_type: ConcreteType,
+}
+
+impl<T: Field> ProgramHasher<T> {
+    pub fn new(artifacts: CompilationArtifacts<T, Vec<Statement<T>>>) -> Result<Self, Error> {
+        let (program, abi) = artifacts.into_inner();
+
+        let node_type = match &abi.outputs[..] {
+            [node_type] => Ok(node_type.clone()),
+            outputs => Err(Error::Type(format!(
+                "Expected the hash program to return one value, found {}",
+                outputs.len()
+            ))),
+        }?;
+
+        match abi.inputs.len() == 2 && abi.inputs.iter().all(|i| i.ty == node_type) {
+            true => Ok(ProgramHasher { program, node_type }),
+            false => Err(Error::Type(format!(
+                "Expected the hash program to take two inputs of type {}",
+                node_type
+            ))),
+        }
+    }
+}
+
+impl<T: Field> Hasher<T> for ProgramHasher<T> {
+    fn hash(&self, left: &Value<T>, right: &Value<T>) -> Result<Value<T>, Error> {
+        let inputs = Values(vec![left.clone(), right.clone()]).encode();
+
+        let witness = Interpreter::default()
+            .execute(self.program.clone(), &inputs)
+            .map_err(|e| Error::Execution(e.to_string()))?;
+
+        Ok(Value::decode(
+            witness.return_values(),
+            self.node_type.clone(),
+        ))
+    }
+}
+
+/// A complete binary Merkle tree
+#[derive(Debug, PartialEq)]
+pub struct MerkleTree<T> {
+    /// The nodes of the tree from the leaves up to the root
+    layers: Vec<Vec<Value<T>>>,
+}
+
+impl<T: Field> MerkleTree<T> {
+    /// Build a tree from its leaves, whose count must be a power of two
+    pub fn new<H: Hasher<T>>(hasher: &H, leaves: Vec<Value<T>>) -> Result<Self, Error> {
+        if !leaves.len().is_power_of_two() {
+            return Err(Error::Conversion(format!(
+                "Expected a power of two number of leaves, found {}",
+                leaves.len()
+            )));
+        }
+
+        let mut layers = vec![leaves];
+
+        while layers.last().unwrap().len() > 1 {
+            let layer = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hasher.hash(&pair[0], &pair[1]))
+                .collect::<Result<_, _>>()?;
+            layers.push(layer);
+        }
+
+        Ok(MerkleTree { layers })
+    }
+
+    pub fn depth(&self) -> usize {
+        self.layers.len() - 1
+    }
+
+    pub fn root(&self) -> &Value<T> {
+        &self.layers[self.depth()][0]
+    }
+
+    pub fn leaves(&self) -> &[Value<T>] {
+        &self.layers[0]
+    }
+
+    /// The inputs `root, leaf, directions, path` proving the inclusion of the leaf at `index`
+    pub fn proof(&self, index: usize) -> Values<T> {
+        assert!(index < self.leaves().len());
+
+        let (directions, path) = self.layers[..self.depth()]
+            .iter()
+            .enumerate()
+            .map(|(level, layer)| {
+                let position = index >> level;
+                // the current digest is on the rhs if its position is odd
+                (
+                    Value::Boolean(position % 2 == 1),
+                    layer[position ^ 1].clone(),
+                )
+            })
+            .unzip();
+
+        Values(vec![
+            self.root().clone(),
+            self.leaves()[index].clone(),
+            Value::Array(directions),
+            Value::Array(path),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    fn hash(left: &Value<Bn128Field>, right: &Value<Bn128Field>) -> Value<Bn128Field> {
+        match (left, right) {
+            (Value::Field(l), Value::Field(r)) => {
+                Value::Field(l.clone() * Bn128Field::from(2) + r.clone())
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn tree() -> MerkleTree<Bn128Field> {
+        MerkleTree::new(
+            &hash,
+            (0..4).map(|i| Value::Field(Bn128Field::from(i))).collect(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn root() {
+        let tree = tree();
+
+        // hash(hash(0, 1), hash(2, 3)) == 2 * 1 + 7
+        assert_eq!(tree.depth(), 2);
+        assert_eq!(tree.root(), &Value::Field(Bn128Field::from(9)));
+    }
+
+    #[test]
+    fn proof() {
+        let tree = tree();
+
+        assert_eq!(
+            tree.proof(2).into_serde_json(),
+            serde_json::json!(["9", "2", [false, true], ["3", "1"]])
+        );
+    }
+
+    #[test]
+    fn leaf_count() {
+        let leaves = (0..3).map(|i| Value::Field(Bn128Field::from(i))).collect();
+
+        assert!(MerkleTree::new(&hash, leaves).is_err());
+    }
+
+    mod program {
+        use super::*;
+        use typed_arena::Arena;
+        use zokrates_core::compile::{compile, CompileConfig};
+        use zokrates_fs_resolver::FileSystemResolver;
+
+        fn compile_source(
+            source: &str,
+        ) -> CompilationArtifacts<Bn128Field, Vec<Statement<Bn128Field>>> {
+            let stdlib = std::fs::canonicalize("../zokrates_stdlib/stdlib").unwrap();
+            let arena = Arena::new();
+
+            compile(
+                source.to_string(),
+                "./main.zok".into(),
+                Some(&FileSystemResolver::with_stdlib_root(
+                    stdlib.to_str().unwrap(),
+                )),
+                CompileConfig::default(),
+                &arena,
+            )
+            .unwrap()
+            .collect()
+        }
+
+        /// Build a tree with `hasher` and check the proof of each leaf with `verifier`
+        fn verify_proofs(hasher: &str, verifier: &str, leaves: Vec<Value<Bn128Field>>) {
+            let hasher = ProgramHasher::new(compile_source(hasher)).unwrap();
+            let tree = MerkleTree::new(&hasher, leaves).unwrap();
+
+            let (verifier, _) = compile_source(verifier).into_inner();
+
+            for index in 0..tree.leaves().len() {
+                let witness = Interpreter::default()
+                    .execute(verifier.clone(), &tree.proof(index).encode())
+                    .unwrap();
+
+                assert_eq!(
+                    Value::decode(witness.return_values(), ConcreteType::Boolean),
+                    Value::Boolean(true),
+                    "the proof of leaf {} should be valid",
+                    index
+                );
+            }
+        }
+
+        #[test]
+        fn poseidon() {
+            let hasher = r#"
+import "hashes/poseidon/poseidon" as hash
+
+def main(field left, field right) -> field:
+    return hash([left, right])
+"#;
+            let verifier = r#"
+import "merkle/poseidon" as verify
+
+def main(field root, field leaf, bool[2] directions, field[2] path) -> bool:
+    return verify(root, leaf, directions, path)
+"#;
+
+            verify_proofs(
+                hasher,
+                verifier,
+                (0..4).map(|i| Value::Field(Bn128Field::from(i))).collect(),
+            );
+        }
+
+        #[test]
+        fn sha256() {
+            let hasher = r#"
+import "hashes/sha256/512bit" as hash
+
+def main(u32[8] left, u32[8] right) -> u32[8]:
+    return hash(left, right)
+"#;
+            let verifier = r#"
+import "merkle/sha256" as verify
+
+def main(u32[8] root, u32[8] leaf, bool[1] directions, u32[1][8] path) -> bool:
+    return verify(root, leaf, directions, path)
+"#;
+
+            verify_proofs(
+                hasher,
+                verifier,
+                (0..2)
+                    .map(|i| Value::Array((0..8).map(|j| Value::U32(8 * i + j)).collect()))
+                    .collect(),
+            );
+        }
+
+        #[test]
+        fn invalid_hasher() {
+            let artifacts =
+                compile_source("def main(field left, u32 right) -> field:\n    return left\n");
+
+            assert!(ProgramHasher::new(artifacts).is_err());
+        }
+    }
+}
@@ -10,8 +10,12 @@
 extern crate lazy_static;
 
 mod constants;
+mod diagnostics;
 mod helpers;
+mod lockfile;
+mod manifest;
 mod ops;
+mod watch;
 
 use clap::{App, AppSettings, Arg};
 use ops::*;
@@ -23,7 +27,10 @@ fn main() {
     env_logger::init();
 
     cli().unwrap_or_else(|e| {
-        println!("{}", e);
+        // errors already reported as diagnostics are empty
+        if !e.is_empty() {
+            println!("{}", e);
+        }
         std::process::exit(1);
     })
 }
@@ -45,6 +52,8 @@ fn cli() -> Result<(), String> {
             compile::subcommand(),
             inspect::subcommand(),
             check::subcommand(),
+            fmt::subcommand(),
+            lint::subcommand(),
             compute_witness::subcommand(),
             #[cfg(feature = "ark")]
             universal_setup::subcommand(),
@@ -57,6 +66,8 @@ fn cli() -> Result<(), String> {
             generate_proof::subcommand(),
             generate_smtlib2::subcommand(),
             print_proof::subcommand(),
+            repl::subcommand(),
+            test::subcommand(),
             #[cfg(any(feature = "bellman", feature = "ark", feature = "libsnark"))]
             verify::subcommand()])
         .get_matches();
@@ -65,6 +76,8 @@ fn cli() -> Result<(), String> {
         ("compile", Some(sub_matches)) => compile::exec(sub_matches),
         ("inspect", Some(sub_matches)) => inspect::exec(sub_matches),
         ("check", Some(sub_matches)) => check::exec(sub_matches),
+        ("fmt", Some(sub_matches)) => fmt::exec(sub_matches),
+        ("lint", Some(sub_matches)) => lint::exec(sub_matches),
         ("compute-witness", Some(sub_matches)) => compute_witness::exec(sub_matches),
         #[cfg(feature = "ark")]
         ("universal-setup", Some(sub_matches)) => universal_setup::exec(sub_matches),
@@ -77,6 +90,8 @@ fn cli() -> Result<(), String> {
         ("generate-proof", Some(sub_matches)) => generate_proof::exec(sub_matches),
         ("generate-smtlib2", Some(sub_matches)) => generate_smtlib2::exec(sub_matches),
         ("print-proof", Some(sub_matches)) => print_proof::exec(sub_matches),
+        ("repl", Some(sub_matches)) => repl::exec(sub_matches),
+        ("test", Some(sub_matches)) => test::exec(sub_matches),
         #[cfg(any(feature = "bellman", feature = "ark", feature = "libsnark"))]
         ("verify", Some(sub_matches)) => verify::exec(sub_matches),
         _ => unreachable!(),
@@ -0,0 +1,112 @@
+use clap::{Arg, ArgMatches};
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use zokrates_core::compile::{CompileError, CompileErrors};
+use zokrates_core::diagnostics::Diagnostic;
+use zokrates_core::ir;
+
+pub const HUMAN: &str = "human";
+pub const JSON: &str = "json";
+
+/// How errors are reported: as text, or as one JSON diagnostic per line on stdout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageFormat {
+    Human,
+    Json,
+}
+
+impl MessageFormat {
+    pub fn from_matches(sub_matches: &ArgMatches) -> Self {
+        match sub_matches.value_of("message-format") {
+            Some(JSON) => MessageFormat::Json,
+            _ => MessageFormat::Human,
+        }
+    }
+
+    pub fn is_human(&self) -> bool {
+        *self == MessageFormat::Human
+    }
+}
+
+pub fn message_format_arg() -> Arg<'static, 'static> {
+    Arg::with_name("message-format")
+        .long("message-format")
+        .help("Format of the reported errors. With `json`, progress messages are omitted and each error is printed as a JSON object on its own line")
+        .takes_value(true)
+        .required(false)
+        .possible_values(&[HUMAN, JSON])
+        .default_value(HUMAN)
+}
+
+/// The path of `file` relative to the current directory, if it is inside it
+fn relative(file: &Path) -> PathBuf {
+    let file = file.canonicalize().unwrap_or_else(|_| file.to_path_buf());
+    file.strip_prefix(std::env::current_dir().unwrap())
+        .map(|p| p.to_path_buf())
+        .unwrap_or(file)
+}
+
+/// Make the paths of a diagnostic relative to the current directory
+fn relativize(mut diagnostic: Diagnostic) -> Diagnostic {
+    diagnostic.file = diagnostic.file.as_deref().map(relative);
+    for note in &mut diagnostic.related {
+        note.file = note.file.as_deref().map(relative);
+    }
+    diagnostic
+}
+
+/// Render an error with excerpts of the source it points to
+pub fn fmt_error(e: &CompileError) -> String {
+    relativize(Diagnostic::from(e)).render(|file| read_to_string(file).ok())
+}
+
+/// Print a diagnostic as a single line of JSON
+pub fn emit(diagnostic: Diagnostic) {
+    println!(
+        "{}",
+        serde_json::to_string(&relativize(diagnostic)).unwrap()
+    );
+}
+
+/// Report compilation errors, returning the error of the command. In the JSON format the errors
+/// are emitted as diagnostics and the returned error is empty.
+pub fn report(format: MessageFormat, summary: &str, errors: &CompileErrors) -> String {
+    match format {
+        MessageFormat::Human => format!(
+            "{}:\n\n{}",
+            summary,
+            errors
+                .0
+                .iter()
+                .map(fmt_error)
+                .collect::<Vec<_>>()
+                .join("\n")
+        ),
+        MessageFormat::Json => {
+            errors.0.iter().map(Diagnostic::from).for_each(emit);
+            String::new()
+        }
+    }
+}
+
+/// Report an execution error, returning the error of the command
+pub fn report_execution(format: MessageFormat, error: &ir::Error) -> String {
+    match format {
+        MessageFormat::Human => format!("Execution failed: {}", error),
+        MessageFormat::Json => {
+            emit(Diagnostic::from(error));
+            String::new()
+        }
+    }
+}
+
+/// Emit any remaining error of a command as a diagnostic without a code in the JSON format
+pub fn finish(format: MessageFormat, result: Result<(), String>) -> Result<(), String> {
+    match (format, result) {
+        (MessageFormat::Json, Err(e)) if !e.is_empty() => {
+            emit(Diagnostic::error(None, e.trim()));
+            Err(String::new())
+        }
+        (_, result) => result,
+    }
+}
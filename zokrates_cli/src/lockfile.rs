@@ -0,0 +1,301 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use zokrates_common::Resolver;
+
+pub const LOCKFILE_NAME: &str = "zokrates.lock";
+
+const LOCKFILE_HEADER: &str = "# This file is generated by ZoKrates, do not edit it by hand\n\n";
+
+/// The hash of the source of a module, as a hex encoded SHA-256 digest
+pub fn hash(source: &str) -> String {
+    hex::encode(Sha256::digest(source.as_bytes()))
+}
+
+/// A resolver which records the hash of every module it resolves
+pub struct RecordingResolver<'a> {
+    inner: &'a dyn Resolver<io::Error>,
+    modules: RefCell<BTreeMap<PathBuf, String>>,
+}
+
+impl<'a> RecordingResolver<'a> {
+    pub fn new(inner: &'a dyn Resolver<io::Error>) -> Self {
+        RecordingResolver {
+            inner,
+            modules: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// Record a module which is not resolved through this resolver, such as the entry point
+    pub fn record(&self, location: &Path, source: &str) {
+        self.modules
+            .borrow_mut()
+            .insert(location.to_path_buf(), hash(source));
+    }
+
+    /// The locations of the modules recorded so far
+    pub fn locations(&self) -> Vec<PathBuf> {
+        self.modules.borrow().keys().cloned().collect()
+    }
+
+    /// The hashes of the modules recorded so far, indexed by location
+    pub fn into_modules(self) -> BTreeMap<PathBuf, String> {
+        self.modules.into_inner()
+    }
+}
+
+impl<'a> Resolver<io::Error> for RecordingResolver<'a> {
+    fn resolve(
+        &self,
+        current_location: PathBuf,
+        import_location: PathBuf,
+    ) -> Result<(String, PathBuf), io::Error> {
+        let (source, location) = self.inner.resolve(current_location, import_location)?;
+        self.record(&location, &source);
+        Ok((source, location))
+    }
+}
+
+/// The hashes of the modules which went into a program, grouped by origin and indexed by their
+/// path relative to the root of that origin
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Modules of the project, relative to the project root
+    #[serde(default)]
+    pub modules: BTreeMap<String, String>,
+    /// Modules of the standard library, relative to its root
+    #[serde(default)]
+    pub stdlib: BTreeMap<String, String>,
+    /// Modules of each dependency, relative to the root of the dependency
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, BTreeMap<String, String>>,
+}
+
+fn canonicalize(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn relative_to(path: &Path, root: &Path) -> Option<String> {
+    path.strip_prefix(canonicalize(root))
+        .ok()
+        .map(|p| p.display().to_string())
+}
+
+/// The path from `root` to `path`, going up through `..` components if `path` is outside of
+/// `root`. Only paths which do not share any prefix with `root` are left absolute.
+fn relative_path(path: &Path, root: &Path) -> String {
+    let root = canonicalize(root);
+
+    let common = path
+        .components()
+        .zip(root.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    if common == 0 {
+        return path.display().to_string();
+    }
+
+    root.components()
+        .skip(common)
+        .map(|_| Component::ParentDir)
+        .chain(path.components().skip(common))
+        .collect::<PathBuf>()
+        .display()
+        .to_string()
+}
+
+impl Lockfile {
+    /// Build a lockfile from the hashes of modules indexed by location, as recorded by a
+    /// `RecordingResolver`
+    pub fn new(
+        modules: BTreeMap<PathBuf, String>,
+        root: &Path,
+        stdlib: &Path,
+        dependencies: &HashMap<String, PathBuf>,
+    ) -> Self {
+        let mut lockfile = Lockfile::default();
+
+        for (location, hash) in modules {
+            let location = canonicalize(&location);
+
+            // dependencies and the standard library may live inside the project, so look them
+            // up first
+            let dependency = dependencies.iter().find_map(|(name, dependency_root)| {
+                relative_to(&location, dependency_root).map(|path| (name, path))
+            });
+
+            match dependency {
+                Some((name, path)) => {
+                    lockfile
+                        .dependencies
+                        .entry(name.clone())
+                        .or_default()
+                        .insert(path, hash);
+                }
+                None => match relative_to(&location, stdlib) {
+                    Some(path) => {
+                        lockfile.stdlib.insert(path, hash);
+                    }
+                    None => {
+                        lockfile
+                            .modules
+                            .insert(relative_path(&location, root), hash);
+                    }
+                },
+            }
+        }
+
+        lockfile
+    }
+
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
+
+        toml::from_str(&contents)
+            .map_err(|why| format!("Could not parse {}: {}", path.display(), why))
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), String> {
+        let contents = toml::to_string(self).map_err(|why| why.to_string())?;
+
+        fs::write(path, format!("{}{}", LOCKFILE_HEADER, contents))
+            .map_err(|why| format!("Could not write {}: {}", path.display(), why))
+    }
+
+    fn entries(&self) -> BTreeMap<String, &String> {
+        self.modules
+            .iter()
+            .map(|(path, hash)| (path.clone(), hash))
+            .chain(
+                self.stdlib
+                    .iter()
+                    .map(|(path, hash)| (format!("stdlib {}", path), hash)),
+            )
+            .chain(self.dependencies.iter().flat_map(|(name, modules)| {
+                modules
+                    .iter()
+                    .map(move |(path, hash)| (format!("dependency `{}` {}", name, path), hash))
+            }))
+            .collect()
+    }
+
+    /// The differences between the modules of `self` and those of the expected lockfile
+    pub fn diff(&self, expected: &Lockfile) -> Vec<String> {
+        let actual = self.entries();
+        let expected = expected.entries();
+
+        let changed = actual
+            .iter()
+            .filter_map(|(module, hash)| match expected.get(module) {
+                Some(expected_hash) if expected_hash == hash => None,
+                Some(expected_hash) => Some(format!(
+                    "{}: expected hash {}, found {}",
+                    module, expected_hash, hash
+                )),
+                None => Some(format!("{}: not found in the lockfile", module)),
+            });
+
+        let removed = expected
+            .keys()
+            .filter(|module| !actual.contains_key(*module))
+            .map(|module| format!("{}: in the lockfile but not imported", module));
+
+        changed.chain(removed).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempdir::TempDir;
+
+    #[test]
+    fn module_outside_root() {
+        let parent = TempDir::new("parent").unwrap();
+        let root = parent.path().join("project");
+        let shared = parent.path().join("shared").join("lib.zok");
+
+        fs::create_dir(&root).unwrap();
+        fs::create_dir(parent.path().join("shared")).unwrap();
+        fs::write(&shared, "").unwrap();
+
+        let modules = vec![(shared, String::from("a"))].into_iter().collect();
+
+        let lockfile = Lockfile::new(modules, &root, Path::new("stdlib"), &HashMap::new());
+
+        assert_eq!(
+            lockfile.modules[&Path::new("..")
+                .join("shared")
+                .join("lib.zok")
+                .display()
+                .to_string()],
+            "a"
+        );
+    }
+
+    #[test]
+    fn group_modules() {
+        let root = TempDir::new("project").unwrap();
+        let stdlib = TempDir::new("stdlib").unwrap();
+        let dependency = TempDir::new("common").unwrap();
+
+        let main = root.path().join("main.zok");
+        let std_module = stdlib.path().join("utils").join("pack.zok");
+        let dependency_module = dependency.path().join("math.zok");
+
+        fs::create_dir(stdlib.path().join("utils")).unwrap();
+        for path in &[&main, &std_module, &dependency_module] {
+            fs::write(path, "").unwrap();
+        }
+
+        let mut dependencies = HashMap::new();
+        dependencies.insert(String::from("common"), dependency.path().to_path_buf());
+
+        let modules = vec![
+            (main, String::from("a")),
+            (std_module, String::from("b")),
+            (dependency_module, String::from("c")),
+        ]
+        .into_iter()
+        .collect();
+
+        let lockfile = Lockfile::new(modules, root.path(), stdlib.path(), &dependencies);
+
+        assert_eq!(lockfile.modules["main.zok"], "a");
+        assert_eq!(
+            lockfile.stdlib[&Path::new("utils").join("pack.zok").display().to_string()],
+            "b"
+        );
+        assert_eq!(lockfile.dependencies["common"]["math.zok"], "c");
+
+        // the lockfile round trips through its serialized form
+        let path = root.path().join(LOCKFILE_NAME);
+        lockfile.write(&path).unwrap();
+        assert_eq!(Lockfile::read(&path).unwrap(), lockfile);
+    }
+
+    #[test]
+    fn diff() {
+        let mut expected = Lockfile::default();
+        expected.modules.insert("main.zok".into(), "a".into());
+        expected.modules.insert("removed.zok".into(), "b".into());
+
+        let mut actual = Lockfile::default();
+        actual.modules.insert("main.zok".into(), "c".into());
+
+        assert_eq!(
+            actual.diff(&expected),
+            vec![
+                String::from("main.zok: expected hash a, found c"),
+                String::from("removed.zok: in the lockfile but not imported")
+            ]
+        );
+        assert!(expected.diff(&expected).is_empty());
+    }
+}
@@ -0,0 +1,302 @@
+use clap::ArgMatches;
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST_FILE_NAME: &str = "zokrates.toml";
+/// Directory, relative to the project root, where archived dependencies are extracted
+pub const DEPENDENCIES_DIR: &str = ".zokrates/deps";
+
+/// A project manifest, read from a `zokrates.toml` file
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Manifest {
+    pub package: Package,
+    #[serde(default)]
+    pub compile: CompileOptions,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+    /// The level of each lint, as `allow`, `warn` or `deny`
+    #[serde(default)]
+    pub lints: BTreeMap<String, String>,
+    /// The directory containing the manifest, against which the paths it contains are resolved
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+pub struct Package {
+    pub name: String,
+    pub entry: Option<PathBuf>,
+    pub curve: Option<String>,
+    pub scheme: Option<String>,
+    pub backend: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub struct CompileOptions {
+    #[serde(default)]
+    pub isolate_branches: bool,
+}
+
+/// The source of a dependency: either a local directory, or a `.tar.gz` archive of one
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Dependency {
+    Path(PathBuf),
+    Archive(PathBuf),
+}
+
+impl Manifest {
+    /// Read the manifest at `path`
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
+
+        let mut manifest: Manifest = toml::from_str(&contents)
+            .map_err(|why| format!("Could not parse {}: {}", path.display(), why))?;
+
+        manifest.root = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+
+        Ok(manifest)
+    }
+
+    /// Find the manifest of the project in the current directory or one of its ancestors
+    pub fn find() -> Result<Option<Self>, String> {
+        let current_dir = std::env::current_dir().map_err(|why| why.to_string())?;
+
+        current_dir
+            .ancestors()
+            .map(|dir| dir.join(MANIFEST_FILE_NAME))
+            .find(|path| path.is_file())
+            .map(|path| Self::read(&path))
+            .transpose()
+    }
+
+    /// The path of the entry point of the project, if any
+    pub fn entry(&self) -> Option<PathBuf> {
+        self.package
+            .entry
+            .as_ref()
+            .map(|entry| self.root.join(entry))
+    }
+
+    /// The directories of the dependencies, indexed by name. Archives are extracted on first use.
+    pub fn dependencies(&self) -> Result<HashMap<String, PathBuf>, String> {
+        self.dependencies
+            .iter()
+            .map(|(name, dependency)| {
+                let directory = match dependency {
+                    Dependency::Path(path) => self.root.join(path),
+                    Dependency::Archive(path) => self.extract(name, &self.root.join(path))?,
+                };
+
+                match directory.is_dir() {
+                    true => Ok((name.clone(), directory)),
+                    false => Err(format!(
+                        "Dependency `{}` not found at {}",
+                        name,
+                        directory.display()
+                    )),
+                }
+            })
+            .collect()
+    }
+
+    /// Extract the archive at `path` for the dependency `name`, returning the root directory of
+    /// the extracted sources.
+    /// The hash of the extracted archive is stored next to the sources, so that they are
+    /// extracted again when the archive changes.
+    fn extract(&self, name: &str, path: &Path) -> Result<PathBuf, String> {
+        let destination = self.root.join(DEPENDENCIES_DIR).join(name);
+        let hash_path = self
+            .root
+            .join(DEPENDENCIES_DIR)
+            .join(format!("{}.sha256", name));
+
+        let mut file = fs::File::open(path)
+            .map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
+
+        let mut hasher = Sha256::new();
+        io::copy(&mut file, &mut hasher)
+            .map_err(|why| format!("Could not read {}: {}", path.display(), why))?;
+        let hash = hex::encode(hasher.finalize());
+
+        let extracted = destination.is_dir()
+            && fs::read_to_string(&hash_path)
+                .map(|extracted_hash| extracted_hash == hash)
+                .unwrap_or(false);
+
+        if !extracted {
+            if destination.exists() {
+                fs::remove_dir_all(&destination).map_err(|why| {
+                    format!("Could not remove {}: {}", destination.display(), why)
+                })?;
+            }
+
+            file.seek(SeekFrom::Start(0))
+                .map_err(|why| format!("Could not read {}: {}", path.display(), why))?;
+
+            tar::Archive::new(GzDecoder::new(file))
+                .unpack(&destination)
+                .map_err(|why| format!("Could not extract {}: {}", path.display(), why))?;
+
+            fs::write(&hash_path, &hash)
+                .map_err(|why| format!("Could not write {}: {}", hash_path.display(), why))?;
+        }
+
+        // archives usually contain a single top level directory holding the sources
+        let entries = fs::read_dir(&destination)
+            .map_err(|why| format!("Could not open {}: {}", destination.display(), why))?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|why| why.to_string())?;
+
+        match entries.as_slice() {
+            [entry] if entry.path().is_dir() => Ok(entry.path()),
+            _ => Ok(destination),
+        }
+    }
+}
+
+/// The path of the source code: the `input` argument if given, otherwise the entry point of the
+/// manifest
+pub fn input(sub_matches: &ArgMatches, manifest: Option<&Manifest>) -> Result<PathBuf, String> {
+    sub_matches
+        .value_of("input")
+        .map(PathBuf::from)
+        .or_else(|| manifest.and_then(|m| m.entry()))
+        .ok_or_else(|| {
+            format!(
+                "No input file given, and no entry point found in {}",
+                MANIFEST_FILE_NAME
+            )
+        })
+}
+
+/// The value of the argument `name`: explicit values given on the command line take precedence
+/// over the one from the manifest, which takes precedence over the default value of the argument
+pub fn value_of<'a>(
+    sub_matches: &'a ArgMatches,
+    name: &str,
+    manifest_value: Option<&'a str>,
+) -> Option<&'a str> {
+    match sub_matches.occurrences_of(name) {
+        0 => manifest_value.or_else(|| sub_matches.value_of(name)),
+        _ => sub_matches.value_of(name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempdir::TempDir;
+
+    #[test]
+    fn parse() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+[package]
+name = "example"
+entry = "src/main.zok"
+curve = "bls12_381"
+
+[compile]
+isolate-branches = true
+
+[dependencies]
+common = { path = "../common" }
+crypto = { archive = "vendor/crypto.tar.gz" }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.package.name, "example");
+        assert_eq!(manifest.package.entry, Some(PathBuf::from("src/main.zok")));
+        assert_eq!(manifest.package.curve, Some(String::from("bls12_381")));
+        assert_eq!(manifest.package.scheme, None);
+        assert!(manifest.compile.isolate_branches);
+        assert_eq!(
+            manifest.dependencies["common"],
+            Dependency::Path(PathBuf::from("../common"))
+        );
+        assert_eq!(
+            manifest.dependencies["crypto"],
+            Dependency::Archive(PathBuf::from("vendor/crypto.tar.gz"))
+        );
+    }
+
+    #[test]
+    fn resolve_dependencies() {
+        let root = TempDir::new("project").unwrap();
+
+        // a local dependency
+        fs::create_dir(root.path().join("common")).unwrap();
+
+        // an archived dependency containing `crypto/sha.zok`
+        let source = TempDir::new("crypto").unwrap();
+        fs::create_dir(source.path().join("crypto")).unwrap();
+        fs::File::create(source.path().join("crypto").join("sha.zok"))
+            .unwrap()
+            .write_all(b"def main():\n    return\n")
+            .unwrap();
+
+        let archive = fs::File::create(root.path().join("crypto.tar.gz")).unwrap();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            archive,
+            flate2::Compression::default(),
+        ));
+        builder
+            .append_dir_all("crypto", source.path().join("crypto"))
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let manifest_path = root.path().join(MANIFEST_FILE_NAME);
+        fs::write(
+            &manifest_path,
+            r#"
+[package]
+name = "example"
+
+[dependencies]
+common = { path = "common" }
+crypto = { archive = "crypto.tar.gz" }
+"#,
+        )
+        .unwrap();
+
+        let dependencies = Manifest::read(&manifest_path)
+            .unwrap()
+            .dependencies()
+            .unwrap();
+
+        assert_eq!(dependencies["common"], root.path().join("common"));
+        assert!(dependencies["crypto"].join("sha.zok").is_file());
+
+        // a new version of the archive replaces the extracted sources
+        fs::remove_file(source.path().join("crypto").join("sha.zok")).unwrap();
+        fs::File::create(source.path().join("crypto").join("blake.zok")).unwrap();
+
+        let archive = fs::File::create(root.path().join("crypto.tar.gz")).unwrap();
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            archive,
+            flate2::Compression::default(),
+        ));
+        builder
+            .append_dir_all("crypto", source.path().join("crypto"))
+            .unwrap();
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let dependencies = Manifest::read(&manifest_path)
+            .unwrap()
+            .dependencies()
+            .unwrap();
+
+        assert!(dependencies["crypto"].join("blake.zok").is_file());
+        assert!(!dependencies["crypto"].join("sha.zok").exists());
+    }
+}
@@ -1,11 +1,15 @@
 use crate::constants;
+use crate::diagnostics::{self, MessageFormat};
 use crate::helpers::CurveParameter;
+use crate::lockfile::RecordingResolver;
+use crate::manifest::{self, Manifest};
+use crate::watch::{self, Cache, Dependencies};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{BufReader, Read};
-use std::path::{Path, PathBuf};
-use zokrates_core::compile::{check, CompileConfig, CompileError};
+use std::path::Path;
+use zokrates_core::compile::{check_with_cache, CompileConfig};
 use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field, Field};
 use zokrates_fs_resolver::FileSystemResolver;
 
@@ -16,10 +20,10 @@ pub fn subcommand() -> App<'static, 'static> {
             Arg::with_name("input")
                 .short("i")
                 .long("input")
-                .help("Path of the source code")
+                .help("Path of the source code, defaults to the entry point of the project manifest")
                 .value_name("FILE")
                 .takes_value(true)
-                .required(true),
+                .required(false),
         )
         .arg(
             Arg::with_name("stdlib-path")
@@ -46,21 +50,57 @@ pub fn subcommand() -> App<'static, 'static> {
             .help("Isolate the execution of branches: a panic in a branch only makes the program panic if this branch is being logically executed")
             .required(false)
         )
+        .arg(diagnostics::message_format_arg())
+        .arg(watch::watch_arg())
 }
 
 pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
-    let curve = CurveParameter::try_from(sub_matches.value_of("curve").unwrap())?;
+    watch::exec(sub_matches, |watched, cache| {
+        run(sub_matches, watched, cache)
+    })
+}
+
+fn run(sub_matches: &ArgMatches, watched: &Dependencies, cache: &Cache) -> Result<(), String> {
+    let manifest = Manifest::find()?;
+    if let Some(manifest) = &manifest {
+        watched.add(manifest.root.join(manifest::MANIFEST_FILE_NAME));
+    }
+    let curve = CurveParameter::try_from(
+        manifest::value_of(
+            sub_matches,
+            "curve",
+            manifest.as_ref().and_then(|m| m.package.curve.as_deref()),
+        )
+        .unwrap(),
+    )?;
     match curve {
-        CurveParameter::Bn128 => cli_check::<Bn128Field>(sub_matches),
-        CurveParameter::Bls12_377 => cli_check::<Bls12_377Field>(sub_matches),
-        CurveParameter::Bls12_381 => cli_check::<Bls12_381Field>(sub_matches),
-        CurveParameter::Bw6_761 => cli_check::<Bw6_761Field>(sub_matches),
+        CurveParameter::Bn128 => {
+            cli_check::<Bn128Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bls12_377 => {
+            cli_check::<Bls12_377Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bls12_381 => {
+            cli_check::<Bls12_381Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bw6_761 => {
+            cli_check::<Bw6_761Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
     }
 }
 
-fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
-    println!("Checking {}\n", sub_matches.value_of("input").unwrap());
-    let path = PathBuf::from(sub_matches.value_of("input").unwrap());
+fn cli_check<T: Field>(
+    sub_matches: &ArgMatches,
+    manifest: Option<&Manifest>,
+    watched: &Dependencies,
+    cache: &Cache,
+) -> Result<(), String> {
+    let format = MessageFormat::from_matches(sub_matches);
+    let path = manifest::input(sub_matches, manifest)?;
+    watched.add(&path);
+    if format.is_human() {
+        println!("Checking {}\n", path.display());
+    }
 
     let file = File::open(path.clone())
         .map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
@@ -69,17 +109,6 @@ fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     let mut source = String::new();
     reader.read_to_string(&mut source).unwrap();
 
-    let fmt_error = |e: &CompileError| {
-        let file = e.file().canonicalize().unwrap();
-        format!(
-            "{}:{}",
-            file.strip_prefix(std::env::current_dir().unwrap())
-                .unwrap_or(file.as_path())
-                .display(),
-            e.value()
-        )
-    };
-
     let stdlib_path = sub_matches.value_of("stdlib-path").unwrap();
     match Path::new(stdlib_path).exists() {
         true => Ok(()),
@@ -89,21 +118,31 @@ fn cli_check<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
         )),
     }?;
 
-    let config =
-        CompileConfig::default().isolate_branches(sub_matches.is_present("isolate-branches"));
+    let config = CompileConfig::default().isolate_branches(
+        sub_matches.is_present("isolate-branches")
+            || manifest
+                .map(|m| m.compile.isolate_branches)
+                .unwrap_or(false),
+    );
 
-    let resolver = FileSystemResolver::with_stdlib_root(stdlib_path);
-    let _ = check::<T, _>(source, path, Some(&resolver), &config).map_err(|e| {
-        format!(
-            "Check failed:\n\n{}",
-            e.0.iter()
-                .map(|e| fmt_error(e))
-                .collect::<Vec<_>>()
-                .join("\n\n")
-        )
-    })?;
+    let dependencies = manifest
+        .map(|m| m.dependencies())
+        .transpose()?
+        .unwrap_or_default();
+    let resolver = FileSystemResolver::with_stdlib_root(stdlib_path)
+        .with_dependencies(dependencies)
+        .map_err(|e| e.to_string())?;
+    let resolver = RecordingResolver::new(&resolver);
+    let res = cache.with(|modules, arena| {
+        check_with_cache::<T, _>(source, path, Some(&resolver), &config, modules, arena)
+    });
+    watched.extend(resolver.locations());
 
-    println!("Program checked, no errors found.");
+    res.map_err(|e| diagnostics::report(format, "Check failed", &e))?;
+
+    if format.is_human() {
+        println!("Program checked, no errors found.");
+    }
 
     Ok(())
 }
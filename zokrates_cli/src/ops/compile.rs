@@ -1,13 +1,18 @@
 use crate::constants;
+use crate::diagnostics::{self, MessageFormat};
 use crate::helpers::CurveParameter;
+use crate::lockfile::{Lockfile, RecordingResolver, LOCKFILE_NAME};
+use crate::manifest::{self, Manifest};
+use crate::watch::{self, Cache, Dependencies};
 use clap::{App, Arg, ArgMatches, SubCommand};
+use serde::Serialize;
 use serde_json::to_writer_pretty;
 use std::convert::TryFrom;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read};
 use std::path::{Path, PathBuf};
-use typed_arena::Arena;
-use zokrates_core::compile::{compile, CompileConfig, CompileError};
+use zokrates_core::compile::{compile_with_cache, CompileConfig};
+use zokrates_core::typed_absy::abi::Abi;
 use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field, Field};
 use zokrates_fs_resolver::FileSystemResolver;
 
@@ -17,10 +22,10 @@ pub fn subcommand() -> App<'static, 'static> {
         .arg(Arg::with_name("input")
             .short("i")
             .long("input")
-            .help("Path of the source code")
+            .help("Path of the source code, defaults to the entry point of the project manifest")
             .value_name("FILE")
             .takes_value(true)
-            .required(true)
+            .required(false)
         ).arg(Arg::with_name("stdlib-path")
         .long("stdlib-path")
         .help("Path to the standard library")
@@ -57,22 +62,75 @@ pub fn subcommand() -> App<'static, 'static> {
         .long("isolate-branches")
         .help("Isolate the execution of branches: a panic in a branch only makes the program panic if this branch is being logically executed")
         .required(false)
-    )
+    ).arg(Arg::with_name("lockfile")
+        .long("lockfile")
+        .help("Path of the lockfile, defaults to zokrates.lock at the root of the project")
+        .value_name("FILE")
+        .takes_value(true)
+        .required(false)
+    ).arg(Arg::with_name("locked")
+        .long("locked")
+        .help("Fail if the hash of an imported module differs from the lockfile, instead of updating it")
+        .required(false)
+    ).arg(diagnostics::message_format_arg())
+        .arg(watch::watch_arg())
+}
+
+/// The ABI specification, along with the hashes of the modules the program was compiled from
+#[derive(Serialize)]
+struct AbiSpec<'a> {
+    #[serde(flatten)]
+    abi: &'a Abi,
+    modules: &'a Lockfile,
 }
 
 pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
-    let curve = CurveParameter::try_from(sub_matches.value_of("curve").unwrap())?;
+    watch::exec(sub_matches, |watched, cache| {
+        run(sub_matches, watched, cache)
+    })
+}
+
+fn run(sub_matches: &ArgMatches, watched: &Dependencies, cache: &Cache) -> Result<(), String> {
+    let manifest = Manifest::find()?;
+    if let Some(manifest) = &manifest {
+        watched.add(manifest.root.join(manifest::MANIFEST_FILE_NAME));
+    }
+    let curve = CurveParameter::try_from(
+        manifest::value_of(
+            sub_matches,
+            "curve",
+            manifest.as_ref().and_then(|m| m.package.curve.as_deref()),
+        )
+        .unwrap(),
+    )?;
     match curve {
-        CurveParameter::Bn128 => cli_compile::<Bn128Field>(sub_matches),
-        CurveParameter::Bls12_377 => cli_compile::<Bls12_377Field>(sub_matches),
-        CurveParameter::Bls12_381 => cli_compile::<Bls12_381Field>(sub_matches),
-        CurveParameter::Bw6_761 => cli_compile::<Bw6_761Field>(sub_matches),
+        CurveParameter::Bn128 => {
+            cli_compile::<Bn128Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bls12_377 => {
+            cli_compile::<Bls12_377Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bls12_381 => {
+            cli_compile::<Bls12_381Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bw6_761 => {
+            cli_compile::<Bw6_761Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
     }
 }
 
-fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
-    println!("Compiling {}\n", sub_matches.value_of("input").unwrap());
-    let path = PathBuf::from(sub_matches.value_of("input").unwrap());
+fn cli_compile<T: Field>(
+    sub_matches: &ArgMatches,
+    manifest: Option<&Manifest>,
+    watched: &Dependencies,
+    cache: &Cache,
+) -> Result<(), String> {
+    let format = MessageFormat::from_matches(sub_matches);
+    let path = manifest::input(sub_matches, manifest)?;
+    watched.add(&path);
+    if format.is_human() {
+        println!("Compiling {}\n", path.display());
+    }
     let bin_output_path = Path::new(sub_matches.value_of("output").unwrap());
     let abi_spec_path = Path::new(sub_matches.value_of("abi-spec").unwrap());
 
@@ -85,17 +143,6 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
     let mut source = String::new();
     reader.read_to_string(&mut source).unwrap();
 
-    let fmt_error = |e: &CompileError| {
-        let file = e.file().canonicalize().unwrap();
-        format!(
-            "{}:{}",
-            file.strip_prefix(std::env::current_dir().unwrap())
-                .unwrap_or(file.as_path())
-                .display(),
-            e.value()
-        )
-    };
-
     let stdlib_path = sub_matches.value_of("stdlib-path").unwrap();
     match Path::new(stdlib_path).exists() {
         true => Ok(()),
@@ -105,25 +152,89 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
         )),
     }?;
 
-    let config =
-        CompileConfig::default().isolate_branches(sub_matches.is_present("isolate-branches"));
+    let config = CompileConfig::default().isolate_branches(
+        sub_matches.is_present("isolate-branches")
+            || manifest
+                .map(|m| m.compile.isolate_branches)
+                .unwrap_or(false),
+    );
+
+    let dependencies = manifest
+        .map(|m| m.dependencies())
+        .transpose()?
+        .unwrap_or_default();
+    let resolver = FileSystemResolver::with_stdlib_root(stdlib_path)
+        .with_dependencies(dependencies.clone())
+        .map_err(|e| e.to_string())?;
+
+    let lockfile_path = sub_matches
+        .value_of("lockfile")
+        .map(PathBuf::from)
+        .or_else(|| manifest.map(|m| m.root.join(LOCKFILE_NAME)));
+    let locked = sub_matches.is_present("locked");
+    if locked && lockfile_path.is_none() {
+        return Err(format!(
+            "No lockfile to check against: use --lockfile or run inside a project with a {}",
+            manifest::MANIFEST_FILE_NAME
+        ));
+    }
 
-    let resolver = FileSystemResolver::with_stdlib_root(stdlib_path);
+    // record the hash of every module the program is compiled from
+    let resolver = RecordingResolver::new(&resolver);
+    resolver.record(&path, &source);
 
     log::debug!("Compile");
 
-    let arena = Arena::new();
+    let artifacts = cache.with(|modules, arena| {
+        compile_with_cache::<T, _>(
+            source,
+            path.clone(),
+            Some(&resolver),
+            config,
+            modules,
+            arena,
+        )
+    });
+    watched.extend(resolver.locations());
+
+    let artifacts = artifacts.map_err(|e| diagnostics::report(format, "Compilation failed", &e))?;
 
-    let artifacts =
-        compile::<T, _>(source, path, Some(&resolver), config, &arena).map_err(|e| {
-            format!(
-                "Compilation failed:\n\n{}",
-                e.0.iter()
-                    .map(|e| fmt_error(e))
-                    .collect::<Vec<_>>()
-                    .join("\n\n")
-            )
-        })?;
+    // module paths are relative to the project, or to the lockfile or the entry point outside of
+    // a project
+    let root = match (manifest, &lockfile_path) {
+        (Some(manifest), _) => Some(manifest.root.as_path()),
+        (None, Some(lockfile_path)) => lockfile_path.parent(),
+        (None, None) => path.parent(),
+    }
+    .filter(|root| !root.as_os_str().is_empty())
+    .unwrap_or_else(|| Path::new("."))
+    .to_path_buf();
+
+    let modules = Lockfile::new(
+        resolver.into_modules(),
+        &root,
+        Path::new(stdlib_path),
+        &dependencies,
+    );
+
+    if let Some(lockfile_path) = lockfile_path {
+        match locked {
+            true => {
+                let differences = modules.diff(&Lockfile::read(&lockfile_path)?);
+                if !differences.is_empty() {
+                    return Err(format!(
+                        "The imported modules do not match {}:\n\n{}",
+                        lockfile_path.display(),
+                        differences.join("\n")
+                    ));
+                }
+            }
+            false => {
+                log::debug!("Write lockfile");
+                modules.write(&lockfile_path)?;
+            }
+        }
+    }
 
     let (program_flattened, abi) = artifacts.into_inner();
 
@@ -142,8 +253,17 @@ fn cli_compile<T: Field>(sub_matches: &ArgMatches) -> Result<(), String> {
         .map_err(|why| format!("Could not create {}: {}", abi_spec_path.display(), why))?;
 
     let mut writer = BufWriter::new(abi_spec_file);
-    to_writer_pretty(&mut writer, &abi).map_err(|_| "Unable to write data to file.".to_string())?;
+    to_writer_pretty(
+        &mut writer,
+        &AbiSpec {
+            abi: &abi,
+            modules: &modules,
+        },
+    )
+    .map_err(|_| "Unable to write data to file.".to_string())?;
 
-    println!("Compiled code written to '{}'", bin_output_path.display());
+    if format.is_human() {
+        println!("Compiled code written to '{}'", bin_output_path.display());
+    }
     Ok(())
 }
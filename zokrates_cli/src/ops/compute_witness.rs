@@ -1,11 +1,15 @@
 use crate::constants::{ABI_SPEC_DEFAULT_PATH, FLATTENED_CODE_DEFAULT_PATH, WITNESS_DEFAULT_PATH};
+use crate::diagnostics::{self, MessageFormat};
+use crate::watch::{self, Dependencies};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use serde_json::from_reader;
 use std::fs::File;
-use std::io::{stdin, BufReader, BufWriter, Read};
+use std::io::{self, stdin, BufRead, BufReader, BufWriter, Read};
 use std::path::Path;
+use std::sync::Mutex;
 use zokrates_abi::Encode;
 use zokrates_core::ir;
+use zokrates_core::ir::bytecode::{Bytecode, BytecodeEnum};
 use zokrates_core::ir::ProgEnum;
 use zokrates_core::typed_absy::abi::Abi;
 use zokrates_core::typed_absy::types::{ConcreteSignature, ConcreteType};
@@ -57,32 +61,201 @@ pub fn subcommand() -> App<'static, 'static> {
         .help("Read arguments from stdin")
         .conflicts_with("arguments")
         .required(false)
-    )
+    ).arg(Arg::with_name("bytecode")
+        .long("bytecode")
+        .help("Lower the program to a register-based bytecode before computing the witness, which is faster for large programs")
+        .required(false)
+    ).arg(Arg::with_name("emit-bytecode")
+        .long("emit-bytecode")
+        .help("Path to write the program lowered to bytecode to. The resulting file can be used as input to compute further witnesses")
+        .value_name("FILE")
+        .takes_value(true)
+        .required(false)
+    ).arg(Arg::with_name("binary")
+        .long("binary")
+        .help("Write the witness in a compact binary format instead of text")
+        .required(false)
+    ).arg(diagnostics::message_format_arg()
+    ).arg(watch::watch_arg()
+    ).args(&[
+        #[cfg(feature = "parallel")]
+        Arg::with_name("parallel")
+            .long("parallel")
+            .help("Compute independent parts of the witness in parallel. Requires loading the whole program in memory")
+            .conflicts_with_all(&["bytecode", "emit-bytecode"])
+            .required(false),
+    ])
 }
 
 pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    watch::exec(sub_matches, |watched, _| run(sub_matches, watched))
+}
+
+fn run(sub_matches: &ArgMatches, watched: &Dependencies) -> Result<(), String> {
     // read compiled program
     let path = Path::new(sub_matches.value_of("input").unwrap());
+    watched.add(path);
+    if sub_matches.is_present("abi") {
+        watched.add(sub_matches.value_of("abi-spec").unwrap());
+    }
+
     let file =
         File::open(&path).map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
 
     let mut reader = BufReader::new(file);
 
-    match ProgEnum::deserialize(&mut reader)? {
-        ProgEnum::Bn128Program(p) => cli_compute(p, sub_matches),
-        ProgEnum::Bls12_377Program(p) => cli_compute(p, sub_matches),
-        ProgEnum::Bls12_381Program(p) => cli_compute(p, sub_matches),
-        ProgEnum::Bw6_761Program(p) => cli_compute(p, sub_matches),
+    let header = reader
+        .fill_buf()
+        .map_err(|why| format!("Could not read {}: {}", path.display(), why))?;
+
+    // the input can also be a program previously lowered with `--emit-bytecode`
+    if BytecodeEnum::is_bytecode(header) {
+        return match BytecodeEnum::deserialize(&mut reader)? {
+            BytecodeEnum::Bn128Bytecode(b) => cli_compute_bytecode(b, sub_matches),
+            BytecodeEnum::Bls12_377Bytecode(b) => cli_compute_bytecode(b, sub_matches),
+            BytecodeEnum::Bls12_381Bytecode(b) => cli_compute_bytecode(b, sub_matches),
+            BytecodeEnum::Bw6_761Bytecode(b) => cli_compute_bytecode(b, sub_matches),
+        };
+    }
+
+    let prog = ProgEnum::deserialize(&mut reader)?;
+    let read_error = prog.read_error();
+
+    match prog {
+        ProgEnum::Bn128Program(p) => cli_compute(p, &read_error, sub_matches),
+        ProgEnum::Bls12_377Program(p) => cli_compute(p, &read_error, sub_matches),
+        ProgEnum::Bls12_381Program(p) => cli_compute(p, &read_error, sub_matches),
+        ProgEnum::Bw6_761Program(p) => cli_compute(p, &read_error, sub_matches),
     }
 }
 
 fn cli_compute<T: Field, I: Iterator<Item = ir::Statement<T>>>(
     ir_prog: ir::ProgIterator<T, I>,
+    read_error: &ir::ReadError,
     sub_matches: &ArgMatches,
 ) -> Result<(), String> {
-    println!("Computing witness...");
+    let format = MessageFormat::from_matches(sub_matches);
+    if format.is_human() {
+        println!("Computing witness...");
+    }
 
-    let verbose = sub_matches.is_present("verbose");
+    let (signature, arguments) =
+        parse_arguments::<T>(ir_prog.arguments.len(), ir_prog.return_count, sub_matches)?;
+
+    let witness = if sub_matches.is_present("bytecode") || sub_matches.is_present("emit-bytecode")
+    {
+        let bytecode = ir_prog.lower();
+        read_error.check()?;
+
+        if let Some(path) = sub_matches.value_of("emit-bytecode") {
+            write_bytecode(&bytecode, Path::new(path), format)?;
+        }
+
+        bytecode.execute(&arguments)
+    } else {
+        execute(ir_prog, &arguments, sub_matches)
+    };
+
+    // the program may have been cut short by an unreadable statement
+    read_error.check()?;
+
+    let witness = witness.map_err(|e| diagnostics::report_execution(format, &e))?;
+
+    write_witness(witness, signature, sub_matches)
+}
+
+#[cfg(feature = "parallel")]
+fn execute<T: Field, I: Iterator<Item = ir::Statement<T>>>(
+    ir_prog: ir::ProgIterator<T, I>,
+    arguments: &[T],
+    sub_matches: &ArgMatches,
+) -> ir::ExecutionResult<T> {
+    let interpreter = ir::Interpreter::default();
+
+    match sub_matches.is_present("parallel") {
+        true => interpreter.execute_parallel(ir_prog, arguments),
+        false => interpreter.execute(ir_prog, arguments),
+    }
+}
+
+#[cfg(not(feature = "parallel"))]
+fn execute<T: Field, I: Iterator<Item = ir::Statement<T>>>(
+    ir_prog: ir::ProgIterator<T, I>,
+    arguments: &[T],
+    _: &ArgMatches,
+) -> ir::ExecutionResult<T> {
+    ir::Interpreter::default().execute(ir_prog, arguments)
+}
+
+fn cli_compute_bytecode<T: Field>(
+    bytecode: Bytecode<T>,
+    sub_matches: &ArgMatches,
+) -> Result<(), String> {
+    #[cfg(feature = "parallel")]
+    if sub_matches.is_present("parallel") {
+        return Err(String::from(
+            "Parallel witness computation is not supported for bytecode input",
+        ));
+    }
+
+    let format = MessageFormat::from_matches(sub_matches);
+    if format.is_human() {
+        println!("Computing witness...");
+    }
+
+    let (signature, arguments) =
+        parse_arguments::<T>(bytecode.arguments.len(), bytecode.return_count, sub_matches)?;
+
+    let witness = bytecode
+        .execute(&arguments)
+        .map_err(|e| diagnostics::report_execution(format, &e))?;
+
+    write_witness(witness, signature, sub_matches)
+}
+
+fn write_bytecode<T: Field>(
+    bytecode: &Bytecode<T>,
+    path: &Path,
+    format: MessageFormat,
+) -> Result<(), String> {
+    let file = File::create(path)
+        .map_err(|why| format!("Could not create {}: {}", path.display(), why))?;
+
+    bytecode
+        .serialize(BufWriter::new(file))
+        .map_err(|why| format!("Could not save bytecode: {}", why))?;
+
+    if format.is_human() {
+        println!("Bytecode written to '{}'", path.display());
+    }
+    Ok(())
+}
+
+lazy_static! {
+    static ref STDIN: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Read the arguments from stdin. Stdin is only read once, so that the same arguments are used
+/// every time the witness is computed again in watch mode
+fn read_stdin() -> io::Result<String> {
+    let mut cached = STDIN.lock().unwrap();
+
+    if cached.is_none() {
+        let mut input = String::new();
+        stdin().read_to_string(&mut input)?;
+        *cached = Some(input);
+    }
+
+    Ok(cached.clone().unwrap())
+}
+
+/// Parse the arguments of a program with `argument_count` arguments and `return_count` return
+/// values, returning the signature to use to decode the results along with the encoded arguments
+fn parse_arguments<T: Field>(
+    argument_count: usize,
+    return_count: usize,
+    sub_matches: &ArgMatches,
+) -> Result<(ConcreteSignature, Vec<T>), String> {
     let is_stdin = sub_matches.is_present("stdin");
     let is_abi = sub_matches.is_present("abi");
 
@@ -102,8 +275,8 @@ fn cli_compute<T: Field, I: Iterator<Item = ir::Statement<T>>>(
             abi.signature()
         }
         false => ConcreteSignature::new()
-            .inputs(vec![ConcreteType::FieldElement; ir_prog.arguments.len()])
-            .outputs(vec![ConcreteType::FieldElement; ir_prog.return_count]),
+            .inputs(vec![ConcreteType::FieldElement; argument_count])
+            .outputs(vec![ConcreteType::FieldElement; return_count]),
     };
 
     use zokrates_abi::Inputs;
@@ -123,24 +296,21 @@ fn cli_compute<T: Field, I: Iterator<Item = ir::Statement<T>>>(
         }
         // take stdin arguments
         true => {
-            let mut stdin = stdin();
-            let mut input = String::new();
-
             match is_abi {
-                true => match stdin.read_to_string(&mut input) {
-                    Ok(_) => {
+                true => match read_stdin() {
+                    Ok(input) => {
                         use zokrates_abi::parse_strict;
 
-                        parse_strict(&input, signature.inputs)
+                        parse_strict(&input, signature.inputs.clone())
                             .map(Inputs::Abi)
                             .map_err(|why| why.to_string())
                     }
                     Err(_) => Err(String::from("???")),
                 },
-                false => match ir_prog.arguments.len() {
+                false => match argument_count {
                     0 => Ok(Inputs::Raw(vec![])),
-                    _ => match stdin.read_to_string(&mut input) {
-                        Ok(_) => {
+                    _ => match read_stdin() {
+                        Ok(mut input) => {
                             input.retain(|x| x != '\n');
                             input
                                 .split(' ')
@@ -156,11 +326,15 @@ fn cli_compute<T: Field, I: Iterator<Item = ir::Statement<T>>>(
     }
     .map_err(|e| format!("Could not parse argument: {}", e))?;
 
-    let interpreter = ir::Interpreter::default();
+    Ok((signature, arguments.encode()))
+}
 
-    let witness = interpreter
-        .execute(ir_prog, &arguments.encode())
-        .map_err(|e| format!("Execution failed: {}", e))?;
+fn write_witness<T: Field>(
+    witness: ir::Witness<T>,
+    signature: ConcreteSignature,
+    sub_matches: &ArgMatches,
+) -> Result<(), String> {
+    let verbose = sub_matches.is_present("verbose");
 
     use zokrates_abi::Decode;
 
@@ -178,10 +352,14 @@ fn cli_compute<T: Field, I: Iterator<Item = ir::Statement<T>>>(
 
     let writer = BufWriter::new(output_file);
 
-    witness
-        .write(writer)
-        .map_err(|why| format!("Could not save witness: {:?}", why))?;
+    match sub_matches.is_present("binary") {
+        true => witness.write_binary(writer),
+        false => witness.write(writer),
+    }
+    .map_err(|why| format!("Could not save witness: {:?}", why))?;
 
-    println!("Witness file written to '{}'", output_path.display());
+    if MessageFormat::from_matches(sub_matches).is_human() {
+        println!("Witness file written to '{}'", output_path.display());
+    }
     Ok(())
 }
@@ -1,5 +1,6 @@
 use crate::constants;
 use crate::helpers::{CurveParameter, SchemeParameter};
+use crate::manifest::{self, Manifest};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use std::convert::TryFrom;
 use std::fs::File;
@@ -55,8 +56,19 @@ pub fn subcommand() -> App<'static, 'static> {
 }
 
 pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
-    let curve = sub_matches.value_of("curve").unwrap();
-    let scheme = sub_matches.value_of("proving-scheme").unwrap();
+    let manifest = Manifest::find()?;
+    let curve = manifest::value_of(
+        sub_matches,
+        "curve",
+        manifest.as_ref().and_then(|m| m.package.curve.as_deref()),
+    )
+    .unwrap();
+    let scheme = manifest::value_of(
+        sub_matches,
+        "proving-scheme",
+        manifest.as_ref().and_then(|m| m.package.scheme.as_deref()),
+    )
+    .unwrap();
 
     let curve_parameter = CurveParameter::try_from(curve)?;
     let scheme_parameter = SchemeParameter::try_from(scheme)?;
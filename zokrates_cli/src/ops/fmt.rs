@@ -0,0 +1,61 @@
+use clap::{App, Arg, ArgMatches, SubCommand};
+use std::fs;
+use std::path::Path;
+use zokrates_core::formatter::format;
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("fmt")
+        .about("Formats programs to the canonical layout")
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Paths of the source files")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("check")
+                .long("check")
+                .help("Only check that the files are formatted, without modifying them")
+                .required(false),
+        )
+}
+
+pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    let check = sub_matches.is_present("check");
+
+    let mut unformatted = vec![];
+
+    for input in sub_matches.values_of("input").unwrap() {
+        let path = Path::new(input);
+
+        let source = fs::read_to_string(path)
+            .map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
+
+        let formatted =
+            format(&source).map_err(|e| format!("Could not parse {}:\n{}", path.display(), e))?;
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            unformatted.push(path.display().to_string());
+        } else {
+            fs::write(path, formatted)
+                .map_err(|why| format!("Could not write {}: {}", path.display(), why))?;
+            println!("Formatted {}", path.display());
+        }
+    }
+
+    match unformatted.is_empty() {
+        true => Ok(()),
+        false => Err(format!(
+            "The following files are not formatted:\n{}",
+            unformatted.join("\n")
+        )),
+    }
+}
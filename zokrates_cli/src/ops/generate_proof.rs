@@ -1,9 +1,10 @@
 use crate::constants;
 use crate::helpers::*;
+use crate::manifest::{self, Manifest};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use std::convert::TryFrom;
 use std::fs::File;
-use std::io::{BufReader, Read, Write};
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::Path;
 use zokrates_core::ir;
 use zokrates_core::ir::ProgEnum;
@@ -84,54 +85,85 @@ pub fn subcommand() -> App<'static, 'static> {
 }
 
 pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    let manifest = Manifest::find()?;
     let program_path = Path::new(sub_matches.value_of("input").unwrap());
     let program_file = File::open(&program_path)
         .map_err(|why| format!("Could not open {}: {}", program_path.display(), why))?;
 
     let mut reader = BufReader::new(program_file);
     let prog = ProgEnum::deserialize(&mut reader)?;
+    let read_error = prog.read_error();
+
+    // backends cannot report unreadable statements, so the program is read once beforehand to
+    // reject it before anything is computed
+    File::open(&program_path)
+        .map_err(|why| format!("Could not open {}: {}", program_path.display(), why))
+        .and_then(|file| ProgEnum::deserialize(BufReader::new(file)))?
+        .check_statements()?;
 
     let parameters = Parameters::try_from((
-        sub_matches.value_of("backend").unwrap(),
+        manifest::value_of(
+            sub_matches,
+            "backend",
+            manifest.as_ref().and_then(|m| m.package.backend.as_deref()),
+        )
+        .unwrap(),
         match prog {
             ProgEnum::Bn128Program(_) => constants::BN128,
             ProgEnum::Bls12_381Program(_) => constants::BLS12_381,
             ProgEnum::Bls12_377Program(_) => constants::BLS12_377,
             ProgEnum::Bw6_761Program(_) => constants::BW6_761,
         },
-        sub_matches.value_of("proving-scheme").unwrap(),
+        manifest::value_of(
+            sub_matches,
+            "proving-scheme",
+            manifest.as_ref().and_then(|m| m.package.scheme.as_deref()),
+        )
+        .unwrap(),
     ))?;
 
     match parameters {
         #[cfg(feature = "bellman")]
         Parameters(BackendParameter::Bellman, _, SchemeParameter::G16) => match prog {
-            ProgEnum::Bn128Program(p) => cli_generate_proof::<_, _, G16, Bellman>(p, sub_matches),
+            ProgEnum::Bn128Program(p) => {
+                cli_generate_proof::<_, _, G16, Bellman>(p, &read_error, sub_matches)
+            }
             ProgEnum::Bls12_381Program(p) => {
-                cli_generate_proof::<_, _, G16, Bellman>(p, sub_matches)
+                cli_generate_proof::<_, _, G16, Bellman>(p, &read_error, sub_matches)
             }
             _ => unreachable!(),
         },
         #[cfg(feature = "ark")]
         Parameters(BackendParameter::Ark, _, SchemeParameter::GM17) => match prog {
-            ProgEnum::Bls12_377Program(p) => cli_generate_proof::<_, _, GM17, Ark>(p, sub_matches),
-            ProgEnum::Bw6_761Program(p) => cli_generate_proof::<_, _, GM17, Ark>(p, sub_matches),
-            ProgEnum::Bn128Program(p) => cli_generate_proof::<_, _, GM17, Ark>(p, sub_matches),
+            ProgEnum::Bls12_377Program(p) => {
+                cli_generate_proof::<_, _, GM17, Ark>(p, &read_error, sub_matches)
+            }
+            ProgEnum::Bw6_761Program(p) => {
+                cli_generate_proof::<_, _, GM17, Ark>(p, &read_error, sub_matches)
+            }
+            ProgEnum::Bn128Program(p) => {
+                cli_generate_proof::<_, _, GM17, Ark>(p, &read_error, sub_matches)
+            }
             _ => unreachable!(),
         },
         #[cfg(feature = "ark")]
         Parameters(BackendParameter::Ark, _, SchemeParameter::MARLIN) => match prog {
             ProgEnum::Bls12_377Program(p) => {
-                cli_generate_proof::<_, _, Marlin, Ark>(p, sub_matches)
+                cli_generate_proof::<_, _, Marlin, Ark>(p, &read_error, sub_matches)
+            }
+            ProgEnum::Bw6_761Program(p) => {
+                cli_generate_proof::<_, _, Marlin, Ark>(p, &read_error, sub_matches)
+            }
+            ProgEnum::Bn128Program(p) => {
+                cli_generate_proof::<_, _, Marlin, Ark>(p, &read_error, sub_matches)
             }
-            ProgEnum::Bw6_761Program(p) => cli_generate_proof::<_, _, Marlin, Ark>(p, sub_matches),
-            ProgEnum::Bn128Program(p) => cli_generate_proof::<_, _, Marlin, Ark>(p, sub_matches),
             _ => unreachable!(),
         },
         #[cfg(feature = "libsnark")]
         Parameters(BackendParameter::Libsnark, CurveParameter::Bn128, SchemeParameter::GM17) => {
             match prog {
                 ProgEnum::Bn128Program(p) => {
-                    cli_generate_proof::<_, _, GM17, Libsnark>(p, sub_matches)
+                    cli_generate_proof::<_, _, GM17, Libsnark>(p, &read_error, sub_matches)
                 }
                 _ => unreachable!(),
             }
@@ -140,7 +172,7 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
         Parameters(BackendParameter::Libsnark, CurveParameter::Bn128, SchemeParameter::PGHR13) => {
             match prog {
                 ProgEnum::Bn128Program(p) => {
-                    cli_generate_proof::<_, _, PGHR13, Libsnark>(p, sub_matches)
+                    cli_generate_proof::<_, _, PGHR13, Libsnark>(p, &read_error, sub_matches)
                 }
                 _ => unreachable!(),
             }
@@ -156,18 +188,11 @@ fn cli_generate_proof<
     B: Backend<T, S>,
 >(
     program: ir::ProgIterator<T, I>,
+    read_error: &ir::ReadError,
     sub_matches: &ArgMatches,
 ) -> Result<(), String> {
     println!("Generating proof...");
 
-    // deserialize witness
-    let witness_path = Path::new(sub_matches.value_of("witness").unwrap());
-    let witness_file = File::open(&witness_path)
-        .map_err(|why| format!("Could not open {}: {}", witness_path.display(), why))?;
-
-    let witness = ir::Witness::read(witness_file)
-        .map_err(|why| format!("Could not load witness: {:?}", why))?;
-
     let pk_path = Path::new(sub_matches.value_of("proving-key-path").unwrap());
     let proof_path = Path::new(sub_matches.value_of("proof-path").unwrap());
 
@@ -180,7 +205,34 @@ fn cli_generate_proof<
         .read_to_end(&mut pk)
         .map_err(|why| format!("Could not read {}: {}", pk_path.display(), why))?;
 
-    let proof = B::generate_proof(program, witness, pk);
+    // deserialize witness
+    let witness_path = Path::new(sub_matches.value_of("witness").unwrap());
+    let witness_file = File::open(&witness_path)
+        .map_err(|why| format!("Could not open {}: {}", witness_path.display(), why))?;
+
+    // the witness can be written as text or in the binary format
+    let mut witness_reader = BufReader::new(witness_file);
+    let header = witness_reader
+        .fill_buf()
+        .map_err(|why| format!("Could not read {}: {}", witness_path.display(), why))?;
+
+    let load_error = |why: std::io::Error| format!("Could not load witness: {:?}", why);
+
+    let proof = match ir::Witness::<T>::is_binary(header) {
+        // binary witnesses are read from disk as the backend needs their values
+        true => B::generate_proof(
+            program,
+            ir::WitnessReader::<T, _>::new(witness_reader).map_err(load_error)?,
+            pk,
+        ),
+        false => B::generate_proof(
+            program,
+            ir::Witness::read(witness_reader).map_err(load_error)?,
+            pk,
+        ),
+    };
+    read_error.check()?;
+
     let mut proof_file = File::create(proof_path).unwrap();
 
     let proof = serde_json::to_string_pretty(&proof).unwrap();
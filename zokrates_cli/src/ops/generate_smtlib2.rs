@@ -41,25 +41,30 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let mut reader = BufReader::new(file);
 
-    match ProgEnum::deserialize(&mut reader)? {
-        ProgEnum::Bn128Program(p) => cli_smtlib2(p, sub_matches),
-        ProgEnum::Bls12_377Program(p) => cli_smtlib2(p, sub_matches),
-        ProgEnum::Bls12_381Program(p) => cli_smtlib2(p, sub_matches),
-        ProgEnum::Bw6_761Program(p) => cli_smtlib2(p, sub_matches),
+    let prog = ProgEnum::deserialize(&mut reader)?;
+    let read_error = prog.read_error();
+
+    match prog {
+        ProgEnum::Bn128Program(p) => cli_smtlib2(p, &read_error, sub_matches),
+        ProgEnum::Bls12_377Program(p) => cli_smtlib2(p, &read_error, sub_matches),
+        ProgEnum::Bls12_381Program(p) => cli_smtlib2(p, &read_error, sub_matches),
+        ProgEnum::Bw6_761Program(p) => cli_smtlib2(p, &read_error, sub_matches),
     }
 }
 
 fn cli_smtlib2<T: Field, I: Iterator<Item = ir::Statement<T>>>(
     ir_prog: ir::ProgIterator<T, I>,
+    read_error: &ir::ReadError,
     sub_matches: &ArgMatches,
 ) -> Result<(), String> {
     println!("Generating SMTLib2...");
 
+    let ir_prog = ir_prog.collect();
+    read_error.check()?;
+
     let output_path = Path::new(sub_matches.value_of("output").unwrap());
     let mut output_file = File::create(output_path).unwrap();
 
-    let ir_prog = ir_prog.collect();
-
     output_file
         .write(format!("{}", SMTLib2Display(&ir_prog)).as_bytes())
         .map_err(|why| format!("Could not save smtlib2: {:?}", why))?;
@@ -36,19 +36,24 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let mut reader = BufReader::new(file);
 
-    match ProgEnum::deserialize(&mut reader)? {
-        ProgEnum::Bn128Program(p) => cli_inspect(p, sub_matches),
-        ProgEnum::Bls12_377Program(p) => cli_inspect(p, sub_matches),
-        ProgEnum::Bls12_381Program(p) => cli_inspect(p, sub_matches),
-        ProgEnum::Bw6_761Program(p) => cli_inspect(p, sub_matches),
+    let prog = ProgEnum::deserialize(&mut reader)?;
+    let read_error = prog.read_error();
+
+    match prog {
+        ProgEnum::Bn128Program(p) => cli_inspect(p, &read_error, sub_matches),
+        ProgEnum::Bls12_377Program(p) => cli_inspect(p, &read_error, sub_matches),
+        ProgEnum::Bls12_381Program(p) => cli_inspect(p, &read_error, sub_matches),
+        ProgEnum::Bw6_761Program(p) => cli_inspect(p, &read_error, sub_matches),
     }
 }
 
 fn cli_inspect<T: Field, I: Iterator<Item = ir::Statement<T>>>(
     ir_prog: ir::ProgIterator<T, I>,
+    read_error: &ir::ReadError,
     sub_matches: &ArgMatches,
 ) -> Result<(), String> {
     let ir_prog: ir::Prog<T> = ir_prog.collect();
+    read_error.check()?;
 
     let curve = format!("{:<17} {}", "curve:", T::name());
     let constraint_count = format!("{:<17} {}", "constraint_count:", ir_prog.constraint_count());
@@ -0,0 +1,228 @@
+use crate::constants;
+use crate::helpers::CurveParameter;
+use crate::manifest::{self, Manifest};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+use zokrates_core::compile::{lint, CompileConfig, CompileError};
+use zokrates_core::linter::{Level, Lint};
+use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field, Field};
+use zokrates_fs_resolver::FileSystemResolver;
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("lint")
+        .about("Reports suspicious patterns in a program")
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Path of the source code, defaults to the entry point of the project manifest")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("stdlib-path")
+                .long("stdlib-path")
+                .help("Path to the standard library")
+                .value_name("PATH")
+                .takes_value(true)
+                .required(false)
+                .env("ZOKRATES_STDLIB")
+                .default_value(constants::DEFAULT_STDLIB_PATH.as_str()),
+        )
+        .arg(
+            Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve to be used in the compilation")
+                .takes_value(true)
+                .required(false)
+                .possible_values(constants::CURVES)
+                .default_value(constants::BN128),
+        )
+        .arg(Arg::with_name("isolate-branches")
+            .long("isolate-branches")
+            .help("Isolate the execution of branches: a panic in a branch only makes the program panic if this branch is being logically executed")
+            .required(false)
+        )
+        .arg(
+            Arg::with_name("allow")
+                .short("A")
+                .long("allow")
+                .help("Lints to ignore")
+                .value_name("LINT")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("warn")
+                .short("W")
+                .long("warn")
+                .help("Lints to report as warnings")
+                .value_name("LINT")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("deny")
+                .short("D")
+                .long("deny")
+                .help("Lints to report as errors")
+                .value_name("LINT")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(false),
+        )
+}
+
+pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    let manifest = Manifest::find()?;
+    let curve = CurveParameter::try_from(
+        manifest::value_of(
+            sub_matches,
+            "curve",
+            manifest.as_ref().and_then(|m| m.package.curve.as_deref()),
+        )
+        .unwrap(),
+    )?;
+    match curve {
+        CurveParameter::Bn128 => cli_lint::<Bn128Field>(sub_matches, manifest.as_ref()),
+        CurveParameter::Bls12_377 => cli_lint::<Bls12_377Field>(sub_matches, manifest.as_ref()),
+        CurveParameter::Bls12_381 => cli_lint::<Bls12_381Field>(sub_matches, manifest.as_ref()),
+        CurveParameter::Bw6_761 => cli_lint::<Bw6_761Field>(sub_matches, manifest.as_ref()),
+    }
+}
+
+/// The level of each lint: all lints warn by default, which can be overridden in the manifest,
+/// which can be overridden on the command line
+fn levels(
+    sub_matches: &ArgMatches,
+    manifest: Option<&Manifest>,
+) -> Result<HashMap<Lint, Level>, String> {
+    let mut levels: HashMap<Lint, Level> =
+        Lint::ALL.iter().map(|lint| (*lint, Level::Warn)).collect();
+
+    if let Some(manifest) = manifest {
+        for (lint, level) in &manifest.lints {
+            levels.insert(
+                Lint::try_from(lint.as_str())?,
+                Level::try_from(level.as_str())?,
+            );
+        }
+    }
+
+    for (arg, level) in &[
+        ("allow", Level::Allow),
+        ("warn", Level::Warn),
+        ("deny", Level::Deny),
+    ] {
+        for lint in sub_matches.values_of(arg).into_iter().flatten() {
+            levels.insert(Lint::try_from(lint)?, *level);
+        }
+    }
+
+    Ok(levels)
+}
+
+fn cli_lint<T: Field>(sub_matches: &ArgMatches, manifest: Option<&Manifest>) -> Result<(), String> {
+    let path = manifest::input(sub_matches, manifest)?;
+    println!("Linting {}\n", path.display());
+
+    let levels = levels(sub_matches, manifest)?;
+
+    let source = fs::read_to_string(&path)
+        .map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
+
+    let fmt_error = |e: &CompileError| {
+        let file = e.file().canonicalize().unwrap();
+        format!(
+            "{}:{}",
+            file.strip_prefix(std::env::current_dir().unwrap())
+                .unwrap_or(file.as_path())
+                .display(),
+            e.value()
+        )
+    };
+
+    let stdlib_path = sub_matches.value_of("stdlib-path").unwrap();
+    match Path::new(stdlib_path).exists() {
+        true => Ok(()),
+        _ => Err(format!(
+            "Invalid standard library source path: {}",
+            stdlib_path
+        )),
+    }?;
+
+    let config = CompileConfig::default().isolate_branches(
+        sub_matches.is_present("isolate-branches")
+            || manifest
+                .map(|m| m.compile.isolate_branches)
+                .unwrap_or(false),
+    );
+
+    let dependencies = manifest
+        .map(|m| m.dependencies())
+        .transpose()?
+        .unwrap_or_default();
+    let resolver = FileSystemResolver::with_stdlib_root(stdlib_path)
+        .with_dependencies(dependencies)
+        .map_err(|e| e.to_string())?;
+
+    let warnings = lint::<T, _>(source, path.clone(), Some(&resolver), &config).map_err(|e| {
+        format!(
+            "Lint failed:\n\n{}",
+            e.0.iter()
+                .map(|e| fmt_error(e))
+                .collect::<Vec<_>>()
+                .join("\n\n")
+        )
+    })?;
+
+    let mut warning_count = 0;
+    let mut error_count = 0;
+
+    for warning in warnings {
+        let level = match levels[&warning.lint] {
+            Level::Allow => continue,
+            Level::Warn => {
+                warning_count += 1;
+                "warning"
+            }
+            Level::Deny => {
+                error_count += 1;
+                "error"
+            }
+        };
+
+        println!(
+            "{}[{}]: {}:{}",
+            level,
+            warning.lint,
+            path.display(),
+            warning
+        );
+    }
+
+    match (warning_count, error_count) {
+        (0, 0) => {
+            println!("No issues found.");
+            Ok(())
+        }
+        (warnings, 0) => {
+            println!("\n{} warning(s) found.", warnings);
+            Ok(())
+        }
+        (warnings, errors) => Err(format!(
+            "\nLint failed: {} error(s) and {} warning(s) found.",
+            errors, warnings
+        )),
+    }
+}
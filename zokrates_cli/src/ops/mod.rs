@@ -2,15 +2,19 @@ pub mod check;
 pub mod compile;
 pub mod compute_witness;
 pub mod export_verifier;
+pub mod fmt;
 #[cfg(any(feature = "bellman", feature = "ark", feature = "libsnark"))]
 pub mod generate_proof;
 pub mod generate_smtlib2;
 pub mod inspect;
+pub mod lint;
 #[cfg(feature = "bellman")]
 pub mod mpc;
 pub mod print_proof;
+pub mod repl;
 #[cfg(any(feature = "bellman", feature = "ark", feature = "libsnark"))]
 pub mod setup;
+pub mod test;
 #[cfg(feature = "ark")]
 pub mod universal_setup;
 #[cfg(any(feature = "bellman", feature = "ark", feature = "libsnark"))]
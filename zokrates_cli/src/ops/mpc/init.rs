@@ -51,9 +51,16 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let mut reader = BufReader::new(file);
 
-    match ProgEnum::deserialize(&mut reader)? {
-        ProgEnum::Bn128Program(p) => cli_mpc_init::<_, _, G16, Bellman>(p, sub_matches),
-        ProgEnum::Bls12_381Program(p) => cli_mpc_init::<_, _, G16, Bellman>(p, sub_matches),
+    let prog = ProgEnum::deserialize(&mut reader)?;
+    let read_error = prog.read_error();
+
+    match prog {
+        ProgEnum::Bn128Program(p) => {
+            cli_mpc_init::<_, _, G16, Bellman>(p, &read_error, sub_matches)
+        }
+        ProgEnum::Bls12_381Program(p) => {
+            cli_mpc_init::<_, _, G16, Bellman>(p, &read_error, sub_matches)
+        }
         _ => Err("Current protocol only supports bn128/bls12_381 programs".into()),
     }
 }
@@ -65,6 +72,7 @@ fn cli_mpc_init<
     B: MpcBackend<T, S>,
 >(
     program: ir::ProgIterator<T, I>,
+    read_error: &ir::ReadError,
     sub_matches: &ArgMatches,
 ) -> Result<(), String> {
     println!("Initializing MPC...");
@@ -82,6 +90,7 @@ fn cli_mpc_init<
     let mut writer = BufWriter::new(output_file);
     B::initialize(program, &mut radix_reader, &mut writer)
         .map_err(|e| format!("Failed to initialize: {}", e))?;
+    read_error.check()?;
 
     println!("Parameters written to `{}`", output_path.display());
     Ok(())
@@ -0,0 +1,286 @@
+use crate::constants;
+use crate::helpers::CurveParameter;
+use crate::manifest::{self, Manifest};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use std::convert::TryFrom;
+use std::fs::read_to_string;
+use std::io::{self, stdin, stdout, BufRead, Write};
+use std::path::{Path, PathBuf};
+use typed_arena::Arena;
+use zokrates_abi::Decode;
+use zokrates_common::Resolver;
+use zokrates_core::compile::{check, compile_expression, CompileConfig, CompileError};
+use zokrates_core::diagnostics::Diagnostic;
+use zokrates_core::ir;
+use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field, Field};
+use zokrates_fs_resolver::FileSystemResolver;
+
+/// The location of the program built from the inputs, from which relative imports are resolved
+const LOCATION: &str = "./<repl>";
+
+/// The beginning of the inputs which are declarations, kept in the context of later inputs
+const DECLARATIONS: &[&str] = &[
+    "import ", "from ", "def ", "const ", "struct ", "type ", "#[",
+];
+
+const HELP: &str = "Enter an expression to print its value and the number of constraints needed to compute it.
+Declarations such as imports, constants, structs and functions are available in the following inputs.
+Declarations spanning several lines, like functions, end with an empty line.
+
+Commands:
+  :declarations  print the declarations entered so far
+  :clear         forget the declarations entered so far
+  :help          print this message
+  :quit          exit";
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("repl")
+        .about("Evaluates expressions interactively, printing their value and the number of constraints needed to compute them")
+        .arg(
+            Arg::with_name("stdlib-path")
+                .long("stdlib-path")
+                .help("Path to the standard library")
+                .value_name("PATH")
+                .takes_value(true)
+                .required(false)
+                .env("ZOKRATES_STDLIB")
+                .default_value(constants::DEFAULT_STDLIB_PATH.as_str()),
+        )
+        .arg(
+            Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve to be used in the compilation")
+                .takes_value(true)
+                .required(false)
+                .possible_values(constants::CURVES)
+                .default_value(constants::BN128),
+        )
+}
+
+pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    let manifest = Manifest::find()?;
+    let curve = CurveParameter::try_from(
+        manifest::value_of(
+            sub_matches,
+            "curve",
+            manifest.as_ref().and_then(|m| m.package.curve.as_deref()),
+        )
+        .unwrap(),
+    )?;
+    match curve {
+        CurveParameter::Bn128 => cli_repl::<Bn128Field>(sub_matches, manifest.as_ref()),
+        CurveParameter::Bls12_377 => cli_repl::<Bls12_377Field>(sub_matches, manifest.as_ref()),
+        CurveParameter::Bls12_381 => cli_repl::<Bls12_381Field>(sub_matches, manifest.as_ref()),
+        CurveParameter::Bw6_761 => cli_repl::<Bw6_761Field>(sub_matches, manifest.as_ref()),
+    }
+}
+
+/// Render an error, without pointing to the program built from the inputs as it is not shown
+fn fmt_error(e: &CompileError) -> String {
+    let location = Path::new(LOCATION);
+    let mut diagnostic = Diagnostic::from(e);
+
+    if diagnostic.file.as_deref() == Some(location) {
+        diagnostic.file = None;
+        diagnostic.span = None;
+    }
+    for note in &mut diagnostic.related {
+        if note.file.as_deref() == Some(location) {
+            note.file = None;
+            note.span = None;
+        }
+    }
+
+    diagnostic.render(|file| read_to_string(file).ok())
+}
+
+fn fmt_errors(errors: &[CompileError]) -> String {
+    errors.iter().map(fmt_error).collect::<Vec<_>>().join("\n")
+}
+
+struct Repl<'a> {
+    resolver: &'a dyn Resolver<io::Error>,
+    config: CompileConfig,
+    /// The declarations entered so far
+    declarations: Vec<String>,
+}
+
+impl<'a> Repl<'a> {
+    fn new(resolver: &'a dyn Resolver<io::Error>, config: CompileConfig) -> Self {
+        Repl {
+            resolver,
+            config,
+            declarations: vec![],
+        }
+    }
+
+    fn source(&self) -> String {
+        self.declarations.join("\n")
+    }
+
+    /// Add a declaration to the context, if the declarations are still valid with it
+    fn declare<T: Field>(&mut self, declaration: &str) -> Result<(), String> {
+        let source = format!(
+            "{}\n{}\ndef main():\n    return\n",
+            self.source(),
+            declaration
+        );
+
+        check::<T, _>(source, LOCATION.into(), Some(self.resolver), &self.config)
+            .map_err(|e| fmt_errors(&e.0))?;
+
+        self.declarations.push(declaration.to_string());
+        Ok(())
+    }
+
+    /// Evaluate an expression, returning its value and the number of constraints of the program
+    /// computing it
+    fn evaluate<T: Field>(&self, expression: &str) -> Result<(serde_json::Value, usize), String> {
+        let arena = Arena::new();
+
+        let artifacts = compile_expression::<T, _>(
+            self.source(),
+            expression,
+            PathBuf::from(LOCATION),
+            Some(self.resolver),
+            self.config,
+            &arena,
+        )
+        .map_err(|e| fmt_errors(&e.0))?;
+
+        let (program, abi) = artifacts.into_inner();
+        let program = program.collect();
+        let constraint_count = program.constraint_count();
+
+        let witness = ir::Interpreter::default()
+            .execute(program, &[])
+            .map_err(|e| format!("Execution failed: {}", e))?;
+
+        let value = zokrates_abi::Values::decode(witness.return_values(), abi.signature().outputs)
+            .into_serde_json();
+
+        Ok((value, constraint_count))
+    }
+}
+
+/// Whether `line` starts a declaration spanning several lines
+fn is_block(line: &str) -> bool {
+    line.ends_with(':') || line.ends_with('{') || line.starts_with("#[")
+}
+
+/// Read a line, returning `None` at the end of the input
+fn read_line(prompt: &str, lines: &mut impl Iterator<Item = io::Result<String>>) -> Option<String> {
+    print!("{}", prompt);
+    stdout().flush().unwrap();
+    lines.next().and_then(|l| l.ok())
+}
+
+fn cli_repl<T: Field>(sub_matches: &ArgMatches, manifest: Option<&Manifest>) -> Result<(), String> {
+    let stdlib_path = sub_matches.value_of("stdlib-path").unwrap();
+    match Path::new(stdlib_path).exists() {
+        true => Ok(()),
+        _ => Err(format!(
+            "Invalid standard library source path: {}",
+            stdlib_path
+        )),
+    }?;
+
+    let dependencies = manifest
+        .map(|m| m.dependencies())
+        .transpose()?
+        .unwrap_or_default();
+    let resolver = FileSystemResolver::with_stdlib_root(stdlib_path)
+        .with_dependencies(dependencies)
+        .map_err(|e| e.to_string())?;
+    let config = CompileConfig::default().isolate_branches(
+        manifest
+            .map(|m| m.compile.isolate_branches)
+            .unwrap_or(false),
+    );
+
+    let mut repl = Repl::new(&resolver, config);
+
+    println!(
+        "ZoKrates {}, enter :help for help",
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let stdin = stdin();
+    let mut lines = stdin.lock().lines();
+
+    while let Some(line) = read_line("> ", &mut lines) {
+        let line = line.trim_end();
+
+        match line.trim() {
+            "" => continue,
+            ":quit" | ":q" => break,
+            ":help" => println!("{}", HELP),
+            ":clear" => repl.declarations.clear(),
+            ":declarations" => println!("{}", repl.source()),
+            command if command.starts_with(':') => {
+                println!("Unknown command {}, enter :help for help", command)
+            }
+            input if DECLARATIONS.iter().any(|d| input.starts_with(d)) => {
+                let mut declaration = line.to_string();
+                if is_block(input) {
+                    while let Some(line) = read_line("... ", &mut lines) {
+                        if line.trim().is_empty() {
+                            break;
+                        }
+                        declaration.push('\n');
+                        declaration.push_str(line.trim_end());
+                    }
+                }
+
+                if let Err(e) = repl.declare::<T>(&declaration) {
+                    println!("{}", e);
+                }
+            }
+            expression => match repl.evaluate::<T>(expression) {
+                Ok((value, constraint_count)) => println!(
+                    "{}\n({} constraint{})",
+                    value,
+                    constraint_count,
+                    if constraint_count == 1 { "" } else { "s" }
+                ),
+                Err(e) => println!("{}", e),
+            },
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evaluate() {
+        let stdlib = std::fs::canonicalize("../zokrates_stdlib/stdlib").unwrap();
+        let resolver = FileSystemResolver::with_stdlib_root(stdlib.to_str().unwrap());
+        let mut repl = Repl::new(&resolver, CompileConfig::default());
+
+        repl.declare::<Bn128Field>("const u32 N = 3").unwrap();
+        repl.declare::<Bn128Field>("def double(field x) -> field:\n    return 2 * x")
+            .unwrap();
+        repl.declare::<Bn128Field>("from \"utils/pack/bool/unpack\" import main as unpack")
+            .unwrap();
+
+        assert_eq!(
+            repl.evaluate::<Bn128Field>("double(21)").unwrap().0,
+            serde_json::json!("42")
+        );
+        assert_eq!(
+            repl.evaluate::<Bn128Field>("[1u8; N]").unwrap().0,
+            serde_json::json!(["0x01", "0x01", "0x01"])
+        );
+        assert!(repl.evaluate::<Bn128Field>("unpack::<2>(2)").is_ok());
+
+        // invalid declarations are not kept
+        assert!(repl.declare::<Bn128Field>("const field N = 1").is_err());
+        assert_eq!(repl.declarations.len(), 3);
+        assert!(repl.evaluate::<Bn128Field>("undefined").is_err());
+    }
+}
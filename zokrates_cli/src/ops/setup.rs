@@ -1,5 +1,6 @@
 use crate::constants;
 use crate::helpers::*;
+use crate::manifest::{self, Manifest};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use std::convert::TryFrom;
 use std::fs::File;
@@ -83,6 +84,8 @@ pub fn subcommand() -> App<'static, 'static> {
 }
 
 pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    let manifest = Manifest::find()?;
+
     // read compiled program
     let path = Path::new(sub_matches.value_of("input").unwrap());
     let file =
@@ -90,38 +93,58 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
 
     let mut reader = BufReader::new(file);
     let prog = ProgEnum::deserialize(&mut reader)?;
+    let read_error = prog.read_error();
+
+    // backends cannot report unreadable statements, so the program is read once beforehand to
+    // reject it before anything is computed
+    File::open(&path)
+        .map_err(|why| format!("Could not open {}: {}", path.display(), why))
+        .and_then(|file| ProgEnum::deserialize(BufReader::new(file)))?
+        .check_statements()?;
 
     let parameters = Parameters::try_from((
-        sub_matches.value_of("backend").unwrap(),
+        manifest::value_of(
+            sub_matches,
+            "backend",
+            manifest.as_ref().and_then(|m| m.package.backend.as_deref()),
+        )
+        .unwrap(),
         match prog {
             ProgEnum::Bn128Program(_) => constants::BN128,
             ProgEnum::Bls12_377Program(_) => constants::BLS12_377,
             ProgEnum::Bls12_381Program(_) => constants::BLS12_381,
             ProgEnum::Bw6_761Program(_) => constants::BW6_761,
         },
-        sub_matches.value_of("proving-scheme").unwrap(),
+        manifest::value_of(
+            sub_matches,
+            "proving-scheme",
+            manifest.as_ref().and_then(|m| m.package.scheme.as_deref()),
+        )
+        .unwrap(),
     ))?;
 
     match parameters {
         #[cfg(feature = "bellman")]
         Parameters(BackendParameter::Bellman, _, SchemeParameter::G16) => match prog {
             ProgEnum::Bn128Program(p) => {
-                cli_setup_non_universal::<_, _, G16, Bellman>(p, sub_matches)
+                cli_setup_non_universal::<_, _, G16, Bellman>(p, &read_error, sub_matches)
             }
             ProgEnum::Bls12_381Program(p) => {
-                cli_setup_non_universal::<_, _, G16, Bellman>(p, sub_matches)
+                cli_setup_non_universal::<_, _, G16, Bellman>(p, &read_error, sub_matches)
             }
             _ => unreachable!(),
         },
         #[cfg(feature = "ark")]
         Parameters(BackendParameter::Ark, _, SchemeParameter::GM17) => match prog {
             ProgEnum::Bls12_377Program(p) => {
-                cli_setup_non_universal::<_, _, GM17, Ark>(p, sub_matches)
+                cli_setup_non_universal::<_, _, GM17, Ark>(p, &read_error, sub_matches)
             }
             ProgEnum::Bw6_761Program(p) => {
-                cli_setup_non_universal::<_, _, GM17, Ark>(p, sub_matches)
+                cli_setup_non_universal::<_, _, GM17, Ark>(p, &read_error, sub_matches)
+            }
+            ProgEnum::Bn128Program(p) => {
+                cli_setup_non_universal::<_, _, GM17, Ark>(p, &read_error, sub_matches)
             }
-            ProgEnum::Bn128Program(p) => cli_setup_non_universal::<_, _, GM17, Ark>(p, sub_matches),
             _ => unreachable!(),
         },
         #[cfg(feature = "ark")]
@@ -141,13 +164,13 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
 
             match prog {
                 ProgEnum::Bls12_377Program(p) => {
-                    cli_setup_universal::<_, _, Marlin, Ark>(p, setup, sub_matches)
+                    cli_setup_universal::<_, _, Marlin, Ark>(p, setup, &read_error, sub_matches)
                 }
                 ProgEnum::Bn128Program(p) => {
-                    cli_setup_universal::<_, _, Marlin, Ark>(p, setup, sub_matches)
+                    cli_setup_universal::<_, _, Marlin, Ark>(p, setup, &read_error, sub_matches)
                 }
                 ProgEnum::Bw6_761Program(p) => {
-                    cli_setup_universal::<_, _, Marlin, Ark>(p, setup, sub_matches)
+                    cli_setup_universal::<_, _, Marlin, Ark>(p, setup, &read_error, sub_matches)
                 }
                 _ => unreachable!(),
             }
@@ -156,7 +179,7 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
         Parameters(BackendParameter::Libsnark, CurveParameter::Bn128, SchemeParameter::GM17) => {
             match prog {
                 ProgEnum::Bn128Program(p) => {
-                    cli_setup_non_universal::<_, _, GM17, Libsnark>(p, sub_matches)
+                    cli_setup_non_universal::<_, _, GM17, Libsnark>(p, &read_error, sub_matches)
                 }
                 _ => unreachable!(),
             }
@@ -165,7 +188,7 @@ pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
         Parameters(BackendParameter::Libsnark, CurveParameter::Bn128, SchemeParameter::PGHR13) => {
             match prog {
                 ProgEnum::Bn128Program(p) => {
-                    cli_setup_non_universal::<_, _, PGHR13, Libsnark>(p, sub_matches)
+                    cli_setup_non_universal::<_, _, PGHR13, Libsnark>(p, &read_error, sub_matches)
                 }
                 _ => unreachable!(),
             }
@@ -181,6 +204,7 @@ fn cli_setup_non_universal<
     B: NonUniversalBackend<T, S>,
 >(
     program: ir::ProgIterator<T, I>,
+    read_error: &ir::ReadError,
     sub_matches: &ArgMatches,
 ) -> Result<(), String> {
     println!("Performing setup...");
@@ -191,6 +215,7 @@ fn cli_setup_non_universal<
 
     // run setup phase
     let keypair = B::setup(program);
+    read_error.check()?;
 
     // write verification key
     let mut vk_file = File::create(vk_path)
@@ -226,6 +251,7 @@ fn cli_setup_universal<
 >(
     program: ir::ProgIterator<T, I>,
     srs: Vec<u8>,
+    read_error: &ir::ReadError,
     sub_matches: &ArgMatches,
 ) -> Result<(), String> {
     println!("Performing setup...");
@@ -236,6 +262,7 @@ fn cli_setup_universal<
 
     // run setup phase
     let keypair = B::setup(srs, program)?;
+    read_error.check()?;
 
     // write verification key
     let mut vk_file = File::create(vk_path)
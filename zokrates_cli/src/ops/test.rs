@@ -0,0 +1,441 @@
+use crate::constants;
+use crate::helpers::CurveParameter;
+use crate::lockfile::RecordingResolver;
+use crate::manifest::{self, Manifest};
+use crate::watch::{self, Cache, Dependencies};
+use clap::{App, Arg, ArgMatches, SubCommand};
+use std::convert::TryFrom;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use zokrates_common::Resolver;
+use zokrates_core::compile::{compile_with_cache, CompileConfig, CompileError, CompileErrorInner};
+use zokrates_core::ir;
+use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field, Field};
+use zokrates_fs_resolver::FileSystemResolver;
+use zokrates_pest_ast as pest;
+
+pub fn subcommand() -> App<'static, 'static> {
+    SubCommand::with_name("test")
+        .about("Runs the functions annotated with #[test] in the given programs")
+        .arg(
+            Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Paths of the source files, defaults to the entry point of the project manifest")
+                .value_name("FILE")
+                .takes_value(true)
+                .multiple(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("filter")
+                .long("filter")
+                .help("Only run the tests whose name contains this string")
+                .value_name("NAME")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::with_name("stdlib-path")
+                .long("stdlib-path")
+                .help("Path to the standard library")
+                .value_name("PATH")
+                .takes_value(true)
+                .required(false)
+                .env("ZOKRATES_STDLIB")
+                .default_value(constants::DEFAULT_STDLIB_PATH.as_str()),
+        )
+        .arg(
+            Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve to be used in the compilation")
+                .takes_value(true)
+                .required(false)
+                .possible_values(constants::CURVES)
+                .default_value(constants::BN128),
+        )
+        .arg(Arg::with_name("isolate-branches")
+            .long("isolate-branches")
+            .help("Isolate the execution of branches: a panic in a branch only makes the program panic if this branch is being logically executed")
+            .required(false)
+        )
+        .arg(watch::watch_arg())
+}
+
+pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    watch::exec(sub_matches, |watched, cache| {
+        run_tests(sub_matches, watched, cache)
+    })
+}
+
+fn run_tests(
+    sub_matches: &ArgMatches,
+    watched: &Dependencies,
+    cache: &Cache,
+) -> Result<(), String> {
+    let manifest = Manifest::find()?;
+    if let Some(manifest) = &manifest {
+        watched.add(manifest.root.join(manifest::MANIFEST_FILE_NAME));
+    }
+    let curve = CurveParameter::try_from(
+        manifest::value_of(
+            sub_matches,
+            "curve",
+            manifest.as_ref().and_then(|m| m.package.curve.as_deref()),
+        )
+        .unwrap(),
+    )?;
+    match curve {
+        CurveParameter::Bn128 => {
+            cli_test::<Bn128Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bls12_377 => {
+            cli_test::<Bls12_377Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bls12_381 => {
+            cli_test::<Bls12_381Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+        CurveParameter::Bw6_761 => {
+            cli_test::<Bw6_761Field>(sub_matches, manifest.as_ref(), watched, cache)
+        }
+    }
+}
+
+/// A function annotated with `#[test]`
+#[derive(Debug, PartialEq)]
+struct Test {
+    name: String,
+    /// `None` if the test is expected to pass, otherwise the message the failure should contain, if any
+    should_fail: Option<Option<String>>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Passed,
+    Failed(String),
+}
+
+#[derive(Debug)]
+struct TestResult {
+    name: String,
+    outcome: Outcome,
+    constraint_count: Option<usize>,
+    duration: Duration,
+}
+
+/// Find the test functions in `source`
+fn discover(source: &str) -> Result<Vec<Test>, pest::Error> {
+    let file = pest::generate_ast(source)?;
+
+    Ok(file
+        .declarations
+        .into_iter()
+        .filter_map(|d| match d {
+            pest::SymbolDeclaration::Function(f) => Some(f),
+            _ => None,
+        })
+        .filter(|f| f.attributes.iter().any(|a| a.id.value == "test"))
+        .map(|f| Test {
+            name: f.id.value,
+            should_fail: f
+                .attributes
+                .into_iter()
+                .find(|a| a.id.value == "should_fail")
+                .map(|a| a.argument.map(|m| m.value)),
+        })
+        .collect())
+}
+
+fn fmt_error(e: &CompileError) -> String {
+    // the entry point of a test program does not exist on disk
+    let file = e.file().canonicalize().unwrap_or_else(|_| e.file().clone());
+    format!(
+        "{}:{}",
+        file.strip_prefix(std::env::current_dir().unwrap())
+            .unwrap_or(file.as_path())
+            .display(),
+        e.value()
+    )
+}
+
+/// Resolves the imports of the program compiled for a test, whose entry point imports the test
+/// function from the tested module
+struct TestResolver<'a> {
+    inner: &'a dyn Resolver<io::Error>,
+    entry: PathBuf,
+    module: PathBuf,
+}
+
+impl<'a> Resolver<io::Error> for TestResolver<'a> {
+    fn resolve(
+        &self,
+        current_location: PathBuf,
+        import_location: PathBuf,
+    ) -> Result<(String, PathBuf), io::Error> {
+        match current_location == self.entry {
+            true => fs::read_to_string(&self.module).map(|source| (source, self.module.clone())),
+            false => self.inner.resolve(current_location, import_location),
+        }
+    }
+}
+
+/// Compile the test function `test` from the module at `path` as the main function of a program
+/// and execute it, reusing the modules in `cache` which did not change
+fn run<T: Field>(
+    path: &Path,
+    test: &Test,
+    resolver: &dyn Resolver<io::Error>,
+    config: CompileConfig,
+    cache: &Cache,
+) -> TestResult {
+    let start = Instant::now();
+
+    let resolver = TestResolver {
+        inner: resolver,
+        entry: path.with_extension("test"),
+        module: path.to_path_buf(),
+    };
+    let source = format!(
+        "from \"./{}\" import {} as main\n",
+        path.file_name().unwrap().to_str().unwrap(),
+        test.name
+    );
+
+    let compiled = cache.with(|modules, arena| {
+        compile_with_cache::<T, _>(
+            source,
+            resolver.entry.clone(),
+            Some(&resolver),
+            config,
+            modules,
+            arena,
+        )
+    });
+
+    let (failure, constraint_count) = match compiled {
+        Ok(artifacts) => {
+            let program = artifacts.prog().collect();
+            let constraint_count = program.constraint_count();
+            let failure = ir::Interpreter::default()
+                .execute(program, &[])
+                .err()
+                .map(|e| Ok(e.to_string()));
+            (failure, Some(constraint_count))
+        }
+        Err(errors) => {
+            // assertions which fail for constant values are detected during compilation
+            let failure = match errors.0.as_slice() {
+                [e] if matches!(e.value(), CompileErrorInner::AnalysisError(_)) => {
+                    Ok(e.value().to_string())
+                }
+                errors => Err(errors
+                    .iter()
+                    .map(fmt_error)
+                    .collect::<Vec<_>>()
+                    .join("\n\n")),
+            };
+            (Some(failure), None)
+        }
+    };
+
+    let outcome = match (failure, &test.should_fail) {
+        (Some(Err(errors)), _) => Outcome::Failed(format!("Compilation failed:\n\n{}", errors)),
+        (None, None) => Outcome::Passed,
+        (None, Some(_)) => Outcome::Failed("Expected the test to fail, but it passed".into()),
+        (Some(Ok(message)), None) => Outcome::Failed(format!("Execution failed: {}", message)),
+        (Some(Ok(message)), Some(expected)) => match expected {
+            Some(expected) if !message.contains(expected.as_str()) => Outcome::Failed(format!(
+                "Expected the failure to contain \"{}\", found: {}",
+                expected, message
+            )),
+            _ => Outcome::Passed,
+        },
+    };
+
+    TestResult {
+        name: test.name.clone(),
+        outcome,
+        constraint_count,
+        duration: start.elapsed(),
+    }
+}
+
+fn run_file<T: Field>(
+    path: &Path,
+    filter: Option<&str>,
+    resolver: &dyn Resolver<io::Error>,
+    config: CompileConfig,
+    cache: &Cache,
+) -> Result<Vec<TestResult>, String> {
+    let source = fs::read_to_string(path)
+        .map_err(|why| format!("Could not open {}: {}", path.display(), why))?;
+
+    let tests = discover(&source)
+        .map_err(|e| format!("Could not parse {}:\n{}", path.display(), e))?
+        .into_iter()
+        .filter(|t| filter.map(|f| t.name.contains(f)).unwrap_or(true))
+        .collect::<Vec<_>>();
+
+    println!(
+        "\nrunning {} test{} in {}",
+        tests.len(),
+        if tests.len() == 1 { "" } else { "s" },
+        path.display()
+    );
+
+    Ok(tests
+        .iter()
+        .map(|test| {
+            let result = run::<T>(path, test, resolver, config, cache);
+            match (&result.outcome, result.constraint_count) {
+                (Outcome::Passed, Some(count)) => println!(
+                    "test {} ... ok ({} constraints, {:.2?})",
+                    result.name, count, result.duration
+                ),
+                (Outcome::Passed, None) => {
+                    println!("test {} ... ok ({:.2?})", result.name, result.duration)
+                }
+                (Outcome::Failed(_), _) => {
+                    println!("test {} ... FAILED ({:.2?})", result.name, result.duration)
+                }
+            }
+            result
+        })
+        .collect())
+}
+
+fn cli_test<T: Field>(
+    sub_matches: &ArgMatches,
+    manifest: Option<&Manifest>,
+    watched: &Dependencies,
+    cache: &Cache,
+) -> Result<(), String> {
+    let stdlib_path = sub_matches.value_of("stdlib-path").unwrap();
+    match Path::new(stdlib_path).exists() {
+        true => Ok(()),
+        _ => Err(format!(
+            "Invalid standard library source path: {}",
+            stdlib_path
+        )),
+    }?;
+
+    let dependencies = manifest
+        .map(|m| m.dependencies())
+        .transpose()?
+        .unwrap_or_default();
+    let resolver = FileSystemResolver::with_stdlib_root(stdlib_path)
+        .with_dependencies(dependencies)
+        .map_err(|e| e.to_string())?;
+    let resolver = RecordingResolver::new(&resolver);
+    let config = CompileConfig::default().isolate_branches(
+        sub_matches.is_present("isolate-branches")
+            || manifest
+                .map(|m| m.compile.isolate_branches)
+                .unwrap_or(false),
+    );
+    let filter = sub_matches.value_of("filter");
+
+    let inputs = match sub_matches.values_of("input") {
+        Some(inputs) => inputs.map(PathBuf::from).collect(),
+        None => vec![manifest::input(sub_matches, manifest)?],
+    };
+
+    watched.extend(inputs.iter().cloned());
+
+    let mut results = vec![];
+    for input in inputs {
+        let res = run_file::<T>(&input, filter, &resolver, config, cache);
+        watched.extend(resolver.locations());
+        results.extend(res?);
+    }
+
+    let failures: Vec<_> = results
+        .iter()
+        .filter_map(|r| match &r.outcome {
+            Outcome::Failed(message) => Some((r.name.as_str(), message)),
+            Outcome::Passed => None,
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        println!("\nfailures:");
+        for (name, message) in &failures {
+            println!("\n---- {} ----\n{}", name, message);
+        }
+    }
+
+    println!(
+        "\ntest result: {}. {} passed; {} failed",
+        if failures.is_empty() { "ok" } else { "FAILED" },
+        results.len() - failures.len(),
+        failures.len()
+    );
+
+    match failures.is_empty() {
+        true => Ok(()),
+        false => Err(String::from("Some tests failed")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discover_tests() {
+        let source = r#"
+def main() -> field:
+    return 1
+
+#[test]
+def passes():
+    return
+
+#[test]
+#[should_fail("Division by zero")]
+def fails():
+    return
+
+#[should_fail]
+def not_a_test():
+    return
+"#;
+
+        assert_eq!(
+            discover(source).unwrap(),
+            vec![
+                Test {
+                    name: "passes".into(),
+                    should_fail: None
+                },
+                Test {
+                    name: "fails".into(),
+                    should_fail: Some(Some("Division by zero".into()))
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn run_example() {
+        let stdlib = std::fs::canonicalize("../zokrates_stdlib/stdlib").unwrap();
+        let resolver = FileSystemResolver::with_stdlib_root(stdlib.to_str().unwrap());
+
+        let results = run_file::<Bn128Field>(
+            Path::new("./examples/book/unit_tests.zok"),
+            None,
+            &resolver,
+            CompileConfig::default(),
+            &Cache::default(),
+        )
+        .unwrap();
+
+        assert_eq!(results.len(), 2);
+        for result in results {
+            assert_eq!(result.outcome, Outcome::Passed, "{}", result.name);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::constants;
 use crate::helpers::*;
+use crate::manifest::{self, Manifest};
 use clap::{App, Arg, ArgMatches, SubCommand};
 use std::convert::TryFrom;
 use std::fs::File;
@@ -62,10 +63,26 @@ pub fn subcommand() -> App<'static, 'static> {
 }
 
 pub fn exec(sub_matches: &ArgMatches) -> Result<(), String> {
+    let manifest = Manifest::find()?;
     let parameters = Parameters::try_from((
-        sub_matches.value_of("backend").unwrap(),
-        sub_matches.value_of("curve").unwrap(),
-        sub_matches.value_of("proving-scheme").unwrap(),
+        manifest::value_of(
+            sub_matches,
+            "backend",
+            manifest.as_ref().and_then(|m| m.package.backend.as_deref()),
+        )
+        .unwrap(),
+        manifest::value_of(
+            sub_matches,
+            "curve",
+            manifest.as_ref().and_then(|m| m.package.curve.as_deref()),
+        )
+        .unwrap(),
+        manifest::value_of(
+            sub_matches,
+            "proving-scheme",
+            manifest.as_ref().and_then(|m| m.package.scheme.as_deref()),
+        )
+        .unwrap(),
     ))?;
 
     match parameters {
@@ -0,0 +1,193 @@
+use crate::diagnostics::{self, MessageFormat};
+use clap::{Arg, ArgMatches};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+use typed_arena::Arena;
+use zokrates_core::compile::ModuleCache;
+use zokrates_field::Field;
+
+/// How often the watched files are checked for changes
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+pub fn watch_arg() -> Arg<'static, 'static> {
+    Arg::with_name("watch")
+        .long("watch")
+        .help("Run again whenever one of the files the command depends on changes, including every imported module")
+        .required(false)
+}
+
+/// The files a run of a command depends on, collected as it reads them
+#[derive(Default)]
+pub struct Dependencies(RefCell<BTreeSet<PathBuf>>);
+
+impl Dependencies {
+    pub fn add<P: Into<PathBuf>>(&self, path: P) {
+        self.0.borrow_mut().insert(path.into());
+    }
+
+    pub fn extend<I: IntoIterator<Item = PathBuf>>(&self, paths: I) {
+        self.0.borrow_mut().extend(paths);
+    }
+
+    fn into_files(self) -> BTreeSet<PathBuf> {
+        self.0.into_inner()
+    }
+}
+
+/// The modules compiled by the previous runs of a command, which the next runs reuse as long as they
+/// do not change
+pub struct Cache {
+    /// The sources of the cached modules, which are kept until the process exits
+    arena: &'static Arena<String>,
+    modules: RefCell<Option<Box<dyn Any>>>,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache {
+            arena: Box::leak(Box::new(Arena::new())),
+            modules: RefCell::new(None),
+        }
+    }
+}
+
+impl Cache {
+    /// Run `f` with the modules compiled over `T`, starting from an empty cache if the previous run
+    /// compiled over another curve
+    pub fn with<T: Field, R, F>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut ModuleCache<'static, T>, &'static Arena<String>) -> R,
+    {
+        let mut modules = self.modules.borrow_mut();
+
+        if !modules
+            .as_ref()
+            .map(|m| m.is::<ModuleCache<'static, T>>())
+            .unwrap_or(false)
+        {
+            *modules = Some(Box::new(ModuleCache::<'static, T>::default()));
+        }
+
+        f(
+            modules.as_mut().unwrap().downcast_mut().unwrap(),
+            self.arena,
+        )
+    }
+}
+
+/// The last modification time of each file, or `None` if it cannot be read
+fn modified(files: &BTreeSet<PathBuf>) -> BTreeMap<&Path, Option<SystemTime>> {
+    files
+        .iter()
+        .map(|f| (f.as_path(), fs::metadata(f).and_then(|m| m.modified()).ok()))
+        .collect()
+}
+
+/// Run a command once, or with `--watch`, run it again every time one of its dependencies
+/// changes. In watch mode, errors are reported without stopping, and the modules which did not
+/// change since the previous run are not compiled again.
+pub fn exec<F: Fn(&Dependencies, &Cache) -> Result<(), String>>(
+    sub_matches: &ArgMatches,
+    run: F,
+) -> Result<(), String> {
+    let format = MessageFormat::from_matches(sub_matches);
+    let cache = Cache::default();
+
+    if !sub_matches.is_present("watch") {
+        return diagnostics::finish(format, run(&Dependencies::default(), &cache));
+    }
+
+    loop {
+        let dependencies = Dependencies::default();
+
+        if let Err(e) = diagnostics::finish(format, run(&dependencies, &cache)) {
+            if !e.is_empty() {
+                println!("{}", e);
+            }
+        }
+
+        let files = dependencies.into_files();
+
+        if format.is_human() {
+            println!("\nWatching {} file(s) for changes...\n", files.len());
+        }
+
+        let snapshot = modified(&files);
+        while modified(&files) == snapshot {
+            sleep(POLL_INTERVAL);
+        }
+
+        // editors often save a file in several writes, let them settle before running again
+        sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lockfile::RecordingResolver;
+    use tempdir::TempDir;
+    use zokrates_core::compile::{check, CompileConfig};
+    use zokrates_field::Bn128Field;
+    use zokrates_fs_resolver::FileSystemResolver;
+
+    #[test]
+    fn watch_transitive_imports() {
+        let project = TempDir::new("project").unwrap();
+        let main = project.path().join("main.zok");
+        let a = project.path().join("a.zok");
+        let b = project.path().join("b.zok");
+
+        fs::write(
+            &main,
+            "from \"./a\" import main as a\n\ndef main() -> field:\n    return a()\n",
+        )
+        .unwrap();
+        fs::write(
+            &a,
+            "from \"./b\" import main as b\n\ndef main() -> field:\n    return b() + 1\n",
+        )
+        .unwrap();
+        fs::write(&b, "def main() -> field:\n    return 41\n").unwrap();
+
+        let dependencies = Dependencies::default();
+        dependencies.add(&main);
+
+        let source = fs::read_to_string(&main).unwrap();
+        let resolver = FileSystemResolver::default();
+        let resolver = RecordingResolver::new(&resolver);
+        check::<Bn128Field, _>(
+            source,
+            main.clone(),
+            Some(&resolver),
+            &CompileConfig::default(),
+        )
+        .unwrap();
+        dependencies.extend(resolver.locations());
+
+        let files = dependencies.into_files();
+        assert_eq!(files.len(), 3);
+        for file in &[main, a, b] {
+            assert!(files.contains(file), "{} is not watched", file.display());
+        }
+    }
+
+    #[test]
+    fn removing_a_file_is_a_change() {
+        let project = TempDir::new("project").unwrap();
+        let main = project.path().join("main.zok");
+        fs::write(&main, "").unwrap();
+
+        let files: BTreeSet<_> = vec![main.clone()].into_iter().collect();
+        let snapshot = modified(&files);
+        assert_eq!(modified(&files), snapshot);
+
+        fs::remove_file(&main).unwrap();
+        assert_ne!(modified(&files), snapshot);
+    }
+}
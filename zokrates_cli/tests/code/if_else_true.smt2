@@ -5,13 +5,13 @@
 (declare-const |~out_0| Int)
 (declare-const |~one| Int)
 (declare-const |_0| Int)
+(declare-const |_1| Int)
 (declare-const |_2| Int)
-(declare-const |_3| Int)
 (assert (and
 (= |~prime| 21888242871839275222246405745257275088548364400416034343698204186575808495617)
 (= |~one| 1)
 
-(= (mod (* (+ (* |~one| 21888242871839275222246405745257275088548364400416034343698204186575808495616) (* |_0| 1)) (* |_3| 1)) |~prime|) (mod (* |_2| 1) |~prime|))
-(= (mod (* (+ (* |~one| 1) (* |_2| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) (+ (* |~one| 21888242871839275222246405745257275088548364400416034343698204186575808495616) (* |_0| 1))) |~prime|) (mod 0 |~prime|))
-(= (mod (* (* |~one| 1) (+ (* |~one| 1) (* |_2| 21888242871839275222246405745257275088548364400416034343698204186575808495616))) |~prime|) (mod (* |~out_0| 1) |~prime|))
+(= (mod (* (+ (* |~one| 21888242871839275222246405745257275088548364400416034343698204186575808495616) (* |_0| 1)) (* |_2| 1)) |~prime|) (mod (* |_1| 1) |~prime|))
+(= (mod (* (+ (* |~one| 1) (* |_1| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) (+ (* |~one| 21888242871839275222246405745257275088548364400416034343698204186575808495616) (* |_0| 1))) |~prime|) (mod 0 |~prime|))
+(= (mod (* (* |~one| 1) (+ (* |~one| 1) (* |_1| 21888242871839275222246405745257275088548364400416034343698204186575808495616))) |~prime|) (mod (* |~out_0| 1) |~prime|))
 ))
\ No newline at end of file
@@ -259,9 +259,9 @@
 (declare-const |_252| Int)
 (declare-const |_253| Int)
 (declare-const |_254| Int)
+(declare-const |_255| Int)
+(declare-const |_256| Int)
 (declare-const |_257| Int)
-(declare-const |_258| Int)
-(declare-const |_264| Int)
 (assert (and
 (= |~prime| 21888242871839275222246405745257275088548364400416034343698204186575808495617)
 (= |~one| 1)
@@ -521,9 +521,9 @@
 (= (mod (* (* |_254| 1) (* |_254| 1)) |~prime|) (mod (* |_254| 1) |~prime|))
 (= (mod (* (* |~one| 1) (+ (* |_2| 7237005577332262213973186563042994240829374041602535252466099000494570602496) (* |_3| 3618502788666131106986593281521497120414687020801267626233049500247285301248) (* |_4| 1809251394333065553493296640760748560207343510400633813116524750123642650624) (* |_5| 904625697166532776746648320380374280103671755200316906558262375061821325312) (* |_6| 452312848583266388373324160190187140051835877600158453279131187530910662656) (* |_7| 226156424291633194186662080095093570025917938800079226639565593765455331328) (* |_8| 113078212145816597093331040047546785012958969400039613319782796882727665664) (* |_9| 56539106072908298546665520023773392506479484700019806659891398441363832832) (* |_10| 28269553036454149273332760011886696253239742350009903329945699220681916416) (* |_11| 14134776518227074636666380005943348126619871175004951664972849610340958208) (* |_12| 7067388259113537318333190002971674063309935587502475832486424805170479104) (* |_13| 3533694129556768659166595001485837031654967793751237916243212402585239552) (* |_14| 1766847064778384329583297500742918515827483896875618958121606201292619776) (* |_15| 883423532389192164791648750371459257913741948437809479060803100646309888) (* |_16| 441711766194596082395824375185729628956870974218904739530401550323154944) (* |_17| 220855883097298041197912187592864814478435487109452369765200775161577472) (* |_18| 110427941548649020598956093796432407239217743554726184882600387580788736) (* |_19| 55213970774324510299478046898216203619608871777363092441300193790394368) (* |_20| 27606985387162255149739023449108101809804435888681546220650096895197184) (* |_21| 13803492693581127574869511724554050904902217944340773110325048447598592) (* |_22| 6901746346790563787434755862277025452451108972170386555162524223799296) (* |_23| 3450873173395281893717377931138512726225554486085193277581262111899648) (* |_24| 1725436586697640946858688965569256363112777243042596638790631055949824) (* |_25| 862718293348820473429344482784628181556388621521298319395315527974912) (* |_26| 431359146674410236714672241392314090778194310760649159697657763987456) (* |_27| 215679573337205118357336120696157045389097155380324579848828881993728) (* |_28| 107839786668602559178668060348078522694548577690162289924414440996864) (* |_29| 53919893334301279589334030174039261347274288845081144962207220498432) (* |_30| 26959946667150639794667015087019630673637144422540572481103610249216) (* |_31| 13479973333575319897333507543509815336818572211270286240551805124608) (* |_32| 6739986666787659948666753771754907668409286105635143120275902562304) (* |_33| 3369993333393829974333376885877453834204643052817571560137951281152) (* |_34| 1684996666696914987166688442938726917102321526408785780068975640576) (* |_35| 842498333348457493583344221469363458551160763204392890034487820288) (* |_36| 421249166674228746791672110734681729275580381602196445017243910144) (* |_37| 210624583337114373395836055367340864637790190801098222508621955072) (* |_38| 105312291668557186697918027683670432318895095400549111254310977536) (* |_39| 52656145834278593348959013841835216159447547700274555627155488768) (* |_40| 26328072917139296674479506920917608079723773850137277813577744384) (* |_41| 13164036458569648337239753460458804039861886925068638906788872192) (* |_42| 6582018229284824168619876730229402019930943462534319453394436096) (* |_43| 3291009114642412084309938365114701009965471731267159726697218048) (* |_44| 1645504557321206042154969182557350504982735865633579863348609024) (* |_45| 822752278660603021077484591278675252491367932816789931674304512) (* |_46| 411376139330301510538742295639337626245683966408394965837152256) (* |_47| 205688069665150755269371147819668813122841983204197482918576128) (* |_48| 102844034832575377634685573909834406561420991602098741459288064) (* |_49| 51422017416287688817342786954917203280710495801049370729644032) (* |_50| 25711008708143844408671393477458601640355247900524685364822016) (* |_51| 12855504354071922204335696738729300820177623950262342682411008) (* |_52| 6427752177035961102167848369364650410088811975131171341205504) (* |_53| 3213876088517980551083924184682325205044405987565585670602752) (* |_54| 1606938044258990275541962092341162602522202993782792835301376) (* |_55| 803469022129495137770981046170581301261101496891396417650688) (* |_56| 401734511064747568885490523085290650630550748445698208825344) (* |_57| 200867255532373784442745261542645325315275374222849104412672) (* |_58| 100433627766186892221372630771322662657637687111424552206336) (* |_59| 50216813883093446110686315385661331328818843555712276103168) (* |_60| 25108406941546723055343157692830665664409421777856138051584) (* |_61| 12554203470773361527671578846415332832204710888928069025792) (* |_62| 6277101735386680763835789423207666416102355444464034512896) (* |_63| 3138550867693340381917894711603833208051177722232017256448) (* |_64| 1569275433846670190958947355801916604025588861116008628224) (* |_65| 784637716923335095479473677900958302012794430558004314112) (* |_66| 392318858461667547739736838950479151006397215279002157056) (* |_67| 196159429230833773869868419475239575503198607639501078528) (* |_68| 98079714615416886934934209737619787751599303819750539264) (* |_69| 49039857307708443467467104868809893875799651909875269632) (* |_70| 24519928653854221733733552434404946937899825954937634816) (* |_71| 12259964326927110866866776217202473468949912977468817408) (* |_72| 6129982163463555433433388108601236734474956488734408704) (* |_73| 3064991081731777716716694054300618367237478244367204352) (* |_74| 1532495540865888858358347027150309183618739122183602176) (* |_75| 766247770432944429179173513575154591809369561091801088) (* |_76| 383123885216472214589586756787577295904684780545900544) (* |_77| 191561942608236107294793378393788647952342390272950272) (* |_78| 95780971304118053647396689196894323976171195136475136) (* |_79| 47890485652059026823698344598447161988085597568237568) (* |_80| 23945242826029513411849172299223580994042798784118784) (* |_81| 11972621413014756705924586149611790497021399392059392) (* |_82| 5986310706507378352962293074805895248510699696029696) (* |_83| 2993155353253689176481146537402947624255349848014848) (* |_84| 1496577676626844588240573268701473812127674924007424) (* |_85| 748288838313422294120286634350736906063837462003712) (* |_86| 374144419156711147060143317175368453031918731001856) (* |_87| 187072209578355573530071658587684226515959365500928) (* |_88| 93536104789177786765035829293842113257979682750464) (* |_89| 46768052394588893382517914646921056628989841375232) (* |_90| 23384026197294446691258957323460528314494920687616) (* |_91| 11692013098647223345629478661730264157247460343808) (* |_92| 5846006549323611672814739330865132078623730171904) (* |_93| 2923003274661805836407369665432566039311865085952) (* |_94| 1461501637330902918203684832716283019655932542976) (* |_95| 730750818665451459101842416358141509827966271488) (* |_96| 365375409332725729550921208179070754913983135744) (* |_97| 182687704666362864775460604089535377456991567872) (* |_98| 91343852333181432387730302044767688728495783936) (* |_99| 45671926166590716193865151022383844364247891968) (* |_100| 22835963083295358096932575511191922182123945984) (* |_101| 11417981541647679048466287755595961091061972992) (* |_102| 5708990770823839524233143877797980545530986496) (* |_103| 2854495385411919762116571938898990272765493248) (* |_104| 1427247692705959881058285969449495136382746624) (* |_105| 713623846352979940529142984724747568191373312) (* |_106| 356811923176489970264571492362373784095686656) (* |_107| 178405961588244985132285746181186892047843328) (* |_108| 89202980794122492566142873090593446023921664) (* |_109| 44601490397061246283071436545296723011960832) (* |_110| 22300745198530623141535718272648361505980416) (* |_111| 11150372599265311570767859136324180752990208) (* |_112| 5575186299632655785383929568162090376495104) (* |_113| 2787593149816327892691964784081045188247552) (* |_114| 1393796574908163946345982392040522594123776) (* |_115| 696898287454081973172991196020261297061888) (* |_116| 348449143727040986586495598010130648530944) (* |_117| 174224571863520493293247799005065324265472) (* |_118| 87112285931760246646623899502532662132736) (* |_119| 43556142965880123323311949751266331066368) (* |_120| 21778071482940061661655974875633165533184) (* |_121| 10889035741470030830827987437816582766592) (* |_122| 5444517870735015415413993718908291383296) (* |_123| 2722258935367507707706996859454145691648) (* |_124| 1361129467683753853853498429727072845824) (* |_125| 680564733841876926926749214863536422912) (* |_126| 340282366920938463463374607431768211456) (* |_127| 170141183460469231731687303715884105728) (* |_128| 85070591730234615865843651857942052864) (* |_129| 42535295865117307932921825928971026432) (* |_130| 21267647932558653966460912964485513216) (* |_131| 10633823966279326983230456482242756608) (* |_132| 5316911983139663491615228241121378304) (* |_133| 2658455991569831745807614120560689152) (* |_134| 1329227995784915872903807060280344576) (* |_135| 664613997892457936451903530140172288) (* |_136| 332306998946228968225951765070086144) (* |_137| 166153499473114484112975882535043072) (* |_138| 83076749736557242056487941267521536) (* |_139| 41538374868278621028243970633760768) (* |_140| 20769187434139310514121985316880384) (* |_141| 10384593717069655257060992658440192) (* |_142| 5192296858534827628530496329220096) (* |_143| 2596148429267413814265248164610048) (* |_144| 1298074214633706907132624082305024) (* |_145| 649037107316853453566312041152512) (* |_146| 324518553658426726783156020576256) (* |_147| 162259276829213363391578010288128) (* |_148| 81129638414606681695789005144064) (* |_149| 40564819207303340847894502572032) (* |_150| 20282409603651670423947251286016) (* |_151| 10141204801825835211973625643008) (* |_152| 5070602400912917605986812821504) (* |_153| 2535301200456458802993406410752) (* |_154| 1267650600228229401496703205376) (* |_155| 633825300114114700748351602688) (* |_156| 316912650057057350374175801344) (* |_157| 158456325028528675187087900672) (* |_158| 79228162514264337593543950336) (* |_159| 39614081257132168796771975168) (* |_160| 19807040628566084398385987584) (* |_161| 9903520314283042199192993792) (* |_162| 4951760157141521099596496896) (* |_163| 2475880078570760549798248448) (* |_164| 1237940039285380274899124224) (* |_165| 618970019642690137449562112) (* |_166| 309485009821345068724781056) (* |_167| 154742504910672534362390528) (* |_168| 77371252455336267181195264) (* |_169| 38685626227668133590597632) (* |_170| 19342813113834066795298816) (* |_171| 9671406556917033397649408) (* |_172| 4835703278458516698824704) (* |_173| 2417851639229258349412352) (* |_174| 1208925819614629174706176) (* |_175| 604462909807314587353088) (* |_176| 302231454903657293676544) (* |_177| 151115727451828646838272) (* |_178| 75557863725914323419136) (* |_179| 37778931862957161709568) (* |_180| 18889465931478580854784) (* |_181| 9444732965739290427392) (* |_182| 4722366482869645213696) (* |_183| 2361183241434822606848) (* |_184| 1180591620717411303424) (* |_185| 590295810358705651712) (* |_186| 295147905179352825856) (* |_187| 147573952589676412928) (* |_188| 73786976294838206464) (* |_189| 36893488147419103232) (* |_190| 18446744073709551616) (* |_191| 9223372036854775808) (* |_192| 4611686018427387904) (* |_193| 2305843009213693952) (* |_194| 1152921504606846976) (* |_195| 576460752303423488) (* |_196| 288230376151711744) (* |_197| 144115188075855872) (* |_198| 72057594037927936) (* |_199| 36028797018963968) (* |_200| 18014398509481984) (* |_201| 9007199254740992) (* |_202| 4503599627370496) (* |_203| 2251799813685248) (* |_204| 1125899906842624) (* |_205| 562949953421312) (* |_206| 281474976710656) (* |_207| 140737488355328) (* |_208| 70368744177664) (* |_209| 35184372088832) (* |_210| 17592186044416) (* |_211| 8796093022208) (* |_212| 4398046511104) (* |_213| 2199023255552) (* |_214| 1099511627776) (* |_215| 549755813888) (* |_216| 274877906944) (* |_217| 137438953472) (* |_218| 68719476736) (* |_219| 34359738368) (* |_220| 17179869184) (* |_221| 8589934592) (* |_222| 4294967296) (* |_223| 2147483648) (* |_224| 1073741824) (* |_225| 536870912) (* |_226| 268435456) (* |_227| 134217728) (* |_228| 67108864) (* |_229| 33554432) (* |_230| 16777216) (* |_231| 8388608) (* |_232| 4194304) (* |_233| 2097152) (* |_234| 1048576) (* |_235| 524288) (* |_236| 262144) (* |_237| 131072) (* |_238| 65536) (* |_239| 32768) (* |_240| 16384) (* |_241| 8192) (* |_242| 4096) (* |_243| 2048) (* |_244| 1024) (* |_245| 512) (* |_246| 256) (* |_247| 128) (* |_248| 64) (* |_249| 32) (* |_250| 16) (* |_251| 8) (* |_252| 4) (* |_253| 2) (* |_254| 1))) |~prime|) (mod (+ (* |~one| 7237005577332262213973186563042994240829374041602535252466099000494570602496) (* |_0| 21888242871839275222246405745257275088548364400416034343698204186575808495616) (* |_1| 1)) |~prime|))
 
-(= (mod (* (+ (* |~one| 14651237294507013008273219182214280847718990358813499091232105186081237893121) (* |_0| 1) (* |_1| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) (* |_258| 1)) |~prime|) (mod (* |_257| 1) |~prime|))
-(= (mod (* (+ (* |~one| 1) (* |_257| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) (+ (* |~one| 14651237294507013008273219182214280847718990358813499091232105186081237893121) (* |_0| 1) (* |_1| 21888242871839275222246405745257275088548364400416034343698204186575808495616))) |~prime|) (mod 0 |~prime|))
-(= (mod (* (* |~one| 1) 0) |~prime|) (mod (+ (* |~one| 1) (* |_257| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) |~prime|))
-(= (mod (* (* |_2| 1) (+ (* |_0| 21888242871839275222246405745257275088548364400416034343698204186575808495616) (* |_1| 1))) |~prime|) (mod (* |_264| 1) |~prime|))
-(= (mod (* (* |~one| 1) (* |_264| 1)) |~prime|) (mod (* |~out_0| 1) |~prime|))
+(= (mod (* (+ (* |~one| 14651237294507013008273219182214280847718990358813499091232105186081237893121) (* |_0| 1) (* |_1| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) (* |_256| 1)) |~prime|) (mod (* |_255| 1) |~prime|))
+(= (mod (* (+ (* |~one| 1) (* |_255| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) (+ (* |~one| 14651237294507013008273219182214280847718990358813499091232105186081237893121) (* |_0| 1) (* |_1| 21888242871839275222246405745257275088548364400416034343698204186575808495616))) |~prime|) (mod 0 |~prime|))
+(= (mod (* (* |~one| 1) 0) |~prime|) (mod (+ (* |~one| 1) (* |_255| 21888242871839275222246405745257275088548364400416034343698204186575808495616)) |~prime|))
+(= (mod (* (* |_2| 1) (+ (* |_0| 21888242871839275222246405745257275088548364400416034343698204186575808495616) (* |_1| 1))) |~prime|) (mod (* |_257| 1) |~prime|))
+(= (mod (* (* |~one| 1) (* |_257| 1)) |~prime|) (mod (* |~out_0| 1) |~prime|))
 ))
\ No newline at end of file
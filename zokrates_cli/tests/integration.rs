@@ -75,6 +75,8 @@ mod integration {
         let abi_spec_path = tmp_base.join(program_name).join("abi.json");
         let witness_path = tmp_base.join(program_name).join("witness");
         let inline_witness_path = tmp_base.join(program_name).join("inline_witness");
+        let bytecode_path = tmp_base.join(program_name).join("out.bytecode");
+        let bytecode_witness_path = tmp_base.join(program_name).join("bytecode_witness");
         let proof_path = tmp_base.join(program_name).join("proof.json");
         let verification_key_path = tmp_base
             .join(program_name)
@@ -111,6 +113,17 @@ mod integration {
         // compile
         assert_cli::Assert::command(&compile).succeeds().unwrap();
 
+        // the hash of the entry point is recorded in the ABI, relative to its directory
+        let abi_spec: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&abi_spec_path).unwrap()).unwrap();
+        let entry_point = program_path.file_name().unwrap().to_str().unwrap();
+        assert_eq!(
+            abi_spec["modules"]["modules"][entry_point]
+                .as_str()
+                .map(str::len),
+            Some(64)
+        );
+
         // COMPUTE_WITNESS
 
         let compute = vec![
@@ -202,6 +215,49 @@ mod integration {
 
         assert_eq!(inline_witness, witness);
 
+        // run witness-computation on the program lowered to bytecode, then on the emitted bytecode
+        let arguments: Vec<&str> = match inputs_raw.is_empty() {
+            true => vec![],
+            false => std::iter::once("-a")
+                .chain(inputs_raw.iter().map(|a| a.as_str()))
+                .collect(),
+        };
+
+        let emit_bytecode = [
+            vec![
+                "../target/release/zokrates",
+                "compute-witness",
+                "-i",
+                flattened_path.to_str().unwrap(),
+                "--emit-bytecode",
+                bytecode_path.to_str().unwrap(),
+                "-o",
+                bytecode_witness_path.to_str().unwrap(),
+            ],
+            arguments.clone(),
+        ]
+        .concat();
+
+        let run_bytecode = [
+            vec![
+                "../target/release/zokrates",
+                "compute-witness",
+                "-i",
+                bytecode_path.to_str().unwrap(),
+                "-o",
+                bytecode_witness_path.to_str().unwrap(),
+            ],
+            arguments,
+        ]
+        .concat();
+
+        for command in &[emit_bytecode, run_bytecode] {
+            assert_cli::Assert::command(command).succeeds().unwrap();
+
+            let bytecode_witness = fs::read_to_string(&bytecode_witness_path).unwrap();
+            assert_eq!(bytecode_witness, witness);
+        }
+
         for line in expected_witness.as_str().split('\n') {
             assert!(
                 witness.contains(line),
@@ -7,3 +7,55 @@ pub trait Resolver<E> {
         import_location: PathBuf,
     ) -> Result<(String, PathBuf), E>;
 }
+
+/// The number of single character insertions, deletions and substitutions to turn `a` into `b`
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + if ca == *cb { 0 } else { 1 };
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+/// The candidate closest to `name`, if it is close enough for `name` to likely be a typo of it
+pub fn suggest<'a, I: IntoIterator<Item = &'a str>>(name: &str, candidates: I) -> Option<&'a str> {
+    let max_distance = name.chars().count() / 3;
+
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance > 0 && *distance <= max_distance)
+        .min()
+        .map(|(_, candidate)| candidate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("foo", "foo"), 0);
+    }
+
+    #[test]
+    fn suggestions() {
+        let candidates = vec!["sha256", "pedersen", "unpack", "a"];
+
+        assert_eq!(suggest("sha265", candidates.clone()), Some("sha256"));
+        assert_eq!(suggest("pedrsen", candidates.clone()), Some("pedersen"));
+        // short names are not suggested, as any name is close to them
+        assert_eq!(suggest("b", candidates.clone()), None);
+        assert_eq!(suggest("poseidon", candidates), None);
+    }
+}
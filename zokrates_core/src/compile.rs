@@ -3,14 +3,16 @@
 //! @file compile.rs
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2018
-use crate::absy::{Module, OwnedModuleId, Program};
+use crate::absy::{Module, ModuleId, OwnedModuleId, Program, Statement, Symbol, SymbolDefinition};
 use crate::flatten::FlattenerIterator;
 use crate::imports::{self, Importer};
 use crate::ir;
+use crate::linter::{self, Warning};
 use crate::macros;
-use crate::semantics::{self, Checker};
+use crate::semantics::{self, CheckedModules, Checker};
 use crate::static_analysis;
 use crate::typed_absy::abi::Abi;
+use crate::typed_absy::TypedProgram;
 use crate::zir::ZirProgram;
 use macros::process_macros;
 use serde::{Deserialize, Serialize};
@@ -76,6 +78,18 @@ impl CompileErrorInner {
             file: context.to_path_buf(),
         }
     }
+
+    /// A stable code identifying the kind of error, as reported in diagnostics
+    pub fn code(&self) -> &'static str {
+        match self {
+            CompileErrorInner::ParserError(_) => "E0100",
+            CompileErrorInner::ImportError(_) => "E0200",
+            CompileErrorInner::MacroError(macros::Error::Curve(..)) => "E0300",
+            CompileErrorInner::SemanticError(_) => "E0400",
+            CompileErrorInner::ReadError(_) => "E0500",
+            CompileErrorInner::AnalysisError(e) => e.code(),
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -187,6 +201,107 @@ impl CompileConfig {
 
 type FilePath = PathBuf;
 
+/// The parsed modules before their imports are applied, along with the source they were parsed
+/// from
+pub type ParsedModules<'ast> = HashMap<OwnedModuleId, (&'ast str, Module<'ast>)>;
+
+/// The modules of previous compilations, which are reused as long as neither their source nor the
+/// source of the modules they import change. The modules borrow their source from the arena they
+/// were compiled with, so all the compilations sharing a cache must use the same arena.
+pub struct ModuleCache<'ast, T> {
+    parsed: ParsedModules<'ast>,
+    checked: CheckedModules<'ast, T>,
+    /// The source each checked module was checked from
+    sources: HashMap<OwnedModuleId, &'ast str>,
+}
+
+impl<'ast, T> Default for ModuleCache<'ast, T> {
+    fn default() -> Self {
+        ModuleCache {
+            parsed: HashMap::new(),
+            checked: CheckedModules::new(),
+            sources: HashMap::new(),
+        }
+    }
+}
+
+impl<'ast, T: Field> ModuleCache<'ast, T> {
+    /// Check `program`, reusing the modules which did not change since they were last checked
+    fn check(&mut self, program: Program<'ast>) -> Result<TypedProgram<'ast, T>, CompileErrors> {
+        let reusable = self.reusable(&program);
+
+        log::debug!("Reuse {} checked module(s)", reusable.len());
+
+        let sources: HashMap<_, _> = program
+            .modules
+            .keys()
+            .map(|id| (id.clone(), self.parsed[id].0))
+            .collect();
+
+        let (typed_ast, checked) =
+            Checker::check_with_modules(program, reusable).map_err(|errors| {
+                CompileErrors(errors.into_iter().map(CompileError::from).collect())
+            })?;
+
+        self.checked = checked;
+        self.sources = sources;
+
+        Ok(typed_ast)
+    }
+
+    /// The checked modules of `program` whose source did not change since they were checked, and
+    /// which only import such modules
+    fn reusable(&self, program: &Program<'ast>) -> CheckedModules<'ast, T> {
+        let mut reusable = HashMap::new();
+
+        for id in program.modules.keys() {
+            self.is_reusable(id, program, &mut reusable);
+        }
+
+        reusable
+            .into_iter()
+            .filter(|(_, is_reusable)| *is_reusable)
+            .map(|(id, _)| {
+                let checked = self.checked[&id].clone();
+                (id, checked)
+            })
+            .collect()
+    }
+
+    fn is_reusable(
+        &self,
+        id: &ModuleId,
+        program: &Program<'ast>,
+        reusable: &mut HashMap<OwnedModuleId, bool>,
+    ) -> bool {
+        if let Some(is_reusable) = reusable.get(id) {
+            return *is_reusable;
+        }
+
+        // a module is not reusable while we go through its imports, in case they are cyclic
+        reusable.insert(id.to_path_buf(), false);
+
+        let unchanged = match (self.sources.get(id), self.parsed.get(id)) {
+            (Some(checked), Some((parsed, _))) => checked == parsed,
+            _ => false,
+        };
+
+        let is_reusable = unchanged
+            && program.modules.get(id).map_or(false, |module| {
+                module.symbols.iter().all(|s| match &s.value.symbol {
+                    Symbol::There(import) => {
+                        self.is_reusable(&import.value.module_id, program, reusable)
+                    }
+                    _ => true,
+                })
+            });
+
+        reusable.insert(id.to_path_buf(), is_reusable);
+
+        is_reusable
+    }
+}
+
 pub fn compile<'ast, T: Field, E: Into<imports::Error>>(
     source: String,
     location: FilePath,
@@ -194,10 +309,39 @@ pub fn compile<'ast, T: Field, E: Into<imports::Error>>(
     config: CompileConfig,
     arena: &'ast Arena<String>,
 ) -> Result<CompilationArtifacts<T, impl IntoIterator<Item = ir::Statement<T>> + 'ast>, CompileErrors>
+{
+    compile_with_cache(
+        source,
+        location,
+        resolver,
+        config,
+        &mut ModuleCache::default(),
+        arena,
+    )
+}
+
+/// Compile a program, reusing the modules of the previous compilations with `cache` which did not
+/// change, and keeping the modules of this compilation in `cache` for the next ones
+pub fn compile_with_cache<'ast, T: Field, E: Into<imports::Error>>(
+    source: String,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+    config: CompileConfig,
+    cache: &mut ModuleCache<'ast, T>,
+    arena: &'ast Arena<String>,
+) -> Result<CompilationArtifacts<T, impl IntoIterator<Item = ir::Statement<T>> + 'ast>, CompileErrors>
 {
     let (typed_ast, abi): (crate::zir::ZirProgram<'_, T>, _) =
-        check_with_arena(source, location, resolver, &config, arena)?;
+        check_with_arena(source, location, resolver, &config, cache, arena)?;
 
+    Ok(compile_analysed(typed_ast, abi, config))
+}
+
+fn compile_analysed<'ast, T: Field>(
+    typed_ast: ZirProgram<'ast, T>,
+    abi: Abi,
+    config: CompileConfig,
+) -> CompilationArtifacts<T, impl IntoIterator<Item = ir::Statement<T>> + 'ast> {
     // flatten input program
     log::debug!("Flatten");
     let program_flattened = FlattenerIterator::from_function_and_config(typed_ast.main, config);
@@ -210,10 +354,76 @@ pub fn compile<'ast, T: Field, E: Into<imports::Error>>(
     log::debug!("Optimise IR");
     let optimized_ir_prog = ir_prog.optimize();
 
-    Ok(CompilationArtifacts {
+    // renumber variables so that the witness is dense
+    log::debug!("Renumber IR variables");
+    let optimized_ir_prog = optimized_ir_prog.renumber();
+
+    CompilationArtifacts {
         prog: optimized_ir_prog,
         abi,
-    })
+    }
+}
+
+/// Compile a program whose main function returns `expression`, evaluated in the context of the
+/// declarations in `source`. The return type of the main function is the type of the expression,
+/// as inferred by the semantic checker.
+pub fn compile_expression<'ast, T: Field, E: Into<imports::Error>>(
+    source: String,
+    expression: &str,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+    config: CompileConfig,
+    arena: &'ast Arena<String>,
+) -> Result<CompilationArtifacts<T, impl IntoIterator<Item = ir::Statement<T>> + 'ast>, CompileErrors>
+{
+    // the expression is parsed as the value returned by a function, which is then removed so that
+    // the checker can declare `main` with the type of the expression
+    let source = format!("{}\ndef main():\n    return {}\n", source, expression);
+
+    log::debug!("Parse program with entry file {}", location.display());
+
+    let mut program = parse_program::<T, E>(
+        source,
+        location.clone(),
+        resolver,
+        &mut ParsedModules::new(),
+        arena,
+    )?;
+
+    let function = program
+        .modules
+        .get_mut(&location)
+        .unwrap()
+        .symbols
+        .pop()
+        .unwrap();
+    let pos = function.pos();
+
+    let mut statements = match function.value.symbol {
+        Symbol::Here(SymbolDefinition::Function(f)) => f.value.statements,
+        _ => unreachable!(),
+    };
+
+    let expressions = match (statements.pop().map(|s| s.value), statements.is_empty()) {
+        (Some(Statement::Return(e)), true) => e.value.expressions,
+        _ => {
+            return Err(CompileErrorInner::SemanticError(semantics::ErrorInner::new(
+                Some(pos),
+                "Expected a single line expression",
+            ))
+            .in_file(&location)
+            .into())
+        }
+    };
+
+    log::debug!("Check semantics");
+
+    let typed_ast = Checker::check_with_expressions(program, expressions)
+        .map_err(|errors| CompileErrors(errors.into_iter().map(CompileError::from).collect()))?;
+
+    let (typed_ast, abi) = analyse(typed_ast, &config)?;
+
+    Ok(compile_analysed(typed_ast, abi, config))
 }
 
 pub fn check<T: Field, E: Into<imports::Error>>(
@@ -224,7 +434,72 @@ pub fn check<T: Field, E: Into<imports::Error>>(
 ) -> Result<(), CompileErrors> {
     let arena = Arena::new();
 
-    check_with_arena::<T, _>(source, location, resolver, config, &arena).map(|_| ())
+    check_with_cache(
+        source,
+        location,
+        resolver,
+        config,
+        &mut ModuleCache::<T>::default(),
+        &arena,
+    )
+}
+
+/// Check a program, reusing the modules of the previous compilations with `cache` which did not
+/// change, and keeping the modules of this compilation in `cache` for the next ones
+pub fn check_with_cache<'ast, T: Field, E: Into<imports::Error>>(
+    source: String,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+    config: &CompileConfig,
+    cache: &mut ModuleCache<'ast, T>,
+    arena: &'ast Arena<String>,
+) -> Result<(), CompileErrors> {
+    check_with_arena(source, location, resolver, config, cache, arena).map(|_| ())
+}
+
+/// Run the lints on the program, returning the warnings which are not suppressed in the source of
+/// the main module
+pub fn lint<T: Field, E: Into<imports::Error>>(
+    source: String,
+    location: FilePath,
+    resolver: Option<&dyn Resolver<E>>,
+    config: &CompileConfig,
+) -> Result<Vec<Warning>, CompileErrors> {
+    let arena = Arena::new();
+
+    let compiled = parse_program::<T, E>(
+        source.clone(),
+        location,
+        resolver,
+        &mut ParsedModules::new(),
+        &arena,
+    )?;
+
+    let spans = linter::spans(&compiled.modules[&compiled.main]);
+
+    let typed_ast = Checker::check(compiled)
+        .map_err(|errors| CompileErrors(errors.into_iter().map(CompileError::from).collect()))?;
+
+    log::debug!("Lint typed program");
+
+    let mut warnings = linter::lint_program(&typed_ast);
+
+    let main_module = typed_ast.main.clone();
+
+    let (typed_ast, abi) = typed_ast
+        .analyse(config)
+        .map_err(|e| CompileErrors(vec![CompileErrorInner::from(e).in_file(&main_module)]))?;
+
+    log::debug!("Lint constraint system");
+
+    let program_flattened = FlattenerIterator::from_function_and_config(typed_ast.main, *config);
+    let program = ir::from_flat::from_flat(program_flattened)
+        .optimize()
+        .collect();
+
+    warnings.extend(linter::lint_constraints(&abi, &program));
+
+    Ok(linter::locate(warnings, &source, &spans))
 }
 
 fn check_with_arena<'ast, T: Field, E: Into<imports::Error>>(
@@ -232,20 +507,25 @@ fn check_with_arena<'ast, T: Field, E: Into<imports::Error>>(
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
     config: &CompileConfig,
+    cache: &mut ModuleCache<'ast, T>,
     arena: &'ast Arena<String>,
 ) -> Result<(ZirProgram<'ast, T>, Abi), CompileErrors> {
-    let source = arena.alloc(source);
-
     log::debug!("Parse program with entry file {}", location.display());
 
-    let compiled = parse_program::<T, E>(source, location, resolver, arena)?;
+    let compiled = parse_program::<T, E>(source, location, resolver, &mut cache.parsed, arena)?;
 
     log::debug!("Check semantics");
 
     // check semantics
-    let typed_ast = Checker::check(compiled)
-        .map_err(|errors| CompileErrors(errors.into_iter().map(CompileError::from).collect()))?;
+    let typed_ast = cache.check(compiled)?;
 
+    analyse(typed_ast, config)
+}
+
+fn analyse<'ast, T: Field>(
+    typed_ast: TypedProgram<'ast, T>,
+    config: &CompileConfig,
+) -> Result<(ZirProgram<'ast, T>, Abi), CompileErrors> {
     log::trace!("\n{}", typed_ast);
 
     let main_module = typed_ast.main.clone();
@@ -259,14 +539,22 @@ fn check_with_arena<'ast, T: Field, E: Into<imports::Error>>(
 }
 
 pub fn parse_program<'ast, T: Field, E: Into<imports::Error>>(
-    source: &'ast str,
+    source: String,
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
+    parsed: &mut ParsedModules<'ast>,
     arena: &'ast Arena<String>,
 ) -> Result<Program<'ast>, CompileErrors> {
     let mut modules = HashMap::new();
 
-    let main = parse_module::<T, E>(source, location.clone(), resolver, &mut modules, arena)?;
+    let main = parse_module::<T, E>(
+        source,
+        location.clone(),
+        resolver,
+        &mut modules,
+        parsed,
+        arena,
+    )?;
 
     modules.insert(location.clone(), main);
 
@@ -276,26 +564,44 @@ pub fn parse_program<'ast, T: Field, E: Into<imports::Error>>(
     })
 }
 
+/// Parse the module at `location` and the modules it imports, reusing the modules in `parsed` whose
+/// source did not change
 pub fn parse_module<'ast, T: Field, E: Into<imports::Error>>(
-    source: &'ast str,
+    source: String,
     location: FilePath,
     resolver: Option<&dyn Resolver<E>>,
     modules: &mut HashMap<OwnedModuleId, Module<'ast>>,
+    parsed: &mut ParsedModules<'ast>,
     arena: &'ast Arena<String>,
 ) -> Result<Module<'ast>, CompileErrors> {
-    log::debug!("Generate pest AST for {}", location.display());
+    let module_without_imports = match parsed.get(&location) {
+        Some((parsed_source, module)) if *parsed_source == source => {
+            log::debug!("Reuse parsed module {}", location.display());
+
+            module.clone()
+        }
+        _ => {
+            let source: &'ast str = arena.alloc(source);
 
-    let ast = pest::generate_ast(source)
-        .map_err(|e| CompileErrors::from(CompileErrorInner::from(e).in_file(&location)))?;
+            log::debug!("Generate pest AST for {}", location.display());
 
-    log::debug!("Process macros for {}", location.display());
+            let ast = pest::generate_ast(source)
+                .map_err(|e| CompileErrors::from(CompileErrorInner::from(e).in_file(&location)))?;
 
-    let ast = process_macros::<T>(ast)
-        .map_err(|e| CompileErrors::from(CompileErrorInner::from(e).in_file(&location)))?;
+            log::debug!("Process macros for {}", location.display());
 
-    log::debug!("Generate absy for {}", location.display());
+            let ast = process_macros::<T>(ast)
+                .map_err(|e| CompileErrors::from(CompileErrorInner::from(e).in_file(&location)))?;
 
-    let module_without_imports: Module = Module::from(ast);
+            log::debug!("Generate absy for {}", location.display());
+
+            let module: Module = Module::from(ast);
+
+            parsed.insert(location.clone(), (source, module.clone()));
+
+            module
+        }
+    };
 
     log::debug!("Apply imports to absy for {}", location.display());
 
@@ -304,6 +610,7 @@ pub fn parse_module<'ast, T: Field, E: Into<imports::Error>>(
         location.clone(),
         resolver,
         modules,
+        parsed,
         arena,
     )
 }
@@ -311,6 +618,7 @@ pub fn parse_module<'ast, T: Field, E: Into<imports::Error>>(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::typed_absy::{ConcreteType, UBitwidth};
     use zokrates_field::Bn128Field;
 
     #[test]
@@ -358,6 +666,151 @@ mod test {
         assert!(res.is_ok());
     }
 
+    #[test]
+    fn compile_expression_with_inferred_type() {
+        let source = "const u32 N = 2\nstruct Point {\n    field x\n    field y\n}\n".to_string();
+
+        let compile = |expression: &str| {
+            let arena = Arena::new();
+            compile_expression::<Bn128Field, io::Error>(
+                source.clone(),
+                expression,
+                "./path/to/file".into(),
+                None,
+                CompileConfig::default(),
+                &arena,
+            )
+            .map(|artifacts| artifacts.abi().signature().outputs)
+        };
+
+        assert_eq!(
+            compile("[true; N]").unwrap(),
+            vec![ConcreteType::array((ConcreteType::Boolean, 2usize))]
+        );
+        assert_eq!(
+            compile("Point { x: 1, y: 2 }").unwrap()[0].to_string(),
+            "Point"
+        );
+        assert_eq!(compile("42f").unwrap(), vec![ConcreteType::FieldElement]);
+        assert_eq!(
+            compile("N, true").unwrap(),
+            vec![ConcreteType::Uint(UBitwidth::B32), ConcreteType::Boolean]
+        );
+        // the type of integer literals is not inferred from the expression alone
+        assert!(compile("42").is_err());
+        assert!(compile("[1, 2]").is_err());
+        assert_eq!(
+            compile("1u8 + 2").unwrap(),
+            vec![ConcreteType::Uint(UBitwidth::B8)]
+        );
+        assert!(compile("a").is_err());
+    }
+
+    mod cache {
+        use super::*;
+
+        struct MemoryResolver<'a>(&'a HashMap<PathBuf, String>);
+
+        impl<'a> Resolver<io::Error> for MemoryResolver<'a> {
+            fn resolve(
+                &self,
+                _: PathBuf,
+                import_location: PathBuf,
+            ) -> Result<(String, PathBuf), io::Error> {
+                Ok((self.0[&import_location].clone(), import_location))
+            }
+        }
+
+        /// Check the program in `files`, returning the modules reused from the previous checks
+        fn check_reusing<'ast>(
+            files: &HashMap<PathBuf, String>,
+            cache: &mut ModuleCache<'ast, Bn128Field>,
+            arena: &'ast Arena<String>,
+        ) -> Vec<String> {
+            let main = PathBuf::from("main");
+
+            let program = parse_program::<Bn128Field, io::Error>(
+                files[&main].clone(),
+                main,
+                Some(&MemoryResolver(files)),
+                &mut cache.parsed,
+                arena,
+            )
+            .unwrap();
+
+            let reusable = cache
+                .reusable(&program)
+                .keys()
+                .map(|id| id.display().to_string())
+                .collect();
+
+            cache.check(program).unwrap();
+
+            reusable
+        }
+
+        fn compile_reusing<'ast>(
+            files: &HashMap<PathBuf, String>,
+            cache: &mut ModuleCache<'ast, Bn128Field>,
+            arena: &'ast Arena<String>,
+        ) -> ir::Prog<Bn128Field> {
+            compile_with_cache::<Bn128Field, io::Error>(
+                files[&PathBuf::from("main")].clone(),
+                "main".into(),
+                Some(&MemoryResolver(files)),
+                CompileConfig::default(),
+                cache,
+                arena,
+            )
+            .unwrap()
+            .collect()
+            .prog()
+        }
+
+        #[test]
+        fn reuse_unchanged_modules() {
+            // main imports a and c, a imports b
+            let mut files: HashMap<PathBuf, String> = vec![
+                ("main", "from \"a\" import main as a\nfrom \"c\" import main as c\n\ndef main() -> field:\n    return a() + c()\n"),
+                ("a", "from \"b\" import main as b\n\ndef main() -> field:\n    return b() + 1\n"),
+                ("b", "def main() -> field:\n    return 41\n"),
+                ("c", "def main() -> field:\n    return 42\n"),
+            ]
+            .into_iter()
+            .map(|(path, source)| (path.into(), source.to_string()))
+            .collect();
+
+            let arena = Arena::new();
+            let mut cache = ModuleCache::default();
+
+            assert!(check_reusing(&files, &mut cache, &arena).is_empty());
+            assert_eq!(
+                check_reusing(&files, &mut cache, &arena),
+                vec!["a", "b", "c", "main"]
+            );
+
+            // a change to main does not affect the modules it imports
+            files.insert(
+                "main".into(),
+                "from \"a\" import main as a\nfrom \"c\" import main as c\n\ndef main() -> field:\n    return a() * c()\n".into(),
+            );
+            assert_eq!(
+                check_reusing(&files, &mut cache, &arena),
+                vec!["a", "b", "c"]
+            );
+
+            // a change to b affects a and main, which import it directly or indirectly
+            files.insert("b".into(), "def main() -> field:\n    return 40\n".into());
+            assert_eq!(check_reusing(&files, &mut cache, &arena), vec!["c"]);
+
+            // the program compiled from reused modules is the one compiled from scratch
+            assert_eq!(
+                compile_reusing(&files, &mut cache, &arena),
+                compile_reusing(&files, &mut ModuleCache::default(), &arena)
+            );
+        }
+    }
+
     mod abi {
         use super::*;
         use crate::typed_absy::abi::*;
@@ -0,0 +1,389 @@
+//! Module containing machine readable diagnostics, built from compilation and execution errors
+
+use crate::compile::{CompileError, CompileErrorInner};
+use crate::ir;
+use crate::linter::Warning;
+use crate::macros;
+use crate::parser::Position;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A region of a source file, from `start` inclusive to `end` exclusive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl From<(Position, Position)> for Span {
+    fn from((start, end): (Position, Position)) -> Self {
+        Span { start, end }
+    }
+}
+
+/// Additional information attached to a diagnostic, possibly pointing to another location
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Note {
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub span: Option<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// A stable code identifying the kind of diagnostic, if any
+    pub code: Option<&'static str>,
+    pub message: String,
+    pub file: Option<PathBuf>,
+    pub span: Option<Span>,
+    pub related: Vec<Note>,
+}
+
+impl Diagnostic {
+    pub fn error<S: Into<String>>(code: Option<&'static str>, message: S) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            message: message.into(),
+            file: None,
+            span: None,
+            related: vec![],
+        }
+    }
+
+    pub fn in_file(self, file: PathBuf) -> Self {
+        Diagnostic {
+            file: Some(file),
+            ..self
+        }
+    }
+
+    pub fn at(self, span: Option<Span>) -> Self {
+        Diagnostic { span, ..self }
+    }
+
+    pub fn with_note(mut self, note: Note) -> Self {
+        self.related.push(note);
+        self
+    }
+}
+
+impl Severity {
+    fn name(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// Render the lines of `source` covered by `span`, with carets under the spanned columns
+fn snippet(source: &str, span: &Span) -> String {
+    let lines: Vec<&str> = source.lines().collect();
+    let gutter = span.end.line.to_string().len();
+    let mut res = format!("{} |\n", " ".repeat(gutter));
+
+    for line in span.start.line..=span.end.line.min(lines.len()) {
+        let text = lines[line - 1];
+        let length = text.chars().count() + 1;
+
+        let start = if line == span.start.line {
+            span.start.col
+        } else {
+            text.chars().take_while(|c| c.is_whitespace()).count() + 1
+        };
+        let end = if line == span.end.line {
+            span.end.col
+        } else {
+            length
+        };
+
+        writeln!(res, "{:>width$} | {}", line, text, width = gutter).unwrap();
+        writeln!(
+            res,
+            "{} | {}{}",
+            " ".repeat(gutter),
+            " ".repeat(start.saturating_sub(1)),
+            "^".repeat(end.saturating_sub(start).max(1))
+        )
+        .unwrap();
+    }
+
+    res
+}
+
+/// Render a location, with an excerpt of the source if it is available
+fn location<F: Fn(&Path) -> Option<String>>(
+    file: Option<&Path>,
+    span: Option<&Span>,
+    source: &F,
+) -> String {
+    match (file, span) {
+        (Some(file), Some(span)) => {
+            let gutter = span.end.line.to_string().len();
+            let excerpt = source(file).map(|s| snippet(&s, span)).unwrap_or_default();
+            format!(
+                "{}--> {}:{}\n{}",
+                " ".repeat(gutter),
+                file.display(),
+                span.start,
+                excerpt
+            )
+        }
+        (Some(file), None) => format!("--> {}\n", file.display()),
+        (None, _) => String::new(),
+    }
+}
+
+impl Diagnostic {
+    /// Render the diagnostic for humans, with excerpts of the sources returned by `source`
+    pub fn render<F: Fn(&Path) -> Option<String>>(&self, source: F) -> String {
+        let mut res = match self.code {
+            Some(code) => format!("{}[{}]: {}\n", self.severity.name(), code, self.message),
+            None => format!("{}: {}\n", self.severity.name(), self.message),
+        };
+
+        res.push_str(&location(self.file.as_deref(), self.span.as_ref(), &source));
+
+        for note in &self.related {
+            match note.span {
+                // notes pointing to the source are rendered as secondary labels
+                Some(span) => {
+                    write!(
+                        res,
+                        "note: {}\n{}",
+                        note.message,
+                        location(note.file.as_deref(), Some(&span), &source)
+                    )
+                    .unwrap();
+                }
+                None => writeln!(res, "  = note: {}", note.message).unwrap(),
+            }
+        }
+
+        res
+    }
+}
+
+impl From<&CompileError> for Diagnostic {
+    fn from(e: &CompileError) -> Self {
+        let code = Some(e.value().code());
+        let e_file = e.file().clone();
+
+        let diagnostic = match e.value() {
+            CompileErrorInner::ParserError(e) => {
+                let (line, col) = e.line_col();
+                let position = Position { line, col };
+                Diagnostic::error(code, e.message()).at(Some((position, position).into()))
+            }
+            CompileErrorInner::ImportError(e) => {
+                Diagnostic::error(code, e.message()).at(e.pos().map(Span::from))
+            }
+            CompileErrorInner::SemanticError(e) => e.notes().iter().fold(
+                Diagnostic::error(code, e.message()).at(e.pos().map(Span::from)),
+                |diagnostic, note| {
+                    diagnostic.with_note(Note {
+                        // notes which point to the source are in the same file as the error
+                        file: note
+                            .file
+                            .clone()
+                            .or_else(|| note.span.map(|_| e_file.clone())),
+                        ..note.clone()
+                    })
+                },
+            ),
+            CompileErrorInner::MacroError(e) => {
+                let diagnostic = Diagnostic::error(code, e.to_string());
+                match e {
+                    macros::Error::Curve(_, found) => diagnostic.with_note(Note {
+                        message: format!(
+                            "remove the curve pragma or compile for curve `{}`",
+                            found
+                        ),
+                        file: None,
+                        span: None,
+                    }),
+                }
+            }
+            CompileErrorInner::ReadError(e) => Diagnostic::error(code, e.to_string()),
+            CompileErrorInner::AnalysisError(e) => {
+                let diagnostic = Diagnostic::error(code, e.to_string());
+                match e.constant() {
+                    Some((name, module)) => diagnostic.with_note(Note {
+                        message: format!("constant `{}` is declared in this module", name),
+                        file: Some(module.to_path_buf()),
+                        span: None,
+                    }),
+                    None => diagnostic,
+                }
+            }
+        };
+
+        diagnostic.in_file(e_file)
+    }
+}
+
+impl From<&Warning> for Diagnostic {
+    fn from(w: &Warning) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            code: Some(w.lint.name()),
+            message: w.message.clone(),
+            file: None,
+            span: w.span.map(Span::from),
+            related: w
+                .function
+                .iter()
+                .map(|f| Note {
+                    message: format!("in function `{}`", f),
+                    file: None,
+                    span: None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<&ir::Error> for Diagnostic {
+    fn from(e: &ir::Error) -> Self {
+        Diagnostic::error(Some(e.code()), e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{check, CompileConfig};
+    use std::io;
+    use zokrates_common::Resolver;
+    use zokrates_field::Bn128Field;
+
+    fn diagnostics(source: &str) -> Vec<Diagnostic> {
+        check::<Bn128Field, io::Error>(
+            source.to_string(),
+            "./main.zok".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        )
+        .unwrap_err()
+        .0
+        .iter()
+        .map(Diagnostic::from)
+        .collect()
+    }
+
+    #[test]
+    fn parser_error() {
+        let diagnostics = diagnostics("def main() -> field:\n    return 1 +\n");
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some("E0100"));
+        assert_eq!(diagnostics[0].file, Some(PathBuf::from("./main.zok")));
+        assert_eq!(diagnostics[0].span.unwrap().start.line, 2);
+    }
+
+    #[test]
+    fn semantic_error() {
+        let diagnostics = diagnostics("def main() -> field:\n    return a\n");
+
+        assert_eq!(
+            diagnostics,
+            vec![Diagnostic {
+                severity: Severity::Error,
+                code: Some("E0400"),
+                message: "Identifier \"a\" is undefined".into(),
+                file: Some(PathBuf::from("./main.zok")),
+                span: Some(Span {
+                    start: Position { line: 2, col: 12 },
+                    end: Position { line: 2, col: 13 }
+                }),
+                related: vec![]
+            }]
+        );
+    }
+
+    #[test]
+    fn suggestion() {
+        let diagnostics =
+            diagnostics("def main() -> field:\n    field value = 1\n    return valu\n");
+
+        assert_eq!(
+            diagnostics[0].related,
+            vec![Note {
+                message: "did you mean `value`?".into(),
+                file: None,
+                span: None
+            }]
+        );
+    }
+
+    #[test]
+    fn secondary_label() {
+        let diagnostics = diagnostics(
+            "const field foo = 1\nconst field foo = 2\n\ndef main() -> field:\n    return foo\n",
+        );
+
+        assert_eq!(diagnostics[0].message, "foo conflicts with another symbol");
+        assert_eq!(diagnostics[0].related.len(), 1);
+        assert_eq!(
+            diagnostics[0].related[0].message,
+            "`foo` is first declared here"
+        );
+        assert_eq!(
+            diagnostics[0].related[0].file,
+            Some(PathBuf::from("./main.zok"))
+        );
+        assert_eq!(diagnostics[0].related[0].span.unwrap().start.line, 1);
+    }
+
+    #[test]
+    fn render() {
+        let diagnostic = Diagnostic::error(Some("E0400"), "Identifier \"valeu\" is undefined")
+            .in_file("main.zok".into())
+            .at(Some(Span {
+                start: Position { line: 3, col: 12 },
+                end: Position { line: 3, col: 17 },
+            }))
+            .with_note(Note {
+                message: "did you mean `value`?".into(),
+                file: None,
+                span: None,
+            });
+
+        let source = "def main() -> field:\n    field value = 1\n    return valeu\n";
+
+        assert_eq!(
+            diagnostic.render(|_| Some(source.to_string())),
+            r#"error[E0400]: Identifier "valeu" is undefined
+ --> main.zok:3:12
+  |
+3 |     return valeu
+  |            ^^^^^
+  = note: did you mean `value`?
+"#
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let diagnostic = Diagnostic::error(
+            Some("E0702"),
+            "Program takes 1 input but was passed 0 values",
+        )
+        .in_file("out".into());
+
+        assert_eq!(
+            serde_json::to_string(&diagnostic).unwrap(),
+            r#"{"severity":"error","code":"E0702","message":"Program takes 1 input but was passed 0 values","file":"out","span":null,"related":[]}"#
+        );
+    }
+}
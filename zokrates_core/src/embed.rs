@@ -12,19 +12,25 @@ use crate::typed_absy::types::{
     GenericIdentifier,
 };
 use std::collections::HashMap;
+use zokrates_embed::babyjubjub::{
+    self, generate_edwards_add_constraints, generate_edwards_fixed_base_scalar_mult_constraints,
+    generate_edwards_scalar_mult_constraints,
+};
+use zokrates_embed::bigint::{generate_mul_mod_constraints, generate_reduce_constraints};
+use zokrates_embed::blake2s::{self, generate_blake2s_round_constraints};
+use zokrates_embed::keccak::{self, generate_keccak_f1600_constraints};
+use zokrates_embed::poseidon::generate_poseidon_permutation_constraints;
+use zokrates_embed::secp256k1::{self, generate_ecdsa_secp256k1_verify_constraints};
+use zokrates_embed::sha256::{self, generate_sha256_round_constraints};
+use zokrates_embed::R1CS;
 use zokrates_field::Field;
 
-cfg_if::cfg_if! {
-    if #[cfg(feature = "bellman")] {
-        use pairing_ce::bn256::Bn256;
-        use zokrates_embed::{bellman::{from_bellman, generate_sha256_round_constraints}};
-    }
-}
-
 cfg_if::cfg_if! {
     if #[cfg(feature = "ark")] {
         use ark_bls12_377::Bls12_377;
-        use zokrates_embed::ark::{from_ark, generate_verify_constraints};
+        use zokrates_embed::ark::{
+            from_ark, generate_verify_constraints, Gm17, Groth16, RecursiveSnark,
+        };
     }
 }
 
@@ -42,10 +48,23 @@ pub enum FlatEmbed {
     U16FromBits,
     U32FromBits,
     U64FromBits,
-    #[cfg(feature = "bellman")]
     Sha256Round,
     #[cfg(feature = "ark")]
     SnarkVerifyBls12377,
+    #[cfg(feature = "ark")]
+    SnarkVerifyGroth16Bls12377,
+    PoseidonPermutation,
+    KeccakF1600,
+    Blake2sRound,
+    EcdsaSecp256k1Verify,
+    BigIntMulMod,
+    BigIntReduce,
+    RangeCheck,
+    Lookup,
+    LookupU8,
+    EdwardsAdd,
+    EdwardsScalarMult,
+    EdwardsFixedBaseScalarMult,
 }
 
 impl FlatEmbed {
@@ -130,7 +149,6 @@ impl FlatEmbed {
                     Expression::U32Constant(64).into(),
                 )
                 .into()]),
-            #[cfg(feature = "bellman")]
             FlatEmbed::Sha256Round => UnresolvedSignature::new()
                 .inputs(vec![
                     UnresolvedType::array(
@@ -150,26 +168,223 @@ impl FlatEmbed {
                 )
                 .into()]),
             #[cfg(feature = "ark")]
-            FlatEmbed::SnarkVerifyBls12377 => UnresolvedSignature::new()
-                .generics(vec!["N".into(), "V".into()])
+            FlatEmbed::SnarkVerifyBls12377 | FlatEmbed::SnarkVerifyGroth16Bls12377 => {
+                UnresolvedSignature::new()
+                    .generics(vec!["N".into(), "V".into()])
+                    .inputs(vec![
+                        UnresolvedType::array(
+                            UnresolvedType::FieldElement.into(),
+                            Expression::Identifier("N").into(),
+                        )
+                        .into(), // inputs
+                        UnresolvedType::array(
+                            UnresolvedType::FieldElement.into(),
+                            Expression::U32Constant(8).into(),
+                        )
+                        .into(), // proof
+                        UnresolvedType::array(
+                            UnresolvedType::FieldElement.into(),
+                            Expression::Identifier("V").into(),
+                        )
+                        .into(), // vk
+                    ])
+                    .outputs(vec![UnresolvedType::Boolean.into()])
+            }
+            FlatEmbed::PoseidonPermutation => UnresolvedSignature::new()
+                .generics(vec![ConstantGenericNode::mock("N")])
+                .inputs(vec![UnresolvedType::array(
+                    UnresolvedType::FieldElement.into(),
+                    Expression::Identifier("N").into(),
+                )
+                .into()])
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::FieldElement.into(),
+                    Expression::Identifier("N").into(),
+                )
+                .into()]),
+            FlatEmbed::KeccakF1600 => UnresolvedSignature::new()
+                .inputs(vec![UnresolvedType::array(
+                    UnresolvedType::Uint(64).into(),
+                    Expression::U32Constant(25).into(),
+                )
+                .into()])
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::Uint(64).into(),
+                    Expression::U32Constant(25).into(),
+                )
+                .into()]),
+            FlatEmbed::Blake2sRound => UnresolvedSignature::new()
+                .inputs(vec![
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(32).into(),
+                        Expression::U32Constant(8).into(),
+                    )
+                    .into(), // h
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(32).into(),
+                        Expression::U32Constant(16).into(),
+                    )
+                    .into(), // m
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(32).into(),
+                        Expression::U32Constant(2).into(),
+                    )
+                    .into(), // t
+                    UnresolvedType::Boolean.into(), // last
+                ])
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::Uint(32).into(),
+                    Expression::U32Constant(8).into(),
+                )
+                .into()]),
+            FlatEmbed::EcdsaSecp256k1Verify => UnresolvedSignature::new()
+                .inputs(vec![
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::U32Constant(4).into(),
+                    )
+                    .into(), // z
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::U32Constant(4).into(),
+                    )
+                    .into(), // r
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::U32Constant(4).into(),
+                    )
+                    .into(), // s
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::U32Constant(4).into(),
+                    )
+                    .into(), // qx
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::U32Constant(4).into(),
+                    )
+                    .into(), // qy
+                ])
+                .outputs(vec![UnresolvedType::Boolean.into()]),
+            FlatEmbed::BigIntMulMod => UnresolvedSignature::new()
+                .generics(vec![ConstantGenericNode::mock("N")])
+                .inputs(vec![
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::Identifier("N").into(),
+                    )
+                    .into(), // a
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::Identifier("N").into(),
+                    )
+                    .into(), // b
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::Identifier("N").into(),
+                    )
+                    .into(), // p
+                ])
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::Uint(64).into(),
+                    Expression::Identifier("N").into(),
+                )
+                .into()]),
+            FlatEmbed::BigIntReduce => UnresolvedSignature::new()
+                .generics(vec![
+                    ConstantGenericNode::mock("N"),
+                    ConstantGenericNode::mock("M"),
+                ])
+                .inputs(vec![
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::Identifier("M").into(),
+                    )
+                    .into(), // a
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(64).into(),
+                        Expression::Identifier("N").into(),
+                    )
+                    .into(), // p
+                ])
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::Uint(64).into(),
+                    Expression::Identifier("N").into(),
+                )
+                .into()]),
+            FlatEmbed::RangeCheck => UnresolvedSignature::new()
+                .generics(vec![ConstantGenericNode::mock("N")])
+                .inputs(vec![UnresolvedType::FieldElement.into()])
+                .outputs(vec![UnresolvedType::FieldElement.into()]),
+            FlatEmbed::Lookup => UnresolvedSignature::new()
+                .generics(vec![ConstantGenericNode::mock("N")])
                 .inputs(vec![
                     UnresolvedType::array(
                         UnresolvedType::FieldElement.into(),
                         Expression::Identifier("N").into(),
                     )
-                    .into(), // inputs
+                    .into(), // table
+                    UnresolvedType::FieldElement.into(), // index
+                ])
+                .outputs(vec![UnresolvedType::FieldElement.into()]),
+            FlatEmbed::LookupU8 => UnresolvedSignature::new()
+                .generics(vec![ConstantGenericNode::mock("N")])
+                .inputs(vec![
+                    UnresolvedType::array(
+                        UnresolvedType::Uint(8).into(),
+                        Expression::Identifier("N").into(),
+                    )
+                    .into(), // table
+                    UnresolvedType::Uint(8).into(), // index
+                ])
+                .outputs(vec![UnresolvedType::Uint(8).into()]),
+            FlatEmbed::EdwardsAdd => UnresolvedSignature::new()
+                .inputs(vec![
                     UnresolvedType::array(
                         UnresolvedType::FieldElement.into(),
-                        Expression::U32Constant(8).into(),
+                        Expression::U32Constant(2).into(),
                     )
-                    .into(), // proof
+                    .into(), // p
                     UnresolvedType::array(
                         UnresolvedType::FieldElement.into(),
-                        Expression::Identifier("V").into(),
+                        Expression::U32Constant(2).into(),
                     )
-                    .into(), // 18 + (2 * n) // vk
+                    .into(), // q
                 ])
-                .outputs(vec![UnresolvedType::Boolean.into()]),
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::FieldElement.into(),
+                    Expression::U32Constant(2).into(),
+                )
+                .into()]),
+            FlatEmbed::EdwardsScalarMult => UnresolvedSignature::new()
+                .inputs(vec![
+                    UnresolvedType::array(
+                        UnresolvedType::Boolean.into(),
+                        Expression::U32Constant(256).into(),
+                    )
+                    .into(), // exponent
+                    UnresolvedType::array(
+                        UnresolvedType::FieldElement.into(),
+                        Expression::U32Constant(2).into(),
+                    )
+                    .into(), // p
+                ])
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::FieldElement.into(),
+                    Expression::U32Constant(2).into(),
+                )
+                .into()]),
+            FlatEmbed::EdwardsFixedBaseScalarMult => UnresolvedSignature::new()
+                .inputs(vec![UnresolvedType::array(
+                    UnresolvedType::Boolean.into(),
+                    Expression::U32Constant(256).into(),
+                )
+                .into()]) // exponent
+                .outputs(vec![UnresolvedType::array(
+                    UnresolvedType::FieldElement.into(),
+                    Expression::U32Constant(2).into(),
+                )
+                .into()]),
         }
     }
 
@@ -247,7 +462,6 @@ impl FlatEmbed {
                     DeclarationType::Boolean,
                     64u32,
                 ))]),
-            #[cfg(feature = "bellman")]
             FlatEmbed::Sha256Round => DeclarationSignature::new()
                 .inputs(vec![
                     DeclarationType::array((DeclarationType::Boolean, 512u32)),
@@ -258,27 +472,172 @@ impl FlatEmbed {
                     256u32,
                 ))]),
             #[cfg(feature = "ark")]
-            FlatEmbed::SnarkVerifyBls12377 => DeclarationSignature::new()
+            FlatEmbed::SnarkVerifyBls12377 | FlatEmbed::SnarkVerifyGroth16Bls12377 => {
+                DeclarationSignature::new()
+                    .generics(vec![
+                        Some(DeclarationConstant::Generic(
+                            GenericIdentifier::with_name("N").with_index(0),
+                        )),
+                        Some(DeclarationConstant::Generic(
+                            GenericIdentifier::with_name("V").with_index(1),
+                        )),
+                    ])
+                    .inputs(vec![
+                        DeclarationType::array((
+                            DeclarationType::FieldElement,
+                            GenericIdentifier::with_name("N").with_index(0),
+                        )), // inputs
+                        DeclarationType::array((DeclarationType::FieldElement, 8u32)), // proof
+                        DeclarationType::array((
+                            DeclarationType::FieldElement,
+                            GenericIdentifier::with_name("V").with_index(1),
+                        )), // vk
+                    ])
+                    .outputs(vec![DeclarationType::Boolean])
+            }
+            FlatEmbed::PoseidonPermutation => DeclarationSignature::new()
+                .generics(vec![Some(DeclarationConstant::Generic(
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))])
+                .inputs(vec![DeclarationType::array((
+                    DeclarationType::FieldElement,
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))])
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::FieldElement,
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))]),
+            FlatEmbed::KeccakF1600 => DeclarationSignature::new()
+                .inputs(vec![DeclarationType::array((
+                    DeclarationType::uint(64),
+                    25u32,
+                ))])
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::uint(64),
+                    25u32,
+                ))]),
+            FlatEmbed::Blake2sRound => DeclarationSignature::new()
+                .inputs(vec![
+                    DeclarationType::array((DeclarationType::uint(32), 8u32)), // h
+                    DeclarationType::array((DeclarationType::uint(32), 16u32)), // m
+                    DeclarationType::array((DeclarationType::uint(32), 2u32)), // t
+                    DeclarationType::Boolean,                                  // last
+                ])
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::uint(32),
+                    8u32,
+                ))]),
+            FlatEmbed::EcdsaSecp256k1Verify => DeclarationSignature::new()
+                .inputs(vec![
+                    DeclarationType::array((DeclarationType::uint(64), 4u32)), // z
+                    DeclarationType::array((DeclarationType::uint(64), 4u32)), // r
+                    DeclarationType::array((DeclarationType::uint(64), 4u32)), // s
+                    DeclarationType::array((DeclarationType::uint(64), 4u32)), // qx
+                    DeclarationType::array((DeclarationType::uint(64), 4u32)), // qy
+                ])
+                .outputs(vec![DeclarationType::Boolean]),
+            FlatEmbed::BigIntMulMod => DeclarationSignature::new()
+                .generics(vec![Some(DeclarationConstant::Generic(
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))])
+                .inputs(vec![
+                    DeclarationType::array((
+                        DeclarationType::uint(64),
+                        GenericIdentifier::with_name("N").with_index(0),
+                    )), // a
+                    DeclarationType::array((
+                        DeclarationType::uint(64),
+                        GenericIdentifier::with_name("N").with_index(0),
+                    )), // b
+                    DeclarationType::array((
+                        DeclarationType::uint(64),
+                        GenericIdentifier::with_name("N").with_index(0),
+                    )), // p
+                ])
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::uint(64),
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))]),
+            FlatEmbed::BigIntReduce => DeclarationSignature::new()
                 .generics(vec![
                     Some(DeclarationConstant::Generic(
                         GenericIdentifier::with_name("N").with_index(0),
                     )),
                     Some(DeclarationConstant::Generic(
-                        GenericIdentifier::with_name("V").with_index(1),
+                        GenericIdentifier::with_name("M").with_index(1),
                     )),
                 ])
                 .inputs(vec![
                     DeclarationType::array((
-                        DeclarationType::FieldElement,
+                        DeclarationType::uint(64),
+                        GenericIdentifier::with_name("M").with_index(1),
+                    )), // a
+                    DeclarationType::array((
+                        DeclarationType::uint(64),
                         GenericIdentifier::with_name("N").with_index(0),
-                    )), // inputs
-                    DeclarationType::array((DeclarationType::FieldElement, 8u32)), // proof
+                    )), // p
+                ])
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::uint(64),
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))]),
+            FlatEmbed::RangeCheck => DeclarationSignature::new()
+                .generics(vec![Some(DeclarationConstant::Generic(
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))])
+                .inputs(vec![DeclarationType::FieldElement])
+                .outputs(vec![DeclarationType::FieldElement]),
+            FlatEmbed::Lookup => DeclarationSignature::new()
+                .generics(vec![Some(DeclarationConstant::Generic(
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))])
+                .inputs(vec![
                     DeclarationType::array((
                         DeclarationType::FieldElement,
-                        GenericIdentifier::with_name("V").with_index(1),
-                    )), // 18 + (2 * n) // vk
+                        GenericIdentifier::with_name("N").with_index(0),
+                    )), // table
+                    DeclarationType::FieldElement, // index
                 ])
-                .outputs(vec![DeclarationType::Boolean]),
+                .outputs(vec![DeclarationType::FieldElement]),
+            FlatEmbed::LookupU8 => DeclarationSignature::new()
+                .generics(vec![Some(DeclarationConstant::Generic(
+                    GenericIdentifier::with_name("N").with_index(0),
+                ))])
+                .inputs(vec![
+                    DeclarationType::array((
+                        DeclarationType::uint(8),
+                        GenericIdentifier::with_name("N").with_index(0),
+                    )), // table
+                    DeclarationType::uint(8), // index
+                ])
+                .outputs(vec![DeclarationType::uint(8)]),
+            FlatEmbed::EdwardsAdd => DeclarationSignature::new()
+                .inputs(vec![
+                    DeclarationType::array((DeclarationType::FieldElement, 2u32)), // p
+                    DeclarationType::array((DeclarationType::FieldElement, 2u32)), // q
+                ])
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::FieldElement,
+                    2u32,
+                ))]),
+            FlatEmbed::EdwardsScalarMult => DeclarationSignature::new()
+                .inputs(vec![
+                    DeclarationType::array((DeclarationType::Boolean, 256u32)), // exponent
+                    DeclarationType::array((DeclarationType::FieldElement, 2u32)), // p
+                ])
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::FieldElement,
+                    2u32,
+                ))]),
+            FlatEmbed::EdwardsFixedBaseScalarMult => DeclarationSignature::new()
+                .inputs(vec![DeclarationType::array((
+                    DeclarationType::Boolean,
+                    256u32,
+                ))]) // exponent
+                .outputs(vec![DeclarationType::array((
+                    DeclarationType::FieldElement,
+                    2u32,
+                ))]),
         }
     }
 
@@ -306,17 +665,29 @@ impl FlatEmbed {
             FlatEmbed::U16FromBits => "_U16_FROM_BITS",
             FlatEmbed::U32FromBits => "_U32_FROM_BITS",
             FlatEmbed::U64FromBits => "_U64_FROM_BITS",
-            #[cfg(feature = "bellman")]
             FlatEmbed::Sha256Round => "_SHA256_ROUND",
             #[cfg(feature = "ark")]
             FlatEmbed::SnarkVerifyBls12377 => "_SNARK_VERIFY_BLS12_377",
+            #[cfg(feature = "ark")]
+            FlatEmbed::SnarkVerifyGroth16Bls12377 => "_SNARK_VERIFY_GROTH16_BLS12_377",
+            FlatEmbed::PoseidonPermutation => "_POSEIDON_PERMUTATION",
+            FlatEmbed::KeccakF1600 => "_KECCAK_F1600",
+            FlatEmbed::Blake2sRound => "_BLAKE2S_ROUND",
+            FlatEmbed::EcdsaSecp256k1Verify => "_ECDSA_SECP256K1_VERIFY",
+            FlatEmbed::BigIntMulMod => "_BIGINT_MUL_MOD",
+            FlatEmbed::BigIntReduce => "_BIGINT_REDUCE",
+            FlatEmbed::RangeCheck => "_RANGE_CHECK",
+            FlatEmbed::Lookup => "_LOOKUP",
+            FlatEmbed::LookupU8 => "_LOOKUP_U8",
+            FlatEmbed::EdwardsAdd => "_EDWARDS_ADD",
+            FlatEmbed::EdwardsScalarMult => "_EDWARDS_SCALAR_MULT",
+            FlatEmbed::EdwardsFixedBaseScalarMult => "_EDWARDS_FIXED_BASE_SCALAR_MULT",
         }
     }
 }
 
 // util to convert a vector of `(variable_id, coefficient)` to a flat_expression
 // we build a binary tree of additions by splitting the vector recursively
-#[cfg(any(feature = "ark", feature = "bellman"))]
 fn flat_expression_from_vec<T: Field>(v: &[(usize, T)]) -> FlatExpression<T> {
     match v.len() {
         0 => FlatExpression::Number(T::zero()),
@@ -341,71 +712,248 @@ fn flat_expression_from_vec<T: Field>(v: &[(usize, T)]) -> FlatExpression<T> {
 ///
 /// # Remarks
 ///
-/// The variables inside the function are set in this order:
-/// - constraint system variables
-/// - arguments
-#[cfg(feature = "bellman")]
+/// The input bits are not checked to be booleans
 pub fn sha256_round<T: Field>(
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    let (r1cs, outputs) = generate_sha256_round_constraints::<T>();
+
+    native_embed(
+        r1cs,
+        (1..=sha256::INPUT_SIZE).collect(),
+        outputs,
+        Solver::Sha256Round,
+    )
+}
+
+/// Returns a flat function which computes the poseidon permutation of a state of size `width`
+pub fn poseidon_permutation<T: Field>(
+    width: usize,
 ) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
     use zokrates_field::Bn128Field;
     assert_eq!(T::id(), Bn128Field::id());
 
-    // Define iterators for all indices at hand
-    let (r1cs, input_indices, current_hash_indices, output_indices) =
-        generate_sha256_round_constraints::<Bn256>();
+    let (r1cs, outputs) = generate_poseidon_permutation_constraints::<T>(width);
 
-    // The output count
-    let return_count = output_indices.len();
-    // indices of the input
-    let input_indices = input_indices.into_iter();
-    // indices of the current hash
-    let current_hash_indices = current_hash_indices.into_iter();
-    // indices of the output
-    let output_indices = output_indices.into_iter();
+    native_embed(
+        r1cs,
+        (1..=width).collect(),
+        outputs,
+        Solver::PoseidonPermutation(width),
+    )
+}
+
+/// Returns a flat function which computes the keccak-f[1600] permutation of the bits of a state
+///
+/// # Remarks
+///
+/// The input bits are not checked to be booleans
+pub fn keccak_f1600<T: Field>(
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    let (r1cs, outputs) = generate_keccak_f1600_constraints::<T>();
+
+    native_embed(
+        r1cs,
+        (1..=keccak::STATE_SIZE).collect(),
+        outputs,
+        Solver::KeccakF1600,
+    )
+}
+
+/// Returns a flat function which computes the blake2s compression function of the bits of the
+/// state, the message block, the offset counter and the final block flag
+///
+/// # Remarks
+///
+/// The input bits are not checked to be booleans
+pub fn blake2s_round<T: Field>(
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    let (r1cs, outputs) = generate_blake2s_round_constraints::<T>();
+
+    native_embed(
+        r1cs,
+        (1..=blake2s::INPUT_SIZE).collect(),
+        outputs,
+        Solver::Blake2sRound,
+    )
+}
+
+/// Returns a flat function which checks an ECDSA signature `(r, s)` of the hash `z` for the
+/// secp256k1 public key `(qx, qy)`, each given as 4 words of 64 bits in big endian order
+///
+/// # Remarks
+///
+/// The input words are not checked to be of 64 bits
+pub fn ecdsa_secp256k1_verify<T: Field>(
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    let (r1cs, output) = generate_ecdsa_secp256k1_verify_constraints::<T>();
+
+    native_embed(
+        r1cs,
+        (1..=secp256k1::INPUT_SIZE).collect(),
+        vec![output],
+        Solver::EcdsaSecp256k1Verify,
+    )
+}
+
+/// Returns a flat function which computes the product of two integers modulo `modulus`, all
+/// given as words of 64 bits in big endian order. The result is less than `modulus`.
+///
+/// # Remarks
+///
+/// The input words are not checked to be of 64 bits
+pub fn bigint_mul_mod<T: Field>(
+    modulus: Vec<u64>,
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    let (r1cs, outputs) = generate_mul_mod_constraints::<T>(&modulus);
+
+    native_embed(
+        r1cs,
+        (1..=2 * modulus.len()).collect(),
+        outputs,
+        Solver::BigIntMulMod(modulus),
+    )
+}
+
+/// Returns a flat function which computes the reduction of an integer of `input_limb_count` words
+/// modulo `modulus`, all given as words of 64 bits in big endian order. The result is less than
+/// `modulus`.
+///
+/// # Remarks
+///
+/// The input words are not checked to be of 64 bits
+pub fn bigint_reduce<T: Field>(
+    input_limb_count: usize,
+    modulus: Vec<u64>,
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    let (r1cs, outputs) = generate_reduce_constraints::<T>(&modulus, input_limb_count);
+
+    native_embed(
+        r1cs,
+        (1..=input_limb_count).collect(),
+        outputs,
+        Solver::BigIntReduce(input_limb_count, modulus),
+    )
+}
+
+/// Returns a flat function which adds two points of the BabyJubJub curve
+///
+/// # Remarks
+///
+/// The input points are not checked to be on the curve
+pub fn edwards_add<T: Field>() -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>>
+{
+    use zokrates_field::Bn128Field;
+    assert_eq!(T::id(), Bn128Field::id());
+
+    let (r1cs, outputs) = generate_edwards_add_constraints::<T>();
+
+    native_embed(r1cs, (1..=4).collect(), outputs, Solver::EdwardsAdd)
+}
+
+/// Returns a flat function which multiplies a point of the BabyJubJub curve by a scalar given as
+/// 256 bits in big endian order
+///
+/// # Remarks
+///
+/// The input bits are not checked to be booleans, and the input point is not checked to be on
+/// the curve
+pub fn edwards_scalar_mult<T: Field>(
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    use zokrates_field::Bn128Field;
+    assert_eq!(T::id(), Bn128Field::id());
+
+    let (r1cs, outputs) = generate_edwards_scalar_mult_constraints::<T>();
+
+    native_embed(
+        r1cs,
+        (1..=babyjubjub::SCALAR_SIZE + 2).collect(),
+        outputs,
+        Solver::EdwardsScalarMult,
+    )
+}
+
+/// Returns a flat function which multiplies the generator of the BabyJubJub curve by a scalar
+/// given as 256 bits in big endian order
+///
+/// # Remarks
+///
+/// The input bits are not checked to be booleans
+pub fn edwards_fixed_base_scalar_mult<T: Field>(
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    use zokrates_field::Bn128Field;
+    assert_eq!(T::id(), Bn128Field::id());
+
+    let (r1cs, outputs) = generate_edwards_fixed_base_scalar_mult_constraints::<T>();
+
+    native_embed(
+        r1cs,
+        (1..=babyjubjub::SCALAR_SIZE).collect(),
+        outputs,
+        Solver::EdwardsFixedBaseScalarMult,
+    )
+}
+
+/// Returns a flat function from a constraint system generated in `zokrates_embed::native`, whose
+/// witness is computed by `solver` from the variables at `input_indices`
+///
+/// # Remarks
+///
+/// The variables inside the function are set in this order:
+/// - constraint system variables
+/// - arguments
+fn native_embed<T: Field>(
+    r1cs: R1CS<T>,
+    input_indices: Vec<usize>,
+    outputs: Vec<Vec<(usize, T)>>,
+    solver: Solver,
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
     let variable_count = r1cs.aux_count + 1; // auxiliary and ONE
-                                             // indices of the sha256round constraint system variables
+    assert_eq!(
+        solver.get_signature(),
+        (input_indices.len(), variable_count)
+    );
+
+    // indices of the constraint system variables
     let cs_indices = 0..variable_count;
-    // indices of the arguments to the function
-    // apply an offset of `variable_count` to get the indice of our dummy `input` argument
-    let input_argument_indices: Vec<_> = input_indices
-        .clone()
-        .into_iter()
+    // apply an offset of `variable_count` to get the indices of the arguments
+    let argument_indices: Vec<_> = (0..input_indices.len())
         .map(|i| i + variable_count)
         .collect();
-    // apply an offset of `variable_count` to get the indice of our dummy `current_hash` argument
-    let current_hash_argument_indices: Vec<_> = current_hash_indices
-        .clone()
-        .into_iter()
-        .map(|i| i + variable_count)
-        .collect();
-    // define parameters to the function based on the variables
-    let arguments = input_argument_indices
-        .clone()
-        .into_iter()
-        .chain(current_hash_argument_indices.clone())
+    let arguments = argument_indices
+        .iter()
         .map(|i| FlatParameter {
-            id: FlatVariable::new(i),
+            id: FlatVariable::new(*i),
             private: true,
         })
         .collect();
-    // define a binding of the first variable in the constraint system to one
+    // set the witness based on the native gadget and the arguments
+    let directive_statement = FlatStatement::Directive(FlatDirective {
+        outputs: cs_indices.map(FlatVariable::new).collect(),
+        inputs: argument_indices
+            .iter()
+            .map(|i| FlatVariable::new(*i).into())
+            .collect(),
+        solver,
+    });
+    // bind the first variable of the constraint system to one
     let one_binding_statement = FlatStatement::Condition(
         FlatVariable::new(0).into(),
         FlatExpression::Number(T::from(1)),
-        RuntimeError::BellmanOneBinding,
+        RuntimeError::EmbedOneBinding,
     );
+    // bind the inputs of the constraint system to the arguments
     let input_binding_statements =
-    // bind input and current_hash to inputs
-    input_indices.chain(current_hash_indices).zip(input_argument_indices.clone().into_iter().chain(current_hash_argument_indices.clone())).map(|(cs_index, argument_index)| {
-        FlatStatement::Condition(
-            FlatVariable::new(cs_index).into(),
-            FlatVariable::new(argument_index).into(),
-            RuntimeError::BellmanInputBinding
-        )
-    });
-    // insert flattened statements to represent constraints
+        input_indices
+            .into_iter()
+            .zip(argument_indices)
+            .map(|(cs_index, argument_index)| {
+                FlatStatement::Condition(
+                    FlatVariable::new(cs_index).into(),
+                    FlatVariable::new(argument_index).into(),
+                    RuntimeError::EmbedInputBinding,
+                )
+            });
     let constraint_statements = r1cs.constraints.into_iter().map(|c| {
-        let c = from_bellman::<T, Bn256>(c);
         let rhs_a = flat_expression_from_vec::<T>(c.a.as_slice());
         let rhs_b = flat_expression_from_vec::<T>(c.b.as_slice());
         let lhs = flat_expression_from_vec::<T>(c.c.as_slice());
@@ -413,27 +961,13 @@ pub fn sha256_round<T: Field>(
         FlatStatement::Condition(
             lhs,
             FlatExpression::Mult(box rhs_a, box rhs_b),
-            RuntimeError::BellmanConstraint,
+            RuntimeError::EmbedConstraint,
         )
     });
-
-    // define which subset of the witness is returned
-    let outputs = output_indices.map(|o| FlatExpression::Identifier(FlatVariable::new(o)));
-    // insert a directive to set the witness based on the bellman gadget and  inputs
-    let directive_statement = FlatStatement::Directive(FlatDirective {
-        outputs: cs_indices.map(FlatVariable::new).collect(),
-        inputs: input_argument_indices
-            .into_iter()
-            .chain(current_hash_argument_indices)
-            .map(|i| FlatVariable::new(i).into())
-            .collect(),
-        solver: Solver::Sha256Round,
+    let return_count = outputs.len();
+    let return_statements = outputs.into_iter().enumerate().map(|(index, e)| {
+        FlatStatement::Definition(FlatVariable::public(index), flat_expression_from_vec(&e))
     });
-    // insert a statement to return the subset of the witness
-    let return_statements = outputs
-        .into_iter()
-        .enumerate()
-        .map(|(index, e)| FlatStatement::Definition(FlatVariable::public(index), e));
     let statements = std::iter::once(directive_statement)
         .chain(std::iter::once(one_binding_statement))
         .chain(input_binding_statements)
@@ -450,12 +984,28 @@ pub fn sha256_round<T: Field>(
 #[cfg(feature = "ark")]
 pub fn snark_verify_bls12_377<T: Field>(
     n: usize,
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    snark_verify::<T, Gm17>(n, Solver::SnarkVerifyBls12377(n))
+}
+
+#[cfg(feature = "ark")]
+pub fn snark_verify_groth16_bls12_377<T: Field>(
+    n: usize,
+) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
+    snark_verify::<T, Groth16>(n, Solver::SnarkVerifyGroth16Bls12377(n))
+}
+
+/// Verify a proof of the proving scheme `S` over BLS12-377, using `solver` to set the witness
+#[cfg(feature = "ark")]
+fn snark_verify<T: Field, S: RecursiveSnark>(
+    n: usize,
+    solver: Solver,
 ) -> FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>> {
     use zokrates_field::Bw6_761Field;
     assert_eq!(T::id(), Bw6_761Field::id());
 
     let (out_index, input_indices, proof_indices, vk_indices, constraints, variable_count) =
-        generate_verify_constraints(n);
+        generate_verify_constraints::<S>(n);
 
     let cs_indices = 0..variable_count;
     let input_indices = input_indices.into_iter();
@@ -539,7 +1089,7 @@ pub fn snark_verify_bls12_377<T: Field>(
             .chain(vk_argument_indices)
             .map(|i| FlatVariable::new(i).into())
             .collect(),
-        solver: Solver::SnarkVerifyBls12377(n),
+        solver,
     });
 
     let statements = std::iter::once(directive_statement)
@@ -699,7 +1249,6 @@ mod tests {
         }
     }
 
-    #[cfg(feature = "bellman")]
     #[cfg(test)]
     mod sha256 {
         use super::*;
@@ -732,26 +1281,26 @@ mod tests {
             // function input should be offset by variable_count
             assert_eq!(
                 compiled.arguments[0].id,
-                FlatVariable::new(directive.outputs.len() + 1)
+                FlatVariable::new(directive.outputs.len())
             );
 
-            // bellman variable #0: index 0 should equal 1
+            // embed variable #0: index 0 should equal 1
             assert_eq!(
                 compiled.statements[1],
                 FlatStatement::Condition(
                     FlatVariable::new(0).into(),
                     FlatExpression::Number(Bn128Field::from(1)),
-                    RuntimeError::BellmanOneBinding
+                    RuntimeError::EmbedOneBinding
                 )
             );
 
-            // bellman input #0: index 1 should equal zokrates input #0: index v_count
+            // embed input #0: index 1 should equal zokrates input #0: index v_count
             assert_eq!(
                 compiled.statements[2],
                 FlatStatement::Condition(
                     FlatVariable::new(1).into(),
-                    FlatVariable::new(26936).into(),
-                    RuntimeError::BellmanInputBinding
+                    FlatVariable::new(26935).into(),
+                    RuntimeError::EmbedInputBinding
                 )
             );
 
@@ -767,4 +1316,209 @@ mod tests {
             interpreter.execute(ir, &input).unwrap();
         }
     }
+
+    #[cfg(test)]
+    mod poseidon {
+        use super::*;
+        use crate::ir::Interpreter;
+
+        #[test]
+        fn compute_permutation() {
+            let compiled = poseidon_permutation::<Bn128Field>(2).collect();
+
+            assert_eq!(compiled.arguments.len(), 2);
+            assert_eq!(compiled.return_count, 2);
+
+            let ir = crate::ir::from_flat::from_flat(compiled);
+
+            let interpreter = Interpreter::default();
+            let witness = interpreter
+                .execute(ir, &[Bn128Field::from(0), Bn128Field::from(1)])
+                .unwrap();
+
+            // the first element of the permuted state is `hashes/poseidon/poseidon([1])`
+            assert_eq!(
+                witness.return_values()[0],
+                Bn128Field::try_from_dec_str(
+                    "18586133768512220936620570745912940619677854269274689475585506675881198879027"
+                )
+                .unwrap()
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod keccak_f1600 {
+        use super::*;
+
+        #[test]
+        fn generate_constraints() {
+            let compiled = keccak_f1600::<Bn128Field>().collect();
+
+            // the function takes and returns the bits of the state
+            assert_eq!(compiled.arguments.len(), 1600);
+            assert_eq!(compiled.return_count, 1600);
+
+            // the first argument is bound to the first input of the constraint system
+            assert_eq!(
+                compiled.statements[2],
+                FlatStatement::Condition(
+                    FlatVariable::new(1).into(),
+                    FlatVariable::new(zokrates_embed::keccak::variable_count()).into(),
+                    RuntimeError::EmbedInputBinding
+                )
+            );
+        }
+    }
+
+    #[cfg(test)]
+    mod blake2s_round {
+        use super::*;
+        use crate::ir::Interpreter;
+
+        #[test]
+        fn generate_constraints() {
+            let compiled = blake2s_round::<Bn128Field>().collect();
+
+            // the function takes the bits of `h`, `m`, `t` and `last` and returns the bits of `h`
+            assert_eq!(compiled.arguments.len(), 833);
+            assert_eq!(compiled.return_count, 256);
+
+            let input: Vec<_> = (0..833).map(|i| Bn128Field::from(i % 3 == 0)).collect();
+
+            let ir = crate::ir::from_flat::from_flat(compiled);
+
+            let interpreter = Interpreter::default();
+            interpreter.execute(ir, &input).unwrap();
+        }
+    }
+
+    mod ecdsa_secp256k1_verify {
+        use super::*;
+
+        #[test]
+        fn generate_constraints() {
+            let compiled = ecdsa_secp256k1_verify::<Bn128Field>().collect();
+
+            // the function takes the words of `z`, `r`, `s`, `qx` and `qy` and returns a boolean
+            assert_eq!(compiled.arguments.len(), 20);
+            assert_eq!(compiled.return_count, 1);
+        }
+    }
+
+    #[cfg(feature = "ark")]
+    mod snark_verify_groth16_bls12_377 {
+        use super::*;
+        use zokrates_field::Bw6_761Field;
+
+        #[test]
+        fn generate_constraints() {
+            let compiled = snark_verify_groth16_bls12_377::<Bw6_761Field>(2).collect();
+
+            // the function takes 2 inputs, a proof of size 8 and a verification key of size 16 + 2 * 2
+            assert_eq!(compiled.arguments.len(), 30);
+            assert_eq!(compiled.return_count, 1);
+        }
+    }
+
+    mod bigint_mul_mod {
+        use super::*;
+        use crate::ir::Interpreter;
+
+        #[test]
+        fn compute_product() {
+            // integers of 2 words modulo 2^64 + 13
+            let compiled = bigint_mul_mod::<Bn128Field>(vec![1, 13]).collect();
+
+            assert_eq!(compiled.arguments.len(), 4);
+            assert_eq!(compiled.return_count, 2);
+
+            let ir = crate::ir::from_flat::from_flat(compiled);
+
+            // (2^64 + 12) * 2 == 2^64 + 11 mod 2^64 + 13
+            let input: Vec<_> = vec![1, 12, 0, 2]
+                .into_iter()
+                .map(Bn128Field::from)
+                .collect();
+
+            let interpreter = Interpreter::default();
+            let witness = interpreter.execute(ir, &input).unwrap();
+
+            assert_eq!(
+                witness.return_values(),
+                vec![Bn128Field::from(1), Bn128Field::from(11)]
+            );
+        }
+    }
+    mod edwards {
+        use super::*;
+        use crate::ir::Interpreter;
+
+        // the generator of BabyJubJub and its double
+        const G: (&str, &str) = (
+            "16540640123574156134436876038791482806971768689494387082833631921987005038935",
+            "20819045374670962167435360035096875258406992893633759881276124905556507972311",
+        );
+        const G2: (&str, &str) = (
+            "17324563846726889236817837922625232543153115346355010501047597319863650987830",
+            "20022170825455209233733649024450576091402881793145646502279487074566492066831",
+        );
+
+        fn point((u, v): (&str, &str)) -> Vec<Bn128Field> {
+            vec![
+                Bn128Field::try_from_dec_str(u).unwrap(),
+                Bn128Field::try_from_dec_str(v).unwrap(),
+            ]
+        }
+
+        /// The bits of `2` in big endian order
+        fn two() -> Vec<Bn128Field> {
+            (0..256)
+                .map(|i| Bn128Field::from((i == 254) as u32))
+                .collect()
+        }
+
+        fn execute<I: IntoIterator<Item = FlatStatement<Bn128Field>>>(
+            compiled: FlatFunctionIterator<Bn128Field, I>,
+            input: Vec<Bn128Field>,
+        ) -> Vec<Bn128Field> {
+            let ir = crate::ir::from_flat::from_flat(compiled.collect());
+            let interpreter = Interpreter::default();
+            interpreter.execute(ir, &input).unwrap().return_values()
+        }
+
+        #[test]
+        fn add() {
+            let compiled = edwards_add::<Bn128Field>();
+
+            assert_eq!(compiled.arguments.len(), 4);
+            assert_eq!(compiled.return_count, 2);
+
+            let input = vec![point(G), point(G)].concat();
+
+            assert_eq!(execute(compiled, input), point(G2));
+        }
+
+        #[test]
+        fn scalar_mult() {
+            let compiled = edwards_scalar_mult::<Bn128Field>();
+
+            assert_eq!(compiled.arguments.len(), 258);
+            assert_eq!(compiled.return_count, 2);
+
+            let input = vec![two(), point(G)].concat();
+
+            assert_eq!(execute(compiled, input), point(G2));
+        }
+
+        #[test]
+        fn fixed_base_scalar_mult() {
+            let compiled = edwards_fixed_base_scalar_mult::<Bn128Field>();
+
+            assert_eq!(compiled.arguments.len(), 256);
+            assert_eq!(compiled.return_count, 2);
+
+            assert_eq!(execute(compiled, two()), point(G2));
+        }
+    }
 }
@@ -33,6 +33,11 @@ impl FlatVariable {
         (self.id as usize) - 1
     }
 
+    pub fn output_id(&self) -> usize {
+        assert!(self.id < 0);
+        (-(self.id + 1)) as usize
+    }
+
     pub fn try_from_human_readable(s: &str) -> Result<Self, &str> {
         if s == "~one" {
             return Ok(FlatVariable::one());
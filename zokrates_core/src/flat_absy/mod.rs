@@ -26,6 +26,9 @@ pub enum RuntimeError {
     ArkConstraint,
     ArkOneBinding,
     ArkInputBinding,
+    EmbedConstraint,
+    EmbedOneBinding,
+    EmbedInputBinding,
     Bitness,
     Sum,
     Equal,
@@ -47,6 +50,7 @@ pub enum RuntimeError {
     SourceAssertion(String),
     ArgumentBitness,
     SelectRangeCheck,
+    RangeCheck,
 }
 
 impl RuntimeError {
@@ -55,7 +59,7 @@ impl RuntimeError {
 
         !matches!(
             self,
-            SourceAssertion(_) | Inverse | LtSum | SelectRangeCheck | ArgumentBitness
+            SourceAssertion(_) | Inverse | LtSum | SelectRangeCheck | ArgumentBitness | RangeCheck
         )
     }
 }
@@ -71,6 +75,9 @@ impl fmt::Display for RuntimeError {
             ArkConstraint => "Ark constraint is unsatisfied",
             ArkOneBinding => "Ark ~one binding is unsatisfied",
             ArkInputBinding => "Ark input binding is unsatisfied",
+            EmbedConstraint => "Embed constraint is unsatisfied",
+            EmbedOneBinding => "Embed ~one binding is unsatisfied",
+            EmbedInputBinding => "Embed input binding is unsatisfied",
             Bitness => "Bitness check failed",
             Sum => "Sum check failed",
             Equal => "Equal check failed",
@@ -92,6 +99,7 @@ impl fmt::Display for RuntimeError {
             SourceAssertion(m) => m.as_str(),
             ArgumentBitness => "Argument bitness check failed",
             SelectRangeCheck => "Out of bounds array access",
+            RangeCheck => "Range check failed",
         };
 
         write!(f, "{}", msg)
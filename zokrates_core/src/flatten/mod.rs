@@ -7,7 +7,7 @@
 
 mod utils;
 
-use self::utils::flat_expression_from_bits;
+use self::utils::{flat_expression_from_bits, scale};
 use crate::ir::Interpreter;
 
 use crate::compile::CompileConfig;
@@ -89,6 +89,10 @@ pub struct Flattener<'ast, T> {
     layout: HashMap<Identifier<'ast>, FlatVariable>,
     /// Cached bit decompositions to avoid re-generating them
     bits_cache: HashMap<FlatExpression<T>, Vec<FlatExpression<T>>>,
+    /// Cached range checks, mapping expressions to a big endian decomposition which they are known to be equal to
+    range_cache: HashMap<FlatExpression<T>, Vec<FlatExpression<T>>>,
+    /// Cached selectors of the entries of constant tables, indexed by the lookup index
+    lookup_cache: HashMap<FlatExpression<T>, Vec<FlatExpression<T>>>,
 }
 
 trait FlattenOutput<T: Field>: Sized {
@@ -217,6 +221,8 @@ impl<'ast, T: Field> Flattener<'ast, T> {
             next_var_idx: 0,
             layout: HashMap::new(),
             bits_cache: HashMap::new(),
+            range_cache: HashMap::new(),
+            lookup_cache: HashMap::new(),
         }
     }
 
@@ -510,11 +516,13 @@ impl<'ast, T: Field> Flattener<'ast, T> {
         let (consequence, alternative) = if self.config.isolate_branches {
             let mut consequence_statements = VecDeque::new();
 
-            let consequence = consequence.flatten(self, &mut consequence_statements);
+            let consequence =
+                self.flatten_isolated(|f| consequence.flatten(f, &mut consequence_statements));
 
             let mut alternative_statements = VecDeque::new();
 
-            let alternative = alternative.flatten(self, &mut alternative_statements);
+            let alternative =
+                self.flatten_isolated(|f| alternative.flatten(f, &mut alternative_statements));
 
             let consequence_statements =
                 self.make_conditional(consequence_statements, condition_id.into());
@@ -582,6 +590,22 @@ impl<'ast, T: Field> Flattener<'ast, T> {
         }
     }
 
+    /// Flattens a branch whose constraints only hold when it is taken. The decompositions and
+    /// selectors it caches are dropped, as reusing them outside of the branch would be unsound
+    fn flatten_isolated<R>(&mut self, f: impl FnOnce(&mut Self) -> R) -> R {
+        let bits_cache = self.bits_cache.clone();
+        let range_cache = self.range_cache.clone();
+        let lookup_cache = self.lookup_cache.clone();
+
+        let res = f(self);
+
+        self.bits_cache = bits_cache;
+        self.range_cache = range_cache;
+        self.lookup_cache = lookup_cache;
+
+        res
+    }
+
     /// Compute a strict check against a constant
     /// # Arguments
     /// * `statements_flattened` - Vector where new flattened statements can be added.
@@ -1098,17 +1122,88 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                     ),
                 )]
             }
+            FlatEmbed::RangeCheck => {
+                let e = params.pop().unwrap().get_field_unchecked();
+
+                self.range_check(
+                    statements_flattened,
+                    e.clone(),
+                    generics[0] as usize,
+                    RuntimeError::RangeCheck,
+                );
+
+                vec![FlatUExpression::with_field(e)]
+            }
+            FlatEmbed::Lookup | FlatEmbed::LookupU8 => {
+                let index = params.pop().unwrap();
+
+                // `u8` indices were reduced, so the lookup can reuse their decomposition
+                if let Some(bits) = index.bits.clone() {
+                    self.range_cache.insert(index.get_field_unchecked(), bits);
+                }
+
+                let index = index.get_field_unchecked();
+
+                let table: Vec<_> = params
+                    .into_iter()
+                    .map(|e| match e.get_field_unchecked() {
+                        FlatExpression::Number(n) => n,
+                        _ => unreachable!(
+                            "the lookup table is checked to be constant during static analysis"
+                        ),
+                    })
+                    .collect();
+
+                vec![FlatUExpression::with_field(self.lookup(
+                    statements_flattened,
+                    table,
+                    index,
+                ))]
+            }
+            FlatEmbed::KeccakF1600 => self.flatten_embed_call_bits(
+                statements_flattened,
+                params,
+                crate::embed::keccak_f1600(),
+                64.into(),
+            ),
+            FlatEmbed::Blake2sRound => self.flatten_embed_call_bits(
+                statements_flattened,
+                params,
+                crate::embed::blake2s_round(),
+                32.into(),
+            ),
+            FlatEmbed::BigIntMulMod => {
+                // the modulus is given as constant arguments after both operands
+                let limb_count = generics[0] as usize;
+                let modulus = Self::constant_words(params.split_off(2 * limb_count));
+
+                self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::bigint_mul_mod::<T>(modulus),
+                )
+            }
+            FlatEmbed::BigIntReduce => {
+                // the modulus is given as constant arguments after the integer to reduce
+                let input_limb_count = generics[1] as usize;
+                let modulus = Self::constant_words(params.split_off(input_limb_count));
+
+                self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::bigint_reduce::<T>(input_limb_count, modulus),
+                )
+            }
             funct => match funct {
                 FlatEmbed::Unpack => self.flatten_embed_call_aux(
                     statements_flattened,
                     params,
                     crate::embed::unpack_to_bitwidth(generics[0] as usize),
                 ),
-                #[cfg(feature = "bellman")]
                 FlatEmbed::Sha256Round => self.flatten_embed_call_aux(
                     statements_flattened,
                     params,
-                    crate::embed::sha256_round(),
+                    crate::embed::sha256_round::<T>(),
                 ),
                 #[cfg(feature = "ark")]
                 FlatEmbed::SnarkVerifyBls12377 => self.flatten_embed_call_aux(
@@ -1116,11 +1211,220 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                     params,
                     crate::embed::snark_verify_bls12_377::<T>(generics[0] as usize),
                 ),
+                #[cfg(feature = "ark")]
+                FlatEmbed::SnarkVerifyGroth16Bls12377 => self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::snark_verify_groth16_bls12_377::<T>(generics[0] as usize),
+                ),
+                FlatEmbed::PoseidonPermutation => self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::poseidon_permutation::<T>(generics[0] as usize),
+                ),
+                FlatEmbed::EcdsaSecp256k1Verify => self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::ecdsa_secp256k1_verify::<T>(),
+                ),
+                FlatEmbed::EdwardsAdd => self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::edwards_add::<T>(),
+                ),
+                FlatEmbed::EdwardsScalarMult => self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::edwards_scalar_mult::<T>(),
+                ),
+                FlatEmbed::EdwardsFixedBaseScalarMult => self.flatten_embed_call_aux(
+                    statements_flattened,
+                    params,
+                    crate::embed::edwards_fixed_base_scalar_mult::<T>(),
+                ),
                 _ => unreachable!(),
             },
         }
     }
 
+    /// Decompose `e` into `bitwidth` bits in big endian order, enforcing that `e < 2^bitwidth`.
+    /// Decompositions are cached, so that checking an expression against a range it was already
+    /// checked against does not introduce any constraint
+    fn range_check(
+        &mut self,
+        statements_flattened: &mut FlatStatements<T>,
+        e: FlatExpression<T>,
+        bitwidth: usize,
+        error: RuntimeError,
+    ) -> Vec<FlatExpression<T>> {
+        assert!(bitwidth < T::get_required_bits());
+
+        // a smaller decomposition can be padded with zeroes
+        if let Some(bits) = self
+            .range_cache
+            .get(&e)
+            .filter(|bits| bits.len() <= bitwidth)
+        {
+            return (bits.len()..bitwidth)
+                .map(|_| FlatExpression::Number(T::zero()))
+                .chain(bits.iter().cloned())
+                .collect();
+        }
+
+        // constants in range do not require directives
+        if let FlatExpression::Number(ref x) = e {
+            if x.bits() as usize <= bitwidth {
+                return Interpreter::execute_solver(&Solver::bits(bitwidth), &[x.clone()])
+                    .unwrap()
+                    .into_iter()
+                    .map(FlatExpression::Number)
+                    .collect();
+            }
+        }
+
+        let bits = (0..bitwidth).map(|_| self.use_sym()).collect::<Vec<_>>();
+        statements_flattened.push_back(FlatStatement::Directive(FlatDirective::new(
+            bits.clone(),
+            Solver::Bits(bitwidth),
+            vec![e.clone()],
+        )));
+
+        let bits: Vec<_> = bits.into_iter().map(FlatExpression::Identifier).collect();
+
+        // bit checks
+        statements_flattened.extend(bits.iter().map(|bit| {
+            FlatStatement::Condition(
+                bit.clone(),
+                FlatExpression::Mult(box bit.clone(), box bit.clone()),
+                RuntimeError::Bitness,
+            )
+        }));
+
+        // sum check
+        statements_flattened.push_back(FlatStatement::Condition(
+            e.clone(),
+            flat_expression_from_bits(bits.clone()),
+            error,
+        ));
+
+        self.range_cache.insert(e, bits.clone());
+
+        bits
+    }
+
+    /// Look up the entry of a constant `table` at `index`. The selectors of the entries are
+    /// cached, so that further lookups at the same index in tables of the same size are linear
+    fn lookup(
+        &mut self,
+        statements_flattened: &mut FlatStatements<T>,
+        table: Vec<T>,
+        index: FlatExpression<T>,
+    ) -> FlatExpression<T> {
+        assert!(
+            table.len().is_power_of_two(),
+            "the size of the lookup table is checked to be a power of two during static analysis"
+        );
+
+        // constant indices select the entry directly
+        if let FlatExpression::Number(ref i) = index {
+            if let Some(entry) = i
+                .to_dec_string()
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| table.get(i))
+            {
+                return FlatExpression::Number(entry.clone());
+            }
+        }
+
+        let cached = self
+            .lookup_cache
+            .get(&index)
+            .filter(|selectors| selectors.len() == table.len())
+            .cloned();
+
+        let selectors = match cached {
+            Some(selectors) => selectors,
+            None => {
+                let bitwidth = table.len().trailing_zeros() as usize;
+
+                let bits = self.range_check(
+                    statements_flattened,
+                    index.clone(),
+                    bitwidth,
+                    RuntimeError::SelectRangeCheck,
+                );
+
+                // split each selector on the next bit, starting from the most significant one
+                let mut selectors = vec![FlatExpression::Number(T::one())];
+                for bit in bits {
+                    let mut next = Vec::with_capacity(selectors.len() * 2);
+                    for selector in selectors {
+                        let high = match selector {
+                            FlatExpression::Number(ref n) if *n == T::one() => bit.clone(),
+                            ref selector => self
+                                .define(
+                                    FlatExpression::Mult(box selector.clone(), box bit.clone()),
+                                    statements_flattened,
+                                )
+                                .into(),
+                        };
+                        next.push(FlatExpression::Sub(box selector, box high.clone()));
+                        next.push(high);
+                    }
+                    selectors = next;
+                }
+
+                self.lookup_cache.insert(index, selectors.clone());
+                selectors
+            }
+        };
+
+        table
+            .into_iter()
+            .zip(selectors)
+            .filter(|(entry, _)| !entry.is_zero())
+            .fold(
+                FlatExpression::Number(T::zero()),
+                |acc, (entry, selector)| FlatExpression::Add(box acc, box scale(selector, entry)),
+            )
+    }
+
+    /// Returns the values of constant `u64` arguments
+    fn constant_words(params: Vec<FlatUExpression<T>>) -> Vec<u64> {
+        params
+            .into_iter()
+            .map(|e| match e.get_field_unchecked() {
+                FlatExpression::Number(n) => n.to_dec_string().parse().unwrap(),
+                _ => unreachable!("the modulus is checked to be constant during static analysis"),
+            })
+            .collect()
+    }
+
+    /// Flattens a call to an embed operating on the bits of its unsigned integer arguments and
+    /// returning the bits of unsigned integers of `bitwidth`
+    fn flatten_embed_call_bits(
+        &mut self,
+        statements_flattened: &mut FlatStatements<T>,
+        params: Vec<FlatUExpression<T>>,
+        funct: FlatFunctionIterator<T, impl IntoIterator<Item = FlatStatement<T>>>,
+        bitwidth: UBitwidth,
+    ) -> Vec<FlatUExpression<T>> {
+        let bits = params
+            .into_iter()
+            .flat_map(|p| match p.bits {
+                Some(bits) => bits,
+                None => vec![p.get_field_unchecked()],
+            })
+            .map(FlatUExpression::with_field)
+            .collect();
+
+        self.flatten_embed_call_aux(statements_flattened, bits, funct)
+            .chunks(bitwidth.to_usize())
+            .map(|bits| self.bits_to_u(bits.to_vec(), bitwidth))
+            .collect()
+    }
+
     fn flatten_embed_call_aux(
         &mut self,
         statements_flattened: &mut FlatStatements<T>,
@@ -1886,34 +2190,15 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                     res
                 }
                 Entry::Vacant(_) => {
-                    let bits = (0..from).map(|_| self.use_sym()).collect::<Vec<_>>();
-                    statements_flattened.push_back(FlatStatement::Directive(FlatDirective::new(
-                        bits.clone(),
-                        Solver::Bits(from),
-                        vec![e.field.clone().unwrap()],
-                    )));
-
-                    let bits: Vec<_> = bits.into_iter().map(FlatExpression::Identifier).collect();
-
-                    // decompose to the actual bitwidth
-
-                    // bit checks
-                    statements_flattened.extend(bits.iter().take(from).map(|bit| {
-                        FlatStatement::Condition(
-                            bit.clone(),
-                            FlatExpression::Mult(box bit.clone(), box bit.clone()),
-                            RuntimeError::Bitness,
-                        )
-                    }));
-
-                    let sum = flat_expression_from_bits(bits.clone());
-
-                    // sum check
-                    statements_flattened.push_back(FlatStatement::Condition(
+                    // decompose to the actual bitwidth, reusing a previous range check if possible
+                    let bits = self.range_check(
+                        statements_flattened,
                         e.field.clone().unwrap(),
-                        sum.clone(),
+                        from,
                         RuntimeError::Sum,
-                    ));
+                    );
+
+                    let sum = flat_expression_from_bits(bits.clone());
 
                     // truncate to the `to` lowest bits
                     let bits = bits[from - to..].to_vec();
@@ -2239,12 +2524,16 @@ impl<'ast, T: Field> Flattener<'ast, T> {
                     let mut consequence_statements = VecDeque::new();
                     let mut alternative_statements = VecDeque::new();
 
-                    consequence
-                        .into_iter()
-                        .for_each(|s| self.flatten_statement(&mut consequence_statements, s));
-                    alternative
-                        .into_iter()
-                        .for_each(|s| self.flatten_statement(&mut alternative_statements, s));
+                    self.flatten_isolated(|f| {
+                        consequence
+                            .into_iter()
+                            .for_each(|s| f.flatten_statement(&mut consequence_statements, s))
+                    });
+                    self.flatten_isolated(|f| {
+                        alternative
+                            .into_iter()
+                            .for_each(|s| f.flatten_statement(&mut alternative_statements, s))
+                    });
 
                     let consequence_statements =
                         self.make_conditional(consequence_statements, condition_id.into());
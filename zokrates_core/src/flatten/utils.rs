@@ -29,3 +29,25 @@ pub fn flat_expression_from_bits<T: Field>(v: Vec<FlatExpression<T>>) -> FlatExp
             .collect::<Vec<_>>(),
     )
 }
+
+/// Multiply a linear expression by a constant, keeping the result linear
+pub fn scale<T: Field>(e: FlatExpression<T>, c: T) -> FlatExpression<T> {
+    match e {
+        FlatExpression::Number(n) => FlatExpression::Number(n * c),
+        FlatExpression::Identifier(id) => FlatExpression::Mult(
+            box FlatExpression::Number(c),
+            box FlatExpression::Identifier(id),
+        ),
+        FlatExpression::Add(box left, box right) => {
+            FlatExpression::Add(box scale(left, c.clone()), box scale(right, c))
+        }
+        FlatExpression::Sub(box left, box right) => {
+            FlatExpression::Sub(box scale(left, c.clone()), box scale(right, c))
+        }
+        FlatExpression::Mult(box FlatExpression::Number(n), box e)
+        | FlatExpression::Mult(box e, box FlatExpression::Number(n)) => {
+            FlatExpression::Mult(box FlatExpression::Number(n * c), box e)
+        }
+        e => unreachable!("expected a linear expression, found {}", e),
+    }
+}
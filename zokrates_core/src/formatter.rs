@@ -0,0 +1,1079 @@
+//! Module containing the source code formatter
+//!
+//! The formatter prints the AST of a module back in a canonical layout. Comments are not part of
+//! the AST, so they are collected from the source separately and emitted next to the closest
+//! item: before it when they are on their own line, after it when they follow it on the same line.
+//! Comments inside an expression are kept in place when the expression is an inline array or
+//! struct, which is then split over several lines, and moved after the statement otherwise.
+
+use zokrates_pest_ast as pest;
+
+const INDENT: &str = "    ";
+const WIDTH: usize = 100;
+
+/// Format `source` to the canonical layout
+pub fn format(source: &str) -> Result<String, pest::Error> {
+    let file = pest::generate_ast(source)?;
+
+    let mut formatter = Formatter::new(source);
+    formatter.file(&file);
+
+    let mut out = formatter.out;
+    let len = out.trim_end().len();
+    out.truncate(len);
+    if !out.is_empty() {
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Comment {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+/// Collect the comments in `source`, skipping over string literals
+fn comments(source: &str) -> Vec<Comment> {
+    let bytes = source.as_bytes();
+    let mut comments = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let end = match (bytes[i], bytes.get(i + 1)) {
+            (b'"', _) => {
+                i = source[i + 1..]
+                    .find('"')
+                    .map(|j| i + 1 + j + 1)
+                    .unwrap_or_else(|| bytes.len());
+                continue;
+            }
+            (b'/', Some(b'/')) => source[i..]
+                .find('\n')
+                .map(|j| i + j)
+                .unwrap_or_else(|| bytes.len()),
+            (b'/', Some(b'*')) => source[i + 2..]
+                .find("*/")
+                .map(|j| i + 2 + j + 2)
+                .unwrap_or_else(|| bytes.len()),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+
+        comments.push(Comment {
+            start: i,
+            end,
+            text: source[i..end].trim_end().to_string(),
+        });
+        i = end;
+    }
+
+    comments
+}
+
+fn indentation(indent: usize) -> String {
+    INDENT.repeat(indent)
+}
+
+fn precedence(e: &pest::Expression) -> u8 {
+    use pest::BinaryOperator::*;
+
+    match e {
+        pest::Expression::Ternary(_) => 1,
+        pest::Expression::Binary(b) => match b.op {
+            Or => 2,
+            And => 3,
+            Eq | NotEq | Lt | Gt | Lte | Gte => 4,
+            BitOr => 5,
+            BitXor => 6,
+            BitAnd => 7,
+            LeftShift | RightShift => 8,
+            Add | Sub => 9,
+            Mul | Div | Rem => 10,
+            Pow => 12,
+        },
+        pest::Expression::Unary(_) => 11,
+        _ => 13,
+    }
+}
+
+fn operator(op: &pest::BinaryOperator) -> &'static str {
+    use pest::BinaryOperator::*;
+
+    match op {
+        BitXor => "^",
+        BitAnd => "&",
+        BitOr => "|",
+        RightShift => ">>",
+        LeftShift => "<<",
+        Or => "||",
+        And => "&&",
+        Add => "+",
+        Sub => "-",
+        Mul => "*",
+        Div => "/",
+        Rem => "%",
+        Eq => "==",
+        NotEq => "!=",
+        Lt => "<",
+        Gt => ">",
+        Lte => "<=",
+        Gte => ">=",
+        Pow => "**",
+    }
+}
+
+fn basic_type(ty: &pest::BasicType) -> &'static str {
+    match ty {
+        pest::BasicType::Field(_) => "field",
+        pest::BasicType::Boolean(_) => "bool",
+        pest::BasicType::U8(_) => "u8",
+        pest::BasicType::U16(_) => "u16",
+        pest::BasicType::U32(_) => "u32",
+        pest::BasicType::U64(_) => "u64",
+    }
+}
+
+fn type_end(ty: &pest::Type) -> usize {
+    match ty {
+        pest::Type::Basic(b) => match b {
+            pest::BasicType::Field(t) => t.span.end(),
+            pest::BasicType::Boolean(t) => t.span.end(),
+            pest::BasicType::U8(t) => t.span.end(),
+            pest::BasicType::U16(t) => t.span.end(),
+            pest::BasicType::U32(t) => t.span.end(),
+            pest::BasicType::U64(t) => t.span.end(),
+        },
+        pest::Type::Array(t) => t.span.end(),
+        pest::Type::Struct(t) => t.span.end(),
+    }
+}
+
+fn statement_span(s: &pest::Statement) -> (usize, usize) {
+    let span = match s {
+        pest::Statement::Return(s) => &s.span,
+        pest::Statement::Definition(s) => &s.span,
+        pest::Statement::Assertion(s) => &s.span,
+        pest::Statement::Iteration(s) => &s.span,
+    };
+    (span.start(), span.end())
+}
+
+fn is_import(d: &pest::SymbolDeclaration) -> bool {
+    matches!(d, pest::SymbolDeclaration::Import(_))
+}
+
+struct Formatter<'a> {
+    source: &'a str,
+    comments: Vec<Comment>,
+    /// The index of the first comment which was not emitted yet
+    next: usize,
+    /// The end of the last code or comment emitted
+    last: usize,
+    /// Whether nothing was emitted yet in the current block
+    fresh: bool,
+    out: String,
+}
+
+impl<'a> Formatter<'a> {
+    fn new(source: &'a str) -> Self {
+        Formatter {
+            source,
+            comments: comments(source),
+            next: 0,
+            last: 0,
+            fresh: true,
+            out: String::new(),
+        }
+    }
+
+    /// Whether the source contains an empty line between `from` and `to`
+    fn blank(&self, from: usize, to: usize) -> bool {
+        if from >= to {
+            return false;
+        }
+
+        let lines: Vec<_> = self.source[from..to].split('\n').collect();
+        lines.len() > 2
+            && lines[1..lines.len() - 1]
+                .iter()
+                .any(|l| l.trim().is_empty())
+    }
+
+    /// Whether only whitespace precedes `pos` on its line
+    fn indented(&self, pos: usize) -> bool {
+        let line = &self.source[..pos];
+        let line = &line[line.rfind('\n').map(|i| i + 1).unwrap_or(0)..];
+        !line.is_empty() && line.chars().all(|c| c == ' ' || c == '\t')
+    }
+
+    /// The offset of the first occurrence of `token` after `from` which is not inside a comment
+    fn find(&self, from: usize, token: &str) -> usize {
+        let mut from = from;
+
+        loop {
+            let i = from
+                + self.source[from..]
+                    .find(token)
+                    .expect("the token should be present in the parsed source");
+
+            match self.comments.iter().find(|c| c.start <= i && i < c.end) {
+                Some(c) => from = c.end,
+                None => return i,
+            }
+        }
+    }
+
+    fn has_comments(&self, from: usize, to: usize) -> bool {
+        self.comments[self.next..]
+            .iter()
+            .take_while(|c| c.start < to)
+            .any(|c| c.start >= from)
+    }
+
+    /// Emit the comments starting before `pos` on their own lines
+    fn closing(&mut self, pos: usize, indent: usize) -> String {
+        let mut res = String::new();
+
+        while self.next < self.comments.len() && self.comments[self.next].start < pos {
+            let comment = self.comments[self.next].clone();
+            if !self.fresh && self.blank(self.last, comment.start) {
+                res.push('\n');
+            }
+            res.push_str(&indentation(indent));
+            res.push_str(&comment.text);
+            res.push('\n');
+            self.next += 1;
+            self.last = comment.end;
+            self.fresh = false;
+        }
+
+        res
+    }
+
+    /// Emit the comments starting before `pos` on their own lines, followed by an empty line if
+    /// the source has one before `pos`
+    fn leading(&mut self, pos: usize, indent: usize) -> String {
+        let mut res = self.closing(pos, indent);
+
+        if !self.fresh && self.blank(self.last, pos) {
+            res.push('\n');
+        }
+        self.fresh = false;
+
+        res
+    }
+
+    /// Take the next comment if it is on the same line as the last code emitted
+    fn take_trailing(&mut self) -> Option<String> {
+        let comment = self.comments.get(self.next)?;
+
+        if comment.start < self.last
+            || !self.source[self.last..comment.start]
+                .chars()
+                .all(|c| matches!(c, ' ' | '\t' | ',' | '\\'))
+        {
+            return None;
+        }
+
+        let text = comment.text.clone();
+        self.last = comment.end;
+        self.next += 1;
+        Some(text)
+    }
+
+    fn trailing(&mut self) -> String {
+        let mut res = String::new();
+
+        while let Some(text) = self.take_trailing() {
+            res.push(' ');
+            res.push_str(&text);
+        }
+
+        res
+    }
+
+    /// End the current line of an item ending at `end`. The comments inside the item are emitted
+    /// on their own lines after it, together with the comments following it on the same line.
+    fn end_line(&mut self, end: usize, indent: usize) {
+        let mut inner = vec![];
+        while self.next < self.comments.len() && self.comments[self.next].start < end {
+            inner.push(self.comments[self.next].text.clone());
+            self.next += 1;
+        }
+
+        self.last = end;
+
+        if inner.is_empty() {
+            let trailing = self.trailing();
+            self.out.push_str(&trailing);
+            self.out.push('\n');
+        } else {
+            self.out.push('\n');
+            while let Some(text) = self.take_trailing() {
+                inner.push(text);
+            }
+            for text in inner {
+                self.out.push_str(&indentation(indent));
+                self.out.push_str(&text);
+                self.out.push('\n');
+            }
+        }
+
+        self.fresh = false;
+    }
+
+    /// Separate two module level declarations by an empty line. The comments directly following
+    /// the previous declaration stay attached to it, unless they are also directly followed by
+    /// the next one.
+    fn separate(&mut self, pos: usize) {
+        let mut attached = 0;
+        let mut previous = self.last;
+        let mut separated = false;
+
+        for c in self.comments[self.next..]
+            .iter()
+            .take_while(|c| c.start < pos)
+        {
+            if self.blank(previous, c.start) {
+                separated = true;
+                break;
+            }
+            attached += 1;
+            previous = c.end;
+        }
+
+        if separated || self.blank(previous, pos) {
+            for _ in 0..attached {
+                let comment = self.comments[self.next].clone();
+                self.out.push_str(&comment.text);
+                self.out.push('\n');
+                self.next += 1;
+                self.last = comment.end;
+            }
+        }
+
+        if !self.out.is_empty() {
+            self.out.push('\n');
+        }
+        self.fresh = true;
+    }
+
+    fn file(&mut self, file: &pest::File) {
+        if let Some(pragma) = &file.pragma {
+            let leading = self.leading(pragma.span.start(), 0);
+            self.out.push_str(&leading);
+            self.out
+                .push_str(&format!("#pragma curve {}", pragma.curve.name));
+            self.end_line(pragma.span.end(), 0);
+        }
+
+        for (i, declaration) in file.declarations.iter().enumerate() {
+            let separate = match i {
+                0 => file.pragma.is_some(),
+                _ => !(is_import(&file.declarations[i - 1]) && is_import(declaration)),
+            };
+
+            self.declaration(declaration, separate);
+        }
+
+        let rest = self.closing(self.source.len(), 0);
+        self.out.push_str(&rest);
+    }
+
+    fn declaration(&mut self, d: &pest::SymbolDeclaration, separate: bool) {
+        let start = match d {
+            pest::SymbolDeclaration::Import(pest::ImportDirective::Main(i)) => i.span.start(),
+            pest::SymbolDeclaration::Import(pest::ImportDirective::From(i)) => i.span.start(),
+            pest::SymbolDeclaration::Constant(c) => c.span.start(),
+            pest::SymbolDeclaration::Struct(s) => s.span.start(),
+            pest::SymbolDeclaration::Type(t) => t.span.start(),
+            pest::SymbolDeclaration::Function(f) => f.span.start(),
+        };
+
+        if separate {
+            self.separate(start);
+        }
+
+        let leading = self.leading(start, 0);
+        self.out.push_str(&leading);
+
+        match d {
+            pest::SymbolDeclaration::Import(pest::ImportDirective::Main(i)) => {
+                self.out.push_str(&format!("import \"{}\"", i.source.value));
+                let end = match &i.alias {
+                    Some(alias) => {
+                        self.out.push_str(&format!(" as {}", alias.value));
+                        alias.span.end()
+                    }
+                    None => i.source.span.end() + 1,
+                };
+                self.end_line(end, 0);
+            }
+            pest::SymbolDeclaration::Import(pest::ImportDirective::From(i)) => {
+                let symbols: Vec<_> = i
+                    .symbols
+                    .iter()
+                    .map(|s| match &s.alias {
+                        Some(alias) => format!("{} as {}", s.id.value, alias.value),
+                        None => s.id.value.clone(),
+                    })
+                    .collect();
+                self.out.push_str(&format!(
+                    "from \"{}\" import {}",
+                    i.source.value,
+                    symbols.join(", ")
+                ));
+                self.end_line(i.symbols.last().unwrap().span.end(), 0);
+            }
+            pest::SymbolDeclaration::Constant(c) => {
+                let ty = self.ty(&c.ty, 0);
+                let expression = self.expression(&c.expression, 0);
+                self.out
+                    .push_str(&format!("const {} {} = {}", ty, c.id.value, expression));
+                self.end_line(c.expression.span().end(), 0);
+            }
+            pest::SymbolDeclaration::Type(t) => {
+                let ty = self.ty(&t.ty, 0);
+                self.out.push_str(&format!(
+                    "type {}{} = {}",
+                    t.id.value,
+                    Self::generics(&t.generics),
+                    ty
+                ));
+                self.end_line(type_end(&t.ty), 0);
+            }
+            pest::SymbolDeclaration::Struct(s) => self.struct_definition(s),
+            pest::SymbolDeclaration::Function(f) => self.function_definition(f),
+        }
+    }
+
+    fn generics(generics: &[pest::IdentifierExpression]) -> String {
+        match generics.len() {
+            0 => String::new(),
+            _ => format!(
+                "<{}>",
+                generics
+                    .iter()
+                    .map(|g| g.value.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    fn struct_definition(&mut self, s: &pest::StructDefinition) {
+        let header = s
+            .generics
+            .last()
+            .map(|g| g.span.end())
+            .unwrap_or_else(|| s.id.span.end());
+        let open = self.find(header, "{") + 1;
+        let close = self.find(s.fields.last().map(|f| f.span.end()).unwrap_or(open), "}");
+
+        self.out.push_str(&format!(
+            "struct {}{} {{",
+            s.id.value,
+            Self::generics(&s.generics)
+        ));
+
+        if s.fields.is_empty() && !self.has_comments(open, close) {
+            self.out.push('}');
+            self.end_line(close + 1, 0);
+            return;
+        }
+
+        self.end_line(open, 1);
+        self.fresh = true;
+
+        for field in &s.fields {
+            let leading = self.leading(field.span.start(), 1);
+            self.out.push_str(&leading);
+            let ty = self.ty(&field.ty, 1);
+            self.out
+                .push_str(&format!("{}{} {}", INDENT, ty, field.id.value));
+            self.end_line(field.span.end(), 1);
+        }
+
+        let closing = self.closing(close, 1);
+        self.out.push_str(&closing);
+        self.out.push('}');
+        self.end_line(close + 1, 0);
+    }
+
+    fn function_definition(&mut self, f: &pest::FunctionDefinition) {
+        for (i, attribute) in f.attributes.iter().enumerate() {
+            // the comments before the first attribute are emitted with the declaration
+            if i > 0 {
+                let leading = self.leading(attribute.span.start(), 0);
+                self.out.push_str(&leading);
+            }
+            let end = match &attribute.argument {
+                Some(argument) => {
+                    self.out.push_str(&format!(
+                        "#[{}(\"{}\")]",
+                        attribute.id.value, argument.value
+                    ));
+                    self.find(argument.span.end() + 1, "]") + 1
+                }
+                None => {
+                    self.out.push_str(&format!("#[{}]", attribute.id.value));
+                    self.find(attribute.id.span.end(), "]") + 1
+                }
+            };
+            self.end_line(end, 0);
+        }
+
+        if !f.attributes.is_empty() {
+            let def = self.find(self.last, "def");
+            let leading = self.leading(def, 0);
+            self.out.push_str(&leading);
+        }
+
+        let signature = [
+            Some(f.id.span.end()),
+            f.generics.last().map(|g| g.span.end()),
+            f.parameters.last().map(|p| p.span.end()),
+            f.returns.last().map(type_end),
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .max()
+        .unwrap();
+        let colon = self.find(signature, ":") + 1;
+
+        let mut parameters = vec![];
+        for p in &f.parameters {
+            let visibility = match p.visibility {
+                Some(pest::Visibility::Public(_)) => "public ",
+                Some(pest::Visibility::Private(_)) => "private ",
+                None => "",
+            };
+            let ty = self.ty(&p.ty, 0);
+            parameters.push(format!("{}{} {}", visibility, ty, p.id.value));
+        }
+
+        let mut returns = vec![];
+        for ty in &f.returns {
+            returns.push(self.ty(ty, 0));
+        }
+        let returns = match returns.len() {
+            0 => String::new(),
+            1 => format!(" -> {}", returns[0]),
+            _ => format!(" -> ({})", returns.join(", ")),
+        };
+
+        self.out.push_str(&format!(
+            "def {}{}({}){}:",
+            f.id.value,
+            Self::generics(&f.generics),
+            parameters.join(", "),
+            returns
+        ));
+        self.end_line(colon, 1);
+
+        self.block(&f.statements, 1);
+
+        // indented comments directly following the body belong to it
+        while self.next < self.comments.len() {
+            let comment = self.comments[self.next].clone();
+            let gap = &self.source[self.last.min(comment.start)..comment.start];
+            if !self.indented(comment.start)
+                || !gap.trim().is_empty()
+                || self.blank(self.last, comment.start)
+            {
+                break;
+            }
+            self.out.push_str(INDENT);
+            self.out.push_str(&comment.text);
+            self.out.push('\n');
+            self.next += 1;
+            self.last = comment.end;
+        }
+    }
+
+    fn block(&mut self, statements: &[pest::Statement], indent: usize) {
+        self.fresh = true;
+
+        for s in statements {
+            self.statement(s, indent);
+        }
+    }
+
+    fn statement(&mut self, s: &pest::Statement, indent: usize) {
+        let (start, end) = statement_span(s);
+        let leading = self.leading(start, indent);
+        self.out.push_str(&leading);
+
+        let code = match s {
+            pest::Statement::Return(r) => {
+                let expressions = self.expressions(&r.expressions, indent);
+                match expressions.is_empty() {
+                    true => "return".to_string(),
+                    false => format!("return {}", expressions),
+                }
+            }
+            pest::Statement::Definition(d) => {
+                let mut lhs = vec![];
+                for a in &d.lhs {
+                    lhs.push(self.typed_identifier_or_assignee(a, indent));
+                }
+                let expression = self.expression(&d.expression, indent);
+                format!("{} = {}", lhs.join(", "), expression)
+            }
+            pest::Statement::Assertion(a) => {
+                let expression = self.expression(&a.expression, indent);
+                match &a.message {
+                    Some(message) => format!("assert({}, \"{}\")", expression, message.value),
+                    None => format!("assert({})", expression),
+                }
+            }
+            pest::Statement::Iteration(i) => return self.iteration(i, indent),
+        };
+
+        self.out.push_str(&indentation(indent));
+        self.out.push_str(&code);
+        self.end_line(end, indent);
+    }
+
+    fn iteration(&mut self, i: &pest::IterationStatement, indent: usize) {
+        let ty = self.ty(&i.ty, indent);
+        let from = self.expression(&i.from, indent);
+        let to = self.expression(&i.to, indent);
+        let header = self.find(i.to.span().end(), "do") + "do".len();
+
+        self.out.push_str(&format!(
+            "{}for {} {} in {}..{} do",
+            indentation(indent),
+            ty,
+            i.index.value,
+            from,
+            to
+        ));
+        self.end_line(header, indent + 1);
+
+        self.block(&i.statements, indent + 1);
+
+        let end = i.span.end();
+        let closing = self.closing(end - "endfor".len(), indent + 1);
+        self.out.push_str(&closing);
+        self.out.push_str(&indentation(indent));
+        self.out.push_str("endfor");
+        self.end_line(end, indent);
+    }
+
+    fn typed_identifier_or_assignee(
+        &mut self,
+        a: &pest::TypedIdentifierOrAssignee,
+        indent: usize,
+    ) -> String {
+        match a {
+            pest::TypedIdentifierOrAssignee::TypedIdentifier(t) => {
+                format!("{} {}", self.ty(&t.ty, indent), t.identifier.value)
+            }
+            pest::TypedIdentifierOrAssignee::Assignee(a) => {
+                let mut res = a.id.value.clone();
+                for access in &a.accesses {
+                    match access {
+                        pest::AssigneeAccess::Select(s) => {
+                            res.push_str(&self.array_access(s, indent))
+                        }
+                        pest::AssigneeAccess::Member(m) => {
+                            res.push_str(&format!(".{}", m.id.value))
+                        }
+                    }
+                }
+                res
+            }
+        }
+    }
+
+    fn ty(&mut self, ty: &pest::Type, indent: usize) -> String {
+        match ty {
+            pest::Type::Basic(b) => basic_type(b).to_string(),
+            pest::Type::Struct(s) => {
+                format!(
+                    "{}{}",
+                    s.id.value,
+                    self.explicit_generics(&s.explicit_generics)
+                )
+            }
+            pest::Type::Array(a) => {
+                let mut res = match &a.ty {
+                    pest::BasicOrStructType::Basic(b) => basic_type(b).to_string(),
+                    pest::BasicOrStructType::Struct(s) => {
+                        format!(
+                            "{}{}",
+                            s.id.value,
+                            self.explicit_generics(&s.explicit_generics)
+                        )
+                    }
+                };
+                for dimension in &a.dimensions {
+                    res.push_str(&format!("[{}]", self.expression(dimension, indent)));
+                }
+                res
+            }
+        }
+    }
+
+    fn explicit_generics(&self, generics: &Option<pest::ExplicitGenerics>) -> String {
+        match generics {
+            Some(generics) => format!(
+                "<{}>",
+                generics
+                    .values
+                    .iter()
+                    .map(|v| match v {
+                        pest::ConstantGenericValue::Value(l) => l.span().as_str().to_string(),
+                        pest::ConstantGenericValue::Identifier(i) => i.value.clone(),
+                        pest::ConstantGenericValue::Underscore(_) => "_".to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            None => String::new(),
+        }
+    }
+
+    fn expressions(&mut self, expressions: &[pest::Expression], indent: usize) -> String {
+        let mut res = vec![];
+        for e in expressions {
+            res.push(self.expression(e, indent));
+        }
+        res.join(", ")
+    }
+
+    /// Format `e`, in parentheses if it binds less tightly than `min`
+    fn operand(&mut self, e: &pest::Expression, min: u8, indent: usize) -> String {
+        let res = self.expression(e, indent);
+        match precedence(e) < min {
+            true => format!("({})", res),
+            false => res,
+        }
+    }
+
+    fn expression(&mut self, e: &pest::Expression, indent: usize) -> String {
+        match e {
+            pest::Expression::Ternary(t) => {
+                let condition = self.operand(&t.condition, 2, indent);
+                let consequence = self.expression(&t.consequence, indent);
+                let alternative = self.expression(&t.alternative, indent);
+                format!("{} ? {} : {}", condition, consequence, alternative)
+            }
+            pest::Expression::IfElse(i) => {
+                let condition = self.expression(&i.condition, indent);
+                let consequence = self.expression(&i.consequence, indent);
+                let alternative = self.expression(&i.alternative, indent);
+                format!(
+                    "if {} then {} else {} fi",
+                    condition, consequence, alternative
+                )
+            }
+            pest::Expression::Binary(b) if b.op == pest::BinaryOperator::Pow => {
+                let base = self.operand(&b.left, 13, indent);
+                let exponent = match *b.right {
+                    pest::Expression::Identifier(_) | pest::Expression::Literal(_) => {
+                        self.expression(&b.right, indent)
+                    }
+                    _ => format!("({})", self.expression(&b.right, indent)),
+                };
+                format!("{} ** {}", base, exponent)
+            }
+            pest::Expression::Binary(b) => {
+                // operators are left associative
+                let p = precedence(e);
+                let left = self.operand(&b.left, p, indent);
+                let right = self.operand(&b.right, p + 1, indent);
+                format!("{} {} {}", left, operator(&b.op), right)
+            }
+            pest::Expression::Unary(u) => {
+                let op = match u.op {
+                    pest::UnaryOperator::Pos(_) => "+",
+                    pest::UnaryOperator::Neg(_) => "-",
+                    pest::UnaryOperator::Not(_) => "!",
+                };
+                format!("{}{}", op, self.operand(&u.expression, 12, indent))
+            }
+            pest::Expression::Postfix(p) => {
+                let mut res = self.operand(&p.base, 13, indent);
+                for access in &p.accesses {
+                    let access = match access {
+                        pest::Access::Call(c) => {
+                            let generics = self.explicit_generics(&c.explicit_generics);
+                            let arguments = self.expressions(&c.arguments.expressions, indent);
+                            match generics.is_empty() {
+                                true => format!("({})", arguments),
+                                false => format!("::{}({})", generics, arguments),
+                            }
+                        }
+                        pest::Access::Select(s) => self.array_access(s, indent),
+                        pest::Access::Member(m) => format!(".{}", m.id.value),
+                    };
+                    res.push_str(&access);
+                }
+                res
+            }
+            pest::Expression::Identifier(i) => i.value.clone(),
+            pest::Expression::Literal(l) => l.span().as_str().to_string(),
+            pest::Expression::InlineArray(a) => {
+                let open = a.span.start() + 1;
+                let close = a.span.end() - 1;
+                self.list(
+                    ("[", "]", false),
+                    (open, close),
+                    &a.expressions,
+                    |e| match e {
+                        pest::SpreadOrExpression::Spread(s) => (s.span.start(), s.span.end()),
+                        pest::SpreadOrExpression::Expression(e) => {
+                            (e.span().start(), e.span().end())
+                        }
+                    },
+                    |f, e, indent| match e {
+                        pest::SpreadOrExpression::Spread(s) => {
+                            format!("...{}", f.expression(&s.expression, indent))
+                        }
+                        pest::SpreadOrExpression::Expression(e) => f.expression(e, indent),
+                    },
+                    indent,
+                )
+            }
+            pest::Expression::InlineStruct(s) => {
+                let open = self.find(s.ty.span.end(), "{") + 1;
+                let close = s.span.end() - 1;
+                let opener = format!("{} {{", s.ty.value);
+                self.list(
+                    (opener.as_str(), "}", true),
+                    (open, close),
+                    &s.members,
+                    |m| (m.span.start(), m.span.end()),
+                    |f, m, indent| {
+                        format!("{}: {}", m.id.value, f.expression(&m.expression, indent))
+                    },
+                    indent,
+                )
+            }
+            pest::Expression::ArrayInitializer(a) => {
+                let value = self.expression(&a.value, indent);
+                let count = self.expression(&a.count, indent);
+                format!("[{}; {}]", value, count)
+            }
+        }
+    }
+
+    fn array_access(&mut self, a: &pest::ArrayAccess, indent: usize) -> String {
+        match &a.expression {
+            pest::RangeOrExpression::Expression(e) => format!("[{}]", self.expression(e, indent)),
+            pest::RangeOrExpression::Range(r) => {
+                let from = match &r.from {
+                    Some(from) => self.expression(&from.0, indent),
+                    None => String::new(),
+                };
+                let to = match &r.to {
+                    Some(to) => self.expression(&to.0, indent),
+                    None => String::new(),
+                };
+                format!("[{}..{}]", from, to)
+            }
+        }
+    }
+
+    /// Format the delimited list of `items` found between `open` and `close` in the source, on
+    /// one line if it fits and has no comments, or with one item per line otherwise
+    #[allow(clippy::too_many_arguments)]
+    fn list<T>(
+        &mut self,
+        (opener, closer, padded): (&str, &str, bool),
+        (open, close): (usize, usize),
+        items: &[T],
+        span: impl Fn(&T) -> (usize, usize),
+        item: impl Fn(&mut Self, &T, usize) -> String,
+        indent: usize,
+    ) -> String {
+        if !self.has_comments(open, close) {
+            let mut one_line = vec![];
+            for i in items {
+                one_line.push(item(self, i, indent));
+            }
+            let one_line = match (one_line.is_empty(), padded) {
+                (false, true) => format!("{} {} {}", opener, one_line.join(", "), closer),
+                _ => format!("{}{}{}", opener, one_line.join(", "), closer),
+            };
+
+            if !one_line.contains('\n') && indent * INDENT.len() + one_line.len() <= WIDTH {
+                return one_line;
+            }
+        }
+
+        let mut res = opener.to_string();
+        self.last = open;
+        res.push_str(&self.trailing());
+        res.push('\n');
+        self.fresh = true;
+
+        for (index, i) in items.iter().enumerate() {
+            let (start, end) = span(i);
+            res.push_str(&self.leading(start, indent + 1));
+            res.push_str(&indentation(indent + 1));
+            res.push_str(&item(self, i, indent + 1));
+            if index + 1 < items.len() {
+                res.push(',');
+            }
+            self.last = end;
+            res.push_str(&self.trailing());
+            res.push('\n');
+        }
+
+        res.push_str(&self.closing(close, indent + 1));
+        res.push_str(&indentation(indent));
+        res.push_str(closer);
+        self.last = close + 1;
+        self.fresh = false;
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glob::glob;
+    use regex::Regex;
+    use std::fs;
+
+    fn sources() -> Vec<(String, String)> {
+        [
+            "../zokrates_stdlib/stdlib/**/*.zok",
+            "../zokrates_cli/examples/**/*.zok",
+        ]
+        .iter()
+        .flat_map(|pattern| glob(pattern).expect("Failed to read glob pattern"))
+        .map(|entry| {
+            let path = entry.unwrap();
+            let source = fs::read_to_string(&path).unwrap();
+            (path.display().to_string(), source)
+        })
+        // skip the files which are expected not to parse
+        .filter(|(_, source)| pest::generate_ast(source).is_ok())
+        .collect()
+    }
+
+    #[test]
+    fn idempotent() {
+        for (path, source) in sources() {
+            let formatted = format(&source).unwrap();
+            assert!(
+                pest::generate_ast(&formatted).is_ok(),
+                "{} does not parse after formatting:\n{}",
+                path,
+                formatted
+            );
+            assert_eq!(formatted, format(&formatted).unwrap(), "{}", path);
+        }
+    }
+
+    /// The AST of `source`, without the spans of its nodes which change with formatting
+    fn ast(source: &str) -> String {
+        let spans =
+            Regex::new(r#"Span \{ str: "(?:[^"\\]|\\.)*", start: \d+, end: \d+ \}"#).unwrap();
+        let ast = format!("{:?}", pest::generate_ast(source).unwrap());
+        spans.replace_all(&ast, "Span").into_owned()
+    }
+
+    #[test]
+    fn preserves_ast() {
+        for (path, source) in sources() {
+            let formatted = format(&source).unwrap();
+            assert_eq!(ast(&source), ast(&formatted), "{}", path);
+        }
+    }
+
+    #[test]
+    fn preserves_comments() {
+        let text =
+            |source: &str| -> Vec<_> { comments(source).into_iter().map(|c| c.text).collect() };
+
+        for (path, source) in sources() {
+            let formatted = format(&source).unwrap();
+            assert_eq!(text(&source), text(&formatted), "{}", path);
+        }
+    }
+
+    #[test]
+    fn layout() {
+        let source = r#"#pragma curve bn128
+import "./a"   as  a
+from "./b" import c,d as e
+struct Foo<N>{
+	field[N]   a // the values
+}
+def main( private field a,Foo<2> b)->(field,bool):
+	// check the input
+	assert(a==b.a[0],"a is not the first value")
+	for u32 i in 0..2 do
+		a=a+b.a[i]*2
+	endfor
+
+
+	return -a**2,!(a==1)
+"#;
+
+        let expected = r#"#pragma curve bn128
+
+import "./a" as a
+from "./b" import c, d as e
+
+struct Foo<N> {
+    field[N] a // the values
+}
+
+def main(private field a, Foo<2> b) -> (field, bool):
+    // check the input
+    assert(a == b.a[0], "a is not the first value")
+    for u32 i in 0..2 do
+        a = a + b.a[i] * 2
+    endfor
+
+    return -a ** 2, !(a == 1)
+"#;
+
+        assert_eq!(format(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn attributes() {
+        let source = "#[test]\n#[should_fail( \"Assertion failed\" )] // expected\ndef fails():\n    assert(false)\n    return\n";
+        let expected = "#[test]\n#[should_fail(\"Assertion failed\")] // expected\ndef fails():\n    assert(false)\n    return\n";
+
+        assert_eq!(format(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn parentheses() {
+        let source = "def main(field a, field b) -> field:\n    return ((a + b) * (a - (b - 1))) ** (a + 1) + (a ? b : (a ? 1 : 2))\n";
+        let expected = "def main(field a, field b) -> field:\n    return ((a + b) * (a - (b - 1))) ** (a + 1) + (a ? b : a ? 1 : 2)\n";
+
+        assert_eq!(format(source).unwrap(), expected);
+    }
+
+    #[test]
+    fn long_inline_arrays() {
+        let source = format!(
+            "def main() -> field[4]:\n    return [{}, {}, 1, 2 /* two */]\n",
+            "1".repeat(50),
+            "2".repeat(50),
+        );
+        let expected = format!(
+            "def main() -> field[4]:\n    return [\n        {},\n        {},\n        1,\n        2 /* two */\n    ]\n",
+            "1".repeat(50),
+            "2".repeat(50),
+        );
+
+        assert_eq!(format(&source).unwrap(), expected);
+    }
+}
@@ -5,7 +5,7 @@
 //! @date 2018
 
 use crate::absy::*;
-use crate::compile::parse_module;
+use crate::compile::{parse_module, ParsedModules};
 use crate::compile::{CompileErrorInner, CompileErrors};
 use crate::embed::FlatEmbed;
 use crate::parser::Position;
@@ -73,6 +73,7 @@ impl Importer {
         location: PathBuf,
         resolver: Option<&dyn Resolver<E>>,
         modules: &mut HashMap<OwnedModuleId, Module<'ast>>,
+        parsed: &mut ParsedModules<'ast>,
         arena: &'ast Arena<String>,
     ) -> Result<Module<'ast>, CompileErrors> {
         let symbols: Vec<_> = destination
@@ -80,7 +81,7 @@ impl Importer {
             .into_iter()
             .map(|s| match s.value.symbol {
                 Symbol::Here(SymbolDefinition::Import(import)) => {
-                    Importer::resolve::<T, E>(import, &location, resolver, modules, arena)
+                    Importer::resolve::<T, E>(import, &location, resolver, modules, parsed, arena)
                 }
                 _ => Ok(s),
             })
@@ -94,6 +95,7 @@ impl Importer {
         location: &Path,
         resolver: Option<&dyn Resolver<E>>,
         modules: &mut HashMap<OwnedModuleId, Module<'ast>>,
+        parsed: &mut ParsedModules<'ast>,
         arena: &'ast Arena<String>,
     ) -> Result<SymbolDeclarationNode<'ast>, CompileErrors> {
         let pos = import.pos();
@@ -102,14 +104,18 @@ impl Importer {
 
         let symbol_declaration = match module_id.to_str().unwrap() {
             "EMBED" => match symbol.id {
-                #[cfg(feature = "bellman")]
-                "sha256round" => {
-                    use zokrates_field::Bn128Field;
-                    if T::id() != Bn128Field::id() {
+                "sha256round" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::Sha256Round),
+                },
+                #[cfg(feature = "ark")]
+                "snark_verify_bls12_377" => {
+                    use zokrates_field::Bw6_761Field;
+                    if T::id() != Bw6_761Field::id() {
                         return Err(CompileErrorInner::ImportError(
                             Error::new(format!(
-                                "`sha256round` is expected to be compiled over `{}` curve, but found `{}`",
-                                Bn128Field::name(),
+                                "`snark_verify_bls12_377` is expected to be compiled over `{}` curve, but found `{}`",
+                                Bw6_761Field::name(),
                                 T::name()
                             ))
                             .with_pos(Some(pos)),
@@ -119,17 +125,17 @@ impl Importer {
                     } else {
                         SymbolDeclaration {
                             id: symbol.get_alias(),
-                            symbol: Symbol::Flat(FlatEmbed::Sha256Round),
+                            symbol: Symbol::Flat(FlatEmbed::SnarkVerifyBls12377),
                         }
                     }
                 }
                 #[cfg(feature = "ark")]
-                "snark_verify_bls12_377" => {
+                "snark_verify_groth16_bls12_377" => {
                     use zokrates_field::Bw6_761Field;
                     if T::id() != Bw6_761Field::id() {
                         return Err(CompileErrorInner::ImportError(
                             Error::new(format!(
-                                "`snark_verify_bls12_377` is expected to be compiled over `{}` curve, but found `{}`",
+                                "`snark_verify_groth16_bls12_377` is expected to be compiled over `{}` curve, but found `{}`",
                                 Bw6_761Field::name(),
                                 T::name()
                             ))
@@ -140,7 +146,84 @@ impl Importer {
                     } else {
                         SymbolDeclaration {
                             id: symbol.get_alias(),
-                            symbol: Symbol::Flat(FlatEmbed::SnarkVerifyBls12377),
+                            symbol: Symbol::Flat(FlatEmbed::SnarkVerifyGroth16Bls12377),
+                        }
+                    }
+                }
+                "poseidon_permutation" => {
+                    use zokrates_field::Bn128Field;
+                    if T::id() != Bn128Field::id() {
+                        return Err(CompileErrorInner::ImportError(
+                            Error::new(format!(
+                                "`poseidon_permutation` is expected to be compiled over `{}` curve, but found `{}`",
+                                Bn128Field::name(),
+                                T::name()
+                            ))
+                            .with_pos(Some(pos)),
+                        )
+                        .in_file(location)
+                        .into());
+                    } else {
+                        SymbolDeclaration {
+                            id: symbol.get_alias(),
+                            symbol: Symbol::Flat(FlatEmbed::PoseidonPermutation),
+                        }
+                    }
+                }
+                "keccak_f1600" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::KeccakF1600),
+                },
+                "blake2s_round" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::Blake2sRound),
+                },
+                "ecdsa_secp256k1_verify" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::EcdsaSecp256k1Verify),
+                },
+                "bigint_mul_mod" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::BigIntMulMod),
+                },
+                "bigint_reduce" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::BigIntReduce),
+                },
+                "range_check" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::RangeCheck),
+                },
+                "lookup" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::Lookup),
+                },
+                "lookup_u8" => SymbolDeclaration {
+                    id: symbol.get_alias(),
+                    symbol: Symbol::Flat(FlatEmbed::LookupU8),
+                },
+                "edwards_add" | "edwards_scalar_mult" | "edwards_fixed_base_scalar_mult" => {
+                    use zokrates_field::Bn128Field;
+                    if T::id() != Bn128Field::id() {
+                        return Err(CompileErrorInner::ImportError(
+                            Error::new(format!(
+                                "`{}` is expected to be compiled over `{}` curve, but found `{}`",
+                                symbol.id,
+                                Bn128Field::name(),
+                                T::name()
+                            ))
+                            .with_pos(Some(pos)),
+                        )
+                        .in_file(location)
+                        .into());
+                    } else {
+                        SymbolDeclaration {
+                            id: symbol.get_alias(),
+                            symbol: Symbol::Flat(match symbol.id {
+                                "edwards_add" => FlatEmbed::EdwardsAdd,
+                                "edwards_scalar_mult" => FlatEmbed::EdwardsScalarMult,
+                                _ => FlatEmbed::EdwardsFixedBaseScalarMult,
+                            }),
                         }
                     }
                 }
@@ -225,12 +308,12 @@ impl Importer {
                         match modules.get(&new_location) {
                             Some(_) => {}
                             None => {
-                                let source = arena.alloc(source);
                                 let compiled = parse_module::<T, E>(
                                     source,
                                     new_location.clone(),
                                     resolver,
                                     modules,
+                                    parsed,
                                     arena,
                                 )?;
 
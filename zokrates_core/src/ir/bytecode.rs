@@ -0,0 +1,708 @@
+//! A register-based form of IR programs, optimised for witness computation
+//!
+//! Variables are mapped to dense register indices, linear combinations are stored in flat term
+//! tables pointing to a deduplicated coefficient table, and whether a constraint assigns a variable
+//! or checks a relation is decided once at lowering time instead of on each execution.
+
+use crate::flat_absy::{FlatParameter, FlatVariable, RuntimeError};
+use crate::ir::{
+    Error, ExecutionResult, Interpreter, Layout, LinComb, ProgIterator, Statement, Witness,
+};
+use crate::solvers::Solver;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use zokrates_field::*;
+
+const ZOKRATES_BYTECODE_MAGIC: &[u8; 4] = &[0x5a, 0x4f, 0x42, 0];
+const ZOKRATES_BYTECODE_VERSION_1: &[u8; 4] = &[0, 0, 0, 1];
+
+pub type Register = u32;
+
+/// The index of the coefficient `1` in the coefficient table
+const ONE_COEFFICIENT: u32 = 0;
+
+/// A range of a table
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Span {
+    start: u32,
+    end: u32,
+}
+
+impl Span {
+    fn range(&self) -> std::ops::Range<usize> {
+        self.start as usize..self.end as usize
+    }
+}
+
+/// A term of a linear combination: the value of a register times a coefficient
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Term {
+    register: Register,
+    coefficient: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Instruction {
+    /// Set `target` to the product of two linear combinations
+    Assign {
+        target: Register,
+        left: Span,
+        right: Span,
+    },
+    /// Check that the product of two linear combinations equals a third one
+    Check {
+        left: Span,
+        right: Span,
+        result: Span,
+        error: Option<u32>,
+    },
+    /// Call `solver` on the quadratic combinations in `inputs` and write the results to the
+    /// registers in `outputs`
+    Solve {
+        solver: Solver,
+        inputs: Span,
+        outputs: Span,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bytecode<T> {
+    /// The arguments of the program. Their values are stored in registers `1..=arguments.len()`,
+    /// register `0` holds `~one`
+    pub arguments: Vec<FlatParameter>,
+    pub return_count: usize,
+    /// The variable held by each register
+    variables: Vec<FlatVariable>,
+    coefficients: Vec<T>,
+    terms: Vec<Term>,
+    /// The quadratic combinations passed to solvers, as pairs of spans in `terms`
+    quadratic: Vec<(Span, Span)>,
+    /// The output registers of solvers
+    outputs: Vec<Register>,
+    errors: Vec<RuntimeError>,
+    instructions: Vec<Instruction>,
+}
+
+impl<T> Bytecode<T> {
+    pub fn register_count(&self) -> usize {
+        self.variables.len()
+    }
+
+    pub fn instruction_count(&self) -> usize {
+        self.instructions.len()
+    }
+}
+
+impl<T: Field, I: IntoIterator<Item = Statement<T>>> ProgIterator<T, I> {
+    /// Lower this program to bytecode
+    pub fn lower(self) -> Bytecode<T> {
+        let mut lowerer = Lowerer {
+            registers: HashMap::new(),
+            coefficients: HashMap::new(),
+            bytecode: Bytecode {
+                arguments: vec![],
+                return_count: self.return_count,
+                variables: vec![],
+                coefficients: vec![],
+                terms: vec![],
+                quadratic: vec![],
+                outputs: vec![],
+                errors: vec![],
+                instructions: vec![],
+            },
+        };
+
+        assert_eq!(lowerer.coefficient(T::one()), ONE_COEFFICIENT);
+
+        lowerer.define(FlatVariable::one());
+
+        for argument in &self.arguments {
+            lowerer.define(argument.id);
+        }
+
+        lowerer.bytecode.arguments = self.arguments;
+
+        for statement in self.statements {
+            lowerer.lower_statement(statement);
+        }
+
+        lowerer.bytecode
+    }
+}
+
+struct Lowerer<T> {
+    registers: HashMap<FlatVariable, Register>,
+    coefficients: HashMap<T, u32>,
+    bytecode: Bytecode<T>,
+}
+
+impl<T: Field> Lowerer<T> {
+    fn define(&mut self, variable: FlatVariable) -> Register {
+        let register = self.bytecode.variables.len() as Register;
+        self.bytecode.variables.push(variable);
+        self.registers.insert(variable, register);
+        register
+    }
+
+    fn read(&self, variable: &FlatVariable) -> Register {
+        *self
+            .registers
+            .get(variable)
+            .unwrap_or_else(|| panic!("variable {} is read before being defined", variable))
+    }
+
+    fn coefficient(&mut self, value: T) -> u32 {
+        let coefficients = &mut self.bytecode.coefficients;
+        *self.coefficients.entry(value).or_insert_with_key(|value| {
+            coefficients.push(value.clone());
+            (coefficients.len() - 1) as u32
+        })
+    }
+
+    fn linear(&mut self, lin: LinComb<T>) -> Span {
+        let start = self.bytecode.terms.len() as u32;
+
+        for (variable, coefficient) in lin.0 {
+            let term = Term {
+                register: self.read(&variable),
+                coefficient: self.coefficient(coefficient),
+            };
+            self.bytecode.terms.push(term);
+        }
+
+        Span {
+            start,
+            end: self.bytecode.terms.len() as u32,
+        }
+    }
+
+    fn lower_statement(&mut self, statement: Statement<T>) {
+        let instruction = match statement {
+            Statement::Constraint(quad, lin, error) => {
+                // this mirrors the way `Interpreter::execute` detects assignments
+                let assignee = match lin.0.as_slice() {
+                    [(v, c)] if *c == T::one() && !self.registers.contains_key(v) => Some(*v),
+                    _ => None,
+                };
+
+                let left = self.linear(quad.left);
+                let right = self.linear(quad.right);
+
+                match assignee {
+                    Some(variable) => Instruction::Assign {
+                        target: self.define(variable),
+                        left,
+                        right,
+                    },
+                    None => {
+                        let result = self.linear(lin);
+                        let error = error.map(|e| {
+                            self.bytecode.errors.push(e);
+                            (self.bytecode.errors.len() - 1) as u32
+                        });
+                        Instruction::Check {
+                            left,
+                            right,
+                            result,
+                            error,
+                        }
+                    }
+                }
+            }
+            Statement::Directive(d) => {
+                let quadratic: Vec<_> = d
+                    .inputs
+                    .into_iter()
+                    .map(|q| (self.linear(q.left), self.linear(q.right)))
+                    .collect();
+
+                let inputs = Span {
+                    start: self.bytecode.quadratic.len() as u32,
+                    end: (self.bytecode.quadratic.len() + quadratic.len()) as u32,
+                };
+                self.bytecode.quadratic.extend(quadratic);
+
+                let start = self.bytecode.outputs.len() as u32;
+                for o in d.outputs {
+                    let register = self.define(o);
+                    self.bytecode.outputs.push(register);
+                }
+                let outputs = Span {
+                    start,
+                    end: self.bytecode.outputs.len() as u32,
+                };
+
+                Instruction::Solve {
+                    solver: d.solver,
+                    inputs,
+                    outputs,
+                }
+            }
+        };
+
+        self.bytecode.instructions.push(instruction);
+    }
+}
+
+impl<T: Field> Bytecode<T> {
+    /// Compute the witness for `inputs`.
+    ///
+    /// The result is the same as the one of `Interpreter::execute` on the program this bytecode
+    /// was lowered from.
+    pub fn execute(&self, inputs: &[T]) -> ExecutionResult<T> {
+        if self.arguments.len() != inputs.len() {
+            return Err(Error::WrongInputCount {
+                expected: self.arguments.len(),
+                received: inputs.len(),
+            });
+        }
+
+        let mut registers = vec![T::zero(); self.variables.len()];
+        registers[0] = T::one();
+        registers[1..=inputs.len()].clone_from_slice(inputs);
+
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Assign {
+                    target,
+                    left,
+                    right,
+                } => {
+                    registers[*target as usize] =
+                        self.evaluate(left, &registers) * self.evaluate(right, &registers);
+                }
+                Instruction::Check {
+                    left,
+                    right,
+                    result,
+                    error,
+                } => {
+                    if self.evaluate(left, &registers) * self.evaluate(right, &registers)
+                        != self.evaluate(result, &registers)
+                    {
+                        return Err(Error::UnsatisfiedConstraint {
+                            error: error.map(|e| self.errors[e as usize].clone()),
+                        });
+                    }
+                }
+                Instruction::Solve {
+                    solver,
+                    inputs,
+                    outputs,
+                } => {
+                    let inputs: Vec<_> = self.quadratic[inputs.range()]
+                        .iter()
+                        .map(|(left, right)| {
+                            self.evaluate(left, &registers) * self.evaluate(right, &registers)
+                        })
+                        .collect();
+
+                    let res =
+                        Interpreter::execute_solver(solver, &inputs).map_err(|_| Error::Solver)?;
+
+                    for (register, value) in self.outputs[outputs.range()].iter().zip(res) {
+                        registers[*register as usize] = value;
+                    }
+                }
+            }
+        }
+
+        let mut witness = Witness::new(Layout::new(self.arguments.len(), self.return_count));
+
+        for (variable, value) in self.variables.iter().cloned().zip(registers) {
+            witness.insert(variable, value);
+        }
+
+        Ok(witness)
+    }
+
+    fn evaluate(&self, span: &Span, registers: &[T]) -> T {
+        self.terms[span.range()]
+            .iter()
+            .fold(T::zero(), |acc, term| match term.coefficient {
+                ONE_COEFFICIENT => acc + &registers[term.register as usize],
+                c => {
+                    acc + registers[term.register as usize].clone() * &self.coefficients[c as usize]
+                }
+            })
+    }
+
+    /// Check that all indices into registers and tables are in bounds and that solvers are called
+    /// with valid parameters and as many inputs and outputs as they expect, so that executing
+    /// deserialized bytecode cannot panic
+    fn validate(&self) -> Result<(), String> {
+        let check = |condition: bool, what: &str| match condition {
+            true => Ok(()),
+            false => Err(format!("Invalid bytecode: {} out of bounds", what)),
+        };
+        let span = |s: &Span, len: usize, what: &str| {
+            check(s.start <= s.end && s.end as usize <= len, what)
+        };
+        let register = |r: Register| check((r as usize) < self.variables.len(), "register");
+
+        check(
+            self.arguments.len() < self.variables.len(),
+            "argument registers",
+        )?;
+        check(
+            self.coefficients.get(ONE_COEFFICIENT as usize) == Some(&T::one()),
+            "coefficient `1`",
+        )?;
+
+        for term in &self.terms {
+            register(term.register)?;
+            check(
+                (term.coefficient as usize) < self.coefficients.len(),
+                "coefficient",
+            )?;
+        }
+
+        for (left, right) in &self.quadratic {
+            span(left, self.terms.len(), "term span")?;
+            span(right, self.terms.len(), "term span")?;
+        }
+
+        for r in &self.outputs {
+            register(*r)?;
+        }
+
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Assign {
+                    target,
+                    left,
+                    right,
+                } => {
+                    register(*target)?;
+                    span(left, self.terms.len(), "term span")?;
+                    span(right, self.terms.len(), "term span")?;
+                }
+                Instruction::Check {
+                    left,
+                    right,
+                    result,
+                    error,
+                } => {
+                    span(left, self.terms.len(), "term span")?;
+                    span(right, self.terms.len(), "term span")?;
+                    span(result, self.terms.len(), "term span")?;
+                    if let Some(e) = error {
+                        check((*e as usize) < self.errors.len(), "error")?;
+                    }
+                }
+                Instruction::Solve {
+                    solver,
+                    inputs,
+                    outputs,
+                } => {
+                    span(inputs, self.quadratic.len(), "solver input span")?;
+                    span(outputs, self.outputs.len(), "solver output span")?;
+                    self.check_solver(solver)?;
+                    let (input_count, output_count) = solver.get_signature();
+                    check(inputs.range().len() == input_count, "solver input count")?;
+                    check(outputs.range().len() == output_count, "solver output count")?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the parameters of `solver` against the ones its embed accepts at compile time, so that
+    /// computing its signature and running it cannot panic
+    fn check_solver(&self, solver: &Solver) -> Result<(), String> {
+        let valid = match solver {
+            Solver::PoseidonPermutation(width) => {
+                use zokrates_embed::poseidon::{MAX_WIDTH, MIN_WIDTH};
+
+                T::id() == Bn128Field::id() && (MIN_WIDTH..=MAX_WIDTH).contains(width)
+            }
+            Solver::EdwardsAdd | Solver::EdwardsScalarMult | Solver::EdwardsFixedBaseScalarMult => {
+                T::id() == Bn128Field::id()
+            }
+            // the input count is checked against the signature afterwards, bounding `n` by the
+            // size of the input table only keeps computing the signature from overflowing
+            #[cfg(feature = "ark")]
+            Solver::SnarkVerifyBls12377(n) | Solver::SnarkVerifyGroth16Bls12377(n) => {
+                T::id() == Bw6_761Field::id() && *n <= self.quadratic.len()
+            }
+            Solver::BigIntMulMod(modulus) => modulus.iter().any(|w| *w != 0),
+            Solver::BigIntReduce(input_limb_count, modulus) => {
+                modulus.iter().any(|w| *w != 0) && *input_limb_count <= 2 * modulus.len()
+            }
+            _ => true,
+        };
+
+        match valid {
+            true => Ok(()),
+            false => Err(format!("Invalid bytecode: invalid solver `{}`", solver)),
+        }
+    }
+
+    pub fn serialize<W: Write>(&self, mut w: W) -> Result<(), String> {
+        w.write_all(ZOKRATES_BYTECODE_MAGIC)
+            .and_then(|_| w.write_all(ZOKRATES_BYTECODE_VERSION_1))
+            .and_then(|_| w.write_all(&T::id()))
+            .map_err(|e| e.to_string())?;
+
+        serde_cbor::to_writer(w, self).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(PartialEq, Debug)]
+pub enum BytecodeEnum {
+    Bls12_381Bytecode(Bytecode<Bls12_381Field>),
+    Bn128Bytecode(Bytecode<Bn128Field>),
+    Bls12_377Bytecode(Bytecode<Bls12_377Field>),
+    Bw6_761Bytecode(Bytecode<Bw6_761Field>),
+}
+
+impl BytecodeEnum {
+    /// Check whether `header` is the start of serialized bytecode
+    pub fn is_bytecode(header: &[u8]) -> bool {
+        header.starts_with(ZOKRATES_BYTECODE_MAGIC)
+    }
+
+    pub fn deserialize<R: Read>(mut r: R) -> Result<Self, String> {
+        // Check the magic number, `ZOB`
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)
+            .map_err(|_| String::from("Cannot read magic number"))?;
+
+        if &magic != ZOKRATES_BYTECODE_MAGIC {
+            return Err(String::from("Wrong magic number"));
+        }
+
+        // Check the version, 1
+        let mut version = [0; 4];
+        r.read_exact(&mut version)
+            .map_err(|_| String::from("Cannot read version"))?;
+
+        if &version != ZOKRATES_BYTECODE_VERSION_1 {
+            return Err(String::from("Unknown version"));
+        }
+
+        // Check the curve identifier, deserializing accordingly
+        let mut curve = [0; 4];
+        r.read_exact(&mut curve)
+            .map_err(|_| String::from("Cannot read curve identifier"))?;
+
+        let read_error = |e: serde_cbor::Error| format!("Cannot read bytecode: {}", e);
+
+        match curve {
+            m if m == Bls12_381Field::id() => serde_cbor::from_reader(r)
+                .map_err(read_error)
+                .and_then(|b: Bytecode<Bls12_381Field>| b.validate().map(|_| b))
+                .map(BytecodeEnum::Bls12_381Bytecode),
+            m if m == Bn128Field::id() => serde_cbor::from_reader(r)
+                .map_err(read_error)
+                .and_then(|b: Bytecode<Bn128Field>| b.validate().map(|_| b))
+                .map(BytecodeEnum::Bn128Bytecode),
+            m if m == Bls12_377Field::id() => serde_cbor::from_reader(r)
+                .map_err(read_error)
+                .and_then(|b: Bytecode<Bls12_377Field>| b.validate().map(|_| b))
+                .map(BytecodeEnum::Bls12_377Bytecode),
+            m if m == Bw6_761Field::id() => serde_cbor::from_reader(r)
+                .map_err(read_error)
+                .and_then(|b: Bytecode<Bw6_761Field>| b.validate().map(|_| b))
+                .map(BytecodeEnum::Bw6_761Bytecode),
+            _ => Err(String::from("Unknown curve identifier")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{Directive, Prog, QuadComb};
+    use std::io::{Cursor, Seek, SeekFrom};
+
+    fn program() -> Prog<Bn128Field> {
+        // def main(private a, b) -> (1):
+        //     _2 = a * b
+        //     # _3, _4 = ConditionEq(_2 - 6)
+        //     (_2 - 6) * _4 == _3
+        //     ~out_0 = 3 * _2 + 5 * a
+        //     _2 == 6 * ~one
+        Prog {
+            arguments: vec![
+                FlatParameter::private(FlatVariable::new(0)),
+                FlatParameter::public(FlatVariable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![
+                Statement::definition(
+                    FlatVariable::new(2),
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::new(1).into(),
+                    ),
+                ),
+                Statement::Directive(Directive {
+                    inputs: vec![(LinComb::from(FlatVariable::new(2))
+                        - LinComb::summand(6, FlatVariable::one()))
+                    .into()],
+                    outputs: vec![FlatVariable::new(3), FlatVariable::new(4)],
+                    solver: Solver::ConditionEq,
+                }),
+                Statement::constraint(
+                    QuadComb::from_linear_combinations(
+                        LinComb::from(FlatVariable::new(2))
+                            - LinComb::summand(6, FlatVariable::one()),
+                        FlatVariable::new(4).into(),
+                    ),
+                    FlatVariable::new(3),
+                ),
+                Statement::definition(
+                    FlatVariable::public(0),
+                    LinComb::summand(3, FlatVariable::new(2))
+                        + LinComb::summand(5, FlatVariable::new(0)),
+                ),
+                Statement::Constraint(
+                    FlatVariable::new(2).into(),
+                    LinComb::summand(6, FlatVariable::one()),
+                    Some(RuntimeError::SourceAssertion("product is 6".into())),
+                ),
+            ],
+        }
+    }
+
+    #[test]
+    fn lower() {
+        let bytecode = program().lower();
+
+        // one register per variable, including `~one`
+        assert_eq!(bytecode.register_count(), 7);
+        assert_eq!(bytecode.instruction_count(), 5);
+        // 1, -6, 3, 5, 6
+        assert_eq!(bytecode.coefficients.len(), 5);
+
+        assert!(matches!(
+            bytecode.instructions[0],
+            Instruction::Assign { target: 3, .. }
+        ));
+        assert!(matches!(
+            bytecode.instructions[2],
+            Instruction::Check { error: None, .. }
+        ));
+        assert!(matches!(
+            bytecode.instructions[4],
+            Instruction::Check { error: Some(0), .. }
+        ));
+    }
+
+    #[test]
+    fn same_witness_as_interpreter() {
+        let program = program();
+        let bytecode = program.clone().lower();
+
+        let inputs = vec![Bn128Field::from(2), Bn128Field::from(3)];
+
+        let expected = Interpreter::default().execute(program, &inputs);
+
+        assert!(expected.is_ok());
+        assert_eq!(bytecode.execute(&inputs), expected);
+    }
+
+    #[test]
+    fn same_error_as_interpreter() {
+        let program = program();
+        let bytecode = program.clone().lower();
+
+        for inputs in &[
+            vec![Bn128Field::from(2), Bn128Field::from(4)],
+            vec![Bn128Field::from(2)],
+        ] {
+            let expected = Interpreter::default().execute(program.clone(), inputs);
+
+            assert!(expected.is_err());
+            assert_eq!(bytecode.execute(inputs), expected);
+        }
+    }
+
+    #[test]
+    fn serialize_deserialize() {
+        let bytecode = program().lower();
+
+        let mut buffer = Cursor::new(vec![]);
+        bytecode.serialize(&mut buffer).unwrap();
+
+        assert!(BytecodeEnum::is_bytecode(buffer.get_ref()));
+
+        buffer.seek(SeekFrom::Start(0)).unwrap();
+
+        assert_eq!(
+            BytecodeEnum::deserialize(buffer).unwrap(),
+            BytecodeEnum::Bn128Bytecode(bytecode)
+        );
+    }
+
+    fn deserialize_error(bytecode: Bytecode<Bn128Field>) -> String {
+        let mut buffer = Cursor::new(vec![]);
+        bytecode.serialize(&mut buffer).unwrap();
+        buffer.seek(SeekFrom::Start(0)).unwrap();
+
+        BytecodeEnum::deserialize(buffer).unwrap_err()
+    }
+
+    /// Replace the solver of the directive of `program`
+    fn with_solver(solver: Solver) -> Bytecode<Bn128Field> {
+        let mut bytecode = program().lower();
+        match &mut bytecode.instructions[1] {
+            Instruction::Solve { solver: s, .. } => *s = solver,
+            _ => unreachable!(),
+        }
+        bytecode
+    }
+
+    #[test]
+    fn deserialize_invalid_register() {
+        let mut bytecode = program().lower();
+        bytecode.terms[0].register = 42;
+
+        assert_eq!(
+            deserialize_error(bytecode),
+            "Invalid bytecode: register out of bounds"
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_solver_parameters() {
+        assert_eq!(
+            deserialize_error(with_solver(Solver::PoseidonPermutation(8))),
+            "Invalid bytecode: invalid solver `PoseidonPermutation(8)`"
+        );
+        assert_eq!(
+            deserialize_error(with_solver(Solver::PoseidonPermutation(1))),
+            "Invalid bytecode: invalid solver `PoseidonPermutation(1)`"
+        );
+        assert_eq!(
+            deserialize_error(with_solver(Solver::BigIntReduce(1, vec![0, 0]))),
+            "Invalid bytecode: invalid solver `BigIntReduce(1, [0, 0])`"
+        );
+        assert_eq!(
+            deserialize_error(with_solver(Solver::BigIntReduce(5, vec![1, 1]))),
+            "Invalid bytecode: invalid solver `BigIntReduce(5, [1, 1])`"
+        );
+    }
+
+    #[test]
+    fn deserialize_invalid_solver_output_count() {
+        // `Bits(n)` takes one input like `ConditionEq`, but has `n` outputs instead of 2
+        assert_eq!(
+            deserialize_error(with_solver(Solver::Bits(usize::MAX))),
+            "Invalid bytecode: solver output count out of bounds"
+        );
+
+        let mut bytecode = program().lower();
+        match &mut bytecode.instructions[1] {
+            Instruction::Solve { outputs, .. } => outputs.end -= 1,
+            _ => unreachable!(),
+        }
+        assert_eq!(
+            deserialize_error(bytecode),
+            "Invalid bytecode: solver output count out of bounds"
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::flat_absy::flat_variable::FlatVariable;
 use crate::flat_absy::RuntimeError;
-use crate::ir::{LinComb, ProgIterator, QuadComb, Statement, Witness};
+use crate::ir::{Layout, LinComb, ProgIterator, QuadComb, Statement, Witness};
 use crate::solvers::Solver;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
@@ -9,7 +9,7 @@ use zokrates_field::Field;
 
 pub type ExecutionResult<T> = Result<Witness<T>, Error>;
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct Interpreter {
     /// Whether we should try to give out-of-range bit decompositions when the input is not a single summand.
     /// Used to do targetted testing of `<` flattening, making sure the bit decomposition we base the result on is unique.
@@ -31,7 +31,7 @@ impl Interpreter {
         inputs: &[T],
     ) -> ExecutionResult<T> {
         self.check_inputs(&program, inputs)?;
-        let mut witness = BTreeMap::new();
+        let mut witness = Witness::new(Layout::new(program.arguments.len(), program.return_count));
         witness.insert(FlatVariable::one(), T::one());
 
         for (arg, value) in program.arguments.iter().zip(inputs.iter()) {
@@ -39,46 +39,53 @@ impl Interpreter {
         }
 
         for statement in program.statements.into_iter() {
-            match statement {
-                Statement::Constraint(quad, lin, error) => match lin.is_assignee(&witness) {
-                    true => {
-                        let val = quad.evaluate(&witness).unwrap();
-                        witness.insert(lin.0.get(0).unwrap().0, val);
-                    }
-                    false => {
-                        let lhs_value = quad.evaluate(&witness).unwrap();
-                        let rhs_value = lin.evaluate(&witness).unwrap();
-                        if lhs_value != rhs_value {
-                            return Err(Error::UnsatisfiedConstraint { error });
-                        }
-                    }
-                },
-                Statement::Directive(ref d) => {
-                    let mut inputs: Vec<_> = d
-                        .inputs
-                        .iter()
-                        .map(|i| i.evaluate(&witness).unwrap())
-                        .collect();
-
-                    let res = match (&d.solver, self.should_try_out_of_range) {
-                        (Solver::Bits(bitwidth), true) if *bitwidth >= T::get_required_bits() => {
-                            Ok(Self::try_solve_with_out_of_range_bits(
-                                *bitwidth,
-                                inputs.pop().unwrap(),
-                            ))
-                        }
-                        _ => Self::execute_solver(&d.solver, &inputs),
-                    }
-                    .map_err(|_| Error::Solver)?;
+            self.execute_statement(statement, &mut witness)?;
+        }
 
-                    for (i, o) in d.outputs.iter().enumerate() {
-                        witness.insert(*o, res[i].clone());
+        Ok(witness)
+    }
+
+    pub(super) fn execute_statement<T: Field, W: Assignment<T>>(
+        &self,
+        statement: Statement<T>,
+        witness: &mut W,
+    ) -> Result<(), Error> {
+        match statement {
+            Statement::Constraint(quad, lin, error) => match lin.is_assignee(witness) {
+                true => {
+                    let val = quad.evaluate(witness).unwrap();
+                    witness.insert(lin.0.get(0).unwrap().0, val);
+                }
+                false => {
+                    let lhs_value = quad.evaluate(witness).unwrap();
+                    let rhs_value = lin.evaluate(witness).unwrap();
+                    if lhs_value != rhs_value {
+                        return Err(Error::UnsatisfiedConstraint { error });
                     }
                 }
+            },
+            Statement::Directive(ref d) => {
+                let mut inputs: Vec<_> = d
+                    .inputs
+                    .iter()
+                    .map(|i| i.evaluate(witness).unwrap())
+                    .collect();
+
+                let res = match (&d.solver, self.should_try_out_of_range) {
+                    (Solver::Bits(bitwidth), true) if *bitwidth >= T::get_required_bits() => Ok(
+                        Self::try_solve_with_out_of_range_bits(*bitwidth, inputs.pop().unwrap()),
+                    ),
+                    _ => Self::execute_solver(&d.solver, &inputs),
+                }
+                .map_err(|_| Error::Solver)?;
+
+                for (i, o) in d.outputs.iter().enumerate() {
+                    witness.insert(*o, res[i].clone());
+                }
             }
         }
 
-        Ok(Witness(witness))
+        Ok(())
     }
 
     fn try_solve_with_out_of_range_bits<T: Field>(bit_width: usize, input: T) -> Vec<T> {
@@ -108,7 +115,7 @@ impl Interpreter {
             .collect()
     }
 
-    fn check_inputs<T: Field, I: IntoIterator<Item = Statement<T>>, U>(
+    pub(super) fn check_inputs<T: Field, I: IntoIterator<Item = Statement<T>>, U>(
         &self,
         program: &ProgIterator<T, I>,
         inputs: &[U],
@@ -190,44 +197,82 @@ impl Interpreter {
                 let r = n - d * &q;
                 vec![T::try_from(q).unwrap(), T::try_from(r).unwrap()]
             }
-            #[cfg(feature = "bellman")]
             Solver::Sha256Round => {
-                use pairing_ce::bn256::Bn256;
-                use zokrates_embed::bellman::generate_sha256_round_witness;
-                use zokrates_field::Bn128Field;
-                assert_eq!(T::id(), Bn128Field::id());
-                let i = &inputs[0..512];
-                let h = &inputs[512..];
-                let to_fr = |x: &T| {
-                    use pairing_ce::ff::{PrimeField, ScalarEngine};
-                    let s = x.to_dec_string();
-                    <Bn256 as ScalarEngine>::Fr::from_str(&s).unwrap()
-                };
-                let i: Vec<_> = i.iter().map(|x| to_fr(x)).collect();
-                let h: Vec<_> = h.iter().map(|x| to_fr(x)).collect();
-                assert_eq!(h.len(), 256);
-                generate_sha256_round_witness::<Bn256>(&i, &h)
-                    .into_iter()
-                    .map(|x| {
-                        use bellman_ce::pairing::ff::{PrimeField, PrimeFieldRepr};
-                        let mut res: Vec<u8> = vec![];
-                        x.into_repr().write_le(&mut res).unwrap();
-                        T::from_byte_vector(res)
-                    })
-                    .collect()
+                use zokrates_embed::sha256::generate_sha256_round_witness;
+
+                generate_sha256_round_witness(inputs)
             }
             #[cfg(feature = "ark")]
             Solver::SnarkVerifyBls12377(n) => {
-                use zokrates_embed::ark::generate_verify_witness;
+                use zokrates_embed::ark::{generate_verify_witness, Gm17};
+                use zokrates_field::Bw6_761Field;
+                assert_eq!(T::id(), Bw6_761Field::id());
+
+                generate_verify_witness::<Gm17, _>(
+                    &inputs[..*n],
+                    &inputs[*n..*n + 8usize],
+                    &inputs[*n + 8usize..],
+                )
+            }
+            #[cfg(feature = "ark")]
+            Solver::SnarkVerifyGroth16Bls12377(n) => {
+                use zokrates_embed::ark::{generate_verify_witness, Groth16};
                 use zokrates_field::Bw6_761Field;
                 assert_eq!(T::id(), Bw6_761Field::id());
 
-                generate_verify_witness(
+                generate_verify_witness::<Groth16, _>(
                     &inputs[..*n],
                     &inputs[*n..*n + 8usize],
                     &inputs[*n + 8usize..],
                 )
             }
+            Solver::PoseidonPermutation(_) => {
+                use zokrates_embed::poseidon::generate_poseidon_permutation_witness;
+                use zokrates_field::Bn128Field;
+                assert_eq!(T::id(), Bn128Field::id());
+
+                generate_poseidon_permutation_witness(inputs)
+            }
+            Solver::KeccakF1600 => {
+                use zokrates_embed::keccak::generate_keccak_f1600_witness;
+
+                generate_keccak_f1600_witness(inputs)
+            }
+            Solver::Blake2sRound => {
+                use zokrates_embed::blake2s::generate_blake2s_round_witness;
+
+                generate_blake2s_round_witness(inputs)
+            }
+            Solver::EcdsaSecp256k1Verify => {
+                use zokrates_embed::secp256k1::generate_ecdsa_secp256k1_verify_witness;
+
+                generate_ecdsa_secp256k1_verify_witness(inputs)
+            }
+            Solver::BigIntMulMod(modulus) => {
+                use zokrates_embed::bigint::generate_mul_mod_witness;
+
+                generate_mul_mod_witness(modulus, inputs)
+            }
+            Solver::BigIntReduce(_, modulus) => {
+                use zokrates_embed::bigint::generate_reduce_witness;
+
+                generate_reduce_witness(modulus, inputs)
+            }
+            Solver::EdwardsAdd => {
+                use zokrates_embed::babyjubjub::generate_edwards_add_witness;
+
+                generate_edwards_add_witness(inputs)?
+            }
+            Solver::EdwardsScalarMult => {
+                use zokrates_embed::babyjubjub::generate_edwards_scalar_mult_witness;
+
+                generate_edwards_scalar_mult_witness(inputs)?
+            }
+            Solver::EdwardsFixedBaseScalarMult => {
+                use zokrates_embed::babyjubjub::generate_edwards_fixed_base_scalar_mult_witness;
+
+                generate_edwards_fixed_base_scalar_mult_witness(inputs)?
+            }
         };
 
         assert_eq!(res.len(), expected_output_count);
@@ -236,11 +281,38 @@ impl Interpreter {
     }
 }
 
+/// Values assigned to variables during execution, either in a full witness or in a part of it
+pub(super) trait Assignment<T> {
+    fn get(&self, variable: &FlatVariable) -> Option<&T>;
+
+    fn insert(&mut self, variable: FlatVariable, value: T);
+}
+
+impl<T> Assignment<T> for Witness<T> {
+    fn get(&self, variable: &FlatVariable) -> Option<&T> {
+        Witness::get(self, variable)
+    }
+
+    fn insert(&mut self, variable: FlatVariable, value: T) {
+        Witness::insert(self, variable, value)
+    }
+}
+
+impl<T> Assignment<T> for BTreeMap<FlatVariable, T> {
+    fn get(&self, variable: &FlatVariable) -> Option<&T> {
+        BTreeMap::get(self, variable)
+    }
+
+    fn insert(&mut self, variable: FlatVariable, value: T) {
+        BTreeMap::insert(self, variable, value);
+    }
+}
+
 #[derive(Debug)]
 pub struct EvaluationError;
 
 impl<T: Field> LinComb<T> {
-    fn evaluate(&self, witness: &BTreeMap<FlatVariable, T>) -> Result<T, EvaluationError> {
+    fn evaluate<W: Assignment<T>>(&self, witness: &W) -> Result<T, EvaluationError> {
         self.0
             .iter()
             .map(|(var, mult)| {
@@ -253,15 +325,15 @@ impl<T: Field> LinComb<T> {
             .map(|v| v.iter().fold(T::from(0), |acc, t| acc + t)) // return the sum
     }
 
-    fn is_assignee<U>(&self, witness: &BTreeMap<FlatVariable, U>) -> bool {
+    fn is_assignee<W: Assignment<T>>(&self, witness: &W) -> bool {
         self.0.len() == 1
             && self.0.get(0).unwrap().1 == T::from(1)
-            && !witness.contains_key(&self.0.get(0).unwrap().0)
+            && witness.get(&self.0.get(0).unwrap().0).is_none()
     }
 }
 
 impl<T: Field> QuadComb<T> {
-    pub fn evaluate(&self, witness: &BTreeMap<FlatVariable, T>) -> Result<T, EvaluationError> {
+    pub(super) fn evaluate<W: Assignment<T>>(&self, witness: &W) -> Result<T, EvaluationError> {
         let left = self.left.evaluate(witness)?;
         let right = self.right.evaluate(witness)?;
         Ok(left * right)
@@ -275,6 +347,17 @@ pub enum Error {
     WrongInputCount { expected: usize, received: usize },
 }
 
+impl Error {
+    /// A stable code identifying the kind of error, as reported in diagnostics
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::UnsatisfiedConstraint { .. } => "E0700",
+            Error::Solver => "E0701",
+            Error::WrongInputCount { .. } => "E0702",
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
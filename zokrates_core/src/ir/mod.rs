@@ -6,10 +6,13 @@ use std::fmt;
 use std::hash::Hash;
 use zokrates_field::Field;
 
+pub mod bytecode;
 mod expression;
 pub mod folder;
 pub mod from_flat;
 mod interpreter;
+#[cfg(feature = "parallel")]
+mod parallel;
 mod serialize;
 pub mod smtlib2;
 pub mod visitor;
@@ -17,10 +20,10 @@ mod witness;
 
 pub use self::expression::QuadComb;
 pub use self::expression::{CanonicalLinComb, LinComb};
-pub use self::serialize::ProgEnum;
+pub use self::serialize::{ProgEnum, ReadError, StatementIterator};
 
 pub use self::interpreter::{Error, ExecutionResult, Interpreter};
-pub use self::witness::Witness;
+pub use self::witness::{Layout, Witness, WitnessReader, WitnessSource};
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq)]
 pub enum Statement<T> {
@@ -98,13 +101,17 @@ impl<T, I: IntoIterator<Item = Statement<T>>> ProgIterator<T, I> {
 }
 
 impl<T: Field, I: IntoIterator<Item = Statement<T>>> ProgIterator<T, I> {
-    pub fn public_inputs(&self, witness: &Witness<T>) -> Vec<T> {
-        self.arguments
+    pub fn public_inputs<W: WitnessSource<T>>(&self, witness: &mut W) -> Vec<T> {
+        let mut inputs: Vec<_> = self
+            .arguments
             .iter()
             .filter(|p| !p.private)
-            .map(|p| witness.0.get(&p.id).unwrap().clone())
-            .chain(witness.return_values())
-            .collect()
+            .map(|p| witness.value(&p.id).unwrap())
+            .collect();
+
+        inputs.extend(self.returns().iter().map(|v| witness.value(v).unwrap()));
+
+        inputs
     }
 }
 
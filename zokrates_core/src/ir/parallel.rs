@@ -0,0 +1,550 @@
+//! Parallel witness computation
+//!
+//! The statements of a program are split into contiguous segments. A segment depends on the
+//! segments which define the variables it reads, and is solved as soon as all of them are.
+//! Independent sub-circuits, for example hashes of unrelated inputs, end up in independent
+//! segments which are solved concurrently on a pool of threads.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{Error, LinComb, QuadComb};
+use crate::ir::{ExecutionResult, Interpreter, Layout, ProgIterator, Statement, Witness};
+use std::any::Any;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use zokrates_field::Field;
+
+/// The number of statements in a segment
+const SEGMENT_SIZE: usize = 1024;
+
+type Assignment<T> = BTreeMap<FlatVariable, T>;
+
+/// Where the value of a variable read by a segment is defined
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Source {
+    Input,
+    Segment(usize),
+}
+
+#[derive(Debug, Default)]
+struct SegmentInfo {
+    /// The variables read by the segment but defined outside of it
+    reads: Vec<(FlatVariable, Source)>,
+    /// The segments this segment reads from
+    dependencies: BTreeSet<usize>,
+    /// The segments reading from this segment
+    dependents: Vec<usize>,
+}
+
+struct State<T> {
+    statements: Vec<Option<Vec<Statement<T>>>>,
+    ready: BinaryHeap<Reverse<usize>>,
+    pending: Vec<usize>,
+    running: usize,
+    solved: Vec<Option<Arc<Assignment<T>>>>,
+    errors: BTreeMap<usize, Error>,
+    panic: Option<Box<dyn Any + Send>>,
+}
+
+struct Shared<T> {
+    inputs: Assignment<T>,
+    segments: Vec<SegmentInfo>,
+    state: Mutex<State<T>>,
+    condvar: Condvar,
+}
+
+impl Interpreter {
+    /// Compute the witness of `program` using all available cores.
+    ///
+    /// The resulting witness is the same as the one returned by `Interpreter::execute`. If the
+    /// execution fails, the error is the one `Interpreter::execute` would have returned.
+    pub fn execute_parallel<T: Field, I: IntoIterator<Item = Statement<T>>>(
+        &self,
+        program: ProgIterator<T, I>,
+        inputs: &[T],
+    ) -> ExecutionResult<T> {
+        self.execute_segments(program, inputs, SEGMENT_SIZE, num_cpus::get())
+    }
+
+    fn execute_segments<T: Field, I: IntoIterator<Item = Statement<T>>>(
+        &self,
+        program: ProgIterator<T, I>,
+        inputs: &[T],
+        segment_size: usize,
+        thread_count: usize,
+    ) -> ExecutionResult<T> {
+        self.check_inputs(&program, inputs)?;
+
+        let layout = Layout::new(program.arguments.len(), program.return_count);
+
+        let mut assignment = BTreeMap::new();
+        assignment.insert(FlatVariable::one(), T::one());
+
+        for (arg, value) in program.arguments.iter().zip(inputs.iter()) {
+            assignment.insert(arg.id, value.clone());
+        }
+
+        let statements: Vec<_> = program.statements.into_iter().collect();
+
+        let (statements, segments) = split(statements, &assignment, segment_size);
+
+        let pending: Vec<_> = segments.iter().map(|s| s.dependencies.len()).collect();
+
+        let ready = pending
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count == 0)
+            .map(|(index, _)| Reverse(index))
+            .collect();
+
+        let shared = Arc::new(Shared {
+            inputs: assignment,
+            state: Mutex::new(State {
+                solved: vec![None; statements.len()],
+                statements: statements.into_iter().map(Some).collect(),
+                ready,
+                pending,
+                running: 0,
+                errors: BTreeMap::new(),
+                panic: None,
+            }),
+            segments,
+            condvar: Condvar::new(),
+        });
+
+        let handles: Vec<_> = (0..thread_count.max(1))
+            .map(|_| {
+                let interpreter = self.clone();
+                let shared = shared.clone();
+                thread::spawn(move || work(interpreter, shared))
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let shared = Arc::try_unwrap(shared)
+            .unwrap_or_else(|_| panic!("all workers should have terminated"));
+
+        let state = shared.state.into_inner().unwrap();
+
+        if let Some(payload) = state.panic {
+            panic::resume_unwind(payload);
+        }
+
+        // the first failing segment is the one the sequential interpreter would have failed in
+        if let Some((_, error)) = state.errors.into_iter().next() {
+            return Err(error);
+        }
+
+        let mut witness = Witness::new(layout);
+
+        for (variable, value) in shared.inputs {
+            witness.insert(variable, value);
+        }
+
+        for assignment in state.solved.into_iter().map(Option::unwrap) {
+            let assignment = Arc::try_unwrap(assignment).unwrap_or_else(|a| (*a).clone());
+            for (variable, value) in assignment {
+                witness.insert(variable, value);
+            }
+        }
+
+        Ok(witness)
+    }
+}
+
+fn work<T: Field>(interpreter: Interpreter, shared: Arc<Shared<T>>) {
+    loop {
+        let (index, statements, dependencies) = {
+            let mut state = shared.state.lock().unwrap();
+
+            let index = loop {
+                // once a segment panicked, no new segment is started
+                if state.panic.is_none() {
+                    if let Some(Reverse(index)) = state.ready.pop() {
+                        break index;
+                    }
+                }
+                if state.running == 0 {
+                    // nothing is ready and nothing will become ready: we are done
+                    shared.condvar.notify_all();
+                    return;
+                }
+                state = shared.condvar.wait(state).unwrap();
+            };
+
+            state.running += 1;
+
+            let statements = state.statements[index].take().unwrap();
+            let dependencies: HashMap<_, _> = shared.segments[index]
+                .dependencies
+                .iter()
+                .map(|d| (*d, state.solved[*d].clone().unwrap()))
+                .collect();
+
+            (index, statements, dependencies)
+        };
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut witness: Assignment<T> = shared.segments[index]
+                .reads
+                .iter()
+                .filter_map(|(variable, source)| {
+                    let value = match source {
+                        Source::Input => shared.inputs.get(variable),
+                        Source::Segment(s) => dependencies[s].get(variable),
+                    };
+                    value.map(|value| (*variable, value.clone()))
+                })
+                .collect();
+
+            statements
+                .into_iter()
+                .try_for_each(|s| interpreter.execute_statement(s, &mut witness))
+                .map(|_| witness)
+        }));
+
+        let mut state = shared.state.lock().unwrap();
+        state.running -= 1;
+
+        match result {
+            Ok(Ok(witness)) => {
+                state.solved[index] = Some(Arc::new(witness));
+                for dependent in &shared.segments[index].dependents {
+                    state.pending[*dependent] -= 1;
+                    if state.pending[*dependent] == 0 {
+                        state.ready.push(Reverse(*dependent));
+                    }
+                }
+            }
+            Ok(Err(e)) => {
+                state.errors.insert(index, e);
+            }
+            Err(payload) => {
+                // let the caller resume the panic
+                state.panic.get_or_insert(payload);
+            }
+        }
+
+        shared.condvar.notify_all();
+    }
+}
+
+/// Split `statements` into segments of at most `segment_size` statements, and compute the
+/// dependencies between them.
+/// `inputs` contains the variables which are known before execution starts.
+fn split<T: Field>(
+    statements: Vec<Statement<T>>,
+    inputs: &Assignment<T>,
+    segment_size: usize,
+) -> (Vec<Vec<Statement<T>>>, Vec<SegmentInfo>) {
+    let mut defined: HashMap<FlatVariable, Source> =
+        inputs.keys().map(|v| (*v, Source::Input)).collect();
+
+    let mut segments: Vec<Vec<Statement<T>>> = vec![];
+    let mut infos: Vec<SegmentInfo> = vec![];
+    let mut seen = HashSet::new();
+
+    for statement in statements {
+        if segments
+            .last()
+            .map(|s| s.len() == segment_size)
+            .unwrap_or(true)
+        {
+            segments.push(vec![]);
+            infos.push(SegmentInfo::default());
+            seen.clear();
+        }
+
+        let index = segments.len() - 1;
+
+        let (reads, writes) = read_write(&statement, &defined);
+
+        for variable in reads {
+            match defined.get(&variable) {
+                Some(Source::Segment(s)) if *s == index => {}
+                Some(source) => {
+                    if seen.insert(variable) {
+                        infos[index].reads.push((variable, *source));
+                        if let Source::Segment(s) = source {
+                            infos[index].dependencies.insert(*s);
+                        }
+                    }
+                }
+                // reading an undefined variable fails during execution, as it does sequentially
+                None => {}
+            }
+        }
+
+        // directives may write to variables which are already defined, in which case later
+        // statements read the latest value like they do sequentially
+        for variable in writes {
+            defined.insert(variable, Source::Segment(index));
+        }
+
+        segments.last_mut().unwrap().push(statement);
+    }
+
+    for index in 0..infos.len() {
+        for dependency in infos[index].dependencies.clone() {
+            infos[dependency].dependents.push(index);
+        }
+    }
+
+    (segments, infos)
+}
+
+/// Return the variables read and written by `statement`, given the variables `defined` before it
+fn read_write<T: Field, U>(
+    statement: &Statement<T>,
+    defined: &HashMap<FlatVariable, U>,
+) -> (Vec<FlatVariable>, Vec<FlatVariable>) {
+    fn variables<T>(quad: &QuadComb<T>) -> impl Iterator<Item = FlatVariable> + '_ {
+        quad.left
+            .0
+            .iter()
+            .chain(quad.right.0.iter())
+            .map(|(v, _)| *v)
+    }
+
+    match statement {
+        Statement::Constraint(quad, lin, _) => match assignee(lin, defined) {
+            Some(v) => (variables(quad).collect(), vec![v]),
+            None => (
+                variables(quad)
+                    .chain(lin.0.iter().map(|(v, _)| *v))
+                    .collect(),
+                vec![],
+            ),
+        },
+        Statement::Directive(d) => (
+            d.inputs.iter().flat_map(variables).collect(),
+            d.outputs.clone(),
+        ),
+    }
+}
+
+/// Return the variable assigned to by a constraint with right hand side `lin`, if any. This mirrors
+/// the way `Interpreter::execute` decides whether a constraint is an assignment.
+fn assignee<T: Field, U>(
+    lin: &LinComb<T>,
+    defined: &HashMap<FlatVariable, U>,
+) -> Option<FlatVariable> {
+    match lin.0.as_slice() {
+        [(v, coefficient)] if *coefficient == T::from(1) && !defined.contains_key(v) => Some(*v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::{FlatParameter, RuntimeError};
+    use crate::ir::{Directive, Prog};
+    use crate::solvers::Solver;
+    use zokrates_field::Bn128Field;
+
+    /// Two independent chains of squarings of the two arguments, each followed by a bit
+    /// decomposition, and a final constraint mixing both chains
+    fn program(length: usize) -> Prog<Bn128Field> {
+        let mut statements = vec![];
+
+        for (chain, input) in [FlatVariable::new(0), FlatVariable::new(1)]
+            .iter()
+            .enumerate()
+        {
+            let mut previous = *input;
+
+            for i in 0..length {
+                let current = FlatVariable::new(2 + chain * length + i);
+                statements.push(Statement::definition(
+                    current,
+                    QuadComb::from_linear_combinations(previous.into(), previous.into()),
+                ));
+                previous = current;
+            }
+
+            let bits: Vec<_> = (0..254)
+                .map(|i| FlatVariable::new(1000 + 254 * chain + i))
+                .collect();
+
+            statements.push(Statement::Directive(Directive {
+                inputs: vec![previous.into()],
+                outputs: bits,
+                solver: Solver::Bits(254),
+            }));
+        }
+
+        statements.push(Statement::definition(
+            FlatVariable::public(0),
+            QuadComb::from_linear_combinations(
+                FlatVariable::new(1 + length).into(),
+                FlatVariable::new(1 + 2 * length).into(),
+            ),
+        ));
+
+        Prog {
+            arguments: vec![
+                FlatParameter::private(FlatVariable::new(0)),
+                FlatParameter::private(FlatVariable::new(1)),
+            ],
+            return_count: 1,
+            statements,
+        }
+    }
+
+    #[test]
+    fn same_witness_as_sequential() {
+        let program = program(10);
+        let inputs = vec![Bn128Field::from(2), Bn128Field::from(3)];
+
+        let expected = Interpreter::default()
+            .execute(program.clone(), &inputs)
+            .unwrap();
+
+        for segment_size in &[1, 2, 3, 7, 1024] {
+            for thread_count in &[1, 2, 4] {
+                let witness = Interpreter::default()
+                    .execute_segments(program.clone(), &inputs, *segment_size, *thread_count)
+                    .unwrap();
+                assert_eq!(witness, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn redefined_variable() {
+        // _2 = a * a
+        // # _2 = Div(b, ~one)
+        // ~out_0 = _2 * b
+        let program: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                FlatParameter::private(FlatVariable::new(0)),
+                FlatParameter::private(FlatVariable::new(1)),
+            ],
+            return_count: 1,
+            statements: vec![
+                Statement::definition(
+                    FlatVariable::new(2),
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::new(0).into(),
+                    ),
+                ),
+                Statement::Directive(Directive {
+                    inputs: vec![FlatVariable::new(1).into(), FlatVariable::one().into()],
+                    outputs: vec![FlatVariable::new(2)],
+                    solver: Solver::Div,
+                }),
+                Statement::definition(
+                    FlatVariable::public(0),
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(2).into(),
+                        FlatVariable::new(1).into(),
+                    ),
+                ),
+            ],
+        };
+
+        let inputs = vec![Bn128Field::from(2), Bn128Field::from(3)];
+
+        let expected = Interpreter::default()
+            .execute(program.clone(), &inputs)
+            .unwrap();
+
+        for segment_size in &[1, 2, 3] {
+            let witness = Interpreter::default()
+                .execute_segments(program.clone(), &inputs, *segment_size, 2)
+                .unwrap();
+            assert_eq!(witness, expected);
+        }
+    }
+
+    #[test]
+    fn independent_chains() {
+        let program = program(5);
+
+        let inputs = vec![
+            FlatVariable::one(),
+            FlatVariable::new(0),
+            FlatVariable::new(1),
+        ]
+        .into_iter()
+        .map(|v| (v, Bn128Field::from(1)))
+        .collect();
+
+        let (_, segments) = split(program.statements, &inputs, 2);
+
+        // each chain spans three segments, and the final constraint is alone in the last one
+        assert_eq!(segments.len(), 7);
+        assert!(segments[0].dependencies.is_empty());
+        assert_eq!(
+            segments[1].dependencies,
+            vec![0].into_iter().collect::<BTreeSet<_>>()
+        );
+        assert!(segments[3].dependencies.is_empty());
+        assert_eq!(
+            segments[3].reads,
+            vec![(FlatVariable::new(1), Source::Input)]
+        );
+        assert_eq!(
+            segments[6].dependencies,
+            vec![2, 5].into_iter().collect::<BTreeSet<_>>()
+        );
+    }
+
+    #[test]
+    fn same_error_as_sequential() {
+        let mut program = program(10);
+
+        // two unsatisfied constraints, one in each chain
+        program.statements.insert(
+            13,
+            Statement::Constraint(
+                FlatVariable::new(13).into(),
+                LinComb::zero(),
+                Some(RuntimeError::SourceAssertion("second".into())),
+            ),
+        );
+        program.statements.insert(
+            3,
+            Statement::Constraint(
+                FlatVariable::new(4).into(),
+                LinComb::zero(),
+                Some(RuntimeError::SourceAssertion("first".into())),
+            ),
+        );
+
+        let inputs = vec![Bn128Field::from(2), Bn128Field::from(3)];
+
+        let expected = Interpreter::default()
+            .execute(program.clone(), &inputs)
+            .unwrap_err();
+
+        for segment_size in &[1, 4, 1024] {
+            let error = Interpreter::default()
+                .execute_segments(program.clone(), &inputs, *segment_size, 4)
+                .unwrap_err();
+            assert_eq!(error, expected);
+        }
+    }
+
+    #[test]
+    fn wrong_input_count() {
+        let error = Interpreter::default()
+            .execute_parallel(program(1), &[Bn128Field::from(2)])
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            Error::WrongInputCount {
+                expected: 2,
+                received: 1
+            }
+        );
+    }
+}
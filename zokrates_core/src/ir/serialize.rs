@@ -1,10 +1,13 @@
+use crate::flat_absy::FlatParameter;
 use crate::ir::{Prog, ProgIterator, Statement};
-use serde_cbor;
+use serde_cbor::{self, de::IoRead, StreamDeserializer};
 use std::io::{Read, Write};
 use zokrates_field::*;
 
 const ZOKRATES_MAGIC: &[u8; 4] = &[0x5a, 0x4f, 0x4b, 0];
 const ZOKRATES_VERSION_1: &[u8; 4] = &[0, 0, 0, 1];
+// version 2 writes a header followed by a sequence of statements, so that they can be read lazily
+const ZOKRATES_VERSION_2: &[u8; 4] = &[0, 0, 0, 2];
 
 #[derive(PartialEq, Debug)]
 pub enum ProgEnum<
@@ -26,8 +29,113 @@ type MemoryProgEnum = ProgEnum<
     Vec<Statement<Bw6_761Field>>,
 >;
 
-use serde::{Serialize, Serializer};
-use std::cell::Cell;
+use serde::{Deserialize, Serialize, Serializer};
+use std::cell::{Cell, RefCell};
+use std::rc::Rc;
+
+#[derive(Serialize, Deserialize)]
+struct ProgHeader {
+    arguments: Vec<FlatParameter>,
+    return_count: usize,
+}
+
+/// The statements of a deserialized program.
+/// Programs serialized with version 1 are read in memory at once, while those serialized with
+/// version 2 are read one statement at a time as the iterator is consumed.
+/// If a statement cannot be read, iteration stops and the error is recorded in the iterator's
+/// `ReadError`, which should be checked once the statements are consumed.
+pub enum StatementIterator<T, R: Read> {
+    Memory(std::vec::IntoIter<Statement<T>>),
+    Stream(
+        StreamDeserializer<'static, IoRead<R>, Statement<T>>,
+        ReadError,
+    ),
+}
+
+/// The error met while reading the statements of a program, if any. It is shared between the
+/// `StatementIterator` reading the statements and its consumers.
+#[derive(Clone, Debug, Default)]
+pub struct ReadError(Rc<RefCell<Option<String>>>);
+
+impl ReadError {
+    /// Check that all statements read so far were read successfully
+    pub fn check(&self) -> Result<(), String> {
+        match &*self.0.borrow() {
+            Some(e) => Err(format!("Cannot read statement: {}", e)),
+            None => Ok(()),
+        }
+    }
+
+    fn is_set(&self) -> bool {
+        self.0.borrow().is_some()
+    }
+
+    fn set(&self, error: String) {
+        *self.0.borrow_mut() = Some(error);
+    }
+}
+
+impl<T, R: Read> StatementIterator<T, R> {
+    pub fn read_error(&self) -> ReadError {
+        match self {
+            StatementIterator::Memory(_) => ReadError::default(),
+            StatementIterator::Stream(_, error) => error.clone(),
+        }
+    }
+}
+
+impl<T: Field, R: Read> Iterator for StatementIterator<T, R> {
+    type Item = Statement<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            StatementIterator::Memory(statements) => statements.next(),
+            StatementIterator::Stream(_, error) if error.is_set() => None,
+            StatementIterator::Stream(statements, error) => match statements.next()? {
+                Ok(statement) => Some(statement),
+                Err(e) => {
+                    error.set(e.to_string());
+                    None
+                }
+            },
+        }
+    }
+}
+
+impl<R: Read>
+    ProgEnum<
+        StatementIterator<Bls12_381Field, R>,
+        StatementIterator<Bn128Field, R>,
+        StatementIterator<Bls12_377Field, R>,
+        StatementIterator<Bw6_761Field, R>,
+    >
+{
+    /// The error met while reading the statements of this program. Consumers must check it
+    /// before using the results of iterating over the statements.
+    pub fn read_error(&self) -> ReadError {
+        match self {
+            ProgEnum::Bls12_381Program(p) => p.statements.read_error(),
+            ProgEnum::Bn128Program(p) => p.statements.read_error(),
+            ProgEnum::Bls12_377Program(p) => p.statements.read_error(),
+            ProgEnum::Bw6_761Program(p) => p.statements.read_error(),
+        }
+    }
+
+    /// Read all statements of this program without keeping them, to check that none of them is
+    /// unreadable before starting a computation which cannot be interrupted
+    pub fn check_statements(self) -> Result<(), String> {
+        let read_error = self.read_error();
+
+        match self {
+            ProgEnum::Bls12_381Program(p) => p.statements.for_each(drop),
+            ProgEnum::Bn128Program(p) => p.statements.for_each(drop),
+            ProgEnum::Bls12_377Program(p) => p.statements.for_each(drop),
+            ProgEnum::Bw6_761Program(p) => p.statements.for_each(drop),
+        }
+
+        read_error.check()
+    }
+}
 
 impl<
         Bls12_381I: IntoIterator<Item = Statement<Bls12_381Field>>,
@@ -57,7 +165,7 @@ where
     struct Wrapper<U>(Cell<Option<U>>);
 
     struct SerializableProgIterator<T: Serialize, I: IntoIterator<Item = Statement<T>>> {
-        arguments: Vec<crate::flat_absy::FlatParameter>,
+        arguments: Vec<FlatParameter>,
         return_count: usize,
         statements: Wrapper<I>,
     }
@@ -101,34 +209,72 @@ where
 impl<T: Field, I: IntoIterator<Item = Statement<T>>> ProgIterator<T, I> {
     pub fn serialize<W: Write>(self, mut w: W) {
         w.write_all(ZOKRATES_MAGIC).unwrap();
-        w.write_all(ZOKRATES_VERSION_1).unwrap();
+        w.write_all(ZOKRATES_VERSION_2).unwrap();
         w.write_all(&T::id()).unwrap();
 
-        write_as_cbor(&mut w, self).unwrap();
+        serde_cbor::to_writer(
+            &mut w,
+            &ProgHeader {
+                arguments: self.arguments,
+                return_count: self.return_count,
+            },
+        )
+        .unwrap();
+
+        for statement in self.statements {
+            serde_cbor::to_writer(&mut w, &statement).unwrap();
+        }
     }
 }
 
-impl
+fn read_program<T: Field, R: Read>(
+    version: &[u8; 4],
+    r: R,
+) -> Result<ProgIterator<T, StatementIterator<T, R>>, String> {
+    if version == ZOKRATES_VERSION_1 {
+        let p: Prog<T> =
+            serde_cbor::from_reader(r).map_err(|e| format!("Cannot read program: {}", e))?;
+
+        Ok(ProgIterator {
+            statements: StatementIterator::Memory(p.statements.into_iter()),
+            arguments: p.arguments,
+            return_count: p.return_count,
+        })
+    } else {
+        let mut deserializer = serde_cbor::Deserializer::from_reader(r);
+
+        let header = ProgHeader::deserialize(&mut deserializer)
+            .map_err(|e| format!("Cannot read program header: {}", e))?;
+
+        Ok(ProgIterator {
+            statements: StatementIterator::Stream(deserializer.into_iter(), ReadError::default()),
+            arguments: header.arguments,
+            return_count: header.return_count,
+        })
+    }
+}
+
+impl<R: Read>
     ProgEnum<
-        std::vec::IntoIter<Statement<Bls12_381Field>>,
-        std::vec::IntoIter<Statement<Bn128Field>>,
-        std::vec::IntoIter<Statement<Bls12_377Field>>,
-        std::vec::IntoIter<Statement<Bw6_761Field>>,
+        StatementIterator<Bls12_381Field, R>,
+        StatementIterator<Bn128Field, R>,
+        StatementIterator<Bls12_377Field, R>,
+        StatementIterator<Bw6_761Field, R>,
     >
 {
-    pub fn deserialize<R: Read>(mut r: R) -> Result<Self, String> {
+    pub fn deserialize(mut r: R) -> Result<Self, String> {
         // Check the magic number, `ZOK`
         let mut magic = [0; 4];
         r.read_exact(&mut magic)
             .map_err(|_| String::from("Cannot read magic number"))?;
 
         if &magic == ZOKRATES_MAGIC {
-            // Check the version, 1
+            // Check the version, 1 or 2
             let mut version = [0; 4];
             r.read_exact(&mut version)
                 .map_err(|_| String::from("Cannot read version"))?;
 
-            if &version == ZOKRATES_VERSION_1 {
+            if &version == ZOKRATES_VERSION_1 || &version == ZOKRATES_VERSION_2 {
                 // Check the curve identifier, deserializing accordingly
                 let mut curve = [0; 4];
                 r.read_exact(&mut curve)
@@ -136,40 +282,16 @@ impl
 
                 match curve {
                     m if m == Bls12_381Field::id() => {
-                        let p: Prog<Bls12_381Field> = serde_cbor::from_reader(r).unwrap();
-
-                        Ok(ProgEnum::Bls12_381Program(ProgIterator {
-                            statements: p.statements.into_iter(),
-                            arguments: p.arguments,
-                            return_count: p.return_count,
-                        }))
+                        Ok(ProgEnum::Bls12_381Program(read_program(&version, r)?))
                     }
                     m if m == Bn128Field::id() => {
-                        let p: Prog<Bn128Field> = serde_cbor::from_reader(r).unwrap();
-
-                        Ok(ProgEnum::Bn128Program(ProgIterator {
-                            statements: p.statements.into_iter(),
-                            arguments: p.arguments,
-                            return_count: p.return_count,
-                        }))
+                        Ok(ProgEnum::Bn128Program(read_program(&version, r)?))
                     }
                     m if m == Bls12_377Field::id() => {
-                        let p: Prog<Bls12_377Field> = serde_cbor::from_reader(r).unwrap();
-
-                        Ok(ProgEnum::Bls12_377Program(ProgIterator {
-                            statements: p.statements.into_iter(),
-                            arguments: p.arguments,
-                            return_count: p.return_count,
-                        }))
+                        Ok(ProgEnum::Bls12_377Program(read_program(&version, r)?))
                     }
                     m if m == Bw6_761Field::id() => {
-                        let p: Prog<Bw6_761Field> = serde_cbor::from_reader(r).unwrap();
-
-                        Ok(ProgEnum::Bw6_761Program(ProgIterator {
-                            statements: p.statements.into_iter(),
-                            arguments: p.arguments,
-                            return_count: p.return_count,
-                        }))
+                        Ok(ProgEnum::Bw6_761Program(read_program(&version, r)?))
                     }
                     _ => Err(String::from("Unknown curve identifier")),
                 }
@@ -185,12 +307,24 @@ impl
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::flat_absy::FlatVariable;
     use crate::ir;
     use std::io::{Cursor, Seek, SeekFrom};
     use zokrates_field::{Bls12_381Field, Bn128Field};
 
+    fn program<T: Field>() -> ir::Prog<T> {
+        ir::Prog {
+            arguments: vec![FlatParameter::private(FlatVariable::new(0))],
+            return_count: 1,
+            statements: vec![
+                Statement::definition(FlatVariable::new(1), FlatVariable::new(0)),
+                Statement::constraint(FlatVariable::new(1), FlatVariable::public(0)),
+            ],
+        }
+    }
+
     #[test]
-    fn ser_deser_v1() {
+    fn ser_deser_v2() {
         let p: ir::Prog<Bn128Field> = ir::Prog::default();
 
         let mut buffer = Cursor::new(vec![]);
@@ -204,7 +338,7 @@ mod tests {
 
         assert_eq!(ProgEnum::Bn128Program(p), deserialized_p.collect());
 
-        let p: ir::Prog<Bls12_381Field> = ir::Prog::default();
+        let p: ir::Prog<Bls12_381Field> = program();
 
         let mut buffer = Cursor::new(vec![]);
         p.clone().serialize(&mut buffer);
@@ -217,4 +351,66 @@ mod tests {
 
         assert_eq!(ProgEnum::Bls12_381Program(p), deserialized_p.collect());
     }
+
+    #[test]
+    fn deser_v1() {
+        let p: ir::Prog<Bn128Field> = program();
+
+        // write the program in the format used before statements were streamed
+        let mut buffer = Cursor::new(vec![]);
+        buffer.write_all(ZOKRATES_MAGIC).unwrap();
+        buffer.write_all(ZOKRATES_VERSION_1).unwrap();
+        buffer.write_all(&Bn128Field::id()).unwrap();
+        write_as_cbor(&mut buffer, p.clone()).unwrap();
+
+        // rewind back to the beginning of the file
+        buffer.seek(SeekFrom::Start(0)).unwrap();
+
+        // deserialize
+        let deserialized_p = ProgEnum::deserialize(buffer).unwrap();
+
+        assert_eq!(ProgEnum::Bn128Program(p), deserialized_p.collect());
+    }
+
+    #[test]
+    fn unknown_version() {
+        let mut buffer = Cursor::new(vec![]);
+        buffer.write_all(ZOKRATES_MAGIC).unwrap();
+        buffer.write_all(&[0, 0, 0, 3]).unwrap();
+        buffer.write_all(&Bn128Field::id()).unwrap();
+
+        buffer.seek(SeekFrom::Start(0)).unwrap();
+
+        assert_eq!(
+            ProgEnum::deserialize(buffer).err(),
+            Some(String::from("Unknown version"))
+        );
+    }
+
+    #[test]
+    fn truncated() {
+        let p: ir::Prog<Bn128Field> = program();
+
+        let mut buffer = Cursor::new(vec![]);
+        p.serialize(&mut buffer);
+
+        // drop the end of the last statement
+        let mut bytes = buffer.into_inner();
+        bytes.truncate(bytes.len() - 2);
+
+        let deserialized_p = ProgEnum::deserialize(Cursor::new(bytes.clone())).unwrap();
+        let error = deserialized_p.read_error();
+
+        assert_eq!(error.check(), Ok(()));
+
+        match deserialized_p {
+            ProgEnum::Bn128Program(p) => assert_eq!(p.statements.count(), 1),
+            _ => unreachable!(),
+        }
+
+        assert!(error.check().is_err());
+
+        let deserialized_p = ProgEnum::deserialize(Cursor::new(bytes)).unwrap();
+        assert!(deserialized_p.check_statements().is_err());
+    }
 }
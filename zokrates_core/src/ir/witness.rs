@@ -1,30 +1,120 @@
 use crate::flat_absy::FlatVariable;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, VecDeque};
 use std::fmt;
 use std::io;
-use std::io::{Read, Write};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::iter::FromIterator;
 use zokrates_field::Field;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Witness<T>(pub BTreeMap<FlatVariable, T>);
+const ZOKRATES_WITNESS_MAGIC: &[u8; 4] = &[0x5a, 0x4f, 0x57, 0];
+const ZOKRATES_WITNESS_VERSION_1: &[u8; 4] = &[0, 0, 0, 1];
 
-impl<T: Field> Witness<T> {
-    pub fn return_values(&self) -> Vec<T> {
-        let out = self
-            .0
+/// The position of each variable of a program in a dense witness: `~one` comes first, then the
+/// arguments, then the return values, then the internal variables.
+/// This is contiguous for programs whose arguments are `_0..n` and whose internal variables
+/// follow without gaps, which is what `ProgIterator::renumber` produces.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct Layout {
+    argument_count: usize,
+    return_count: usize,
+}
+
+impl Layout {
+    pub fn new(argument_count: usize, return_count: usize) -> Self {
+        Layout {
+            argument_count,
+            return_count,
+        }
+    }
+
+    pub fn index(&self, variable: &FlatVariable) -> usize {
+        if *variable == FlatVariable::one() {
+            0
+        } else if variable.is_output() {
+            1 + self.argument_count + variable.output_id()
+        } else if variable.id() < self.argument_count {
+            1 + variable.id()
+        } else {
+            1 + self.return_count + variable.id()
+        }
+    }
+
+    pub fn variable(&self, index: usize) -> FlatVariable {
+        match index {
+            0 => FlatVariable::one(),
+            i if i <= self.argument_count => FlatVariable::new(i - 1),
+            i if i <= self.argument_count + self.return_count => {
+                FlatVariable::public(i - 1 - self.argument_count)
+            }
+            i => FlatVariable::new(i - 1 - self.return_count),
+        }
+    }
+}
+
+/// The values of the variables of a program, stored densely following a `Layout`
+#[derive(Clone, Debug)]
+pub struct Witness<T> {
+    layout: Layout,
+    values: Vec<Option<T>>,
+}
+
+impl<T> Witness<T> {
+    pub fn new(layout: Layout) -> Self {
+        Witness {
+            layout,
+            values: vec![],
+        }
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn get(&self, variable: &FlatVariable) -> Option<&T> {
+        self.get_index(self.layout.index(variable))
+    }
+
+    pub fn get_index(&self, index: usize) -> Option<&T> {
+        self.values.get(index).and_then(Option::as_ref)
+    }
+
+    pub fn contains(&self, variable: &FlatVariable) -> bool {
+        self.get(variable).is_some()
+    }
+
+    pub fn insert(&mut self, variable: FlatVariable, value: T) {
+        let index = self.layout.index(&variable);
+        if index >= self.values.len() {
+            self.values.resize_with(index + 1, || None);
+        }
+        self.values[index] = Some(value);
+    }
+
+    /// Take the value of `variable` out of the witness
+    pub fn remove(&mut self, variable: &FlatVariable) -> Option<T> {
+        let index = self.layout.index(variable);
+        self.values.get_mut(index).and_then(Option::take)
+    }
+
+    /// Iterate over the assigned variables in the order of the layout
+    pub fn iter(&self) -> impl Iterator<Item = (FlatVariable, &T)> {
+        let layout = self.layout;
+        self.values
             .iter()
-            .filter(|(k, _)| k.is_output())
-            .collect::<HashMap<_, _>>();
+            .enumerate()
+            .filter_map(move |(index, value)| value.as_ref().map(|v| (layout.variable(index), v)))
+    }
+}
 
-        (0..out.len())
-            .map(|i| *out.get(&FlatVariable::public(i)).unwrap())
-            .cloned()
+impl<T: Field> Witness<T> {
+    pub fn return_values(&self) -> Vec<T> {
+        (0..self.layout.return_count)
+            .map(|i| self.get(&FlatVariable::public(i)).unwrap().clone())
             .collect()
     }
 
     pub fn format_outputs(&self) -> String {
-        self.0
-            .iter()
+        self.iter()
             .filter_map(|(variable, value)| match variable {
                 variable if variable.is_output() => Some(format!("{} {}", variable, value)),
                 _ => None,
@@ -34,7 +124,7 @@ impl<T: Field> Witness<T> {
     }
 
     pub fn empty() -> Self {
-        Witness(BTreeMap::new())
+        Witness::new(Layout::default())
     }
 
     pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
@@ -45,7 +135,7 @@ impl<T: Field> Witness<T> {
             .from_writer(writer);
 
         // Write each line of the witness to the file
-        for (variable, value) in &self.0 {
+        for (variable, value) in self.iter() {
             wtr.serialize((variable.to_string(), value.to_dec_string()))?;
         }
 
@@ -83,9 +173,291 @@ impl<T: Field> Witness<T> {
                     e => io::Error::new(io::ErrorKind::Other, format!("{:?}", e)),
                 })?
             })
-            .collect::<io::Result<BTreeMap<FlatVariable, T>>>()?;
+            .collect::<io::Result<Witness<T>>>()?;
+
+        Ok(map)
+    }
+
+    /// Check whether `header` is the start of a witness written with `write_binary`
+    pub fn is_binary(header: &[u8]) -> bool {
+        header.starts_with(ZOKRATES_WITNESS_MAGIC)
+    }
+
+    /// Write the witness in a compact binary format: a header with the curve and the layout,
+    /// then for each variable in the layout a presence byte followed by the value, if any
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(ZOKRATES_WITNESS_MAGIC)?;
+        writer.write_all(ZOKRATES_WITNESS_VERSION_1)?;
+        writer.write_all(&T::id())?;
+
+        for count in &[
+            self.layout.argument_count,
+            self.layout.return_count,
+            self.values.len(),
+        ] {
+            writer.write_all(&(*count as u64).to_le_bytes())?;
+        }
+
+        for value in &self.values {
+            match value {
+                Some(value) => {
+                    writer.write_all(&[1])?;
+                    writer.write_all(&value.to_byte_vector())?;
+                }
+                None => writer.write_all(&[0])?,
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn read_binary<R: Read>(mut reader: R) -> io::Result<Self> {
+        let (layout, len) = read_binary_header::<T, _>(&mut reader)?;
+        let values = read_binary_values(&mut reader, len)?;
+
+        Ok(Witness { layout, values })
+    }
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Read the header of a binary witness, returning its layout and its number of values
+fn read_binary_header<T: Field, R: Read>(reader: &mut R) -> io::Result<(Layout, usize)> {
+    let mut header = [0; 4];
+
+    reader.read_exact(&mut header)?;
+    if &header != ZOKRATES_WITNESS_MAGIC {
+        return Err(invalid("Wrong magic number"));
+    }
+
+    reader.read_exact(&mut header)?;
+    if &header != ZOKRATES_WITNESS_VERSION_1 {
+        return Err(invalid("Unknown version"));
+    }
+
+    reader.read_exact(&mut header)?;
+    if header != T::id() {
+        return Err(invalid("Witness is for a different curve"));
+    }
+
+    let mut read_count = || -> io::Result<usize> {
+        let mut count = [0; 8];
+        reader.read_exact(&mut count)?;
+        Ok(u64::from_le_bytes(count) as usize)
+    };
+
+    let layout = Layout::new(read_count()?, read_count()?);
+    let len = read_count()?;
+
+    Ok((layout, len))
+}
+
+fn read_binary_values<T: Field, R: Read>(
+    reader: &mut R,
+    count: usize,
+) -> io::Result<Vec<Option<T>>> {
+    let size = T::zero().to_byte_vector().len();
+
+    (0..count)
+        .map(|_| {
+            let mut present = [0; 1];
+            reader.read_exact(&mut present)?;
+            match present[0] {
+                0 => Ok(None),
+                1 => {
+                    let mut value = vec![0; size];
+                    reader.read_exact(&mut value)?;
+                    Ok(Some(T::from_byte_vector(value)))
+                }
+                _ => Err(invalid("Invalid value in witness")),
+            }
+        })
+        .collect()
+}
+
+/// Where backends take the values of the variables of a program from when generating a proof
+pub trait WitnessSource<T> {
+    /// The value of `variable`, if any
+    fn value(&mut self, variable: &FlatVariable) -> Option<T>;
+
+    /// Take the value of `variable`, which is not requested again afterwards
+    fn take(&mut self, variable: &FlatVariable) -> Option<T>;
+
+    /// Read all values in memory
+    fn into_witness(self) -> Witness<T>;
+}
+
+impl<T: Clone> WitnessSource<T> for Witness<T> {
+    fn value(&mut self, variable: &FlatVariable) -> Option<T> {
+        self.get(variable).cloned()
+    }
+
+    fn take(&mut self, variable: &FlatVariable) -> Option<T> {
+        self.remove(variable)
+    }
+
+    fn into_witness(self) -> Witness<T> {
+        self
+    }
+}
+
+/// The number of values of a binary witness read at once by a `WitnessReader`
+const CHUNK_SIZE: usize = 1 << 12;
+/// The number of chunks a `WitnessReader` keeps in memory
+const CACHED_CHUNKS: usize = 16;
 
-        Ok(Witness(map))
+/// A binary witness which is read from disk one chunk at a time as its values are requested, so
+/// that generating a proof does not require holding the whole witness in memory.
+/// The witness is checked to be well-formed when the reader is created, so failing to read it
+/// afterwards is only possible if the underlying file changes, in which case the reader panics.
+pub struct WitnessReader<T, R> {
+    reader: R,
+    layout: Layout,
+    len: usize,
+    /// The position of the first value of each chunk
+    offsets: Vec<u64>,
+    /// The chunks in memory, least recently loaded first
+    chunks: VecDeque<(usize, Vec<Option<T>>)>,
+}
+
+impl<T: Field, R: Read + Seek> WitnessReader<T, R> {
+    pub fn new(mut reader: R) -> io::Result<Self> {
+        let (layout, len) = read_binary_header::<T, _>(&mut reader)?;
+
+        let size = T::zero().to_byte_vector().len();
+        let mut value = vec![0; size];
+
+        // find where each chunk starts, checking the witness is complete on the way
+        let mut offset = reader.stream_position()?;
+        let mut offsets = vec![];
+
+        for index in 0..len {
+            if index % CHUNK_SIZE == 0 {
+                offsets.push(offset);
+            }
+
+            let mut present = [0; 1];
+            reader.read_exact(&mut present)?;
+            offset += 1;
+
+            match present[0] {
+                0 => {}
+                1 => {
+                    reader.read_exact(&mut value)?;
+                    offset += size as u64;
+                }
+                _ => return Err(invalid("Invalid value in witness")),
+            }
+        }
+
+        Ok(WitnessReader {
+            reader,
+            layout,
+            len,
+            offsets,
+            chunks: VecDeque::new(),
+        })
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    fn load(&mut self, chunk: usize) -> io::Result<Vec<Option<T>>> {
+        let count = std::cmp::min(CHUNK_SIZE, self.len - chunk * CHUNK_SIZE);
+
+        self.reader.seek(SeekFrom::Start(self.offsets[chunk]))?;
+        read_binary_values(&mut self.reader, count)
+    }
+
+    /// The value at `index` in the layout, which is read from disk if it is not in memory
+    fn slot(&mut self, index: usize) -> Option<&mut Option<T>> {
+        if index >= self.len {
+            return None;
+        }
+
+        let chunk = index / CHUNK_SIZE;
+
+        let position = match self.chunks.iter().position(|(c, _)| *c == chunk) {
+            Some(position) => position,
+            None => {
+                let values = self
+                    .load(chunk)
+                    .unwrap_or_else(|e| panic!("Cannot read witness: {}", e));
+
+                if self.chunks.len() == CACHED_CHUNKS {
+                    self.chunks.pop_front();
+                }
+                self.chunks.push_back((chunk, values));
+                self.chunks.len() - 1
+            }
+        };
+
+        Some(&mut self.chunks[position].1[index % CHUNK_SIZE])
+    }
+}
+
+impl<T: Field, R: Read + Seek> WitnessSource<T> for WitnessReader<T, R> {
+    fn value(&mut self, variable: &FlatVariable) -> Option<T> {
+        let index = self.layout.index(variable);
+        self.slot(index).and_then(|value| value.clone())
+    }
+
+    fn take(&mut self, variable: &FlatVariable) -> Option<T> {
+        let index = self.layout.index(variable);
+        self.slot(index).and_then(Option::take)
+    }
+
+    fn into_witness(mut self) -> Witness<T> {
+        let values = match self.offsets.first() {
+            Some(offset) => self
+                .reader
+                .seek(SeekFrom::Start(*offset))
+                .and_then(|_| read_binary_values(&mut self.reader, self.len))
+                .unwrap_or_else(|e| panic!("Cannot read witness: {}", e)),
+            None => vec![],
+        };
+
+        Witness {
+            layout: self.layout,
+            values,
+        }
+    }
+}
+
+/// Build a witness from arbitrary assignments, using a layout which fits all of them
+impl<T> FromIterator<(FlatVariable, T)> for Witness<T> {
+    fn from_iter<I: IntoIterator<Item = (FlatVariable, T)>>(iter: I) -> Self {
+        let assignments: Vec<_> = iter.into_iter().collect();
+
+        let return_count = assignments
+            .iter()
+            .filter(|(variable, _)| variable.is_output())
+            .map(|(variable, _)| variable.output_id() + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut witness = Witness::new(Layout::new(0, return_count));
+
+        for (variable, value) in assignments {
+            witness.insert(variable, value);
+        }
+
+        witness
+    }
+}
+
+/// Two witnesses are equal if they assign the same values to the same variables, whatever their
+/// layouts
+impl<T: PartialEq> PartialEq for Witness<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if self.layout == other.layout {
+            self.iter().eq(other.iter())
+        } else {
+            self.iter().collect::<BTreeMap<_, _>>() == other.iter().collect::<BTreeMap<_, _>>()
+        }
     }
 }
 
@@ -94,8 +466,7 @@ impl<T: Field> fmt::Display for Witness<T> {
         write!(
             f,
             "{}",
-            self.0
-                .iter()
+            self.iter()
                 .map(|(k, v)| format!("{} {}", k, v.to_dec_string()))
                 .collect::<Vec<_>>()
                 .join("\n")
@@ -108,21 +479,38 @@ mod tests {
     use super::*;
     use zokrates_field::Bn128Field;
 
+    #[test]
+    fn layout() {
+        let layout = Layout::new(2, 1);
+
+        let variables = vec![
+            FlatVariable::one(),
+            FlatVariable::new(0),
+            FlatVariable::new(1),
+            FlatVariable::public(0),
+            FlatVariable::new(2),
+            FlatVariable::new(3),
+        ];
+
+        for (index, variable) in variables.into_iter().enumerate() {
+            assert_eq!(layout.index(&variable), index);
+            assert_eq!(layout.variable(index), variable);
+        }
+    }
+
     mod io {
         use super::*;
         use std::io::Cursor;
 
         #[test]
         fn serialize_deserialize() {
-            let w = Witness(
-                vec![
-                    (FlatVariable::new(42), Bn128Field::from(42)),
-                    (FlatVariable::public(8), Bn128Field::from(8)),
-                    (FlatVariable::one(), Bn128Field::from(1)),
-                ]
-                .into_iter()
-                .collect(),
-            );
+            let w: Witness<Bn128Field> = vec![
+                (FlatVariable::new(42), Bn128Field::from(42)),
+                (FlatVariable::public(8), Bn128Field::from(8)),
+                (FlatVariable::one(), Bn128Field::from(1)),
+            ]
+            .into_iter()
+            .collect();
 
             let mut buff = Cursor::new(vec![]);
 
@@ -134,6 +522,78 @@ mod tests {
             assert_eq!(w, r);
         }
 
+        #[test]
+        fn serialize_deserialize_binary() {
+            let mut w = Witness::new(Layout::new(2, 1));
+            w.insert(FlatVariable::one(), Bn128Field::from(1));
+            w.insert(FlatVariable::new(0), Bn128Field::from(2));
+            w.insert(FlatVariable::new(1), Bn128Field::from(3));
+            w.insert(FlatVariable::public(0), Bn128Field::from(6));
+            w.insert(FlatVariable::new(3), Bn128Field::from(-1));
+
+            let mut buff = Cursor::new(vec![]);
+
+            w.write_binary(&mut buff).unwrap();
+            assert!(Witness::<Bn128Field>::is_binary(buff.get_ref()));
+            buff.set_position(0);
+
+            let r = Witness::read_binary(buff).unwrap();
+
+            assert_eq!(w.layout(), r.layout());
+            assert_eq!(w, r);
+        }
+
+        #[test]
+        fn reader() {
+            // more values than fit in the chunks kept in memory
+            let len = CHUNK_SIZE * (CACHED_CHUNKS + 1) + 1;
+
+            let mut w = Witness::new(Layout::new(1, 1));
+            for i in (0..len).step_by(3) {
+                w.insert(Layout::new(1, 1).variable(i), Bn128Field::from(i));
+            }
+
+            let mut buff = Cursor::new(vec![]);
+            w.write_binary(&mut buff).unwrap();
+            buff.set_position(0);
+
+            let mut r = WitnessReader::<Bn128Field, _>::new(buff).unwrap();
+            assert_eq!(r.layout(), w.layout());
+
+            for i in (0..len + 1).rev() {
+                let variable = w.layout().variable(i);
+                assert_eq!(r.value(&variable), w.get(&variable).cloned());
+                assert_eq!(r.take(&variable), w.get(&variable).cloned());
+            }
+
+            assert_eq!(r.into_witness(), w);
+        }
+
+        #[test]
+        fn reader_truncated() {
+            let mut w = Witness::new(Layout::new(1, 0));
+            w.insert(FlatVariable::one(), Bn128Field::from(1));
+            w.insert(FlatVariable::new(0), Bn128Field::from(2));
+
+            let mut buff = vec![];
+            w.write_binary(&mut buff).unwrap();
+            buff.pop();
+
+            assert!(WitnessReader::<Bn128Field, _>::new(Cursor::new(buff)).is_err());
+        }
+
+        #[test]
+        fn binary_wrong_curve() {
+            let w: Witness<Bn128Field> = Witness::new(Layout::new(0, 0));
+
+            let mut buff = Cursor::new(vec![]);
+
+            w.write_binary(&mut buff).unwrap();
+            buff.set_position(0);
+
+            assert!(Witness::<zokrates_field::Bls12_381Field>::read_binary(buff).is_err());
+        }
+
         #[test]
         fn wrong_value() {
             let mut buff = Cursor::new(vec![]);
@@ -21,7 +21,10 @@ mod zir;
 
 pub mod absy;
 pub mod compile;
+pub mod diagnostics;
 pub mod flat_absy;
+pub mod formatter;
 pub mod ir;
+pub mod linter;
 pub mod proof_system;
 pub mod typed_absy;
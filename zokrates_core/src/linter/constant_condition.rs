@@ -0,0 +1,77 @@
+//! Report conditional expressions whose condition does not depend on any variable
+
+use super::{lint_statements, Site};
+use crate::typed_absy::{
+    folder::*, BooleanExpression, Conditional, ConditionalExpression, ConditionalOrExpression,
+    CoreIdentifier, Expr, FunctionCall, FunctionCallExpression, FunctionCallOrExpression, Id,
+    Identifier, TypedExpression, TypedFunction, TypedProgram,
+};
+use zokrates_field::Field;
+
+#[derive(Default)]
+struct ConstantConditions {
+    messages: Vec<String>,
+}
+
+pub fn lint<'ast, T: Field>(
+    f: &TypedFunction<'ast, T>,
+    _: &TypedProgram<'ast, T>,
+) -> Vec<(Site, String)> {
+    lint_statements(&f.statements, &mut |s| {
+        let mut checker = ConstantConditions::default();
+        checker.fold_statement(s);
+        checker.messages
+    })
+}
+
+/// Detects whether an expression refers to variables, generic parameters or function calls
+struct ConstantChecker {
+    constant: bool,
+}
+
+impl ConstantChecker {
+    fn is_constant<'ast, T: Field>(e: &BooleanExpression<'ast, T>) -> bool {
+        let mut checker = ConstantChecker { constant: true };
+        checker.fold_boolean_expression(e.clone());
+        checker.constant
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for ConstantChecker {
+    fn fold_name(&mut self, n: Identifier<'ast>) -> Identifier<'ast> {
+        // module level constants are constant, other identifiers are not
+        if !matches!(n.id, CoreIdentifier::Constant(_)) {
+            self.constant = false;
+        }
+        n
+    }
+
+    fn fold_function_call_expression<
+        E: Id<'ast, T> + From<TypedExpression<'ast, T>> + Expr<'ast, T> + FunctionCall<'ast, T>,
+    >(
+        &mut self,
+        ty: &E::Ty,
+        e: FunctionCallExpression<'ast, T, E>,
+    ) -> FunctionCallOrExpression<'ast, T, E> {
+        self.constant = false;
+        fold_function_call_expression(self, ty, e)
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for ConstantConditions {
+    fn fold_conditional_expression<
+        E: Expr<'ast, T> + Fold<'ast, T> + Conditional<'ast, T> + From<TypedExpression<'ast, T>>,
+    >(
+        &mut self,
+        ty: &E::Ty,
+        e: ConditionalExpression<'ast, T, E>,
+    ) -> ConditionalOrExpression<'ast, T, E> {
+        if ConstantChecker::is_constant(&e.condition) {
+            self.messages.push(format!(
+                "Condition `{}` is constant, so only one branch is ever taken",
+                e.condition
+            ));
+        }
+        fold_conditional_expression(self, ty, e)
+    }
+}
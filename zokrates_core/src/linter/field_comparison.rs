@@ -0,0 +1,41 @@
+//! Report comparisons of field elements, which require a costly bit decomposition of their operands
+
+use super::{lint_statements, Site};
+use crate::typed_absy::{folder::*, BooleanExpression, TypedFunction, TypedProgram};
+use zokrates_field::Field;
+
+#[derive(Default)]
+struct FieldComparisons {
+    messages: Vec<String>,
+}
+
+pub fn lint<'ast, T: Field>(
+    f: &TypedFunction<'ast, T>,
+    _: &TypedProgram<'ast, T>,
+) -> Vec<(Site, String)> {
+    lint_statements(&f.statements, &mut |s| {
+        let mut checker = FieldComparisons::default();
+        checker.fold_statement(s);
+        checker.messages
+    })
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for FieldComparisons {
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        match &e {
+            BooleanExpression::FieldLt(..)
+            | BooleanExpression::FieldLe(..)
+            | BooleanExpression::FieldGe(..)
+            | BooleanExpression::FieldGt(..) => self.messages.push(format!(
+                "Comparison `{}` of field elements costs around {} constraints, consider comparing unsigned integers instead",
+                e,
+                T::get_required_bits()
+            )),
+            _ => {}
+        }
+        fold_boolean_expression(self, e)
+    }
+}
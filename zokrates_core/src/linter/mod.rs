@@ -0,0 +1,574 @@
+//! Module containing the linter, which reports legal but suspicious patterns
+//!
+//! Lints run on the functions of the main module of a typed program, except for
+//! `unconstrained_input` which runs on the constraint system of the compiled program.
+
+mod constant_condition;
+mod field_comparison;
+mod unchecked_unpack;
+mod unconstrained_input;
+mod unused_variable;
+
+use crate::absy::{self, Statement, StatementNode, Symbol, SymbolDefinition};
+use crate::ir::Prog;
+use crate::parser::Position;
+use crate::typed_absy::abi::Abi;
+use crate::typed_absy::{TypedFunction, TypedFunctionSymbol, TypedProgram, TypedStatement};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use zokrates_field::Field;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Lint {
+    UnusedVariable,
+    UnconstrainedInput,
+    ConstantCondition,
+    FieldComparison,
+    UncheckedUnpack,
+}
+
+impl Lint {
+    pub const ALL: [Lint; 5] = [
+        Lint::UnusedVariable,
+        Lint::UnconstrainedInput,
+        Lint::ConstantCondition,
+        Lint::FieldComparison,
+        Lint::UncheckedUnpack,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Lint::UnusedVariable => "unused_variable",
+            Lint::UnconstrainedInput => "unconstrained_input",
+            Lint::ConstantCondition => "constant_condition",
+            Lint::FieldComparison => "field_comparison",
+            Lint::UncheckedUnpack => "unchecked_unpack",
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl TryFrom<&str> for Lint {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Lint, Self::Error> {
+        Lint::ALL
+            .iter()
+            .find(|lint| lint.name() == s)
+            .cloned()
+            .ok_or_else(|| format!("Unknown lint {}", s))
+    }
+}
+
+/// How a lint is reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Allow,
+    Warn,
+    Deny,
+}
+
+impl TryFrom<&str> for Level {
+    type Error = String;
+
+    fn try_from(s: &str) -> Result<Level, Self::Error> {
+        match s {
+            "allow" => Ok(Level::Allow),
+            "warn" => Ok(Level::Warn),
+            "deny" => Ok(Level::Deny),
+            _ => Err(format!("Unknown lint level {}", s)),
+        }
+    }
+}
+
+/// Where a warning was found in a function
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Site {
+    /// The parameter at this index
+    Parameter(usize),
+    /// The statement at these indices, starting from the body of the function and going into loops
+    Statement(Vec<usize>),
+}
+
+/// The spans of the nodes of the main module which warnings are located and suppressed with
+#[derive(Debug, Default)]
+pub struct Spans {
+    /// The spans of the parameters and statements of each function, by function name
+    sites: HashMap<(String, Site), (Position, Position)>,
+    /// The spans of the definitions of each function, by function name
+    functions: HashMap<String, Vec<(Position, Position)>>,
+    /// The span of the first declaration of the module, and whether it is a function
+    first_declaration: Option<((Position, Position), bool)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Warning {
+    pub lint: Lint,
+    /// The function the warning was found in, if any
+    pub function: Option<String>,
+    pub message: String,
+    /// The span of the parameter or statement the warning was found in, if known, otherwise the
+    /// position of the definition of the function
+    pub span: Option<(Position, Position)>,
+    site: Site,
+}
+
+impl Warning {
+    fn new<S: Into<String>>(lint: Lint, function: Option<S>, site: Site, message: String) -> Self {
+        Warning {
+            lint,
+            function: function.map(|f| f.into()),
+            message,
+            span: None,
+            site,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let location = self
+            .span
+            .map(|(start, _)| format!("{}", start))
+            .unwrap_or_else(|| "?".to_string());
+
+        match &self.function {
+            Some(function) => write!(f, "{}: in `{}`: {}", location, function, self.message),
+            None => write!(f, "{}: {}", location, self.message),
+        }
+    }
+}
+
+type FunctionLint<'ast, T> =
+    fn(&TypedFunction<'ast, T>, &TypedProgram<'ast, T>) -> Vec<(Site, String)>;
+
+/// Run `lint` on each statement of `statements`, going into loops, and attach the site of the
+/// statement to the messages it returns
+fn lint_statements<'ast, T: Field>(
+    statements: &[TypedStatement<'ast, T>],
+    lint: &mut dyn FnMut(TypedStatement<'ast, T>) -> Vec<String>,
+) -> Vec<(Site, String)> {
+    fn visit<'ast, T: Field>(
+        statements: &[TypedStatement<'ast, T>],
+        path: &mut Vec<usize>,
+        lint: &mut dyn FnMut(TypedStatement<'ast, T>) -> Vec<String>,
+        messages: &mut Vec<(Site, String)>,
+    ) {
+        for (index, s) in statements.iter().enumerate() {
+            path.push(index);
+
+            // the body of a loop is visited statement by statement, so only lint the bounds here
+            let (s, body) = match s {
+                TypedStatement::For(v, from, to, body) => (
+                    TypedStatement::For(v.clone(), from.clone(), to.clone(), vec![]),
+                    &body[..],
+                ),
+                s => (s.clone(), &[][..]),
+            };
+
+            messages.extend(
+                lint(s)
+                    .into_iter()
+                    .map(|message| (Site::Statement(path.clone()), message)),
+            );
+
+            visit(body, path, lint, messages);
+
+            path.pop();
+        }
+    }
+
+    let mut messages = vec![];
+    visit(statements, &mut vec![], lint, &mut messages);
+    messages
+}
+
+/// Run the lints on the functions defined in the main module of `program`
+pub fn lint_program<'ast, T: Field>(program: &TypedProgram<'ast, T>) -> Vec<Warning> {
+    let lints: [(Lint, FunctionLint<'ast, T>); 4] = [
+        (Lint::UnusedVariable, unused_variable::lint),
+        (Lint::ConstantCondition, constant_condition::lint),
+        (Lint::FieldComparison, field_comparison::lint),
+        (Lint::UncheckedUnpack, unchecked_unpack::lint),
+    ];
+
+    program
+        .modules
+        .get(&program.main)
+        .unwrap()
+        .functions_iter()
+        .filter_map(|d| match &d.symbol {
+            TypedFunctionSymbol::Here(f) => Some((d.key.id, f)),
+            _ => None,
+        })
+        .flat_map(|(id, f)| {
+            lints.iter().flat_map(move |(lint, run)| {
+                run(f, program)
+                    .into_iter()
+                    .map(move |(site, message)| Warning::new(*lint, Some(id), site, message))
+            })
+        })
+        .collect()
+}
+
+/// Report the public inputs of the compiled program which do not reach a constraint
+pub fn lint_constraints<T: Field>(abi: &Abi, program: &Prog<T>) -> Vec<Warning> {
+    unconstrained_input::lint(abi, program)
+        .into_iter()
+        .map(|(index, message)| {
+            Warning::new(
+                Lint::UnconstrainedInput,
+                Some("main"),
+                Site::Parameter(index),
+                message,
+            )
+        })
+        .collect()
+}
+
+/// Collect the spans of the declarations of `module`, the main module of the program before
+/// semantic checking, and of the parameters and statements of its functions. Semantic checking
+/// maps each parameter and statement to exactly one, so sites in the typed program can be looked
+/// up here.
+pub fn spans(module: &absy::Module) -> Spans {
+    fn visit(
+        function: &str,
+        statements: &[StatementNode],
+        path: &mut Vec<usize>,
+        spans: &mut Spans,
+    ) {
+        for (index, s) in statements.iter().enumerate() {
+            path.push(index);
+
+            spans
+                .sites
+                .entry((function.to_string(), Site::Statement(path.clone())))
+                .or_insert_with(|| s.pos());
+
+            if let Statement::For(_, _, _, body) = &s.value {
+                visit(function, body, path, spans);
+            }
+
+            path.pop();
+        }
+    }
+
+    let mut spans = Spans::default();
+
+    for declaration in &module.symbols {
+        let is_function = matches!(
+            declaration.value.symbol,
+            Symbol::Here(SymbolDefinition::Function(_))
+        );
+
+        // imports of several symbols are split into one declaration each, sharing a span
+        if spans
+            .first_declaration
+            .map(|((start, _), _)| declaration.pos().0 < start)
+            .unwrap_or(true)
+        {
+            spans.first_declaration = Some((declaration.pos(), is_function));
+        }
+
+        if let Symbol::Here(SymbolDefinition::Function(f)) = &declaration.value.symbol {
+            let id = declaration.value.id;
+
+            spans
+                .functions
+                .entry(id.to_string())
+                .or_default()
+                .push(declaration.pos());
+
+            // overloaded functions share a name, so only the parameters and statements of the
+            // first definition are located
+            if spans.functions[id].len() > 1 {
+                continue;
+            }
+
+            for (index, p) in f.value.arguments.iter().enumerate() {
+                spans
+                    .sites
+                    .insert((id.to_string(), Site::Parameter(index)), p.pos());
+            }
+
+            visit(id, &f.value.statements, &mut vec![], &mut spans);
+        }
+    }
+
+    spans
+}
+
+const ALLOW_PREFIX: &str = "// zokrates-allow(";
+
+/// The lints suppressed by a `// zokrates-allow(lint, ...)` comment on a line
+fn allowed(line: &str) -> Option<HashSet<String>> {
+    let line = line.trim_start();
+    let start = line.strip_prefix(ALLOW_PREFIX)?;
+    let end = start.find(')')?;
+
+    Some(
+        start[..end]
+            .split(',')
+            .map(|lint| lint.trim().to_string())
+            .collect(),
+    )
+}
+
+/// Locate the warnings in `source`, the source of the main module, and remove those suppressed by
+/// `// zokrates-allow(lint)` comments, using the `spans` of the main module:
+/// - in the comments before the first declaration of the module, for the whole module
+/// - in the comments directly above a function or inside its body, for that function
+pub fn locate(warnings: Vec<Warning>, source: &str, spans: &Spans) -> Vec<Warning> {
+    let lines: Vec<&str> = source.lines().collect();
+
+    // lines are 1-indexed
+    let allowed_in = |from: usize, to: usize| -> HashSet<String> {
+        lines[from.saturating_sub(1)..to.min(lines.len())]
+            .iter()
+            .filter_map(|line| allowed(line))
+            .flatten()
+            .collect()
+    };
+
+    // comments are not part of the AST, so the comments directly above a declaration are found
+    // by going up from its first line
+    let comments_above = |line: usize| -> usize {
+        (1..line)
+            .rev()
+            .take_while(|l| lines[l - 1].trim_start().starts_with("//"))
+            .last()
+            .unwrap_or(line)
+    };
+
+    let module_allowed = match spans.first_declaration {
+        Some(((start, _), true)) => allowed_in(1, comments_above(start.line) - 1),
+        Some(((start, _), false)) => allowed_in(1, start.line - 1),
+        None => allowed_in(1, lines.len()),
+    };
+
+    warnings
+        .into_iter()
+        .filter(|w| !module_allowed.contains(w.lint.name()))
+        .filter_map(|w| {
+            let definitions = w
+                .function
+                .as_ref()
+                .and_then(|function| spans.functions.get(function))
+                .map(|definitions| &definitions[..])
+                .unwrap_or_default();
+
+            let is_allowed = definitions.iter().any(|(start, end)| {
+                allowed_in(comments_above(start.line), end.line).contains(w.lint.name())
+            });
+
+            let span = w
+                .function
+                .as_ref()
+                .and_then(|function| spans.sites.get(&(function.clone(), w.site.clone())))
+                .cloned()
+                .or_else(|| definitions.first().map(|(start, _)| (*start, *start)));
+
+            match is_allowed {
+                true => None,
+                false => Some(Warning { span, ..w }),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{lint, CompileConfig};
+    use std::io;
+    use zokrates_common::Resolver;
+    use zokrates_field::Bn128Field;
+    use zokrates_pest_ast as pest;
+
+    fn warnings(source: &str) -> Vec<Warning> {
+        lint::<Bn128Field, io::Error>(
+            source.to_string(),
+            "./main.zok".into(),
+            None::<&dyn Resolver<io::Error>>,
+            &CompileConfig::default(),
+        )
+        .unwrap()
+    }
+
+    fn run(source: &str) -> Vec<(Lint, String)> {
+        warnings(source)
+            .into_iter()
+            .map(|w| (w.lint, w.function.unwrap()))
+            .collect()
+    }
+
+    /// The lint and line of each warning
+    fn lines(source: &str) -> Vec<(Lint, usize)> {
+        warnings(source)
+            .into_iter()
+            .map(|w| (w.lint, w.span.unwrap().0.line))
+            .collect()
+    }
+
+    #[test]
+    fn clean() {
+        let source = r#"
+def main(field a, private field b) -> field:
+    field c = a * b
+    return c
+"#;
+        assert_eq!(run(source), vec![]);
+    }
+
+    #[test]
+    fn unused_variables() {
+        let source = r#"
+def foo(field a, field b) -> field:
+    field c = 42
+    return a
+
+def main(field a) -> field:
+    return foo(a, a)
+"#;
+        assert_eq!(
+            run(source),
+            vec![
+                (Lint::UnusedVariable, "foo".into()),
+                (Lint::UnusedVariable, "foo".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn shadowed_variable() {
+        let source = r#"
+def main(field a) -> field:
+    field b = a
+    for u32 i in 0..2 do
+        field b = a
+        a = b
+    endfor
+    return a
+"#;
+        assert_eq!(lines(source), vec![(Lint::UnusedVariable, 3)]);
+    }
+
+    #[test]
+    fn spans() {
+        let source = r#"
+def main(field a, field b) -> bool:
+    field c = 42
+    for u32 i in 0..2 do
+        assert(a < 2)
+    endfor
+    return if true then a == 1 else a == 2 fi
+"#;
+        assert_eq!(
+            lines(source),
+            vec![
+                (Lint::UnusedVariable, 2),
+                (Lint::UnusedVariable, 3),
+                (Lint::ConstantCondition, 7),
+                (Lint::FieldComparison, 5),
+                (Lint::UnconstrainedInput, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn unconstrained_input() {
+        let source = r#"
+def main(field a, u32 b, private field c) -> field:
+    u32 d = b
+    return a
+"#;
+        let warnings = run(source);
+        assert_eq!(
+            warnings,
+            vec![
+                (Lint::UnusedVariable, "main".into()),
+                (Lint::UnusedVariable, "main".into()),
+                (Lint::UnconstrainedInput, "main".into())
+            ]
+        );
+    }
+
+    #[test]
+    fn suspicious_expressions() {
+        let source = r#"
+from "EMBED" import unpack
+
+def main(field a) -> bool:
+    bool[2] b = unpack(a)
+    return if true then a < 2 else b[0] fi
+"#;
+        let warnings = run(source);
+        assert!(warnings.contains(&(Lint::UncheckedUnpack, "main".into())));
+        assert!(warnings.contains(&(Lint::ConstantCondition, "main".into())));
+        assert!(warnings.contains(&(Lint::FieldComparison, "main".into())));
+    }
+
+    #[test]
+    fn suppression() {
+        // sites without a span are located at the definition of their function
+        let site = Site::Statement(vec![1]);
+        let warnings = vec![
+            Warning::new(
+                Lint::UnusedVariable,
+                Some("foo"),
+                site.clone(),
+                String::new(),
+            ),
+            Warning::new(
+                Lint::UnusedVariable,
+                Some("bar"),
+                site.clone(),
+                String::new(),
+            ),
+            Warning::new(
+                Lint::FieldComparison,
+                Some("bar"),
+                site.clone(),
+                String::new(),
+            ),
+            Warning::new(
+                Lint::ConstantCondition,
+                Some("bar"),
+                site.clone(),
+                String::new(),
+            ),
+        ];
+
+        let source = r#"// zokrates-allow(constant_condition)
+
+// zokrates-allow(unused_variable)
+def foo():
+    return
+
+def bar():
+    // zokrates-allow(field_comparison)
+    return
+"#;
+
+        let position = Position { line: 7, col: 1 };
+
+        let module = absy::Module::from(pest::generate_ast(source).unwrap());
+
+        assert_eq!(
+            locate(warnings, source, &spans(&module)),
+            vec![Warning {
+                lint: Lint::UnusedVariable,
+                function: Some("bar".into()),
+                message: String::new(),
+                span: Some((position, position)),
+                site
+            }]
+        );
+    }
+}
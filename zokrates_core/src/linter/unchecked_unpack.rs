@@ -0,0 +1,77 @@
+//! Report calls to bit decompositions which do not check for overflows
+
+use super::{lint_statements, Site};
+use crate::embed::FlatEmbed;
+use crate::typed_absy::{
+    folder::*, DeclarationFunctionKey, Expr, FunctionCall, FunctionCallExpression,
+    FunctionCallOrExpression, Id, TypedExpression, TypedFunction, TypedFunctionSymbol,
+    TypedProgram,
+};
+use std::path::Path;
+use zokrates_field::Field;
+
+/// The standard library module wrapping the `unpack` embed
+const UNPACK_UNCHECKED_MODULE: &str = "utils/pack/bool/unpack_unchecked.zok";
+
+struct UncheckedUnpacks<'a, 'ast, T> {
+    program: &'a TypedProgram<'ast, T>,
+    messages: Vec<String>,
+}
+
+pub fn lint<'ast, T: Field>(
+    f: &TypedFunction<'ast, T>,
+    program: &TypedProgram<'ast, T>,
+) -> Vec<(Site, String)> {
+    lint_statements(&f.statements, &mut |s| {
+        let mut checker = UncheckedUnpacks {
+            program,
+            messages: vec![],
+        };
+        checker.fold_statement(s);
+        checker.messages
+    })
+}
+
+impl<'a, 'ast, T: Field> UncheckedUnpacks<'a, 'ast, T> {
+    /// Follow the imports of `key` to check whether it refers to an unchecked bit decomposition
+    fn is_unchecked_unpack(&self, key: &DeclarationFunctionKey<'ast, T>) -> bool {
+        let mut key = key.clone();
+
+        loop {
+            if key.module.ends_with(Path::new(UNPACK_UNCHECKED_MODULE)) {
+                return true;
+            }
+
+            let symbol = self
+                .program
+                .modules
+                .get(&key.module)
+                .and_then(|m| m.functions_iter().find(|d| d.key == key))
+                .map(|d| &d.symbol);
+
+            match symbol {
+                Some(TypedFunctionSymbol::There(k)) => key = k.clone(),
+                Some(TypedFunctionSymbol::Flat(FlatEmbed::Unpack)) => return true,
+                _ => return false,
+            }
+        }
+    }
+}
+
+impl<'a, 'ast, T: Field> Folder<'ast, T> for UncheckedUnpacks<'a, 'ast, T> {
+    fn fold_function_call_expression<
+        E: Id<'ast, T> + From<TypedExpression<'ast, T>> + Expr<'ast, T> + FunctionCall<'ast, T>,
+    >(
+        &mut self,
+        ty: &E::Ty,
+        e: FunctionCallExpression<'ast, T, E>,
+    ) -> FunctionCallOrExpression<'ast, T, E> {
+        if self.is_unchecked_unpack(&e.function_key) {
+            self.messages.push(format!(
+                "`{}` decomposes a field element into bits without checking for overflows, so the decomposition is not unique: use `utils/pack/bool/unpack` instead",
+                e.function_key.id
+            ));
+        }
+        fold_function_call_expression(self, ty, e)
+    }
+}
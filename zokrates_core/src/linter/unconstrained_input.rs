@@ -0,0 +1,115 @@
+//! Report public inputs which do not reach a constraint
+//!
+//! Variables are connected when they appear in the same constraint. A public input is reported if
+//! it is not connected to an output or to a source assertion: its only constraints check that it
+//! is well formed, for example that an unsigned integer is in range.
+
+use crate::flat_absy::{FlatParameter, FlatVariable, RuntimeError};
+use crate::ir::{visitor::*, Directive, Prog, Statement};
+use crate::typed_absy::abi::Abi;
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+#[derive(Default)]
+struct ConstraintGraph {
+    parents: HashMap<FlatVariable, FlatVariable>,
+    /// variables which are part of a constraint with an output or of a source assertion
+    anchors: Vec<FlatVariable>,
+    /// variables of the constraint being visited
+    current: Vec<FlatVariable>,
+}
+
+impl ConstraintGraph {
+    fn find(&mut self, v: FlatVariable) -> FlatVariable {
+        let parent = *self.parents.get(&v).unwrap_or(&v);
+        match parent == v {
+            true => v,
+            false => {
+                let root = self.find(parent);
+                self.parents.insert(v, root);
+                root
+            }
+        }
+    }
+
+    fn union(&mut self, a: FlatVariable, b: FlatVariable) {
+        let a = self.find(a);
+        let b = self.find(b);
+        if a != b {
+            self.parents.insert(a, b);
+        }
+    }
+}
+
+/// The messages for the unconstrained public inputs, along with the index of each input
+pub fn lint<T: Field>(abi: &Abi, program: &Prog<T>) -> Vec<(usize, String)> {
+    let mut graph = ConstraintGraph::default();
+    graph.visit_module(program);
+
+    let anchors: Vec<_> = std::mem::take(&mut graph.anchors)
+        .into_iter()
+        .map(|v| graph.find(v))
+        .collect();
+
+    let mut arguments = program.arguments.iter();
+
+    abi.inputs
+        .iter()
+        .enumerate()
+        .filter_map(|(index, input)| {
+            let variables: Vec<_> = arguments
+                .by_ref()
+                .take(input.ty.get_primitive_count())
+                .collect();
+
+            let reaches_constraint = variables
+                .iter()
+                .any(|p| anchors.contains(&graph.find(p.id)));
+
+            match input.public && !reaches_constraint {
+                true => Some((index, format!(
+                    "Public input `{}` does not reach any constraint, so the proof does not depend on its value",
+                    input.name
+                ))),
+                false => None,
+            }
+        })
+        .collect()
+}
+
+impl<T: Field> Visitor<T> for ConstraintGraph {
+    fn visit_argument(&mut self, _: &FlatParameter) {}
+
+    fn visit_directive(&mut self, _: &Directive<T>) {}
+
+    fn visit_statement(&mut self, s: &Statement<T>) {
+        if let Statement::Constraint(..) = s {
+            self.current.clear();
+            visit_statement(self, s);
+
+            let variables = std::mem::take(&mut self.current);
+
+            // outputs have negative ids, so they are smaller than `~one`
+            let is_anchor = variables.iter().any(|v| *v < FlatVariable::one())
+                || matches!(
+                    s,
+                    Statement::Constraint(_, _, Some(RuntimeError::SourceAssertion(_)))
+                );
+
+            if let Some(first) = variables.first() {
+                for v in &variables[1..] {
+                    self.union(*first, *v);
+                }
+                if is_anchor {
+                    self.anchors.push(*first);
+                }
+            }
+        }
+    }
+
+    fn visit_variable(&mut self, v: &FlatVariable) {
+        if *v != FlatVariable::one() {
+            self.current.push(*v);
+        }
+    }
+}
@@ -0,0 +1,143 @@
+//! Report variables and parameters which are declared but never read
+//!
+//! Variables declared in a loop shadow those declared outside of it with the same name, so reads
+//! are resolved to the innermost declaration in scope.
+
+use super::Site;
+use crate::typed_absy::{
+    folder::*, CoreIdentifier, Identifier, TypedAssignee, TypedFunction, TypedProgram,
+    TypedStatement,
+};
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+struct Declaration<'ast> {
+    id: Identifier<'ast>,
+    site: Site,
+    read: bool,
+}
+
+#[derive(Default)]
+struct UnusedVariables<'ast> {
+    /// the declared variables, in order
+    declared: Vec<Declaration<'ast>>,
+    /// the variables in scope, from the outermost to the innermost scope, as indices into
+    /// `declared`, or `None` for loop indices which are not reported
+    scopes: Vec<HashMap<Identifier<'ast>, Option<usize>>>,
+    /// the indices of the statement being visited
+    path: Vec<usize>,
+}
+
+pub fn lint<'ast, T: Field>(
+    f: &TypedFunction<'ast, T>,
+    _: &TypedProgram<'ast, T>,
+) -> Vec<(Site, String)> {
+    let mut checker = UnusedVariables {
+        scopes: vec![HashMap::new()],
+        ..UnusedVariables::default()
+    };
+
+    for (index, p) in f.arguments.iter().enumerate() {
+        checker.declare(&p.id.id, Some(Site::Parameter(index)));
+    }
+
+    checker.fold_block(f.statements.clone());
+
+    checker
+        .declared
+        .into_iter()
+        .filter(|d| !d.read)
+        .map(|d| {
+            let message = match d.site {
+                Site::Parameter(_) => format!("Unused parameter `{}`", d.id),
+                Site::Statement(_) => format!("Unused variable `{}`", d.id),
+            };
+            (d.site, message)
+        })
+        .collect()
+}
+
+impl<'ast> UnusedVariables<'ast> {
+    /// Declare `id` in the innermost scope, to be reported at `site` if it is never read
+    fn declare(&mut self, id: &Identifier<'ast>, site: Option<Site>) {
+        if let CoreIdentifier::Source(_) = id.id {
+            let index = site.map(|site| {
+                self.declared.push(Declaration {
+                    id: id.clone(),
+                    site,
+                    read: false,
+                });
+                self.declared.len() - 1
+            });
+            self.scopes.last_mut().unwrap().insert(id.clone(), index);
+        }
+    }
+
+    fn fold_block<T: Field>(
+        &mut self,
+        statements: Vec<TypedStatement<'ast, T>>,
+    ) -> Vec<TypedStatement<'ast, T>> {
+        let mut folded = vec![];
+
+        for (index, s) in statements.into_iter().enumerate() {
+            self.path.push(index);
+            folded.extend(self.fold_statement(s));
+            self.path.pop();
+        }
+
+        folded
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for UnusedVariables<'ast> {
+    fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
+        match s {
+            TypedStatement::Declaration(v) => {
+                self.declare(&v.id, Some(Site::Statement(self.path.clone())));
+                vec![TypedStatement::Declaration(v)]
+            }
+            // the bounds are read outside of the loop, and the loop index is not reported
+            TypedStatement::For(v, from, to, statements) => {
+                let from = self.fold_uint_expression(from);
+                let to = self.fold_uint_expression(to);
+
+                self.scopes.push(HashMap::new());
+                self.declare(&v.id, None);
+                let statements = self.fold_block(statements);
+                self.scopes.pop();
+
+                vec![TypedStatement::For(v, from, to, statements)]
+            }
+            s => fold_statement(self, s),
+        }
+    }
+
+    // writing to a variable does not count as reading it, but the indices in the assignee are read
+    fn fold_assignee(&mut self, a: TypedAssignee<'ast, T>) -> TypedAssignee<'ast, T> {
+        match a {
+            TypedAssignee::Identifier(v) => TypedAssignee::Identifier(v),
+            TypedAssignee::Select(box a, box index) => TypedAssignee::Select(
+                box self.fold_assignee(a),
+                box self.fold_uint_expression(index),
+            ),
+            TypedAssignee::Member(box s, m) => TypedAssignee::Member(box self.fold_assignee(s), m),
+        }
+    }
+
+    fn fold_name(&mut self, n: Identifier<'ast>) -> Identifier<'ast> {
+        if let CoreIdentifier::Source(_) = n.id {
+            let declaration = self
+                .scopes
+                .iter()
+                .rev()
+                .find_map(|scope| scope.get(&n))
+                .cloned()
+                .flatten();
+
+            if let Some(index) = declaration {
+                self.declared[index].read = true;
+            }
+        }
+        n
+    }
+}
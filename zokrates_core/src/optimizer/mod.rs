@@ -8,6 +8,7 @@ mod canonicalizer;
 mod directive;
 mod duplicate;
 mod redefinition;
+mod renumber;
 mod tautology;
 
 use self::canonicalizer::Canonicalizer;
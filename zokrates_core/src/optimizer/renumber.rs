@@ -0,0 +1,148 @@
+//! Module containing the `Renumberer` to make variable identifiers contiguous
+//!
+//! After optimization, the identifiers of the remaining variables are sparse. The `Renumberer`
+//! assigns `_0.._n` to the arguments, then numbers the internal variables in the order they
+//! appear in. `~one` and the return values are left untouched, so that the program fits a dense
+//! `ir::Layout` exactly.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::folder::Folder;
+use crate::ir::{ProgIterator, Statement};
+use std::collections::HashMap;
+use zokrates_field::Field;
+
+#[derive(Debug, Default)]
+pub struct Renumberer {
+    substitution: HashMap<FlatVariable, FlatVariable>,
+    next: usize,
+}
+
+impl<T: Field> Folder<T> for Renumberer {
+    fn fold_variable(&mut self, v: FlatVariable) -> FlatVariable {
+        if v == FlatVariable::one() || v.is_output() {
+            return v;
+        }
+
+        let next = &mut self.next;
+
+        *self.substitution.entry(v).or_insert_with(|| {
+            let renumbered = FlatVariable::new(*next);
+            *next += 1;
+            renumbered
+        })
+    }
+}
+
+impl<T: Field, I: IntoIterator<Item = Statement<T>>> ProgIterator<T, I> {
+    pub fn renumber(self) -> ProgIterator<T, impl IntoIterator<Item = Statement<T>>> {
+        let mut renumberer = Renumberer::default();
+
+        // the arguments are renumbered first so that they get the first identifiers
+        let arguments = self
+            .arguments
+            .into_iter()
+            .map(|a| <Renumberer as Folder<T>>::fold_argument(&mut renumberer, a))
+            .collect();
+
+        ProgIterator {
+            arguments,
+            statements: self
+                .statements
+                .into_iter()
+                .flat_map(move |s| renumberer.fold_statement(s)),
+            return_count: self.return_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::FlatParameter;
+    use crate::ir::{Directive, Interpreter, LinComb, Prog, QuadComb};
+    use crate::solvers::Solver;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn renumber() {
+        // def main(_3, _1):
+        //     # _7, _4 = ConditionEq(_3)
+        //     _9 = _1 * _7
+        //     ~out_0 = _9 + _4
+
+        // becomes
+
+        // def main(_0, _1):
+        //     # _2, _3 = ConditionEq(_0)
+        //     _4 = _1 * _2
+        //     ~out_0 = _4 + _3
+
+        let p: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                FlatParameter::private(FlatVariable::new(3)),
+                FlatParameter::public(FlatVariable::new(1)),
+            ],
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![FlatVariable::new(3).into()],
+                    outputs: vec![FlatVariable::new(7), FlatVariable::new(4)],
+                    solver: Solver::ConditionEq,
+                }),
+                Statement::constraint(
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(1).into(),
+                        FlatVariable::new(7).into(),
+                    ),
+                    FlatVariable::new(9),
+                ),
+                Statement::constraint(
+                    LinComb::from(FlatVariable::new(9)) + LinComb::from(FlatVariable::new(4)),
+                    FlatVariable::public(0),
+                ),
+            ],
+            return_count: 1,
+        };
+
+        let expected: Prog<Bn128Field> = Prog {
+            arguments: vec![
+                FlatParameter::private(FlatVariable::new(0)),
+                FlatParameter::public(FlatVariable::new(1)),
+            ],
+            statements: vec![
+                Statement::Directive(Directive {
+                    inputs: vec![FlatVariable::new(0).into()],
+                    outputs: vec![FlatVariable::new(2), FlatVariable::new(3)],
+                    solver: Solver::ConditionEq,
+                }),
+                Statement::constraint(
+                    QuadComb::from_linear_combinations(
+                        FlatVariable::new(1).into(),
+                        FlatVariable::new(2).into(),
+                    ),
+                    FlatVariable::new(4),
+                ),
+                Statement::constraint(
+                    LinComb::from(FlatVariable::new(4)) + LinComb::from(FlatVariable::new(3)),
+                    FlatVariable::public(0),
+                ),
+            ],
+            return_count: 1,
+        };
+
+        let interpreter = Interpreter::default();
+        let inputs = vec![Bn128Field::from(2), Bn128Field::from(3)];
+
+        let renumbered: Prog<Bn128Field> = p.clone().renumber().collect();
+
+        assert_eq!(renumbered, expected);
+
+        // the return values are preserved
+        assert_eq!(
+            interpreter.execute(p, &inputs).unwrap().return_values(),
+            interpreter
+                .execute(renumbered, &inputs)
+                .unwrap()
+                .return_values()
+        );
+    }
+}
@@ -1,6 +1,7 @@
+use serde::Serialize;
 use std::fmt;
 
-#[derive(Clone, PartialEq, Eq, Copy, Hash, Default, PartialOrd, Ord)]
+#[derive(Clone, PartialEq, Eq, Copy, Hash, Default, PartialOrd, Ord, Serialize)]
 pub struct Position {
     pub line: usize,
     pub col: usize,
@@ -6,7 +6,7 @@ use ark_gm17::{
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use zokrates_field::{ArkFieldExtensions, Bw6_761Field, Field};
 
-use crate::ir::{ProgIterator, Statement, Witness};
+use crate::ir::{ProgIterator, Statement, WitnessSource};
 use crate::proof_system::ark::Ark;
 use crate::proof_system::ark::Computation;
 use crate::proof_system::ark::{parse_fr, parse_g1, parse_g2, parse_g2_fq};
@@ -42,12 +42,12 @@ impl<T: Field + ArkFieldExtensions + NotBw6_761Field> NonUniversalBackend<T, GM1
 }
 
 impl<T: Field + ArkFieldExtensions + NotBw6_761Field> Backend<T, GM17> for Ark {
-    fn generate_proof<I: IntoIterator<Item = Statement<T>>>(
+    fn generate_proof<I: IntoIterator<Item = Statement<T>>, W: WitnessSource<T>>(
         program: ProgIterator<T, I>,
-        witness: Witness<T>,
+        witness: W,
         proving_key: Vec<u8>,
     ) -> Proof<<GM17 as Scheme<T>>::ProofPoints> {
-        let computation = Computation::with_witness(program, witness);
+        let mut computation = Computation::with_witness(program, witness);
 
         let inputs = computation
             .public_inputs_values()
@@ -138,12 +138,15 @@ impl NonUniversalBackend<Bw6_761Field, GM17> for Ark {
 }
 
 impl Backend<Bw6_761Field, GM17> for Ark {
-    fn generate_proof<I: IntoIterator<Item = Statement<Bw6_761Field>>>(
+    fn generate_proof<
+        I: IntoIterator<Item = Statement<Bw6_761Field>>,
+        W: WitnessSource<Bw6_761Field>,
+    >(
         program: ProgIterator<Bw6_761Field, I>,
-        witness: Witness<Bw6_761Field>,
+        witness: W,
         proving_key: Vec<u8>,
     ) -> Proof<<GM17 as Scheme<Bw6_761Field>>::ProofPoints> {
-        let computation = Computation::with_witness(program, witness);
+        let mut computation = Computation::with_witness(program, witness);
 
         let inputs = computation
             .public_inputs_values()
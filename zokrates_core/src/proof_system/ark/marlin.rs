@@ -10,7 +10,7 @@ use sha2::Sha256;
 
 use zokrates_field::{ArkFieldExtensions, Field};
 
-use crate::ir::{ProgIterator, Statement, Witness};
+use crate::ir::{ProgIterator, Statement, WitnessSource};
 use crate::proof_system::ark::parse_fr;
 use crate::proof_system::ark::Ark;
 use crate::proof_system::ark::Computation;
@@ -93,12 +93,12 @@ impl<T: Field + ArkFieldExtensions> UniversalBackend<T, marlin::Marlin> for Ark
 }
 
 impl<T: Field + ArkFieldExtensions> Backend<T, marlin::Marlin> for Ark {
-    fn generate_proof<I: IntoIterator<Item = Statement<T>>>(
+    fn generate_proof<I: IntoIterator<Item = Statement<T>>, W: WitnessSource<T>>(
         program: ProgIterator<T, I>,
-        witness: Witness<T>,
+        witness: W,
         proving_key: Vec<u8>,
     ) -> Proof<<marlin::Marlin as Scheme<T>>::ProofPoints> {
-        let computation = Computation::with_witness(program, witness);
+        let mut computation = Computation::with_witness(program, witness);
 
         use rand_0_7::SeedableRng;
 
@@ -1,7 +1,8 @@
 pub mod gm17;
 pub mod marlin;
 
-use crate::ir::{CanonicalLinComb, ProgIterator, Statement, Witness};
+use crate::ir::{CanonicalLinComb, Layout, ProgIterator, Statement, Witness, WitnessSource};
+use crate::proof_system::Symbols;
 use ark_gm17::Proof;
 use ark_gm17::{
     create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
@@ -14,7 +15,6 @@ use ark_relations::r1cs::{
     ConstraintSynthesizer, ConstraintSystem, ConstraintSystemRef, LinearCombination,
     SynthesisError, Variable,
 };
-use std::collections::BTreeMap;
 use zokrates_field::{ArkFieldExtensions, Field};
 
 pub use self::parse::*;
@@ -23,20 +23,26 @@ use rand_0_7::SeedableRng;
 
 pub struct Ark;
 
+/// A program to set up or prove, along with the source of its witness when proving.
+/// Statements and witness values are consumed as the constraint system is built, so that the
+/// program and the witness are never materialised next to it. The backend still holds the whole
+/// constraint system in memory.
 #[derive(Clone)]
-pub struct Computation<T, I: IntoIterator<Item = Statement<T>>> {
+pub struct Computation<T, I: IntoIterator<Item = Statement<T>>, W = Witness<T>> {
     program: ProgIterator<T, I>,
-    witness: Option<Witness<T>>,
+    witness: Option<W>,
 }
 
-impl<T, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
-    pub fn with_witness(program: ProgIterator<T, I>, witness: Witness<T>) -> Self {
+impl<T, I: IntoIterator<Item = Statement<T>>, W: WitnessSource<T>> Computation<T, I, W> {
+    pub fn with_witness(program: ProgIterator<T, I>, witness: W) -> Self {
         Computation {
             program,
             witness: Some(witness),
         }
     }
+}
 
+impl<T, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
     pub fn without_witness(program: ProgIterator<T, I>) -> Self {
         Computation {
             program,
@@ -45,32 +51,31 @@ impl<T, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
     }
 }
 
-fn ark_combination<T: Field + ArkFieldExtensions>(
+fn take<T: Field + ArkFieldExtensions, W: WitnessSource<T>>(
+    witness: &mut Option<W>,
+    variable: &FlatVariable,
+) -> Result<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr, SynthesisError> {
+    Ok(witness
+        .as_mut()
+        .and_then(|w| w.take(variable))
+        .ok_or(SynthesisError::AssignmentMissing)?
+        .into_ark())
+}
+
+fn ark_combination<T: Field + ArkFieldExtensions, W: WitnessSource<T>>(
     l: CanonicalLinComb<T>,
     cs: &mut ConstraintSystem<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr>,
-    symbols: &mut BTreeMap<FlatVariable, Variable>,
-    witness: &mut Witness<T>,
+    symbols: &mut Symbols<Variable>,
+    witness: &mut Option<W>,
 ) -> LinearCombination<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr> {
     l.0.into_iter()
         .map(|(k, v)| {
             (
                 v.into_ark(),
-                *symbols.entry(k).or_insert_with(|| {
+                symbols.get_or_insert_with(&k, || {
                     match k.is_output() {
-                        true => cs.new_input_variable(|| {
-                            Ok(witness
-                                .0
-                                .remove(&k)
-                                .ok_or(SynthesisError::AssignmentMissing)?
-                                .into_ark())
-                        }),
-                        false => cs.new_witness_variable(|| {
-                            Ok(witness
-                                .0
-                                .remove(&k)
-                                .ok_or(SynthesisError::AssignmentMissing)?
-                                .into_ark())
-                        }),
+                        true => cs.new_input_variable(|| take(witness, &k)),
+                        false => cs.new_witness_variable(|| take(witness, &k)),
                     }
                     .unwrap()
                 }),
@@ -80,52 +85,41 @@ fn ark_combination<T: Field + ArkFieldExtensions>(
 }
 
 impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>> ProgIterator<T, I> {
-    pub fn generate_constraints(
+    pub fn generate_constraints<W: WitnessSource<T>>(
         self,
         cs: ConstraintSystemRef<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr>,
-        witness: Option<Witness<T>>,
+        mut witness: Option<W>,
     ) -> Result<(), SynthesisError> {
         // mapping from IR variables
-        let mut symbols = BTreeMap::new();
-
-        let mut witness = witness.unwrap_or_else(Witness::empty);
+        let mut symbols = Symbols::new(Layout::new(self.arguments.len(), self.return_count));
 
-        assert!(symbols.insert(FlatVariable::one(), ConstraintSystem::<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr>::one()).is_none());
+        symbols.insert(
+            &FlatVariable::one(),
+            ConstraintSystem::<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr>::one(),
+        );
 
         match cs {
             ConstraintSystemRef::CS(rc) => {
                 let mut cs = rc.borrow_mut();
-                symbols.extend(self.arguments.iter().enumerate().map(|(_, p)| {
+                for p in &self.arguments {
                     let wire = match p.private {
-                        true => cs.new_witness_variable(|| {
-                            Ok(witness
-                                .0
-                                .remove(&p.id)
-                                .ok_or(SynthesisError::AssignmentMissing)?
-                                .into_ark())
-                        }),
-                        false => cs.new_input_variable(|| {
-                            Ok(witness
-                                .0
-                                .remove(&p.id)
-                                .ok_or(SynthesisError::AssignmentMissing)?
-                                .into_ark())
-                        }),
+                        true => cs.new_witness_variable(|| take(&mut witness, &p.id)),
+                        false => cs.new_input_variable(|| take(&mut witness, &p.id)),
                     }
                     .unwrap();
-                    (p.id, wire)
-                }));
+                    symbols.insert(&p.id, wire);
+                }
 
                 for statement in self.statements {
                     if let Statement::Constraint(quad, lin, _) = statement {
                         let a = ark_combination(
-                            quad.left.clone().into_canonical(),
+                            quad.left.into_canonical(),
                             &mut cs,
                             &mut symbols,
                             &mut witness,
                         );
                         let b = ark_combination(
-                            quad.right.clone().into_canonical(),
+                            quad.right.into_canonical(),
                             &mut cs,
                             &mut symbols,
                             &mut witness,
@@ -148,8 +142,10 @@ impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>> ProgIt
     }
 }
 
-impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
-    pub fn prove(self, params: &ProvingKey<T::ArkEngine>) -> Proof<T::ArkEngine> {
+impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>, W: WitnessSource<T>>
+    Computation<T, I, W>
+{
+    pub fn prove(mut self, params: &ProvingKey<T::ArkEngine>) -> Proof<T::ArkEngine> {
         let rng = &mut rand_0_7::rngs::StdRng::from_entropy();
 
         let public_inputs = self.public_inputs_values();
@@ -163,9 +159,9 @@ impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>> Comput
         proof
     }
 
-    pub fn public_inputs_values(&self) -> Vec<<T::ArkEngine as PairingEngine>::Fr> {
+    pub fn public_inputs_values(&mut self) -> Vec<<T::ArkEngine as PairingEngine>::Fr> {
         self.program
-            .public_inputs(self.witness.as_ref().unwrap())
+            .public_inputs(self.witness.as_mut().unwrap())
             .iter()
             .map(|v| v.clone().into_ark())
             .collect()
@@ -179,9 +175,9 @@ impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>> Comput
     }
 }
 
-impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>>
+impl<T: Field + ArkFieldExtensions, I: IntoIterator<Item = Statement<T>>, W: WitnessSource<T>>
     ConstraintSynthesizer<<<T as ArkFieldExtensions>::ArkEngine as PairingEngine>::Fr>
-    for Computation<T, I>
+    for Computation<T, I, W>
 {
     fn generate_constraints(
         self,
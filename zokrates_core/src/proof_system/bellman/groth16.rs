@@ -8,7 +8,7 @@ use crate::proof_system::{Backend, MpcBackend, NonUniversalBackend, Proof, Setup
 use zokrates_field::BellmanFieldExtensions;
 use zokrates_field::Field;
 
-use crate::ir::{ProgIterator, Statement, Witness};
+use crate::ir::{ProgIterator, Statement, WitnessSource};
 use crate::proof_system::bellman::Bellman;
 use crate::proof_system::bellman::Computation;
 use crate::proof_system::bellman::{parse_g1, parse_g2};
@@ -21,14 +21,14 @@ use std::io::{Read, Write};
 const G16_WARNING: &str = "WARNING: You are using the G16 scheme which is subject to malleability. See zokrates.github.io/toolbox/proving_schemes.html#g16-malleability for implications.";
 
 impl<T: Field + BellmanFieldExtensions> Backend<T, G16> for Bellman {
-    fn generate_proof<I: IntoIterator<Item = Statement<T>>>(
+    fn generate_proof<I: IntoIterator<Item = Statement<T>>, W: WitnessSource<T>>(
         program: ProgIterator<T, I>,
-        witness: Witness<T>,
+        witness: W,
         proving_key: Vec<u8>,
     ) -> Proof<<G16 as Scheme<T>>::ProofPoints> {
         println!("{}", G16_WARNING);
 
-        let computation = Computation::with_witness(program, witness);
+        let mut computation = Computation::with_witness(program, witness);
         let params = Parameters::read(proving_key.as_slice(), true).unwrap();
 
         let public_inputs: Vec<String> = computation
@@ -1,6 +1,7 @@
 pub mod groth16;
 
-use crate::ir::{CanonicalLinComb, ProgIterator, Statement, Witness};
+use crate::ir::{CanonicalLinComb, Layout, ProgIterator, Statement, Witness, WitnessSource};
+use crate::proof_system::Symbols;
 use bellman::groth16::Proof;
 use bellman::groth16::{
     create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof,
@@ -8,7 +9,6 @@ use bellman::groth16::{
 };
 use bellman::pairing::ff::ScalarEngine;
 use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError, Variable};
-use std::collections::BTreeMap;
 use zokrates_field::BellmanFieldExtensions;
 use zokrates_field::Field;
 
@@ -19,20 +19,26 @@ pub use self::parse::*;
 
 pub struct Bellman;
 
+/// A program to set up or prove, along with the source of its witness when proving.
+/// Statements and witness values are consumed as the constraint system is built, so that the
+/// program and the witness are never materialised next to it. The backend still holds the whole
+/// constraint system in memory.
 #[derive(Clone)]
-pub struct Computation<T, I: IntoIterator<Item = Statement<T>>> {
+pub struct Computation<T, I: IntoIterator<Item = Statement<T>>, W = Witness<T>> {
     program: ProgIterator<T, I>,
-    witness: Option<Witness<T>>,
+    witness: Option<W>,
 }
 
-impl<T: Field, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
-    pub fn with_witness(program: ProgIterator<T, I>, witness: Witness<T>) -> Self {
+impl<T: Field, I: IntoIterator<Item = Statement<T>>, W: WitnessSource<T>> Computation<T, I, W> {
+    pub fn with_witness(program: ProgIterator<T, I>, witness: W) -> Self {
         Computation {
             program,
             witness: Some(witness),
         }
     }
+}
 
+impl<T: Field, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
     pub fn without_witness(program: ProgIterator<T, I>) -> Self {
         Computation {
             program,
@@ -41,38 +47,35 @@ impl<T: Field, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
     }
 }
 
-fn bellman_combination<T: BellmanFieldExtensions, CS: ConstraintSystem<T::BellmanEngine>>(
+fn take<T: BellmanFieldExtensions, W: WitnessSource<T>>(
+    witness: &mut Option<W>,
+    variable: &FlatVariable,
+) -> Result<<T::BellmanEngine as ScalarEngine>::Fr, SynthesisError> {
+    Ok(witness
+        .as_mut()
+        .and_then(|w| w.take(variable))
+        .ok_or(SynthesisError::AssignmentMissing)?
+        .into_bellman())
+}
+
+fn bellman_combination<
+    T: BellmanFieldExtensions,
+    W: WitnessSource<T>,
+    CS: ConstraintSystem<T::BellmanEngine>,
+>(
     l: CanonicalLinComb<T>,
     cs: &mut CS,
-    symbols: &mut BTreeMap<FlatVariable, Variable>,
-    witness: &mut Witness<T>,
+    symbols: &mut Symbols<Variable>,
+    witness: &mut Option<W>,
 ) -> LinearCombination<T::BellmanEngine> {
     l.0.into_iter()
         .map(|(k, v)| {
             (
                 v.into_bellman(),
-                *symbols.entry(k).or_insert_with(|| {
+                symbols.get_or_insert_with(&k, || {
                     match k.is_output() {
-                        true => cs.alloc_input(
-                            || format!("{}", k),
-                            || {
-                                Ok(witness
-                                    .0
-                                    .remove(&k)
-                                    .ok_or(SynthesisError::AssignmentMissing)?
-                                    .into_bellman())
-                            },
-                        ),
-                        false => cs.alloc(
-                            || format!("{}", k),
-                            || {
-                                Ok(witness
-                                    .0
-                                    .remove(&k)
-                                    .ok_or(SynthesisError::AssignmentMissing)?
-                                    .into_bellman())
-                            },
-                        ),
+                        true => cs.alloc_input(|| format!("{}", k), || take(witness, &k)),
+                        false => cs.alloc(|| format!("{}", k), || take(witness, &k)),
                     }
                     .unwrap()
                 }),
@@ -82,44 +85,30 @@ fn bellman_combination<T: BellmanFieldExtensions, CS: ConstraintSystem<T::Bellma
 }
 
 impl<T: BellmanFieldExtensions + Field, I: IntoIterator<Item = Statement<T>>> ProgIterator<T, I> {
-    pub fn synthesize<CS: ConstraintSystem<T::BellmanEngine>>(
+    pub fn synthesize<CS: ConstraintSystem<T::BellmanEngine>, W: WitnessSource<T>>(
         self,
         cs: &mut CS,
-        witness: Option<Witness<T>>,
+        mut witness: Option<W>,
     ) -> Result<(), SynthesisError> {
         // mapping from IR variables
-        let mut symbols = BTreeMap::new();
-
-        let mut witness = witness.unwrap_or_else(Witness::empty);
+        let mut symbols = Symbols::new(Layout::new(self.arguments.len(), self.return_count));
 
-        assert!(symbols.insert(FlatVariable::one(), CS::one()).is_none());
+        symbols.insert(&FlatVariable::one(), CS::one());
 
-        symbols.extend(self.arguments.iter().enumerate().map(|(index, p)| {
+        for (index, p) in self.arguments.iter().enumerate() {
             let wire = match p.private {
                 true => cs.alloc(
                     || format!("PRIVATE_INPUT_{}", index),
-                    || {
-                        Ok(witness
-                            .0
-                            .remove(&p.id)
-                            .ok_or(SynthesisError::AssignmentMissing)?
-                            .into_bellman())
-                    },
+                    || take(&mut witness, &p.id),
                 ),
                 false => cs.alloc_input(
                     || format!("PUBLIC_INPUT_{}", index),
-                    || {
-                        Ok(witness
-                            .0
-                            .remove(&p.id)
-                            .ok_or(SynthesisError::AssignmentMissing)?
-                            .into_bellman())
-                    },
+                    || take(&mut witness, &p.id),
                 ),
             }
             .unwrap();
-            (p.id, wire)
-        }));
+            symbols.insert(&p.id, wire);
+        }
 
         for statement in self.statements {
             if let Statement::Constraint(quad, lin, _) = statement {
@@ -145,7 +134,12 @@ impl<T: BellmanFieldExtensions + Field, I: IntoIterator<Item = Statement<T>>> Pr
     }
 }
 
-impl<T: BellmanFieldExtensions + Field, I: IntoIterator<Item = Statement<T>>> Computation<T, I> {
+impl<
+        T: BellmanFieldExtensions + Field,
+        I: IntoIterator<Item = Statement<T>>,
+        W: WitnessSource<T>,
+    > Computation<T, I, W>
+{
     fn get_random_seed(&self) -> Result<[u32; 8], getrandom::Error> {
         let mut seed = [0u8; 32];
         getrandom::getrandom(&mut seed)?;
@@ -158,7 +152,7 @@ impl<T: BellmanFieldExtensions + Field, I: IntoIterator<Item = Statement<T>>> Co
         Ok(seed)
     }
 
-    pub fn prove(self, params: &Parameters<T::BellmanEngine>) -> Proof<T::BellmanEngine> {
+    pub fn prove(mut self, params: &Parameters<T::BellmanEngine>) -> Proof<T::BellmanEngine> {
         use rand_0_4::SeedableRng;
         let seed = self.get_random_seed().unwrap();
         let rng = &mut ChaChaRng::from_seed(seed.as_ref());
@@ -175,9 +169,9 @@ impl<T: BellmanFieldExtensions + Field, I: IntoIterator<Item = Statement<T>>> Co
         proof
     }
 
-    pub fn public_inputs_values(&self) -> Vec<<T::BellmanEngine as ScalarEngine>::Fr> {
+    pub fn public_inputs_values(&mut self) -> Vec<<T::BellmanEngine as ScalarEngine>::Fr> {
         self.program
-            .public_inputs(self.witness.as_ref().unwrap())
+            .public_inputs(self.witness.as_mut().unwrap())
             .iter()
             .map(|v| v.clone().into_bellman())
             .collect()
@@ -192,8 +186,11 @@ impl<T: BellmanFieldExtensions + Field, I: IntoIterator<Item = Statement<T>>> Co
     }
 }
 
-impl<T: BellmanFieldExtensions + Field, I: IntoIterator<Item = Statement<T>>>
-    Circuit<T::BellmanEngine> for Computation<T, I>
+impl<
+        T: BellmanFieldExtensions + Field,
+        I: IntoIterator<Item = Statement<T>>,
+        W: WitnessSource<T>,
+    > Circuit<T::BellmanEngine> for Computation<T, I, W>
 {
     fn synthesize<CS: ConstraintSystem<T::BellmanEngine>>(
         self,
@@ -314,6 +311,39 @@ mod tests {
             let _proof = computation.prove(&params);
         }
 
+        #[test]
+        fn binary_witness() {
+            use crate::ir::WitnessReader;
+            use std::io::Cursor;
+
+            let program: Prog<Bn128Field> = Prog {
+                arguments: vec![FlatParameter::public(FlatVariable::new(0))],
+                return_count: 1,
+                statements: vec![Statement::constraint(
+                    FlatVariable::new(0),
+                    FlatVariable::public(0),
+                )],
+            };
+
+            let interpreter = Interpreter::default();
+
+            let witness = interpreter
+                .execute(program.clone(), &[Bn128Field::from(42)])
+                .unwrap();
+
+            let mut buffer = Cursor::new(vec![]);
+            witness.write_binary(&mut buffer).unwrap();
+            buffer.set_position(0);
+
+            let params = Computation::without_witness(program.clone()).setup();
+
+            let computation = Computation::with_witness(
+                program,
+                WitnessReader::<Bn128Field, _>::new(buffer).unwrap(),
+            );
+            let _proof = computation.prove(&params);
+        }
+
         #[test]
         fn no_arguments() {
             let program: Prog<Bn128Field> = Prog {
@@ -1,4 +1,4 @@
-use crate::ir::{ProgIterator, Statement, Witness};
+use crate::ir::{ProgIterator, Statement, WitnessSource};
 use crate::proof_system::gm17::{ProofPoints, VerificationKey, GM17};
 use crate::proof_system::libsnark::ffi::{c_free, Buffer, ProofResult, SetupResult};
 use crate::proof_system::libsnark::{
@@ -39,12 +39,16 @@ extern "C" {
 }
 
 impl Backend<Bn128Field, GM17> for Libsnark {
-    fn generate_proof<I: IntoIterator<Item = Statement<Bn128Field>>>(
+    fn generate_proof<
+        I: IntoIterator<Item = Statement<Bn128Field>>,
+        W: WitnessSource<Bn128Field>,
+    >(
         program: ProgIterator<Bn128Field, I>,
-        witness: Witness<Bn128Field>,
+        witness: W,
         proving_key: Vec<u8>,
     ) -> Proof<<GM17 as Scheme<Bn128Field>>::ProofPoints> {
         let program = program.collect();
+        let mut witness = witness.into_witness();
 
         let (public_inputs_arr, public_inputs_length, private_inputs_arr, private_inputs_length) =
             prepare_generate_proof(program.clone(), witness.clone());
@@ -76,7 +80,7 @@ impl Backend<Bn128Field, GM17> for Libsnark {
 
         let points = ProofPoints::<G1Affine, G2Affine> { a, b, c };
         let public_inputs: Vec<String> = program
-            .public_inputs(&witness)
+            .public_inputs(&mut witness)
             .iter()
             .map(|f| format!("0x{:064x}", f.to_biguint()))
             .collect();
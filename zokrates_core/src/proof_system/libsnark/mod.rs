@@ -162,7 +162,10 @@ pub fn prepare_generate_proof<T: Field>(
     // recover variable order from the program
     let (variables, public_variables_count, _, _, _) = r1cs_program(program);
 
-    let witness: Vec<_> = variables.iter().map(|x| witness.0[x].clone()).collect();
+    let witness: Vec<_> = variables
+        .iter()
+        .map(|x| witness.get(x).unwrap().clone())
+        .collect();
 
     // split witness into public and private inputs at offset
     let mut public_inputs: Vec<_> = witness.clone();
@@ -4,7 +4,7 @@ use crate::proof_system::libsnark::{
 };
 use crate::proof_system::{Backend, G1Affine, G2Affine, NonUniversalBackend, Proof, SetupKeypair};
 
-use crate::ir::{ProgIterator, Statement, Witness};
+use crate::ir::{ProgIterator, Statement, WitnessSource};
 use crate::proof_system::libsnark::serialization::{read_g1, read_g2, write_g1, write_g2};
 use crate::proof_system::pghr13::{ProofPoints, VerificationKey, PGHR13};
 use crate::proof_system::Scheme;
@@ -42,12 +42,16 @@ extern "C" {
 }
 
 impl Backend<Bn128Field, PGHR13> for Libsnark {
-    fn generate_proof<I: IntoIterator<Item = Statement<Bn128Field>>>(
+    fn generate_proof<
+        I: IntoIterator<Item = Statement<Bn128Field>>,
+        W: WitnessSource<Bn128Field>,
+    >(
         program: ProgIterator<Bn128Field, I>,
-        witness: Witness<Bn128Field>,
+        witness: W,
         proving_key: Vec<u8>,
     ) -> Proof<<PGHR13 as Scheme<Bn128Field>>::ProofPoints> {
         let program = program.collect();
+        let mut witness = witness.into_witness();
 
         let (public_inputs_arr, public_inputs_length, private_inputs_arr, private_inputs_length) =
             prepare_generate_proof(program.clone(), witness.clone());
@@ -94,7 +98,7 @@ impl Backend<Bn128Field, PGHR13> for Libsnark {
         };
 
         let public_inputs: Vec<String> = program
-            .public_inputs(&witness)
+            .public_inputs(&mut witness)
             .iter()
             .map(|f| format!("0x{:064x}", f.to_biguint()))
             .collect();
@@ -20,6 +20,47 @@ use std::io::{Read, Write};
 use zokrates_field::BellmanFieldExtensions;
 use zokrates_field::Field;
 
+#[cfg(any(feature = "bellman", feature = "ark"))]
+use crate::flat_absy::FlatVariable;
+
+/// Mapping from IR variables to the variables of a backend, indexed by their position in the
+/// witness layout of the program
+#[cfg(any(feature = "bellman", feature = "ark"))]
+struct Symbols<V> {
+    layout: ir::Layout,
+    variables: Vec<Option<V>>,
+}
+
+#[cfg(any(feature = "bellman", feature = "ark"))]
+impl<V: Copy> Symbols<V> {
+    fn new(layout: ir::Layout) -> Self {
+        Symbols {
+            layout,
+            variables: vec![],
+        }
+    }
+
+    fn insert(&mut self, variable: &FlatVariable, value: V) {
+        let index = self.layout.index(variable);
+        if index >= self.variables.len() {
+            self.variables.resize(index + 1, None);
+        }
+        self.variables[index] = Some(value);
+    }
+
+    fn get_or_insert_with<F: FnOnce() -> V>(&mut self, variable: &FlatVariable, f: F) -> V {
+        let index = self.layout.index(variable);
+        match self.variables.get(index).cloned().flatten() {
+            Some(value) => value,
+            None => {
+                let value = f();
+                self.insert(variable, value);
+                value
+            }
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct SetupKeypair<V> {
     pub vk: V,
@@ -84,9 +125,9 @@ impl ToString for G2Affine {
 }
 
 pub trait Backend<T: Field, S: Scheme<T>> {
-    fn generate_proof<I: IntoIterator<Item = ir::Statement<T>>>(
+    fn generate_proof<I: IntoIterator<Item = ir::Statement<T>>, W: ir::WitnessSource<T>>(
         program: ir::ProgIterator<T, I>,
-        witness: ir::Witness<T>,
+        witness: W,
         proving_key: Vec<u8>,
     ) -> Proof<S::ProofPoints>;
 
@@ -15,14 +15,16 @@ use std::fmt;
 use std::path::PathBuf;
 use zokrates_field::Field;
 
+use crate::diagnostics::{Note, Span};
 use crate::parser::Position;
+use zokrates_common::suggest;
 
 use crate::absy::types::{UnresolvedSignature, UnresolvedType, UserTypeId};
 
 use crate::typed_absy::types::{
-    check_type, specialize_declaration_type, ArrayType, DeclarationArrayType, DeclarationConstant,
-    DeclarationFunctionKey, DeclarationSignature, DeclarationStructMember, DeclarationStructType,
-    DeclarationType, GenericIdentifier, StructLocation, StructMember,
+    check_type, specialize_declaration_type, try_from_g_type, ArrayType, DeclarationArrayType,
+    DeclarationConstant, DeclarationFunctionKey, DeclarationSignature, DeclarationStructMember,
+    DeclarationStructType, DeclarationType, GenericIdentifier, StructLocation, StructMember,
 };
 use std::hash::{Hash, Hasher};
 
@@ -30,6 +32,8 @@ use std::hash::{Hash, Hasher};
 pub struct ErrorInner {
     pos: Option<(Position, Position)>,
     message: String,
+    /// Suggestions and secondary labels attached to the error
+    notes: Vec<Note>,
 }
 
 #[derive(PartialEq, Debug)]
@@ -39,6 +43,14 @@ pub struct Error {
 }
 
 impl ErrorInner {
+    pub(crate) fn new<S: Into<String>>(pos: Option<(Position, Position)>, message: S) -> Self {
+        ErrorInner {
+            pos,
+            message: message.into(),
+            notes: vec![],
+        }
+    }
+
     pub fn pos(&self) -> &Option<(Position, Position)> {
         &self.pos
     }
@@ -47,6 +59,19 @@ impl ErrorInner {
         &self.message
     }
 
+    pub fn notes(&self) -> &[Note] {
+        &self.notes
+    }
+
+    fn with_note<S: Into<String>>(mut self, pos: Option<(Position, Position)>, message: S) -> Self {
+        self.notes.push(Note {
+            message: message.into(),
+            file: None,
+            span: pos.map(Span::from),
+        });
+        self
+    }
+
     fn in_file(self, id: &ModuleId) -> Error {
         Error {
             inner: self,
@@ -85,6 +110,18 @@ type TypeMap<'ast, T> = BTreeMap<OwnedModuleId, BTreeMap<UserTypeId, UserDeclara
 type ConstantMap<'ast, T> =
     BTreeMap<OwnedModuleId, BTreeMap<ConstantIdentifier<'ast>, DeclarationType<'ast, T>>>;
 
+/// A checked module along with the types and constants it declares or imports, from which the
+/// modules importing it can be checked without checking it again
+#[derive(Debug, Clone)]
+pub struct CheckedModule<'ast, T> {
+    module: TypedModule<'ast, T>,
+    types: BTreeMap<UserTypeId, UserDeclarationType<'ast, T>>,
+    constants: BTreeMap<ConstantIdentifier<'ast>, DeclarationType<'ast, T>>,
+}
+
+/// A collection of `CheckedModule`s
+pub type CheckedModules<'ast, T> = BTreeMap<OwnedModuleId, CheckedModule<'ast, T>>;
+
 /// The global state of the program during semantic checks
 #[derive(Debug)]
 struct State<'ast, T> {
@@ -110,9 +147,27 @@ enum SymbolType<'ast, T> {
 #[derive(Default)]
 struct SymbolUnifier<'ast, T> {
     symbols: BTreeMap<String, SymbolType<'ast, T>>,
+    /// The position of the first declaration of each symbol
+    positions: BTreeMap<String, (Position, Position)>,
 }
 
 impl<'ast, T: std::cmp::Ord> SymbolUnifier<'ast, T> {
+    fn declare<S: Into<String>>(&mut self, id: S, pos: (Position, Position)) {
+        self.positions.entry(id.into()).or_insert(pos);
+    }
+
+    /// The error for the symbol `id` declared at `pos`, which conflicts with a previous declaration
+    fn conflict(&self, id: &str, pos: (Position, Position)) -> ErrorInner {
+        let error = ErrorInner::new(Some(pos), format!("{} conflicts with another symbol", id));
+
+        match self.positions.get(id) {
+            Some(first) if *first != pos => {
+                error.with_note(Some(*first), format!("`{}` is first declared here", id))
+            }
+            _ => error,
+        }
+    }
+
     fn insert_type<S: Into<String>>(&mut self, id: S) -> bool {
         let e = self.symbols.entry(id.into());
         match e {
@@ -173,6 +228,37 @@ impl<'ast, T: Field> State<'ast, T> {
             constants: BTreeMap::new(),
         }
     }
+
+    /// The state of a program whose modules in `checked` do not need to be checked again
+    fn with_checked(modules: Modules<'ast>, checked: CheckedModules<'ast, T>) -> Self {
+        let mut state = State::new(modules);
+
+        for (id, checked) in checked {
+            state.modules.remove(&id);
+            state.typed_modules.insert(id.clone(), checked.module);
+            state.types.insert(id.clone(), checked.types);
+            state.constants.insert(id, checked.constants);
+        }
+
+        state
+    }
+
+    /// The modules checked so far
+    fn checked(&self) -> CheckedModules<'ast, T> {
+        self.typed_modules
+            .iter()
+            .map(|(id, module)| {
+                (
+                    id.clone(),
+                    CheckedModule {
+                        module: module.clone(),
+                        types: self.types.get(id).cloned().unwrap_or_default(),
+                        constants: self.constants.get(id).cloned().unwrap_or_default(),
+                    },
+                )
+            })
+            .collect()
+    }
 }
 
 /// A function query in the current module.
@@ -347,6 +433,43 @@ pub struct Checker<'ast, T> {
     level: usize,
 }
 
+/// A note describing the generic parameters `type_id` is declared with
+fn declared_generics(type_id: &str, generics: &[&str]) -> String {
+    match generics.len() {
+        0 => format!("`{}` does not take generic arguments", type_id),
+        _ => format!(
+            "`{}` is declared as `{}<{}>`",
+            type_id,
+            type_id,
+            generics.join(", ")
+        ),
+    }
+}
+
+/// The error for a type `id` which is not among the types `declared` in the module
+fn undefined_type<'ast, T>(
+    id: &str,
+    declared: &BTreeMap<UserTypeId, UserDeclarationType<'ast, T>>,
+    pos: (Position, Position),
+) -> ErrorInner {
+    let error = ErrorInner::new(Some(pos), format!("Undefined type {}", id));
+
+    match suggest(id, declared.keys().map(String::as_str)) {
+        Some(similar) => error.with_note(None, format!("did you mean `{}`?", similar)),
+        None => error,
+    }
+}
+
+/// Whether `ty` contains integer literals, whose type is only known from where they are used
+fn contains_int<T>(ty: &Type<'_, T>) -> bool {
+    match ty {
+        Type::Int => true,
+        Type::Array(array_type) => contains_int(&array_type.ty),
+        Type::Struct(struct_type) => struct_type.members.iter().any(|m| contains_int(&m.ty)),
+        _ => false,
+    }
+}
+
 impl<'ast, T: Field> Checker<'ast, T> {
     /// Check a `Program`
     ///
@@ -357,16 +480,139 @@ impl<'ast, T: Field> Checker<'ast, T> {
         Checker::default().check_program(prog)
     }
 
+    /// Check a `Program` without checking the modules in `checked` again, returning the checked
+    /// modules of the program along with it
+    ///
+    /// # Arguments
+    ///
+    /// * `prog` - The `Program` to be checked
+    /// * `checked` - Modules of the program which were already checked, along with all the modules
+    /// they import
+    pub fn check_with_modules(
+        prog: Program<'ast>,
+        checked: CheckedModules<'ast, T>,
+    ) -> Result<(TypedProgram<'ast, T>, CheckedModules<'ast, T>), Vec<Error>> {
+        let mut state = State::with_checked(prog.modules, checked);
+
+        Checker::default().check_main(&prog.main, &mut state)?;
+
+        let checked = state.checked();
+
+        Ok((
+            TypedProgram {
+                main: prog.main,
+                modules: state.typed_modules,
+            },
+            checked,
+        ))
+    }
+
+    /// Check a `Program` along with a `main` function returning `expressions`, which are checked
+    /// in the scope of the declarations of the main module. The return types of `main` are the
+    /// types of `expressions`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prog` - The `Program` to be checked, without a `main` function
+    /// * `expressions` - The expressions returned by `main`
+    pub fn check_with_expressions(
+        prog: Program<'ast>,
+        expressions: Vec<ExpressionNode<'ast>>,
+    ) -> Result<TypedProgram<'ast, T>, Vec<Error>> {
+        let mut checker = Checker::default();
+        let mut state = State::new(prog.modules);
+
+        let main_id = prog.main;
+
+        checker.check_module(&main_id, &mut state)?;
+
+        let mut errors = vec![];
+        let mut outputs = vec![];
+        let mut expressions_checked = vec![];
+
+        for e in expressions {
+            let pos = e.pos();
+
+            match checker.check_expression(e, &main_id, &state.types) {
+                Ok(e) => {
+                    let ty = e.get_type();
+
+                    // the types of integer literals are inferred from where they are used, which
+                    // the expression alone does not tell
+                    match contains_int(&ty) {
+                        true => errors.push(ErrorInner::new(
+                            Some(pos),
+                            format!(
+                                "Cannot infer the type of an expression of type {}, add a suffix to its integer literals such as `1f` or `1u32`",
+                                ty
+                            ),
+                        )),
+                        false => match try_from_g_type(ty.clone()) {
+                            Ok(ty) => {
+                                outputs.push(ty);
+                                expressions_checked.push(e);
+                            }
+                            Err(_) => errors.push(ErrorInner::new(
+                                Some(pos),
+                                format!("Cannot return an expression of type {} from `main`", ty),
+                            )),
+                        },
+                    }
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.into_iter().map(|e| e.in_file(&main_id)).collect());
+        }
+
+        let key = DeclarationFunctionKey::with_location(main_id.clone(), "main")
+            .signature(DeclarationSignature::new().outputs(outputs));
+
+        let main = TypedFunction {
+            arguments: vec![],
+            statements: vec![TypedStatement::Return(expressions_checked)],
+            signature: key.signature.clone(),
+        };
+
+        let module = state.typed_modules.get_mut(&main_id).unwrap();
+
+        module
+            .symbols
+            .push(TypedFunctionSymbolDeclaration::new(key, TypedFunctionSymbol::Here(main)).into());
+
+        Checker::check_single_main(module).map_err(|inner| vec![inner.in_file(&main_id)])?;
+
+        Ok(TypedProgram {
+            main: main_id,
+            modules: state.typed_modules,
+        })
+    }
+
     fn check_program(
         &mut self,
         program: Program<'ast>,
     ) -> Result<TypedProgram<'ast, T>, Vec<Error>> {
         let mut state = State::new(program.modules);
 
+        self.check_main(&program.main, &mut state)?;
+
+        Ok(TypedProgram {
+            main: program.main,
+            modules: state.typed_modules,
+        })
+    }
+
+    fn check_main(
+        &mut self,
+        main_id: &ModuleId,
+        state: &mut State<'ast, T>,
+    ) -> Result<(), Vec<Error>> {
         let mut errors = vec![];
 
         // recursively type-check modules starting with `main`
-        match self.check_module(&program.main, &mut state) {
+        match self.check_module(main_id, state) {
             Ok(()) => {}
             Err(e) => errors.extend(e),
         };
@@ -375,20 +621,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
             return Err(errors);
         }
 
-        let main_id = program.main.clone();
-
-        Checker::check_single_main(state.typed_modules.get(&program.main).unwrap()).map_err(
-            |inner| {
-                vec![Error {
-                    inner,
-                    module_id: main_id,
-                }]
-            },
-        )?;
-
-        Ok(TypedProgram {
-            main: program.main,
-            modules: state.typed_modules,
+        Checker::check_single_main(state.typed_modules.get(main_id).unwrap()).map_err(|inner| {
+            vec![Error {
+                inner,
+                module_id: main_id.to_path_buf(),
+            }]
         })
     }
 
@@ -413,13 +650,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 .and_then(|m| m.get(g.value))
                 .is_some()
             {
-                errors.push(ErrorInner {
-                    pos: Some(g.pos()),
-                    message: format!(
+                errors.push(ErrorInner::new(
+                    Some(g.pos()),
+                    format!(
                         "Generic parameter {p} conflicts with constant symbol {p}",
                         p = g.value
                     ),
-                });
+                ));
             } else {
                 match generics_map.insert(g.value, index).is_none() {
                     true => {
@@ -428,10 +665,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         ));
                     }
                     false => {
-                        errors.push(ErrorInner {
-                            pos: Some(g.pos()),
-                            message: format!("Generic parameter {} is already declared", g.value),
-                        });
+                        errors.push(ErrorInner::new(
+                            Some(g.pos()),
+                            format!("Generic parameter {} is already declared", g.value),
+                        ));
                     }
                 }
             }
@@ -450,10 +687,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 // check that all declared generics were used
                 for declared_generic in generics_map.keys() {
                     if !used_generics.contains(declared_generic) {
-                        errors.push(ErrorInner {
-                            pos: Some(pos),
-                            message: format!("Generic parameter {} must be used", declared_generic),
-                        });
+                        errors.push(ErrorInner::new(
+                            Some(pos),
+                            format!("Generic parameter {} must be used", declared_generic),
+                        ));
                     }
                 }
 
@@ -507,15 +744,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
             }
             DeclarationType::Int => Err(checked_expr), // Integers cannot be assigned
         }
-        .map_err(|e| ErrorInner {
-            pos: Some(pos),
-            message: format!(
-                "Expression `{}` of type `{}` cannot be assigned to constant `{}` of type `{}`",
-                e,
-                e.get_type(),
-                id,
-                ty
-            ),
+        .map_err(|e| {
+            ErrorInner::new(
+                Some(pos),
+                format!(
+                    "Expression `{}` of type `{}` cannot be assigned to constant `{}` of type `{}`",
+                    e,
+                    e.get_type(),
+                    id,
+                    ty
+                ),
+            )
         })
         .map(|e| TypedConstant::new(e, ty))
     }
@@ -544,13 +783,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 .and_then(|m| m.get(g.value))
                 .is_some()
             {
-                errors.push(ErrorInner {
-                    pos: Some(g.pos()),
-                    message: format!(
+                errors.push(ErrorInner::new(
+                    Some(g.pos()),
+                    format!(
                         "Generic parameter {p} conflicts with constant symbol {p}",
                         p = g.value
                     ),
-                });
+                ));
             } else {
                 match generics_map.insert(g.value, index).is_none() {
                     true => {
@@ -559,10 +798,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         )));
                     }
                     false => {
-                        errors.push(ErrorInner {
-                            pos: Some(g.pos()),
-                            message: format!("Generic parameter {} is already declared", g.value),
-                        });
+                        errors.push(ErrorInner::new(
+                            Some(g.pos()),
+                            format!("Generic parameter {} is already declared", g.value),
+                        ));
                     }
                 }
             }
@@ -584,10 +823,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
             {
                 Ok(f) => match fields_set.insert(f.0.clone()) {
                     true => fields.push(f),
-                    false => errors.push(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Duplicate key {} in struct definition", f.0,),
-                    }),
+                    false => errors.push(ErrorInner::new(
+                        Some(pos),
+                        format!("Duplicate key {} in struct definition", f.0,),
+                    )),
                 },
                 Err(e) => {
                     errors.push(e);
@@ -598,10 +837,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
         // check that all declared generics were used
         for declared_generic in generics_map.keys() {
             if !used_generics.contains(declared_generic) {
-                errors.push(ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Generic parameter {} must be used", declared_generic),
-                });
+                errors.push(ErrorInner::new(
+                    Some(pos),
+                    format!("Generic parameter {} must be used", declared_generic),
+                ));
             }
         }
 
@@ -633,6 +872,8 @@ impl<'ast, T: Field> Checker<'ast, T> {
         let pos = declaration.pos();
         let declaration = declaration.value;
 
+        symbol_unifier.declare(declaration.id, pos);
+
         match declaration.symbol.clone() {
             Symbol::Here(SymbolDefinition::Struct(t)) => {
                 match self.check_struct_type_declaration(
@@ -644,14 +885,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     Ok(ty) => {
                         match symbol_unifier.insert_type(declaration.id) {
                             false => errors.push(
-                                ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
-                                        "{} conflicts with another symbol",
-                                        declaration.id
-                                    ),
-                                }
-                                .in_file(module_id),
+                                symbol_unifier
+                                    .conflict(declaration.id, pos)
+                                    .in_file(module_id),
                             ),
                             true => {
                                 // there should be no entry in the map for this type yet
@@ -686,14 +922,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     Ok(c) => {
                         match symbol_unifier.insert_constant(declaration.id) {
                             false => errors.push(
-                                ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
-                                        "{} conflicts with another symbol",
-                                        declaration.id
-                                    ),
-                                }
-                                .in_file(module_id),
+                                symbol_unifier
+                                    .conflict(declaration.id, pos)
+                                    .in_file(module_id),
                             ),
                             true => {
                                 symbols.push(
@@ -732,14 +963,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     Ok(ty) => {
                         match symbol_unifier.insert_type(declaration.id) {
                             false => errors.push(
-                                ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
-                                        "{} conflicts with another symbol",
-                                        declaration.id,
-                                    ),
-                                }
-                                .in_file(module_id),
+                                symbol_unifier
+                                    .conflict(declaration.id, pos)
+                                    .in_file(module_id),
                             ),
                             true => {
                                 assert!(state
@@ -763,14 +989,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                             .insert_function(declaration.id, funct.signature.clone())
                         {
                             false => errors.push(
-                                ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
-                                        "{} conflicts with another symbol",
-                                        declaration.id
-                                    ),
-                                }
-                                .in_file(module_id),
+                                symbol_unifier
+                                    .conflict(declaration.id, pos)
+                                    .in_file(module_id),
                             ),
                             true => {}
                         };
@@ -858,15 +1079,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                 // we imported a type, so the symbol it gets bound to should not already exist
                                 match symbol_unifier.insert_type(declaration.id) {
                                     false => {
-                                        errors.push(Error {
-                                            module_id: module_id.to_path_buf(),
-                                            inner: ErrorInner {
-                                                pos: Some(pos),
-                                                message: format!(
-                                                    "{} conflicts with another symbol",
-                                                    declaration.id,
-                                                ),
-                                            }});
+                                        errors.push(symbol_unifier.conflict(declaration.id, pos).in_file(module_id));
                                     }
                                     true => {}
                                 };
@@ -879,15 +1092,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                             (0, None, Some(ty)) => {
                                 match symbol_unifier.insert_constant(declaration.id) {
                                     false => {
-                                        errors.push(Error {
-                                            module_id: module_id.to_path_buf(),
-                                            inner: ErrorInner {
-                                                pos: Some(pos),
-                                                message: format!(
-                                                    "{} conflicts with another symbol",
-                                                    declaration.id,
-                                                ),
-                                            }});
+                                        errors.push(symbol_unifier.conflict(declaration.id, pos).in_file(module_id));
                                     }
                                     true => {
                                         let imported_id = CanonicalConstantIdentifier::new(import.symbol_id, import.module_id);
@@ -911,13 +1116,41 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                 };
                             }
                             (0, None, None) => {
-                                errors.push(ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
+                                let error = ErrorInner::new(Some(pos), format!(
                                         "Could not find symbol {} in module {}",
                                         import.symbol_id, import.module_id.display(),
-                                    ),
-                                }.in_file(module_id));
+                                    ));
+
+                                // suggest a symbol of the imported module with a similar name
+                                let functions = state
+                                    .typed_modules
+                                    .get(&import.module_id)
+                                    .unwrap()
+                                    .functions_iter()
+                                    .map(|d| d.key.id.to_string());
+                                let types = state
+                                    .types
+                                    .get(&import.module_id)
+                                    .into_iter()
+                                    .flat_map(|t| t.keys().cloned());
+                                let constants = state
+                                    .constants
+                                    .get(&import.module_id)
+                                    .into_iter()
+                                    .flat_map(|c| c.keys().map(|c| c.to_string()));
+                                let symbols: Vec<String> =
+                                    functions.chain(types).chain(constants).collect();
+
+                                let error = match suggest(
+                                    import.symbol_id,
+                                    symbols.iter().map(String::as_str),
+                                ) {
+                                    Some(similar) => error
+                                        .with_note(None, format!("did you mean `{}`?", similar)),
+                                    None => error,
+                                };
+
+                                errors.push(error.in_file(module_id));
                             }
                             (_, Some(_), Some(_)) => unreachable!("collision in module we're importing from should have been caught when checking it"),
                             _ => {
@@ -925,13 +1158,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                                     match symbol_unifier.insert_function(declaration.id, candidate.signature.clone()) {
                                         false => {
-                                            errors.push(ErrorInner {
-                                                pos: Some(pos),
-                                                message: format!(
-                                                    "{} conflicts with another symbol",
-                                                    declaration.id,
-                                                ),
-                                            }.in_file(module_id));
+                                            errors.push(symbol_unifier.conflict(declaration.id, pos).in_file(module_id));
                                         },
                                         true => {}
                                     };
@@ -959,14 +1186,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 match symbol_unifier.insert_function(declaration.id, funct.typed_signature()) {
                     false => {
                         errors.push(
-                            ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
-                                    "{} conflicts with another symbol",
-                                    declaration.id
-                                ),
-                            }
-                            .in_file(module_id),
+                            symbol_unifier
+                                .conflict(declaration.id, pos)
+                                .in_file(module_id),
                         );
                     }
                     true => {}
@@ -1056,24 +1278,21 @@ impl<'ast, T: Field> Checker<'ast, T> {
             .count()
         {
             1 => Ok(()),
-            0 => Err(ErrorInner {
-                pos: None,
-                message: "No main function found".into(),
-            }),
-            n => Err(ErrorInner {
-                pos: None,
-                message: format!("Only one main function allowed, found {}", n),
-            }),
+            0 => Err(ErrorInner::new(None, "No main function found")),
+            n => Err(ErrorInner::new(
+                None,
+                format!("Only one main function allowed, found {}", n),
+            )),
         }
     }
 
     fn check_for_var(&self, var: &VariableNode<'ast>) -> Result<(), ErrorInner> {
         match var.value.get_type() {
             UnresolvedType::Uint(32) => Ok(()),
-            t => Err(ErrorInner {
-                pos: Some(var.pos()),
-                message: format!("Variable in for loop cannot have type {}", t),
-            }),
+            t => Err(ErrorInner::new(
+                Some(var.pos()),
+                format!("Variable in for loop cannot have type {}", t),
+            )),
         }
     }
 
@@ -1141,10 +1360,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     }) {
                         true => {}
                         false => {
-                            errors.push(ErrorInner {
-                                pos: Some(pos),
-                                message: format!("Duplicate name in function definition: `{}` was previously declared as an argument or a generic constant", arg.id.value.id)
-                            });
+                            errors.push(ErrorInner::new(Some(pos), format!("Duplicate name in function definition: `{}` was previously declared as an argument or a generic constant", arg.id.value.id)));
                         }
                     };
 
@@ -1161,10 +1377,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                     if let Statement::Return(..) = stat.value {
                         if found_return {
-                            errors.push(ErrorInner {
+                            errors.push(ErrorInner::new(
                                 pos,
-                                message: "Expected a single return statement".to_string(),
-                            });
+                                "Expected a single return statement".to_string(),
+                            ));
                         }
 
                         found_return = true;
@@ -1177,9 +1393,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                     == s.outputs
                                 {
                                     true => {}
-                                    false => errors.push(ErrorInner {
+                                    false => errors.push(ErrorInner::new(
                                         pos,
-                                        message: format!(
+                                        format!(
                                             "Expected ({}) in return statement, found ({})",
                                             s.outputs
                                                 .iter()
@@ -1192,7 +1408,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                                 .collect::<Vec<_>>()
                                                 .join(", ")
                                         ),
-                                    }),
+                                    )),
                                 }
                             };
                             statements_checked.push(statement);
@@ -1204,10 +1420,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 }
 
                 if !found_return {
-                    errors.push(ErrorInner {
-                        pos: Some(pos),
-                        message: "Expected a return statement".to_string(),
-                    });
+                    errors.push(ErrorInner::new(
+                        Some(pos),
+                        "Expected a return statement".to_string(),
+                    ));
                 }
 
                 signature = Some(s);
@@ -1252,13 +1468,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 .and_then(|m| m.get(g.value))
                 .is_some()
             {
-                errors.push(ErrorInner {
-                    pos: Some(g.pos()),
-                    message: format!(
+                errors.push(ErrorInner::new(
+                    Some(g.pos()),
+                    format!(
                         "Generic parameter {p} conflicts with constant symbol {p}",
                         p = g.value
                     ),
-                });
+                ));
             } else {
                 match generics_map.insert(g.value, index).is_none() {
                     true => {
@@ -1267,10 +1483,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         )));
                     }
                     false => {
-                        errors.push(ErrorInner {
-                            pos: Some(g.pos()),
-                            message: format!("Generic parameter {} is already declared", g.value),
-                        });
+                        errors.push(ErrorInner::new(
+                            Some(g.pos()),
+                            format!("Generic parameter {} is already declared", g.value),
+                        ));
                     }
                 }
             }
@@ -1344,32 +1560,32 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let size = match size {
                     TypedExpression::Uint(e) => match e.bitwidth() {
                         UBitwidth::B32 => Ok(e),
-                        _ => Err(ErrorInner {
-                            pos: Some(pos),
-                            message: format!(
+                        _ => Err(ErrorInner::new(
+                            Some(pos),
+                            format!(
                             "Expected array dimension to be a u32 constant, found {} of type {}",
                             e, ty
                         ),
-                        }),
+                        )),
                     },
                     TypedExpression::Int(v) => {
                         UExpression::try_from_int(v.clone(), &UBitwidth::B32).map_err(|_| {
-                            ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            ErrorInner::new(
+                                Some(pos),
+                                format!(
                             "Expected array dimension to be a u32 constant, found {} of type {}",
                             v, ty
                         ),
-                            }
+                            )
                         })
                     }
-                    _ => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    _ => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected array dimension to be a u32 constant, found {} of type {}",
                             size, ty
                         ),
-                    }),
+                    )),
                 }?;
 
                 Ok(Type::Array(ArrayType::new(
@@ -1378,16 +1594,12 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 )))
             }
             UnresolvedType::User(id, generics) => {
-                let declared_ty =
-                    types
-                        .get(module_id)
-                        .unwrap()
-                        .get(&id)
-                        .cloned()
-                        .ok_or_else(|| ErrorInner {
-                            pos: Some(pos),
-                            message: format!("Undefined type {}", id),
-                        })?;
+                let declared_ty = types
+                    .get(module_id)
+                    .unwrap()
+                    .get(&id)
+                    .cloned()
+                    .ok_or_else(|| undefined_type(&id, types.get(module_id).unwrap(), pos))?;
 
                 let generic_identifiers = declared_ty.declaration_generics();
 
@@ -1411,27 +1623,20 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                         .and_then(|e| {
                                             UExpression::try_from_typed(e, &UBitwidth::B32)
                                                 .map(|e| (GenericIdentifier::with_name(g).with_index(i), e))
-                                                .map_err(|e| ErrorInner {
-                                                    pos: Some(pos),
-                                                    message: format!("Expected u32 expression, but got expression of type {}", e.get_type()),
-                                                })
+                                                .map_err(|e| ErrorInner::new(Some(pos), format!("Expected u32 expression, but got expression of type {}", e.get_type())))
                                         })
                                 },
-                                None => Err(ErrorInner {
-                                    pos: Some(pos),
-                                    message:
-                                    "Expected u32 constant or identifier, but found `_`. Generic inference is not supported yet."
-                                        .into(),
-                                })
+                                None => Err(ErrorInner::new(Some(pos), "Expected u32 constant or identifier, but found `_`. Generic inference is not supported yet."
+                                        ))
                             })
                             .collect::<Result<_, _>>()?);
 
                         // specialize the declared type using the generic assignment
                         Ok(specialize_declaration_type(declaration_type, &assignment).unwrap())
                     }
-                    false => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    false => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected {} generic argument{} on type {}, but got {}",
                             generic_identifiers.len(),
                             if generic_identifiers.len() == 1 {
@@ -1442,7 +1647,8 @@ impl<'ast, T: Field> Checker<'ast, T> {
                             id,
                             generics.len()
                         ),
-                    }),
+                    )
+                    .with_note(None, declared_generics(&id, &generic_identifiers))),
                 }
             }
         }
@@ -1465,13 +1671,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         u32::from_str_radix(&c.to_str_radix(16), 16).unwrap(),
                     ))
                 } else {
-                    Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                     "Expected array dimension to be a u32 constant or an identifier, found {}",
                     Expression::IntConstant(c)
                 ),
-                    })
+                    ))
                 }
             }
             Expression::Identifier(name) => {
@@ -1481,29 +1687,23 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     (Some(ty), None) => {
                         match ty {
                             DeclarationType::Uint(UBitwidth::B32) => Ok(DeclarationConstant::Constant(CanonicalConstantIdentifier::new(name, module_id.into()))),
-                            _ => Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            _ => Err(ErrorInner::new(Some(pos), format!(
                                     "Expected array dimension to be a u32 constant or an identifier, found {} of type {}",
                                     name, ty
-                                ),
-                            })
+                                )))
                         }
                     }
                     (None, Some(index)) => Ok(DeclarationConstant::Generic(GenericIdentifier::with_name(name).with_index(*index))),
-                    _ => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Undeclared symbol `{}`", name)
-                    })
+                    _ => Err(ErrorInner::new(Some(pos), format!("Undeclared symbol `{}`", name)))
                 }
             }
-            e => Err(ErrorInner {
-                pos: Some(pos),
-                message: format!(
+            e => Err(ErrorInner::new(
+                Some(pos),
+                format!(
                     "Expected array dimension to be a u32 constant or an identifier, found {}",
                     e
                 ),
-            }),
+            )),
         }
     }
 
@@ -1543,10 +1743,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     .unwrap()
                     .get(&id)
                     .cloned()
-                    .ok_or_else(|| ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Undefined type {}", id),
-                    })?;
+                    .ok_or_else(|| undefined_type(&id, state.types.get(module_id).unwrap(), pos))?;
 
                 let generics = generics.unwrap_or_default();
                 let checked_generics: Vec<_> = generics
@@ -1561,10 +1758,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                 used_generics,
                             )
                             .map(Some),
-                        None => Err(ErrorInner {
-                            pos: Some(pos),
-                            message: "Expected u32 constant or identifier, but found `_`".into(),
-                        }),
+                        None => Err(ErrorInner::new(
+                            Some(pos),
+                            "Expected u32 constant or identifier, but found `_`",
+                        )),
                     })
                     .collect::<Result<_, _>>()?;
 
@@ -1595,16 +1792,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                         Ok(res)
                     }
-                    false => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    false => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected {} generic argument{} on type {}, but got {}",
                             ty.generics.len(),
                             if ty.generics.len() == 1 { "" } else { "s" },
                             id,
                             checked_generics.len()
                         ),
-                    }),
+                    )
+                    .with_note(None, declared_generics(&id, &ty.declaration_generics()))),
                 }
             }
         }
@@ -1646,60 +1844,64 @@ impl<'ast, T: Field> Checker<'ast, T> {
         let from = match from {
             TypedExpression::Uint(from) => match from.bitwidth() {
                 UBitwidth::B32 => Ok(from),
-                bitwidth => Err(ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
+                bitwidth => Err(ErrorInner::new(
+                    Some(pos),
+                    format!(
                         "Expected lower loop bound to be of type u32, found {}",
                         Type::<T>::Uint(bitwidth)
                     ),
-                }),
+                )),
             },
             TypedExpression::Int(v) => {
-                UExpression::try_from_int(v, &UBitwidth::B32).map_err(|_| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Expected lower loop bound to be of type u32, found {}",
-                        Type::<T>::Int
-                    ),
+                UExpression::try_from_int(v, &UBitwidth::B32).map_err(|_| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Expected lower loop bound to be of type u32, found {}",
+                            Type::<T>::Int
+                        ),
+                    )
                 })
             }
-            from => Err(ErrorInner {
-                pos: Some(pos),
-                message: format!(
+            from => Err(ErrorInner::new(
+                Some(pos),
+                format!(
                     "Expected lower loop bound to be of type u32, found {}",
                     from.get_type()
                 ),
-            }),
+            )),
         }
         .map_err(|e| vec![e])?;
 
         let to = match to {
             TypedExpression::Uint(to) => match to.bitwidth() {
                 UBitwidth::B32 => Ok(to),
-                bitwidth => Err(ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
+                bitwidth => Err(ErrorInner::new(
+                    Some(pos),
+                    format!(
                         "Expected upper loop bound to be of type u32, found {}",
                         Type::<T>::Uint(bitwidth)
                     ),
-                }),
+                )),
             },
             TypedExpression::Int(v) => {
-                UExpression::try_from_int(v, &UBitwidth::B32).map_err(|_| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Expected upper loop bound to be of type u32, found {}",
-                        Type::<T>::Int
-                    ),
+                UExpression::try_from_int(v, &UBitwidth::B32).map_err(|_| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Expected upper loop bound to be of type u32, found {}",
+                            Type::<T>::Int
+                        ),
+                    )
                 })
             }
-            to => Err(ErrorInner {
-                pos: Some(pos),
-                message: format!(
+            to => Err(ErrorInner::new(
+                Some(pos),
+                format!(
                     "Expected upper loop bound to be of type u32, found {}",
                     to.get_type()
                 ),
-            }),
+            )),
         }
         .map_err(|e| vec![e])?;
 
@@ -1746,21 +1948,21 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         .map(|(e, t)| TypedExpression::align_to_type(e, t))
                         .collect::<Result<Vec<_>, _>>()
                         .map_err(|e| {
-                            vec![ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            vec![ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "Expected return value to be of type {}, found {}",
                                     e.1, e.0
                                 ),
-                            }]
+                            )]
                         }) {
                         Ok(e) => {
                             match e.iter().map(|e| e.get_type()).collect::<Vec<_>>() == return_types
                             {
                                 true => {}
-                                false => errors.push(ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
+                                false => errors.push(ErrorInner::new(
+                                    Some(pos),
+                                    format!(
                                         "Expected ({}) in return statement, found ({})",
                                         return_types
                                             .iter()
@@ -1773,7 +1975,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                             .collect::<Vec<_>>()
                                             .join(", ")
                                     ),
-                                }),
+                                )),
                             };
                             TypedStatement::Return(e)
                         }
@@ -1783,14 +1985,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         }
                     },
                     false => {
-                        errors.push(ErrorInner {
-                            pos: Some(pos),
-                            message: format!(
+                        errors.push(ErrorInner::new(
+                            Some(pos),
+                            format!(
                                 "Expected {} expressions in return statement, found {}",
                                 return_types.len(),
                                 expression_list_checked.len()
                             ),
-                        });
+                        ));
                         TypedStatement::Return(expression_list_checked)
                     }
                 };
@@ -1805,10 +2007,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let var = self.check_variable(var, module_id, types)?;
                 match self.insert_into_scope(var.clone()) {
                     true => Ok(TypedStatement::Declaration(var)),
-                    false => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Duplicate declaration for variable named {}", var.id),
-                    }),
+                    false => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Duplicate declaration for variable named {}", var.id),
+                    )),
                 }
                 .map_err(|e| vec![e])
             }
@@ -1850,15 +2052,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     }
                     Type::Int => Err(checked_expr), // Integers cannot be assigned
                 }
-                .map_err(|e| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Expression `{}` of type `{}` cannot be assigned to `{}` of type `{}`",
-                        e,
-                        e.get_type(),
-                        var.clone(),
-                        var_type
-                    ),
+                .map_err(|e| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Expression `{}` of type `{}` cannot be assigned to `{}` of type `{}`",
+                            e,
+                            e.get_type(),
+                            var.clone(),
+                            var_type
+                        ),
+                    )
                 })
                 .map(|rhs| TypedStatement::Definition(var, rhs))
                 .map_err(|e| vec![e])
@@ -1877,14 +2081,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                             message,
                         }),
                     )),
-                    e => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
-                            "Expected {} to be of type bool, found {}",
-                            e,
-                            e.get_type(),
-                        ),
-                    }),
+                    e => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Expected {} to be of type bool, found {}", e, e.get_type(),),
+                    )),
                 }
                 .map_err(|e| vec![e])
             }
@@ -1904,13 +2104,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                         let fun_id = match fun_id_expression.value {
                             Expression::Identifier(id) => Ok(id),
-                            e => Err(vec![ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            e => Err(vec![ErrorInner::new(Some(pos), format!(
                                     "Expected function in function call to be an identifier, found {}",
                                     e
-                                ),
-                            }])
+                                ))])
                         }?;
 
                         // check the generic arguments, if any
@@ -1921,14 +2118,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                         let pos = g.pos();
                                         self.check_expression(g, module_id, types).and_then(|g| {
                                             UExpression::try_from_typed(g, &UBitwidth::B32).map_err(
-                                                |e| ErrorInner {
-                                                    pos: Some(pos),
-                                                    message: format!(
+                                                |e| ErrorInner::new(Some(pos), format!(
                                                         "Expected {} to be of type u32, found {}",
                                                         e,
                                                         e.get_type(),
-                                                    ),
-                                                },
+                                                    )),
                                             )
                                         })
                                     })
@@ -1969,27 +2163,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                 let mut functions = functions;
                                 let f = functions.pop().unwrap();
 
-                                let arguments_checked = arguments_checked.into_iter().zip(f.signature.inputs.iter()).map(|(a, t)| TypedExpression::align_to_type(a, t)).collect::<Result<Vec<_>, _>>().map_err(|e| vec![ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!("Expected function call argument to be of type {}, found {} of type {}", e.1, e.0, e.0.get_type())
-                                }])?;
+                                let arguments_checked = arguments_checked.into_iter().zip(f.signature.inputs.iter()).map(|(a, t)| TypedExpression::align_to_type(a, t)).collect::<Result<Vec<_>, _>>().map_err(|e| vec![ErrorInner::new(Some(pos), format!("Expected function call argument to be of type {}, found {} of type {}", e.1, e.0, e.0.get_type()))])?;
 
                                 let call = TypedExpressionList::function_call(f.clone(), generics_checked.unwrap_or_else(|| vec![None; f.signature.generics.len()]), arguments_checked).annotate(Types { inner: assignees.iter().map(|a| a.get_type()).collect()});
 
                                 Ok(TypedStatement::MultipleDefinition(assignees, call))
                     		},
-                    		0 => Err(ErrorInner {                         pos: Some(pos),
- message: format!("Function definition for function {} with signature {} not found.", fun_id, query) }),
-                            n => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Ambiguous call to function {}, {} candidates were found. Please be more explicit.", fun_id, n)
-                    })
+                    		0 => Err(self.function_not_found(&query, pos)),
+                            n => Err(ErrorInner::new(Some(pos), format!("Ambiguous call to function {}, {} candidates were found. Please be more explicit.", fun_id, n)))
                     	}
                     }
-                    _ => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("{} should be a function call", rhs),
-                    }),
+                    _ => Err(ErrorInner::new(Some(pos), format!("{} should be a function call", rhs))),
                 }.map_err(|e| vec![e])
             }
         }
@@ -2006,19 +2190,19 @@ impl<'ast, T: Field> Checker<'ast, T> {
         match assignee.value {
             Assignee::Identifier(variable_name) => match self.get_key_value_scope(variable_name) {
                 Some((id, ty)) => match id.is_constant() {
-                    true => Err(ErrorInner {
-                        pos: Some(assignee.pos()),
-                        message: format!("Assignment to constant variable `{}`", variable_name),
-                    }),
+                    true => Err(ErrorInner::new(
+                        Some(assignee.pos()),
+                        format!("Assignment to constant variable `{}`", variable_name),
+                    )),
                     false => Ok(TypedAssignee::Identifier(Variable::with_id_and_type(
                         variable_name,
                         ty.clone(),
                     ))),
                 },
-                None => Err(ErrorInner {
-                    pos: Some(assignee.pos()),
-                    message: format!("Variable `{}` is undeclared", variable_name),
-                }),
+                None => Err(ErrorInner::new(
+                    Some(assignee.pos()),
+                    format!("Variable `{}` is undeclared", variable_name),
+                )),
             },
             Assignee::Select(box assignee, box index) => {
                 let checked_assignee = self.check_assignee(assignee, module_id, types)?;
@@ -2038,13 +2222,15 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                         let checked_typed_index =
                             UExpression::try_from_typed(checked_index, &UBitwidth::B32).map_err(
-                                |e| ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
-                                        "Expected array {} index to have type u32, found {}",
-                                        checked_assignee,
-                                        e.get_type()
-                                    ),
+                                |e| {
+                                    ErrorInner::new(
+                                        Some(pos),
+                                        format!(
+                                            "Expected array {} index to have type u32, found {}",
+                                            checked_assignee,
+                                            e.get_type()
+                                        ),
+                                    )
                                 },
                             )?;
 
@@ -2053,13 +2239,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
                             box checked_typed_index,
                         ))
                     }
-                    ty => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    ty => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot access element at index {} on {} of type {}",
                             index, checked_assignee, ty,
                         ),
-                    }),
+                    )),
                 }
             }
             Assignee::Member(box assignee, box member) => {
@@ -2069,9 +2255,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 match &ty {
                     Type::Struct(members) => match members.iter().find(|m| m.id == member) {
                         Some(_) => Ok(TypedAssignee::Member(box checked_assignee, member.into())),
-                        None => Err(ErrorInner {
-                            pos: Some(pos),
-                            message: format!(
+                        None => Err(ErrorInner::new(
+                            Some(pos),
+                            format!(
                                 "{} {{{}}} doesn't have member {}",
                                 ty,
                                 members
@@ -2081,16 +2267,15 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                     .join(", "),
                                 member
                             ),
-                        }),
+                        )),
                     },
-                    ty => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
+                    ty => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot access field {} on {} as of type {}",
                             member, checked_assignee, ty,
                         ),
-                    }),
+                    )),
                 }
             }
         }
@@ -2111,13 +2296,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                 match checked_expression {
                     TypedExpression::Array(a) => Ok(TypedExpressionOrSpread::Spread(a.into())),
-                    e => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    e => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected spread operator to apply on array, found {}",
                             e.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             SpreadOrExpression::Expression(e) => {
@@ -2163,10 +2348,19 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         }
                         Type::Int => unreachable!(),
                     },
-                    None => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Identifier \"{}\" is undefined", name),
-                    }),
+                    None => {
+                        let error = ErrorInner::new(
+                            Some(pos),
+                            format!("Identifier \"{}\" is undefined", name),
+                        );
+
+                        Err(match self.similar_identifier(name) {
+                            Some(similar) => {
+                                error.with_note(None, format!("did you mean `{}`?", similar))
+                            }
+                            None => error,
+                        })
+                    }
                 }
             }
             Expression::Add(box e1, box e2) => {
@@ -2178,9 +2372,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `+` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `+` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2193,15 +2389,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok((e1 + e2).into())
                     }
-                    (t1, t2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot apply `+` to {}, {}",
-                            t1.get_type(),
-                            t2.get_type()
-                        ),
-                    }),
+                    (t1, t2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `+` to {}, {}", t1.get_type(), t2.get_type()),
+                    )),
                 }
             }
             Expression::Sub(box e1, box e2) => {
@@ -2213,9 +2404,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `-` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `-` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2224,15 +2417,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         Ok(FieldElementExpression::Sub(box e1, box e2).into())
                     }
                     (Uint(e1), Uint(e2)) if e1.get_type() == e2.get_type() => Ok((e1 - e2).into()),
-                    (t1, t2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
+                    (t1, t2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected only field elements, found {}, {}",
                             t1.get_type(),
                             t2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Mult(box e1, box e2) => {
@@ -2244,9 +2436,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `*` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `*` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2259,15 +2453,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok((e1 * e2).into())
                     }
-                    (t1, t2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot apply `*` to {}, {}",
-                            t1.get_type(),
-                            t2.get_type()
-                        ),
-                    }),
+                    (t1, t2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `*` to {}, {}", t1.get_type(), t2.get_type()),
+                    )),
                 }
             }
             Expression::Div(box e1, box e2) => {
@@ -2279,9 +2468,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `/` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `/` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2294,15 +2485,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok((e1 / e2).into())
                     }
-                    (t1, t2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot apply `/` to {}, {}",
-                            t1.get_type(),
-                            t2.get_type()
-                        ),
-                    }),
+                    (t1, t2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `/` to {}, {}", t1.get_type(), t2.get_type()),
+                    )),
                 }
             }
             Expression::Rem(box e1, box e2) => {
@@ -2312,9 +2498,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `%` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `%` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2323,15 +2511,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok((e1 % e2).into())
                     }
-                    (t1, t2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot apply `%` to {}, {}",
-                            t1.get_type(),
-                            t2.get_type()
-                        ),
-                    }),
+                    (t1, t2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `%` to {}, {}", t1.get_type(), t2.get_type()),
+                    )),
                 }
             }
             Expression::Pow(box e1, box e2) => {
@@ -2351,15 +2534,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     (TypedExpression::FieldElement(e1), TypedExpression::Uint(e2)) => Ok(
                         TypedExpression::FieldElement(FieldElementExpression::Pow(box e1, box e2)),
                     ),
-                    (t1, t2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
+                    (t1, t2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected `field` and `u32`, found {}, {}",
                             t1.get_type(),
                             t2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Neg(box e) => {
@@ -2371,14 +2553,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         Ok(FieldElementExpression::Neg(box e).into())
                     }
                     TypedExpression::Uint(e) => Ok((-e).into()),
-                    e => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    e => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Unary operator `-` cannot be applied to {} of type {}",
                             e,
                             e.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Pos(box e) => {
@@ -2390,14 +2572,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         Ok(FieldElementExpression::Pos(box e).into())
                     }
                     TypedExpression::Uint(e) => Ok(UExpression::pos(e).into()),
-                    e => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    e => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Unary operator `+` cannot be applied to {} of type {}",
                             e,
                             e.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Conditional(box condition, box consequence, box alternative, kind) => {
@@ -2410,10 +2592,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         consequence_checked,
                         alternative_checked,
                     )
-                    .map_err(|(e1, e2)| ErrorInner {
-                        pos: Some(pos),
-                        message: format!("{{consequence}} and {{alternative}} in conditional expression should have the same type, found {}, {}", e1.get_type(), e2.get_type()),
-                    })?;
+                    .map_err(|(e1, e2)| ErrorInner::new(Some(pos), format!("{{consequence}} and {{alternative}} in conditional expression should have the same type, found {}, {}", e1.get_type(), e2.get_type())))?;
 
                 let kind = match kind {
                     crate::absy::ConditionalKind::IfElse => {
@@ -2445,32 +2624,28 @@ impl<'ast, T: Field> Checker<'ast, T> {
                             (TypedExpression::Int(consequence), TypedExpression::Int(alternative)) => {
                                 Ok(IntExpression::conditional(condition, consequence, alternative, kind).into())
                             },
-                            (c, a) => Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!("{{consequence}} and {{alternative}} in conditional expression should have the same type, found {}, {}", c.get_type(), a.get_type())
-                            })
+                            (c, a) => Err(ErrorInner::new(Some(pos), format!("{{consequence}} and {{alternative}} in conditional expression should have the same type, found {}, {}", c.get_type(), a.get_type())))
                         }
                     }
-                    c => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
-                            "{{condition}} should be a boolean, found {}",
-                            c.get_type()
-                        ),
-                    }),
+                    c => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("{{condition}} should be a boolean, found {}", c.get_type()),
+                    )),
                 }
             }
-            Expression::FieldConstant(n) => Ok(FieldElementExpression::Number(
-                T::try_from(n).map_err(|_| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Field constant not in the representable range [{}, {}]",
-                        T::min_value(),
-                        T::max_value()
-                    ),
-                })?,
-            )
-            .into()),
+            Expression::FieldConstant(n) => {
+                Ok(FieldElementExpression::Number(T::try_from(n).map_err(|_| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Field constant not in the representable range [{}, {}]",
+                            T::min_value(),
+                            T::max_value()
+                        ),
+                    )
+                })?)
+                .into())
+            }
             Expression::U8Constant(n) => Ok(UExpressionInner::Value(n.into()).annotate(8).into()),
             Expression::U16Constant(n) => Ok(UExpressionInner::Value(n.into()).annotate(16).into()),
             Expression::U32Constant(n) => Ok(UExpressionInner::Value(n.into()).annotate(32).into()),
@@ -2478,13 +2653,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
             Expression::FunctionCall(fun_id_expression, generics, arguments) => {
                 let fun_id = match fun_id_expression.value {
                     Expression::Identifier(id) => Ok(id),
-                    e => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    e => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected function in function call to be an identifier, found `{}`",
                             e
                         ),
-                    }),
+                    )),
                 }?;
 
                 // check the generic arguments, if any
@@ -2497,13 +2672,15 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                     let pos = g.pos();
                                     self.check_expression(g, module_id, types).and_then(|g| {
                                         UExpression::try_from_typed(g, &UBitwidth::B32).map_err(
-                                            |e| ErrorInner {
-                                                pos: Some(pos),
-                                                message: format!(
-                                                    "Expected {} to be of type u32, found {}",
-                                                    e,
-                                                    e.get_type(),
-                                                ),
+                                            |e| {
+                                                ErrorInner::new(
+                                                    Some(pos),
+                                                    format!(
+                                                        "Expected {} to be of type u32, found {}",
+                                                        e,
+                                                        e.get_type(),
+                                                    ),
+                                                )
                                             },
                                         )
                                     })
@@ -2542,23 +2719,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                         let signature = f.signature;
 
-                        let arguments_checked = arguments_checked.into_iter().zip(signature.inputs.iter()).map(|(a, t)| TypedExpression::align_to_type(a, t)).collect::<Result<Vec<_>, _>>().map_err(|e| ErrorInner {
-                           pos: Some(pos),
-                           message: format!("Expected function call argument to be of type {}, found {}", e.1, e.0)
-                        })?;
+                        let arguments_checked = arguments_checked.into_iter().zip(signature.inputs.iter()).map(|(a, t)| TypedExpression::align_to_type(a, t)).collect::<Result<Vec<_>, _>>().map_err(|e| ErrorInner::new(Some(pos), format!("Expected function call argument to be of type {}, found {}", e.1, e.0)))?;
 
                         let generics_checked = generics_checked.unwrap_or_else(|| vec![None; signature.generics.len()]);
 
                         let mut output_types = signature.get_output_types(
                             generics_checked.clone(),
                             arguments_checked.iter().map(|a| a.get_type()).collect()
-                        ).map_err(|e| ErrorInner {
-                            pos: Some(pos),
-                            message: format!(
+                        ).map_err(|e| ErrorInner::new(Some(pos), format!(
                                 "Failed to infer value for generic parameter `{}`, try providing an explicit value",
                                 e,
-                            ),
-                        })?;
+                            )))?;
 
                         let function_key = DeclarationFunctionKey {
                             module: module_id.to_path_buf(),
@@ -2596,28 +2767,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                     arguments_checked,
                                 ).annotate(*array_ty.ty, array_ty.size).into()),
                             },
-                            n => Err(ErrorInner {
-                                pos: Some(pos),
-
-                                message: format!(
+                            n => Err(ErrorInner::new(Some(pos), format!(
                                     "{} returns {} values but is called outside of a definition",
                                     f.id, n
-                                ),
-                            }),
+                                ))),
                         }
                     }
-                    0 => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Function definition for function {} with signature {} not found.",
-                            fun_id, query
-                        ),
-                    }),
-                    n => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Ambiguous call to function {}, {} candidates were found. Please be more explicit.", fun_id, n)
-                    }),
+                    0 => Err(self.function_not_found(&query, pos)),
+                    n => Err(ErrorInner::new(Some(pos), format!("Ambiguous call to function {}, {} candidates were found. Please be more explicit.", fun_id, n))),
                 }
             }
             Expression::Lt(box e1, box e2) => {
@@ -2627,15 +2784,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Cannot compare {} of type {} to {} of type {}",
-                        e1,
-                        e1.get_type(),
-                        e2,
-                        e2.get_type()
-                    ),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Cannot compare {} of type {} to {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2646,28 +2805,28 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         if e1.get_type() == e2.get_type() {
                             Ok(BooleanExpression::UintLt(box e1, box e2).into())
                         } else {
-                            Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            Err(ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "Cannot compare {} of type {} to {} of type {}",
                                     e1,
                                     e1.get_type(),
                                     e2,
                                     e2.get_type()
                                 ),
-                            })
+                            ))
                         }
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot compare {} of type {} to {} of type {}",
                             e1,
                             e1.get_type(),
                             e2,
                             e2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Le(box e1, box e2) => {
@@ -2677,15 +2836,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Cannot compare {} of type {} to {} of type {}",
-                        e1,
-                        e1.get_type(),
-                        e2,
-                        e2.get_type()
-                    ),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Cannot compare {} of type {} to {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2696,28 +2857,28 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         if e1.get_type() == e2.get_type() {
                             Ok(BooleanExpression::UintLe(box e1, box e2).into())
                         } else {
-                            Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            Err(ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "Cannot compare {} of type {} to {} of type {}",
                                     e1,
                                     e1.get_type(),
                                     e2,
                                     e2.get_type()
                                 ),
-                            })
+                            ))
                         }
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot compare {} of type {} to {} of type {}",
                             e1,
                             e1.get_type(),
                             e2,
                             e2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Eq(box e1, box e2) => {
@@ -2727,15 +2888,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Cannot compare {} of type {} to {} of type {}",
-                        e1,
-                        e1.get_type(),
-                        e2,
-                        e2.get_type()
-                    ),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Cannot compare {} of type {} to {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2756,16 +2919,16 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok(BooleanExpression::UintEq(box e1, box e2).into())
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot compare {} of type {} to {} of type {}",
                             e1,
                             e1.get_type(),
                             e2,
                             e2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Ge(box e1, box e2) => {
@@ -2775,15 +2938,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Cannot compare {} of type {} to {} of type {}",
-                        e1,
-                        e1.get_type(),
-                        e2,
-                        e2.get_type()
-                    ),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Cannot compare {} of type {} to {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2794,28 +2959,28 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         if e1.get_type() == e2.get_type() {
                             Ok(BooleanExpression::UintGe(box e1, box e2).into())
                         } else {
-                            Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            Err(ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "Cannot compare {} of type {} to {} of type {}",
                                     e1,
                                     e1.get_type(),
                                     e2,
                                     e2.get_type()
                                 ),
-                            })
+                            ))
                         }
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot compare {} of type {} to {} of type {}",
                             e1,
                             e1.get_type(),
                             e2,
                             e2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Gt(box e1, box e2) => {
@@ -2825,15 +2990,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Cannot compare {} of type {} to {} of type {}",
-                        e1,
-                        e1.get_type(),
-                        e2,
-                        e2.get_type()
-                    ),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Cannot compare {} of type {} to {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -2844,28 +3011,28 @@ impl<'ast, T: Field> Checker<'ast, T> {
                         if e1.get_type() == e2.get_type() {
                             Ok(BooleanExpression::UintGt(box e1, box e2).into())
                         } else {
-                            Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            Err(ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "Cannot compare {} of type {} to {} of type {}",
                                     e1,
                                     e1.get_type(),
                                     e2,
                                     e2.get_type()
                                 ),
-                            })
+                            ))
                         }
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot compare {} of type {} to {} of type {}",
                             e1,
                             e1.get_type(),
                             e2,
                             e2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Select(box array, box index) => {
@@ -2892,23 +3059,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                     .map(|e| self.check_expression(e, module_id, types))
                                     .unwrap_or_else(|| Ok(array_size.clone().into()))?;
 
-                                let from = UExpression::try_from_typed(from, &UBitwidth::B32).map_err(|e| ErrorInner {
-                                                    pos: Some(pos),
-                                                    message: format!(
+                                let from = UExpression::try_from_typed(from, &UBitwidth::B32).map_err(|e| ErrorInner::new(Some(pos), format!(
                                                         "Expected the lower bound of the range to be a u32, found {} of type {}",
                                                         e,
                                                         e.get_type()
-                                                    ),
-                                                })?;
+                                                    )))?;
 
-                                let to = UExpression::try_from_typed(to, &UBitwidth::B32).map_err(|e| ErrorInner {
-                                                    pos: Some(pos),
-                                                    message: format!(
+                                let to = UExpression::try_from_typed(to, &UBitwidth::B32).map_err(|e| ErrorInner::new(Some(pos), format!(
                                                         "Expected the upper bound of the range to be a u32, found {} of type {}",
                                                         e,
                                                         e.get_type()
-                                                    ),
-                                                })?;
+                                                    )))?;
 
                                 Ok(ArrayExpressionInner::Slice(
                                     box array,
@@ -2918,14 +3079,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                 .annotate(inner_type, UExpression::floor_sub(to, from))
                                 .into())
                             }
-                            e => Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            e => Err(ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "Cannot access slice of expression {} of type {}",
                                     e,
                                     e.get_type(),
                                 ),
-                            }),
+                            )),
                         }
                     }
                     RangeOrExpression::Expression(index) => {
@@ -2933,13 +3094,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                         let index =
                             UExpression::try_from_typed(index, &UBitwidth::B32).map_err(|e| {
-                                ErrorInner {
-                                    pos: Some(pos),
-                                    message: format!(
-                                        "Expected index to be of type u32, found {}",
-                                        e
-                                    ),
-                                }
+                                ErrorInner::new(
+                                    Some(pos),
+                                    format!("Expected index to be of type u32, found {}", e),
+                                )
                             })?;
 
                         match array {
@@ -2955,16 +3113,13 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                     Type::Int => unreachable!(),
                                 }
                             }
-                            a => Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            a => Err(ErrorInner::new(Some(pos), format!(
                                     "Cannot access element as index {} of type {} on expression {} of type {}",
                                     index,
                                     index.get_type(),
                                     a,
                                     a.get_type()
-                                ),
-                            }),
+                                ))),
                         }
                     }
                 }
@@ -2994,9 +3149,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                     Ok(StructExpression::member(s, id.to_string()).into())
                                 }
                             },
-                            None => Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            None => Err(ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "{} {{{}}} doesn't have member {}",
                                     s.get_type(),
                                     s.ty()
@@ -3007,17 +3162,17 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                         .join(", "),
                                     id,
                                 ),
-                            }),
+                            )),
                         }
                     }
-                    e => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    e => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot access member {} on expression of type {}",
                             id,
                             e.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::InlineArray(expressions_or_spreads) => {
@@ -3029,10 +3184,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 }
 
                 if expressions_or_spreads_checked.is_empty() {
-                    return Err(ErrorInner {
-                        pos: Some(pos),
-                        message: "Empty arrays are not allowed".to_string(),
-                    });
+                    return Err(ErrorInner::new(
+                        Some(pos),
+                        "Empty arrays are not allowed".to_string(),
+                    ));
                 }
 
                 // we infer the inner type to be the type of the first non-integer element
@@ -3056,9 +3211,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                             .into_iter()
                             .map(|e| {
                                 TypedExpressionOrSpread::align_to_type(e, &target_array_ty).map_err(
-                                    |(e, ty)| ErrorInner {
-                                        pos: Some(pos),
-                                        message: format!("Expected {} to have type {}", e, ty,),
+                                    |(e, ty)| {
+                                        ErrorInner::new(
+                                            Some(pos),
+                                            format!("Expected {} to have type {}", e, ty,),
+                                        )
                                     },
                                 )
                             })
@@ -3102,14 +3259,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let count = self.check_expression(count, module_id, types)?;
 
                 let count = UExpression::try_from_typed(count, &UBitwidth::B32).map_err(|e| {
-                    ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected array initializer count to be a u32, found {} of type {}",
                             e,
                             e.get_type(),
                         ),
-                    }
+                    )
                 })?;
 
                 Ok(ArrayExpressionInner::Repeat(box e, box count.clone())
@@ -3118,10 +3275,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
             }
             Expression::InlineStruct(id, inline_members) => {
                 let ty = match types.get(module_id).unwrap().get(&id).cloned() {
-                    None => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Undefined type `{}`", id),
-                    }),
+                    None => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Undefined type `{}`", id),
+                    )),
                     Some(ty) => Ok(ty),
                 }?;
 
@@ -3140,9 +3297,9 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                 // check that we provided the required number of values
                 if declared_struct_type.members_count() != inline_members.len() {
-                    return Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                    return Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Inline struct {} does not match {} {{{}}}",
                             Expression::InlineStruct(id, inline_members),
                             declared_struct_type,
@@ -3153,7 +3310,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                 .collect::<Vec<_>>()
                                 .join(", ")
                         ),
-                    });
+                    ));
                 }
 
                 // check that the mapping of values matches the expected type
@@ -3175,9 +3332,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
 
                                 let expression_checked =
                                     TypedExpression::align_to_type(expression_checked, &*member.ty)
-                                        .map_err(|e| ErrorInner {
-                                            pos: Some(pos),
-                                            message: format!(
+                                        .map_err(|e| {
+                                            ErrorInner::new(
+                                                Some(pos),
+                                                format!(
                                         "Member {} of struct {} has type {}, found {} of type {}",
                                         member.id,
                                         id.clone(),
@@ -3185,13 +3343,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                         e.0,
                                         e.0.get_type(),
                                     ),
+                                            )
                                         })?;
 
                                 Ok(expression_checked)
                             }
-                            None => Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            None => Err(ErrorInner::new(
+                                Some(pos),
+                                format!(
                                     "Member {} of struct {} {{{}}} not found in value {}",
                                     member.id,
                                     declared_struct_type,
@@ -3203,7 +3362,7 @@ impl<'ast, T: Field> Checker<'ast, T> {
                                         .join(", "),
                                     Expression::InlineStruct(id.clone(), inline_members.clone()),
                                 ),
-                            }),
+                            )),
                         },
                     )
                     .collect::<Result<Vec<_>, _>>()?;
@@ -3215,14 +3374,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     .zip(inferred_values.iter())
                     .map(|(m, v)| {
                         if !check_type(&m.ty, &v.get_type(), &mut generics_map) {
-                            Err(ErrorInner {
-                                pos: Some(pos),
-                                message: format!(
+                            Err(ErrorInner::new(Some(pos), format!(
                                     "Value `{}` doesn't match the expected type `{}` because of conflict in generic values",
                                     Expression::InlineStruct(id.clone(), inline_members.clone()),
                                     declared_struct_type
-                                ),
-                            })
+                                )))
                         } else {
                             Ok(StructMember {
                                 id: m.id.clone(),
@@ -3252,13 +3408,15 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!(
-                        "Cannot apply boolean operators to {} and {}",
-                        e1.get_type(),
-                        e2.get_type()
-                    ),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
+                            "Cannot apply boolean operators to {} and {}",
+                            e1.get_type(),
+                            e2.get_type()
+                        ),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -3268,15 +3426,14 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     (TypedExpression::Boolean(e1), TypedExpression::Boolean(e2)) => {
                         Ok(BooleanExpression::And(box e1, box e2).into())
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Cannot apply boolean operators to {} and {}",
                             e1.get_type(),
                             e2.get_type()
                         ),
-                    }),
+                    )),
                 }
             }
             Expression::Or(box e1, box e2) => {
@@ -3286,70 +3443,58 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     (TypedExpression::Boolean(e1), TypedExpression::Boolean(e2)) => {
                         Ok(BooleanExpression::Or(box e1, box e2).into())
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
-                            "Cannot apply `||` to {}, {}",
-                            e1.get_type(),
-                            e2.get_type()
-                        ),
-                    }),
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `||` to {}, {}", e1.get_type(), e2.get_type()),
+                    )),
                 }
             }
             Expression::LeftShift(box e1, box e2) => {
                 let e1 = self.check_expression(e1, module_id, types)?;
                 let e2 = self.check_expression(e2, module_id, types)?;
 
-                let e2 =
-                    UExpression::try_from_typed(e2, &UBitwidth::B32).map_err(|e| ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                let e2 = UExpression::try_from_typed(e2, &UBitwidth::B32).map_err(|e| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected the left shift right operand to have type `u32`, found {}",
                             e
                         ),
-                    })?;
+                    )
+                })?;
 
                 match e1 {
                     TypedExpression::Int(e1) => Ok(IntExpression::LeftShift(box e1, box e2).into()),
                     TypedExpression::Uint(e1) => Ok(UExpression::left_shift(e1, e2).into()),
-                    e1 => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot left-shift {} by {}",
-                            e1.get_type(),
-                            e2.get_type()
-                        ),
-                    }),
+                    e1 => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot left-shift {} by {}", e1.get_type(), e2.get_type()),
+                    )),
                 }
             }
             Expression::RightShift(box e1, box e2) => {
                 let e1 = self.check_expression(e1, module_id, types)?;
                 let e2 = self.check_expression(e2, module_id, types)?;
 
-                let e2 =
-                    UExpression::try_from_typed(e2, &UBitwidth::B32).map_err(|e| ErrorInner {
-                        pos: Some(pos),
-                        message: format!(
+                let e2 = UExpression::try_from_typed(e2, &UBitwidth::B32).map_err(|e| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!(
                             "Expected the right shift right operand to be of type `u32`, found {}",
                             e
                         ),
-                    })?;
+                    )
+                })?;
 
                 match e1 {
                     TypedExpression::Int(e1) => {
                         Ok(IntExpression::RightShift(box e1, box e2).into())
                     }
                     TypedExpression::Uint(e1) => Ok(UExpression::right_shift(e1, e2).into()),
-                    e1 => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot right-shift {} by {}",
-                            e1.get_type(),
-                            e2.get_type()
-                        ),
-                    }),
+                    e1 => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot right-shift {} by {}", e1.get_type(), e2.get_type()),
+                    )),
                 }
             }
             Expression::BitOr(box e1, box e2) => {
@@ -3359,9 +3504,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `|` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `|` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -3373,15 +3520,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok(UExpression::or(e1, e2).into())
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot apply `|` to {}, {}",
-                            e1.get_type(),
-                            e2.get_type()
-                        ),
-                    }),
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `|` to {}, {}", e1.get_type(), e2.get_type()),
+                    )),
                 }
             }
             Expression::BitAnd(box e1, box e2) => {
@@ -3391,9 +3533,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `&` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `&` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -3405,15 +3549,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok(UExpression::and(e1, e2).into())
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot apply `&` to {}, {}",
-                            e1.get_type(),
-                            e2.get_type()
-                        ),
-                    }),
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `&` to {}, {}", e1.get_type(), e2.get_type()),
+                    )),
                 }
             }
             Expression::BitXor(box e1, box e2) => {
@@ -3423,9 +3562,11 @@ impl<'ast, T: Field> Checker<'ast, T> {
                 let (e1_checked, e2_checked) = TypedExpression::align_without_integers(
                     e1_checked, e2_checked,
                 )
-                .map_err(|(e1, e2)| ErrorInner {
-                    pos: Some(pos),
-                    message: format!("Cannot apply `^` to {}, {}", e1.get_type(), e2.get_type()),
+                .map_err(|(e1, e2)| {
+                    ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `^` to {}, {}", e1.get_type(), e2.get_type()),
+                    )
                 })?;
 
                 match (e1_checked, e2_checked) {
@@ -3437,15 +3578,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     {
                         Ok(UExpression::xor(e1, e2).into())
                     }
-                    (e1, e2) => Err(ErrorInner {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Cannot apply `^` to {}, {}",
-                            e1.get_type(),
-                            e2.get_type()
-                        ),
-                    }),
+                    (e1, e2) => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot apply `^` to {}, {}", e1.get_type(), e2.get_type()),
+                    )),
                 }
             }
             Expression::Not(box e) => {
@@ -3454,10 +3590,10 @@ impl<'ast, T: Field> Checker<'ast, T> {
                     TypedExpression::Int(e) => Ok(IntExpression::Not(box e).into()),
                     TypedExpression::Boolean(e) => Ok(BooleanExpression::Not(box e).into()),
                     TypedExpression::Uint(e) => Ok((!e).into()),
-                    e => Err(ErrorInner {
-                        pos: Some(pos),
-                        message: format!("Cannot negate {}", e.get_type()),
-                    }),
+                    e => Err(ErrorInner::new(
+                        Some(pos),
+                        format!("Cannot negate {}", e.get_type()),
+                    )),
                 }
             }
         }
@@ -3492,6 +3628,55 @@ impl<'ast, T: Field> Checker<'ast, T> {
         query.match_funcs(&self.functions)
     }
 
+    /// The name closest to `name` among the variables and constants in scope and the functions of
+    /// the module, if any
+    fn similar_identifier(&self, name: &str) -> Option<&'ast str> {
+        let variables = self.scope.keys().filter_map(|v| match &v.id {
+            CoreIdentifier::Source(id) => Some(*id),
+            CoreIdentifier::Constant(c) => Some(c.id),
+            _ => None,
+        });
+        let functions = self.functions.iter().map(|f| f.id);
+
+        suggest(name, variables.chain(functions))
+    }
+
+    /// The error for a function call matching no function, listing the signatures of the functions
+    /// with the same name, or suggesting a similar name if there are none
+    fn function_not_found(
+        &self,
+        query: &FunctionQuery<'ast, T>,
+        pos: (Position, Position),
+    ) -> ErrorInner {
+        let error = ErrorInner::new(
+            Some(pos),
+            format!(
+                "Function definition for function {} with signature {} not found.",
+                query.id, query
+            ),
+        );
+
+        let signatures: BTreeSet<_> = self
+            .functions
+            .iter()
+            .filter(|f| f.id == query.id)
+            .map(|f| f.signature.to_string())
+            .collect();
+
+        match signatures.is_empty() {
+            true => match suggest(query.id, self.functions.iter().map(|f| f.id)) {
+                Some(similar) => error.with_note(None, format!("did you mean `{}`?", similar)),
+                None => error,
+            },
+            false => signatures.into_iter().fold(error, |error, signature| {
+                error.with_note(
+                    None,
+                    format!("`{}` is declared with signature {}", query.id, signature),
+                )
+            }),
+        }
+    }
+
     fn enter_scope(&mut self) {
         self.level += 1;
     }
@@ -4248,10 +4433,10 @@ mod tests {
             .mock()]);
             assert_eq!(
                 Checker::<Bn128Field>::default().check_signature(signature, &*MODULE_ID, &state),
-                Err(vec![ErrorInner {
-                    pos: Some((Position::mock(), Position::mock())),
-                    message: "Undeclared symbol `K`".to_string()
-                }])
+                Err(vec![ErrorInner::new(
+                    Some((Position::mock(), Position::mock())),
+                    "Undeclared symbol `K`".to_string()
+                )])
             );
         }
 
@@ -4317,10 +4502,10 @@ mod tests {
 
         assert_eq!(
             checker.check_statement(statement, &*MODULE_ID, &TypeMap::new()),
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-                message: "Identifier \"b\" is undefined".into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Identifier \"b\" is undefined"
+            )])
         );
     }
 
@@ -4433,10 +4618,10 @@ mod tests {
         assert_eq!(
             checker.check_module(&*MODULE_ID, &mut state),
             Err(vec![Error {
-                inner: ErrorInner {
-                    pos: Some((Position::mock(), Position::mock())),
-                    message: "Identifier \"a\" is undefined".into()
-                },
+                inner: ErrorInner::new(
+                    Some((Position::mock(), Position::mock())),
+                    "Identifier \"a\" is undefined"
+                ),
                 module_id: (*MODULE_ID).clone()
             }])
         );
@@ -4588,10 +4773,10 @@ mod tests {
         let mut checker: Checker<Bn128Field> = Checker::default();
         assert_eq!(
             checker.check_function(foo, &*MODULE_ID, &state),
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-                message: "Identifier \"i\" is undefined".into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Identifier \"i\" is undefined"
+            )])
         );
     }
 
@@ -4727,12 +4912,14 @@ mod tests {
         let mut checker: Checker<Bn128Field> = new_with_args(HashMap::new(), 0, functions);
         assert_eq!(
             checker.check_function(bar, &*MODULE_ID, &state),
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-                message:
-                    "Function definition for function foo with signature () -> field not found."
-                        .into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Function definition for function foo with signature () -> field not found."
+            )
+            .with_note(
+                None,
+                "`foo` is declared with signature () -> (field, field)"
+            )])
         );
     }
 
@@ -4792,11 +4979,14 @@ mod tests {
         let mut checker: Checker<Bn128Field> = new_with_args(HashMap::new(), 0, functions);
         assert_eq!(
             checker.check_function(bar, &*MODULE_ID, &state),
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-                message: "Function definition for function foo with signature () -> _ not found."
-                    .into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Function definition for function foo with signature () -> _ not found."
+            )
+            .with_note(
+                None,
+                "`foo` is declared with signature () -> (field, field)"
+            )])
         );
     }
 
@@ -4839,13 +5029,10 @@ mod tests {
         let mut checker: Checker<Bn128Field> = new_with_args(HashMap::new(), 0, HashSet::new());
         assert_eq!(
             checker.check_function(bar, &*MODULE_ID, &state),
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-
-                message:
-                    "Function definition for function foo with signature () -> field not found."
-                        .into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Function definition for function foo with signature () -> field not found."
+            )])
         );
     }
 
@@ -4947,10 +5134,10 @@ mod tests {
         assert_eq!(
             checker.check_module(&*MODULE_ID, &mut state),
             Err(vec![Error {
-                inner: ErrorInner {
-                    pos: Some((Position::mock(), Position::mock())),
-                    message: "Identifier \"x\" is undefined".into()
-                },
+                inner: ErrorInner::new(
+                    Some((Position::mock(), Position::mock())),
+                    "Identifier \"x\" is undefined"
+                ),
                 module_id: (*MODULE_ID).clone()
             }])
         );
@@ -5035,17 +5222,17 @@ mod tests {
             checker.check_module(&*MODULE_ID, &mut state),
             Err(vec![
                 Error {
-                    inner: ErrorInner {
-                        pos: Some((Position::mock(), Position::mock())),
-                        message: "Variable `a` is undeclared".into()
-                    },
+                    inner: ErrorInner::new(
+                        Some((Position::mock(), Position::mock())),
+                        "Variable `a` is undeclared"
+                    ),
                     module_id: (*MODULE_ID).clone()
                 },
                 Error {
-                    inner: ErrorInner {
-                        pos: Some((Position::mock(), Position::mock())),
-                        message: "Variable `b` is undeclared".into()
-                    },
+                    inner: ErrorInner::new(
+                        Some((Position::mock(), Position::mock())),
+                        "Variable `b` is undeclared"
+                    ),
                     module_id: (*MODULE_ID).clone()
                 }
             ])
@@ -5194,12 +5381,10 @@ mod tests {
         let mut checker: Checker<Bn128Field> = new_with_args(HashMap::new(), 0, HashSet::new());
         assert_eq!(
             checker.check_function(bar, &*MODULE_ID, &state),
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-
-                message: "Function definition for function foo with signature () -> _ not found."
-                    .into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Function definition for function foo with signature () -> _ not found."
+            )])
         );
     }
 
@@ -5235,10 +5420,10 @@ mod tests {
         let mut checker: Checker<Bn128Field> = new_with_args(HashMap::new(), 0, HashSet::new());
         assert_eq!(
             checker.check_function(bar, &*MODULE_ID, &state),
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-                message: "Identifier \"a\" is undefined".into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Identifier \"a\" is undefined"
+            )])
         );
     }
 
@@ -5460,10 +5645,7 @@ mod tests {
         assert_eq!(
             checker.check_program(program),
             Err(vec![Error {
-                inner: ErrorInner {
-                    pos: None,
-                    message: "Only one main function allowed, found 2".into()
-                },
+                inner: ErrorInner::new(None, "Only one main function allowed, found 2"),
                 module_id: (*MODULE_ID).clone()
             }])
         );
@@ -5496,10 +5678,10 @@ mod tests {
             );
         assert_eq!(
             s2_checked,
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-                message: "Duplicate declaration for variable named a".into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Duplicate declaration for variable named a"
+            )])
         );
     }
 
@@ -5530,10 +5712,10 @@ mod tests {
             );
         assert_eq!(
             s2_checked,
-            Err(vec![ErrorInner {
-                pos: Some((Position::mock(), Position::mock())),
-                message: "Duplicate declaration for variable named a".into()
-            }])
+            Err(vec![ErrorInner::new(
+                Some((Position::mock(), Position::mock())),
+                "Duplicate declaration for variable named a"
+            )])
         );
     }
 
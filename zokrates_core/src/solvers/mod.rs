@@ -1,5 +1,9 @@
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "ark")]
+use std::collections::HashMap;
 use std::fmt;
+#[cfg(feature = "ark")]
+use std::sync::Mutex;
 
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize, Hash, Eq)]
 pub enum Solver {
@@ -11,10 +15,20 @@ pub enum Solver {
     ShaAndXorAndXorAnd,
     ShaCh,
     EuclideanDiv,
-    #[cfg(feature = "bellman")]
     Sha256Round,
     #[cfg(feature = "ark")]
     SnarkVerifyBls12377(usize),
+    #[cfg(feature = "ark")]
+    SnarkVerifyGroth16Bls12377(usize),
+    PoseidonPermutation(usize),
+    KeccakF1600,
+    Blake2sRound,
+    EcdsaSecp256k1Verify,
+    BigIntMulMod(Vec<u64>),
+    BigIntReduce(usize, Vec<u64>),
+    EdwardsAdd,
+    EdwardsScalarMult,
+    EdwardsFixedBaseScalarMult,
 }
 
 impl fmt::Display for Solver {
@@ -23,6 +37,13 @@ impl fmt::Display for Solver {
     }
 }
 
+#[cfg(feature = "ark")]
+lazy_static::lazy_static! {
+    // counting the variables of the groth16 verifier requires synthesizing it, so we only do it once per input count
+    static ref GROTH16_BLS12_377_VARIABLE_COUNTS: Mutex<HashMap<usize, usize>> =
+        Mutex::new(HashMap::new());
+}
+
 impl Solver {
     pub fn get_signature(&self) -> (usize, usize) {
         match self {
@@ -34,10 +55,55 @@ impl Solver {
             Solver::ShaAndXorAndXorAnd => (3, 1),
             Solver::ShaCh => (3, 1),
             Solver::EuclideanDiv => (2, 2),
-            #[cfg(feature = "bellman")]
-            Solver::Sha256Round => (768, 26935),
+            Solver::Sha256Round => (
+                zokrates_embed::sha256::INPUT_SIZE,
+                zokrates_embed::sha256::variable_count(),
+            ),
             #[cfg(feature = "ark")]
             Solver::SnarkVerifyBls12377(n) => (26 + 3 * n, 41991 + 4972 * n),
+            #[cfg(feature = "ark")]
+            Solver::SnarkVerifyGroth16Bls12377(n) => (
+                24 + 3 * n,
+                *GROTH16_BLS12_377_VARIABLE_COUNTS
+                    .lock()
+                    .unwrap()
+                    .entry(*n)
+                    .or_insert_with(|| {
+                        zokrates_embed::ark::variable_count::<zokrates_embed::ark::Groth16>(*n)
+                    }),
+            ),
+            Solver::PoseidonPermutation(width) => {
+                (*width, zokrates_embed::poseidon::variable_count(*width))
+            }
+            Solver::KeccakF1600 => (
+                zokrates_embed::keccak::STATE_SIZE,
+                zokrates_embed::keccak::variable_count(),
+            ),
+            Solver::Blake2sRound => (
+                zokrates_embed::blake2s::INPUT_SIZE,
+                zokrates_embed::blake2s::variable_count(),
+            ),
+            Solver::EcdsaSecp256k1Verify => (
+                zokrates_embed::secp256k1::INPUT_SIZE,
+                zokrates_embed::secp256k1::variable_count(),
+            ),
+            Solver::BigIntMulMod(modulus) => (
+                2 * modulus.len(),
+                zokrates_embed::bigint::mul_mod_variable_count(modulus),
+            ),
+            Solver::BigIntReduce(input_limb_count, modulus) => (
+                *input_limb_count,
+                zokrates_embed::bigint::reduce_variable_count(modulus, *input_limb_count),
+            ),
+            Solver::EdwardsAdd => (4, zokrates_embed::babyjubjub::add_variable_count()),
+            Solver::EdwardsScalarMult => (
+                zokrates_embed::babyjubjub::SCALAR_SIZE + 2,
+                zokrates_embed::babyjubjub::scalar_mult_variable_count(),
+            ),
+            Solver::EdwardsFixedBaseScalarMult => (
+                zokrates_embed::babyjubjub::SCALAR_SIZE,
+                zokrates_embed::babyjubjub::fixed_base_scalar_mult_variable_count(),
+            ),
         }
     }
 }
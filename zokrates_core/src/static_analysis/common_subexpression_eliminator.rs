@@ -0,0 +1,551 @@
+//! Module containing common subexpression elimination in zir
+//!
+//! Each expression assigned to a variable is remembered. When the same expression is computed
+//! again later, it is replaced by that variable. This also applies to embed calls, so that
+//! hashing the same input twice only produces the constraints once.
+//!
+//! Expressions computed inside a branch are not reused outside of it, as isolated branches only
+//! constrain their variables when their condition holds.
+
+use crate::zir::folder::*;
+use crate::zir::types::Type;
+use crate::zir::*;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use zokrates_field::Field;
+
+/// A map whose changes can be undone back to a checkpoint, so that what is computed in a branch
+/// can be forgotten without copying the map
+struct ScopedMap<K, V> {
+    map: HashMap<K, V>,
+    /// The previous value of each key changed since the outermost open checkpoint
+    log: Vec<(K, Option<V>)>,
+    /// The number of open checkpoints
+    depth: usize,
+}
+
+impl<K, V> Default for ScopedMap<K, V> {
+    fn default() -> Self {
+        ScopedMap {
+            map: HashMap::new(),
+            log: vec![],
+            depth: 0,
+        }
+    }
+}
+
+impl<K: Hash + Eq + Clone, V> ScopedMap<K, V> {
+    fn get(&self, k: &K) -> Option<&V> {
+        self.map.get(k)
+    }
+
+    /// Insert `v` at `k` unless `k` is already present
+    fn insert_new(&mut self, k: K, v: V) {
+        if !self.map.contains_key(&k) {
+            self.map.insert(k.clone(), v);
+            if self.depth > 0 {
+                self.log.push((k, None));
+            }
+        }
+    }
+
+    fn retain(&mut self, mut f: impl FnMut(&V) -> bool) {
+        if self.depth == 0 {
+            self.map.retain(|_, v| f(v));
+            return;
+        }
+
+        let removed: Vec<_> = self
+            .map
+            .iter()
+            .filter(|(_, v)| !f(v))
+            .map(|(k, _)| k.clone())
+            .collect();
+
+        for k in removed {
+            let v = self.map.remove(&k);
+            self.log.push((k, v));
+        }
+    }
+
+    fn checkpoint(&mut self) -> usize {
+        self.depth += 1;
+        self.log.len()
+    }
+
+    /// Undo all changes made since `checkpoint`, and close it
+    fn rollback(&mut self, checkpoint: usize) {
+        for (k, previous) in self.log.drain(checkpoint..).rev() {
+            match previous {
+                Some(v) => self.map.insert(k, v),
+                None => self.map.remove(&k),
+            };
+        }
+        self.depth -= 1;
+    }
+}
+
+/// Expressions of type `E` which were already computed, along with the variable holding their
+/// value and the variables they read
+type Computed<'ast, E> = ScopedMap<E, (Identifier<'ast>, HashSet<Identifier<'ast>>)>;
+
+/// Calls to embeds which were already made, along with the variables holding their results and
+/// the variables they read
+type Calls<'ast, T> =
+    ScopedMap<ZirExpressionList<'ast, T>, (Vec<Identifier<'ast>>, HashSet<Identifier<'ast>>)>;
+
+struct Cache<'ast, T> {
+    field: Computed<'ast, FieldElementExpression<'ast, T>>,
+    boolean: Computed<'ast, BooleanExpression<'ast, T>>,
+    uint: Computed<'ast, UExpression<'ast, T>>,
+    calls: Calls<'ast, T>,
+}
+
+impl<'ast, T> Default for Cache<'ast, T> {
+    fn default() -> Self {
+        Cache {
+            field: ScopedMap::default(),
+            boolean: ScopedMap::default(),
+            uint: ScopedMap::default(),
+            calls: ScopedMap::default(),
+        }
+    }
+}
+
+fn invalidate<'ast, K: Hash + Eq + Clone, V>(
+    entries: &mut ScopedMap<K, (V, HashSet<Identifier<'ast>>)>,
+    id: &Identifier<'ast>,
+    holds: impl Fn(&V) -> bool,
+) {
+    entries.retain(|(value, reads)| !holds(value) && !reads.contains(id));
+}
+
+impl<'ast, T: Field> Cache<'ast, T> {
+    /// Forget everything which depends on the value of `id`
+    fn invalidate(&mut self, id: &Identifier<'ast>) {
+        invalidate(&mut self.field, id, |v| v == id);
+        invalidate(&mut self.boolean, id, |v| v == id);
+        invalidate(&mut self.uint, id, |v| v == id);
+        invalidate(&mut self.calls, id, |v| v.contains(id));
+    }
+
+    fn checkpoint(&mut self) -> [usize; 4] {
+        [
+            self.field.checkpoint(),
+            self.boolean.checkpoint(),
+            self.uint.checkpoint(),
+            self.calls.checkpoint(),
+        ]
+    }
+
+    fn rollback(&mut self, [field, boolean, uint, calls]: [usize; 4]) {
+        self.field.rollback(field);
+        self.boolean.rollback(boolean);
+        self.uint.rollback(uint);
+        self.calls.rollback(calls);
+    }
+}
+
+/// Collects the identifiers read by an expression
+#[derive(Default)]
+struct Reads<'ast> {
+    ids: HashSet<Identifier<'ast>>,
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for Reads<'ast> {
+    fn fold_name(&mut self, n: Identifier<'ast>) -> Identifier<'ast> {
+        self.ids.insert(n.clone());
+        n
+    }
+}
+
+fn remember<'ast, E: Hash + Eq>(
+    computed: &mut Computed<'ast, E>,
+    e: E,
+    reads: HashSet<Identifier<'ast>>,
+    id: Identifier<'ast>,
+) {
+    // in `a = a * b`, `a * b` refers to the previous value of `a`
+    if !reads.contains(&id) {
+        computed.insert_new(e, (id, reads));
+    }
+}
+
+fn identifier<'ast, T>(id: Identifier<'ast>, ty: &Type) -> ZirExpression<'ast, T> {
+    match ty {
+        Type::FieldElement => FieldElementExpression::Identifier(id).into(),
+        Type::Boolean => BooleanExpression::Identifier(id).into(),
+        Type::Uint(bitwidth) => UExpressionInner::Identifier(id).annotate(*bitwidth).into(),
+    }
+}
+
+#[derive(Default)]
+pub struct CommonSubexpressionEliminator<'ast, T> {
+    cache: Cache<'ast, T>,
+    // the variables defined so far, used to detect redefinitions
+    defined: HashSet<Identifier<'ast>>,
+    // the variables which were redefined so far
+    redefined: Vec<Identifier<'ast>>,
+}
+
+impl<'ast, T: Field> CommonSubexpressionEliminator<'ast, T> {
+    pub fn eliminate(p: ZirProgram<'ast, T>) -> ZirProgram<'ast, T> {
+        CommonSubexpressionEliminator::default().fold_program(p)
+    }
+
+    fn define(&mut self, id: &Identifier<'ast>) {
+        if !self.defined.insert(id.clone()) {
+            self.cache.invalidate(id);
+            self.redefined.push(id.clone());
+        }
+    }
+
+    fn reads<E, F: FnOnce(&mut Reads<'ast>, E) -> E>(e: E, f: F) -> (E, HashSet<Identifier<'ast>>) {
+        let mut reads = Reads::default();
+        let e = f(&mut reads, e);
+        (e, reads.ids)
+    }
+
+    fn remember(&mut self, id: Identifier<'ast>, e: &ZirExpression<'ast, T>) {
+        match e.clone() {
+            ZirExpression::FieldElement(FieldElementExpression::Identifier(_))
+            | ZirExpression::FieldElement(FieldElementExpression::Number(_))
+            | ZirExpression::Boolean(BooleanExpression::Identifier(_))
+            | ZirExpression::Boolean(BooleanExpression::Value(_))
+            | ZirExpression::Uint(UExpression {
+                inner: UExpressionInner::Identifier(_),
+                ..
+            })
+            | ZirExpression::Uint(UExpression {
+                inner: UExpressionInner::Value(_),
+                ..
+            }) => {}
+            ZirExpression::FieldElement(e) => {
+                let (e, reads) = Self::reads(e, |r, e| r.fold_field_expression(e));
+                remember(&mut self.cache.field, e, reads, id);
+            }
+            ZirExpression::Boolean(e) => {
+                let (e, reads) = Self::reads(e, |r, e| r.fold_boolean_expression(e));
+                remember(&mut self.cache.boolean, e, reads, id);
+            }
+            ZirExpression::Uint(e) => {
+                let (e, reads) = Self::reads(e, |r, e| r.fold_uint_expression(e));
+                remember(&mut self.cache.uint, e, reads, id);
+            }
+        }
+    }
+
+    fn fold_branch(
+        &mut self,
+        statements: Vec<ZirStatement<'ast, T>>,
+    ) -> Vec<ZirStatement<'ast, T>> {
+        let checkpoint = self.cache.checkpoint();
+        let redefined = self.redefined.len();
+
+        let statements = statements
+            .into_iter()
+            .flat_map(|s| self.fold_statement(s))
+            .collect();
+
+        // forget what was computed in the branch, as well as what depends on variables the
+        // branch redefined
+        self.cache.rollback(checkpoint);
+        for id in &self.redefined[redefined..] {
+            self.cache.invalidate(id);
+        }
+
+        statements
+    }
+}
+
+impl<'ast, T: Field> Folder<'ast, T> for CommonSubexpressionEliminator<'ast, T> {
+    fn fold_parameter(&mut self, p: Parameter<'ast>) -> Parameter<'ast> {
+        self.defined.insert(p.id.id.clone());
+        p
+    }
+
+    fn fold_statement(&mut self, s: ZirStatement<'ast, T>) -> Vec<ZirStatement<'ast, T>> {
+        match s {
+            ZirStatement::Definition(a, e) => {
+                let e = self.fold_expression(e);
+                self.define(&a.id);
+                self.remember(a.id.clone(), &e);
+                vec![ZirStatement::Definition(a, e)]
+            }
+            ZirStatement::MultipleDefinition(assignees, list) => {
+                let list = self.fold_expression_list(list);
+
+                for a in &assignees {
+                    self.define(&a.id);
+                }
+
+                match self.cache.calls.get(&list) {
+                    Some((ids, _)) => assignees
+                        .into_iter()
+                        .zip(ids.clone())
+                        .map(|(a, id)| {
+                            let e = identifier(id, &a._type);
+                            ZirStatement::Definition(a, e)
+                        })
+                        .collect(),
+                    None => {
+                        let ids: Vec<_> = assignees.iter().map(|a| a.id.clone()).collect();
+                        let (list, reads) = Self::reads(list, |r, l| r.fold_expression_list(l));
+                        // in `a = f(a)`, the call refers to the previous value of `a`
+                        if ids.iter().all(|id| !reads.contains(id)) {
+                            self.cache.calls.insert_new(list.clone(), (ids, reads));
+                        }
+                        vec![ZirStatement::MultipleDefinition(assignees, list)]
+                    }
+                }
+            }
+            ZirStatement::IfElse(condition, consequence, alternative) => {
+                let condition = self.fold_boolean_expression(condition);
+                let consequence = self.fold_branch(consequence);
+                let alternative = self.fold_branch(alternative);
+                vec![ZirStatement::IfElse(condition, consequence, alternative)]
+            }
+            s => fold_statement(self, s),
+        }
+    }
+
+    fn fold_field_expression(
+        &mut self,
+        e: FieldElementExpression<'ast, T>,
+    ) -> FieldElementExpression<'ast, T> {
+        let e = fold_field_expression(self, e);
+        match self.cache.field.get(&e) {
+            Some((id, _)) => FieldElementExpression::Identifier(id.clone()),
+            None => e,
+        }
+    }
+
+    fn fold_boolean_expression(
+        &mut self,
+        e: BooleanExpression<'ast, T>,
+    ) -> BooleanExpression<'ast, T> {
+        let e = fold_boolean_expression(self, e);
+        match self.cache.boolean.get(&e) {
+            Some((id, _)) => BooleanExpression::Identifier(id.clone()),
+            None => e,
+        }
+    }
+
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> UExpression<'ast, T> {
+        let e = fold_uint_expression(self, e);
+        match self.cache.uint.get(&e) {
+            Some((id, _)) => UExpressionInner::Identifier(id.clone()).annotate(e.bitwidth),
+            None => e,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embed::FlatEmbed;
+    use crate::zir::types::UBitwidth;
+    use zokrates_field::Bn128Field;
+
+    fn eliminate(statements: Vec<ZirStatement<Bn128Field>>) -> Vec<ZirStatement<Bn128Field>> {
+        let mut eliminator = CommonSubexpressionEliminator::default();
+        // `a` and `b` are parameters
+        eliminator
+            .defined
+            .extend(vec![Identifier::from("a"), Identifier::from("b")]);
+        statements
+            .into_iter()
+            .flat_map(|s| eliminator.fold_statement(s))
+            .collect()
+    }
+
+    fn product() -> FieldElementExpression<'static, Bn128Field> {
+        FieldElementExpression::Mult(
+            box FieldElementExpression::Identifier("a".into()),
+            box FieldElementExpression::Identifier("b".into()),
+        )
+    }
+
+    #[test]
+    fn reuse_definition() {
+        // c = a * b
+        // d = a * b + 1
+        // e = a * b
+
+        // becomes
+
+        // c = a * b
+        // d = c + 1
+        // e = c
+
+        let statements = vec![
+            ZirStatement::Definition(Variable::field_element("c"), product().into()),
+            ZirStatement::Definition(
+                Variable::field_element("d"),
+                FieldElementExpression::Add(
+                    box product(),
+                    box FieldElementExpression::Number(Bn128Field::from(1)),
+                )
+                .into(),
+            ),
+            ZirStatement::Definition(Variable::field_element("e"), product().into()),
+        ];
+
+        let expected = vec![
+            ZirStatement::Definition(Variable::field_element("c"), product().into()),
+            ZirStatement::Definition(
+                Variable::field_element("d"),
+                FieldElementExpression::Add(
+                    box FieldElementExpression::Identifier("c".into()),
+                    box FieldElementExpression::Number(Bn128Field::from(1)),
+                )
+                .into(),
+            ),
+            ZirStatement::Definition(
+                Variable::field_element("e"),
+                FieldElementExpression::Identifier("c".into()).into(),
+            ),
+        ];
+
+        assert_eq!(eliminate(statements), expected);
+    }
+
+    #[test]
+    fn redefinition() {
+        // c = a * b
+        // a = 42
+        // d = a * b
+
+        // is unchanged, as `a` changed in between
+
+        let statements = vec![
+            ZirStatement::Definition(Variable::field_element("c"), product().into()),
+            ZirStatement::Definition(
+                Variable::field_element("a"),
+                FieldElementExpression::Number(Bn128Field::from(42)).into(),
+            ),
+            ZirStatement::Definition(Variable::field_element("d"), product().into()),
+        ];
+
+        assert_eq!(eliminate(statements.clone()), statements);
+    }
+
+    #[test]
+    fn branches() {
+        // if a == b then { c = a * b } else {} fi
+        // d = a * b
+
+        // is unchanged, as `c` is only constrained in the branch
+
+        let statements = vec![
+            ZirStatement::IfElse(
+                BooleanExpression::FieldEq(
+                    box FieldElementExpression::Identifier("a".into()),
+                    box FieldElementExpression::Identifier("b".into()),
+                ),
+                vec![ZirStatement::Definition(
+                    Variable::field_element("c"),
+                    product().into(),
+                )],
+                vec![],
+            ),
+            ZirStatement::Definition(Variable::field_element("d"), product().into()),
+        ];
+
+        assert_eq!(eliminate(statements.clone()), statements);
+    }
+
+    #[test]
+    fn nested_branches() {
+        // c = a * b
+        // if a == b then { if a == b then { d = a * b; a = 42 } else {} fi } else {} fi
+        // e = a * b
+
+        // becomes
+
+        // c = a * b
+        // if a == b then { if a == b then { d = c; a = 42 } else {} fi } else {} fi
+        // e = a * b
+
+        // as `a` was redefined in the inner branch
+
+        let condition = || {
+            BooleanExpression::FieldEq(
+                box FieldElementExpression::Identifier("a".into()),
+                box FieldElementExpression::Identifier("b".into()),
+            )
+        };
+
+        let statements = |d: FieldElementExpression<'static, Bn128Field>| {
+            vec![
+                ZirStatement::Definition(Variable::field_element("c"), product().into()),
+                ZirStatement::IfElse(
+                    condition(),
+                    vec![ZirStatement::IfElse(
+                        condition(),
+                        vec![
+                            ZirStatement::Definition(Variable::field_element("d"), d.into()),
+                            ZirStatement::Definition(
+                                Variable::field_element("a"),
+                                FieldElementExpression::Number(Bn128Field::from(42)).into(),
+                            ),
+                        ],
+                        vec![],
+                    )],
+                    vec![],
+                ),
+                ZirStatement::Definition(Variable::field_element("e"), product().into()),
+            ]
+        };
+
+        assert_eq!(
+            eliminate(statements(product())),
+            statements(FieldElementExpression::Identifier("c".into()))
+        );
+    }
+
+    #[test]
+    fn embed_call() {
+        // c = u8_from_bits([a; 8])
+        // d = u8_from_bits([a; 8])
+
+        // becomes
+
+        // c = u8_from_bits([a; 8])
+        // d = c
+
+        let call = || {
+            ZirExpressionList::EmbedCall(
+                FlatEmbed::U8FromBits,
+                vec![],
+                vec![BooleanExpression::Identifier("a".into()).into(); 8],
+            )
+        };
+
+        let statements = vec![
+            ZirStatement::MultipleDefinition(
+                vec![Variable::uint("c".into(), UBitwidth::B8)],
+                call(),
+            ),
+            ZirStatement::MultipleDefinition(
+                vec![Variable::uint("d".into(), UBitwidth::B8)],
+                call(),
+            ),
+        ];
+
+        let expected = vec![
+            ZirStatement::MultipleDefinition(
+                vec![Variable::uint("c".into(), UBitwidth::B8)],
+                call(),
+            ),
+            ZirStatement::Definition(
+                Variable::uint("d".into(), UBitwidth::B8),
+                UExpressionInner::Identifier("c".into())
+                    .annotate(UBitwidth::B8)
+                    .into(),
+            ),
+        ];
+
+        assert_eq!(eliminate(statements), expected);
+    }
+}
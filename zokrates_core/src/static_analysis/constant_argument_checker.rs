@@ -3,7 +3,8 @@ use crate::typed_absy::TypedProgram;
 use crate::typed_absy::{
     result_folder::ResultFolder,
     result_folder::{fold_expression_list_inner, fold_uint_expression_inner},
-    Constant, TypedExpressionListInner, Types, UBitwidth, UExpressionInner,
+    ArrayExpressionInner, Constant, TypedExpression, TypedExpressionListInner,
+    TypedExpressionOrSpread, Types, UBitwidth, UExpressionInner,
 };
 use std::fmt;
 use zokrates_field::Field;
@@ -89,7 +90,117 @@ impl<'ast, T: Field> ResultFolder<'ast, T> for ConstantArgumentChecker {
                     )))
                 }
             }
+            TypedExpressionListInner::EmbedCall(FlatEmbed::RangeCheck, generics, arguments) => {
+                let bitwidth = generics[0] as usize;
+
+                if bitwidth >= T::get_required_bits() {
+                    return Err(Error(format!(
+                        "Cannot check that a field element fits in {} bits, expected at most {} bits",
+                        bitwidth,
+                        T::get_required_bits() - 1
+                    )));
+                }
+
+                let arguments = arguments
+                    .into_iter()
+                    .map(|a| self.fold_expression(a))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok(TypedExpressionListInner::EmbedCall(
+                    FlatEmbed::RangeCheck,
+                    generics,
+                    arguments,
+                ))
+            }
+            TypedExpressionListInner::EmbedCall(
+                embed @ (FlatEmbed::Lookup | FlatEmbed::LookupU8),
+                generics,
+                arguments,
+            ) => {
+                let size = generics[0];
+
+                if !size.is_power_of_two() {
+                    return Err(Error(format!(
+                        "Expected the size of the lookup table to be a power of two, found {}",
+                        size
+                    )));
+                }
+
+                let arguments = arguments
+                    .into_iter()
+                    .map(|a| self.fold_expression(a))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if arguments[0].is_constant() {
+                    Ok(TypedExpressionListInner::EmbedCall(
+                        embed, generics, arguments,
+                    ))
+                } else {
+                    Err(Error(format!(
+                        "Expected the lookup table to be a constant, found `{}`",
+                        arguments[0]
+                    )))
+                }
+            }
+            TypedExpressionListInner::EmbedCall(
+                embed @ (FlatEmbed::BigIntMulMod | FlatEmbed::BigIntReduce),
+                generics,
+                arguments,
+            ) => {
+                let arguments = arguments
+                    .into_iter()
+                    .map(|a| self.fold_expression(a))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                // the modulus is the last argument
+                let modulus = arguments.last().unwrap();
+
+                if !modulus.is_constant() {
+                    return Err(Error(format!(
+                        "Expected the modulus to be a constant, found `{}`",
+                        modulus
+                    )));
+                }
+
+                if is_zero(modulus.clone()) {
+                    return Err(Error("The modulus cannot be zero".into()));
+                }
+
+                if let FlatEmbed::BigIntReduce = embed {
+                    let (limb_count, input_limb_count) = (generics[0], generics[1]);
+
+                    if input_limb_count > 2 * limb_count {
+                        return Err(Error(format!(
+                            "Cannot reduce an integer of {} words modulo an integer of {} words, expected at most {} words",
+                            input_limb_count,
+                            limb_count,
+                            2 * limb_count
+                        )));
+                    }
+                }
+
+                Ok(TypedExpressionListInner::EmbedCall(
+                    embed, generics, arguments,
+                ))
+            }
             l => fold_expression_list_inner(self, tys, l),
         }
     }
 }
+
+/// Whether `e`, a constant array of unsigned integers, only holds zeros
+fn is_zero<T: Field>(e: TypedExpression<T>) -> bool {
+    match e.into_canonical_constant() {
+        TypedExpression::Array(a) => match a.into_inner() {
+            ArrayExpressionInner::Value(v) => v.0.iter().all(|e| {
+                matches!(
+                    e,
+                    TypedExpressionOrSpread::Expression(TypedExpression::Uint(e))
+                        if matches!(e.as_inner(), UExpressionInner::Value(0))
+                )
+            }),
+            _ => unreachable!(),
+        },
+        _ => unreachable!(),
+    }
+}
@@ -5,6 +5,7 @@
 //! @date 2018
 
 mod branch_isolator;
+mod common_subexpression_eliminator;
 mod condition_redefiner;
 mod constant_argument_checker;
 mod constant_resolver;
@@ -19,6 +20,7 @@ mod variable_write_remover;
 mod zir_propagation;
 
 use self::branch_isolator::Isolator;
+use self::common_subexpression_eliminator::CommonSubexpressionEliminator;
 use self::condition_redefiner::ConditionRedefiner;
 use self::constant_argument_checker::ConstantArgumentChecker;
 use self::flatten_complex_types::Flattener;
@@ -34,6 +36,7 @@ use crate::static_analysis::zir_propagation::ZirPropagator;
 use crate::typed_absy::{abi::Abi, TypedProgram};
 use crate::zir::ZirProgram;
 use std::fmt;
+use std::path::Path;
 use zokrates_field::Field;
 
 #[derive(Debug)]
@@ -75,6 +78,46 @@ impl From<constant_argument_checker::Error> for Error {
     }
 }
 
+impl Error {
+    /// A stable code identifying the kind of error
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Reducer(e) => match e {
+                reducer::Error::Incompatible(_) => "E0600",
+                reducer::Error::GenericsInMain => "E0601",
+                reducer::Error::NoProgress => "E0602",
+                reducer::Error::LoopTooLarge(_) => "E0603",
+                reducer::Error::ConstantReduction(..) => "E0604",
+                reducer::Error::Type(_) => "E0605",
+            },
+            Error::Propagation(e) => match e {
+                propagation::Error::Type(_) => "E0610",
+                propagation::Error::AssertionFailed(_) => "E0611",
+                propagation::Error::ValueTooLarge(_) => "E0612",
+                propagation::Error::OutOfBounds(..) => "E0613",
+                propagation::Error::NonConstantExponent(_) => "E0614",
+            },
+            Error::ZirPropagation(e) => match e {
+                zir_propagation::Error::OutOfBounds(..) => "E0620",
+                zir_propagation::Error::DivisionByZero => "E0621",
+                zir_propagation::Error::AssertionFailed(_) => "E0622",
+            },
+            Error::NonConstantArgument(_) => "E0630",
+            Error::OutOfBounds(_) => "E0640",
+        }
+    }
+
+    /// The module and name of the constant which could not be reduced, if any
+    pub fn constant(&self) -> Option<(&str, &Path)> {
+        match self {
+            Error::Reducer(reducer::Error::ConstantReduction(name, module)) => {
+                Some((name.as_str(), module.as_path()))
+            }
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
@@ -157,6 +200,11 @@ impl<'ast, T: Field> TypedProgram<'ast, T> {
         let zir = ZirPropagator::propagate(zir).map_err(Error::from)?;
         log::trace!("\n{}", zir);
 
+        // reuse identical expressions and embed calls
+        log::debug!("Static analyser: Eliminate common subexpressions");
+        let zir = CommonSubexpressionEliminator::eliminate(zir);
+        log::trace!("\n{}", zir);
+
         // optimize uint expressions
         log::debug!("Static analyser: Optimize uints");
         let zir = UintOptimizer::optimize(zir);
@@ -473,10 +473,23 @@ impl<'ast, 'a, T: Field> ResultFolder<'ast, T> for Propagator<'ast, 'a, T> {
                                             _ => unreachable!("should be a field value"),
                                         }
                                     }
-                                    #[cfg(feature = "bellman")]
                                     FlatEmbed::Sha256Round => Ok(None),
                                     #[cfg(feature = "ark")]
                                     FlatEmbed::SnarkVerifyBls12377 => Ok(None),
+                                    #[cfg(feature = "ark")]
+                                    FlatEmbed::SnarkVerifyGroth16Bls12377 => Ok(None),
+                                    FlatEmbed::PoseidonPermutation => Ok(None),
+                                    FlatEmbed::KeccakF1600 => Ok(None),
+                                    FlatEmbed::Blake2sRound => Ok(None),
+                                    FlatEmbed::EcdsaSecp256k1Verify => Ok(None),
+                                    FlatEmbed::BigIntMulMod => Ok(None),
+                                    FlatEmbed::BigIntReduce => Ok(None),
+                                    FlatEmbed::RangeCheck => Ok(None),
+                                    FlatEmbed::Lookup => Ok(None),
+                                    FlatEmbed::LookupU8 => Ok(None),
+                                    FlatEmbed::EdwardsAdd => Ok(None),
+                                    FlatEmbed::EdwardsScalarMult => Ok(None),
+                                    FlatEmbed::EdwardsFixedBaseScalarMult => Ok(None),
                                 }?;
 
                                 Ok(match r {
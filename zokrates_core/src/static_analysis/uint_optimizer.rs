@@ -436,7 +436,15 @@ impl<'ast, T: Field> Folder<'ast, T> for UintOptimizer<'ast, T> {
                         ZirExpression::Uint(e) => {
                             let e = self.fold_uint_expression(e);
 
-                            let e = force_reduce(e);
+                            let metadata = e.metadata.as_ref().unwrap();
+
+                            // values which are already known to be in range do not need to be reduced
+                            let e = match !metadata.should_reduce.is_true()
+                                && metadata.bitwidth() <= e.bitwidth.to_usize() as u32
+                            {
+                                true => force_no_reduce(e),
+                                false => force_reduce(e),
+                            };
 
                             ZirExpression::Uint(e)
                         }
@@ -489,6 +497,50 @@ impl<'ast, T: Field> Folder<'ast, T> for UintOptimizer<'ast, T> {
                             },
                         );
                     }
+                    FlatEmbed::KeccakF1600 => {
+                        for l in &lhs {
+                            self.register(
+                                l.clone(),
+                                UMetadata {
+                                    max: T::from(2).pow(64) - T::from(1),
+                                    should_reduce: ShouldReduce::False,
+                                },
+                            );
+                        }
+                    }
+                    FlatEmbed::Blake2sRound => {
+                        for l in &lhs {
+                            self.register(
+                                l.clone(),
+                                UMetadata {
+                                    max: T::from(2).pow(32) - T::from(1),
+                                    should_reduce: ShouldReduce::False,
+                                },
+                            );
+                        }
+                    }
+                    // the entries of the table are reduced, so the entry we look up is in range
+                    FlatEmbed::LookupU8 => {
+                        assert_eq!(lhs.len(), 1);
+                        self.register(
+                            lhs[0].clone(),
+                            UMetadata {
+                                max: T::from(2).pow(8) - T::from(1),
+                                should_reduce: ShouldReduce::False,
+                            },
+                        );
+                    }
+                    FlatEmbed::BigIntMulMod | FlatEmbed::BigIntReduce => {
+                        for l in &lhs {
+                            self.register(
+                                l.clone(),
+                                UMetadata {
+                                    max: T::from(2).pow(64) - T::from(1),
+                                    should_reduce: ShouldReduce::False,
+                                },
+                            );
+                        }
+                    }
                     _ => {}
                 };
 
@@ -496,7 +548,13 @@ impl<'ast, T: Field> Folder<'ast, T> for UintOptimizer<'ast, T> {
                     FlatEmbed::U8ToBits
                     | FlatEmbed::U16ToBits
                     | FlatEmbed::U32ToBits
-                    | FlatEmbed::U64ToBits => {
+                    | FlatEmbed::U64ToBits
+                    | FlatEmbed::KeccakF1600
+                    | FlatEmbed::Blake2sRound
+                    | FlatEmbed::EcdsaSecp256k1Verify
+                    | FlatEmbed::BigIntMulMod
+                    | FlatEmbed::BigIntReduce
+                    | FlatEmbed::LookupU8 => {
                         vec![ZirStatement::MultipleDefinition(
                             lhs,
                             ZirExpressionList::EmbedCall(
@@ -800,4 +858,22 @@ mod tests {
             Bn128Field::from(42)
         );
     }
+
+    #[test]
+    fn return_values() {
+        // values in range are returned as is, others are reduced
+        let in_range = e_with_max(42);
+        let out_of_range = e_with_max(2_u128.pow(32));
+
+        assert_eq!(
+            UintOptimizer::new().fold_statement(ZirStatement::Return(vec![
+                ZirExpression::Uint(in_range.clone()),
+                ZirExpression::Uint(out_of_range.clone())
+            ])),
+            vec![ZirStatement::Return(vec![
+                ZirExpression::Uint(force_no_reduce(in_range)),
+                ZirExpression::Uint(force_reduce(out_of_range))
+            ])]
+        );
+    }
 }
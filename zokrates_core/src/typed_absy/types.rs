@@ -273,6 +273,23 @@ impl<'ast, T> TryInto<u32> for UExpression<'ast, T> {
     }
 }
 
+impl<'ast, T> TryInto<DeclarationConstant<'ast, T>> for UExpression<'ast, T> {
+    type Error = SpecializationError;
+
+    fn try_into(self) -> Result<DeclarationConstant<'ast, T>, Self::Error> {
+        assert_eq!(self.bitwidth, UBitwidth::B32);
+
+        match self.into_inner() {
+            UExpressionInner::Value(v) => Ok(DeclarationConstant::Concrete(v as u32)),
+            UExpressionInner::Identifier(Identifier {
+                id: CoreIdentifier::Constant(c),
+                ..
+            }) => Ok(DeclarationConstant::Constant(c)),
+            _ => Err(SpecializationError),
+        }
+    }
+}
+
 impl<'ast, T> TryInto<usize> for DeclarationConstant<'ast, T> {
     type Error = SpecializationError;
 
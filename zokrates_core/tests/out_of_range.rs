@@ -168,3 +168,35 @@ fn unpack256_unchecked() {
         .execute(res.prog(), &[Bn128Field::from(0)])
         .is_ok());
 }
+
+#[test]
+fn range_check_isolated_branch() {
+    let source = r#"
+        from "EMBED" import range_check
+
+		def main(private bool b, private field a) -> field:
+	        field x = if b then range_check::<8>(a) else 0 fi
+			return x + range_check::<8>(a)
+	"#
+    .to_string();
+
+    // let's try to prove that 256 fits in 8 bits by checking it in a branch which is not taken
+    // the decomposition of the branch only holds when it is taken, so it must not be reused outside of it
+
+    let arena = Arena::new();
+
+    let res: CompilationArtifacts<Bn128Field, _> = compile(
+        source,
+        "./path/to/file".into(),
+        None::<&dyn Resolver<io::Error>>,
+        CompileConfig::default().isolate_branches(true),
+        &arena,
+    )
+    .unwrap();
+
+    let interpreter = Interpreter::default();
+
+    assert!(interpreter
+        .execute(res.prog(), &[Bn128Field::from(0), Bn128Field::from(256)])
+        .is_err());
+}
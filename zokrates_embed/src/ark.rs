@@ -1,6 +1,6 @@
 use ark_bls12_377::{
-    constraints::PairingVar as BLS12PairingVar, Bls12_377 as BLS12PairingEngine, Fq as BLS12Fq,
-    Fq2 as BLS12Fq2,
+    constraints::{G1Var, G2Var, PairingVar as BLS12PairingVar},
+    Bls12_377 as BLS12PairingEngine, Fq as BLS12Fq, Fq2 as BLS12Fq2, Fr as BLS12Fr,
 };
 use ark_bw6_761::Fr as BW6Fr;
 use ark_ec::PairingEngine;
@@ -11,8 +11,20 @@ use ark_relations::{
     r1cs::{ConstraintSystem, ConstraintSystemRef},
 };
 
-use ark_crypto_primitives::snark::constraints::SNARKGadget;
-use ark_gm17::{constraints::GM17VerifierGadget, Proof, VerifyingKey, GM17};
+use ark_crypto_primitives::snark::constraints::{BooleanInputVar, SNARKGadget};
+use ark_gm17::{
+    constraints::{
+        GM17VerifierGadget, ProofVar as GM17ProofVar, VerifyingKeyVar as GM17VerifyingKeyVar,
+    },
+    Proof as GM17Proof, VerifyingKey as GM17VerifyingKey, GM17,
+};
+use ark_groth16::{
+    constraints::{
+        Groth16VerifierGadget, ProofVar as Groth16ProofVar,
+        VerifyingKeyVar as Groth16VerifyingKeyVar,
+    },
+    Groth16 as Groth16Snark, Proof as Groth16Proof, VerifyingKey as Groth16VerifyingKey,
+};
 use ark_r1cs_std::alloc::{AllocVar, AllocationMode};
 
 use crate::Constraint;
@@ -24,12 +36,143 @@ use ark_std::test_rng;
 use std::str::FromStr;
 use zokrates_field::Field;
 
-type GM17Snark = GM17<BLS12PairingEngine>;
-type VerifierGadget = GM17VerifierGadget<BLS12PairingEngine, BLS12PairingVar>;
-
 type G1 = <ark_ec::bls12::Bls12<ark_bls12_377::Parameters> as PairingEngine>::G1Affine;
 type G2 = <ark_ec::bls12::Bls12<ark_bls12_377::Parameters> as PairingEngine>::G2Affine;
 
+/// A proving scheme over BLS12-377 whose proofs can be verified inside a BW6-761 circuit
+pub trait RecursiveSnark {
+    type Snark: SNARK<BLS12Fr>;
+    type VerifierGadget: SNARKGadget<
+        BLS12Fr,
+        BLS12Fq,
+        Self::Snark,
+        InputVar = BooleanInputVar<BLS12Fr, BLS12Fq>,
+    >;
+
+    /// The number of field elements in a flattened verification key for `public_input_size` inputs
+    fn verification_key_size(public_input_size: usize) -> usize;
+
+    /// The variables of a proof, in the order of its flattened representation
+    fn proof_variables(
+        proof: &<Self::VerifierGadget as SNARKGadget<BLS12Fr, BLS12Fq, Self::Snark>>::ProofVar,
+    ) -> Vec<&FpVar<BLS12Fq>>;
+
+    /// The variables of a verification key, in the order of its flattened representation
+    fn verification_key_variables(
+        vk: &<Self::VerifierGadget as SNARKGadget<BLS12Fr, BLS12Fq, Self::Snark>>::VerifyingKeyVar,
+    ) -> Vec<&FpVar<BLS12Fq>>;
+
+    fn proof<T: Field>(flat: &[T]) -> <Self::Snark as SNARK<BLS12Fr>>::Proof;
+
+    fn verification_key<T: Field>(flat: &[T]) -> <Self::Snark as SNARK<BLS12Fr>>::VerifyingKey;
+}
+
+/// The GM17 proving scheme
+pub struct Gm17;
+
+impl RecursiveSnark for Gm17 {
+    type Snark = GM17<BLS12PairingEngine>;
+    type VerifierGadget = GM17VerifierGadget<BLS12PairingEngine, BLS12PairingVar>;
+
+    fn verification_key_size(public_input_size: usize) -> usize {
+        18 + 2 * public_input_size
+    }
+
+    fn proof_variables(
+        proof: &GM17ProofVar<BLS12PairingEngine, BLS12PairingVar>,
+    ) -> Vec<&FpVar<BLS12Fq>> {
+        g1_variables(&proof.a)
+            .into_iter()
+            .chain(g2_variables(&proof.b))
+            .chain(g1_variables(&proof.c))
+            .collect()
+    }
+
+    fn verification_key_variables(
+        vk: &GM17VerifyingKeyVar<BLS12PairingEngine, BLS12PairingVar>,
+    ) -> Vec<&FpVar<BLS12Fq>> {
+        g2_variables(&vk.h_g2)
+            .into_iter()
+            .chain(g1_variables(&vk.g_alpha_g1))
+            .chain(g2_variables(&vk.h_beta_g2))
+            .chain(g1_variables(&vk.g_gamma_g1))
+            .chain(g2_variables(&vk.h_gamma_g2))
+            .chain(vk.query.iter().flat_map(g1_variables))
+            .collect()
+    }
+
+    fn proof<T: Field>(flat: &[T]) -> GM17Proof<BLS12PairingEngine> {
+        GM17Proof {
+            a: new_g1(&flat[0..2]),
+            b: new_g2(&flat[2..6]),
+            c: new_g1(&flat[6..8]),
+        }
+    }
+
+    fn verification_key<T: Field>(flat: &[T]) -> GM17VerifyingKey<BLS12PairingEngine> {
+        GM17VerifyingKey {
+            h_g2: new_g2(&flat[0..4]),
+            g_alpha_g1: new_g1(&flat[4..6]),
+            h_beta_g2: new_g2(&flat[6..10]),
+            g_gamma_g1: new_g1(&flat[10..12]),
+            h_gamma_g2: new_g2(&flat[12..16]),
+            query: flat[16..].chunks(2).map(new_g1).collect(),
+        }
+    }
+}
+
+/// The Groth16 proving scheme
+pub struct Groth16;
+
+impl RecursiveSnark for Groth16 {
+    type Snark = Groth16Snark<BLS12PairingEngine>;
+    type VerifierGadget = Groth16VerifierGadget<BLS12PairingEngine, BLS12PairingVar>;
+
+    fn verification_key_size(public_input_size: usize) -> usize {
+        16 + 2 * public_input_size
+    }
+
+    fn proof_variables(
+        proof: &Groth16ProofVar<BLS12PairingEngine, BLS12PairingVar>,
+    ) -> Vec<&FpVar<BLS12Fq>> {
+        g1_variables(&proof.a)
+            .into_iter()
+            .chain(g2_variables(&proof.b))
+            .chain(g1_variables(&proof.c))
+            .collect()
+    }
+
+    fn verification_key_variables(
+        vk: &Groth16VerifyingKeyVar<BLS12PairingEngine, BLS12PairingVar>,
+    ) -> Vec<&FpVar<BLS12Fq>> {
+        g1_variables(&vk.alpha_g1)
+            .into_iter()
+            .chain(g2_variables(&vk.beta_g2))
+            .chain(g2_variables(&vk.gamma_g2))
+            .chain(g2_variables(&vk.delta_g2))
+            .chain(vk.gamma_abc_g1.iter().flat_map(g1_variables))
+            .collect()
+    }
+
+    fn proof<T: Field>(flat: &[T]) -> Groth16Proof<BLS12PairingEngine> {
+        Groth16Proof {
+            a: new_g1(&flat[0..2]),
+            b: new_g2(&flat[2..6]),
+            c: new_g1(&flat[6..8]),
+        }
+    }
+
+    fn verification_key<T: Field>(flat: &[T]) -> Groth16VerifyingKey<BLS12PairingEngine> {
+        Groth16VerifyingKey {
+            alpha_g1: new_g1(&flat[0..2]),
+            beta_g2: new_g2(&flat[2..6]),
+            gamma_g2: new_g2(&flat[6..10]),
+            delta_g2: new_g2(&flat[10..14]),
+            gamma_abc_g1: flat[14..].chunks(2).map(new_g1).collect(),
+        }
+    }
+}
+
 #[derive(Copy, Clone)]
 struct DefaultCircuit {
     pub public_input_size: usize,
@@ -45,7 +188,7 @@ impl<F: PrimeField> ConstraintSynthesizer<F> for DefaultCircuit {
 }
 
 #[allow(clippy::type_complexity)]
-pub fn generate_verify_constraints(
+pub fn generate_verify_constraints<S: RecursiveSnark>(
     public_input_size: usize,
 ) -> (
     usize,
@@ -61,8 +204,8 @@ pub fn generate_verify_constraints(
     let mut rng = test_rng(); // has a fixed seed
     let circuit = DefaultCircuit { public_input_size };
 
-    let (pk, vk) = GM17Snark::circuit_specific_setup(circuit, &mut rng).unwrap();
-    let proof = GM17Snark::prove(&pk, circuit, &mut rng).unwrap();
+    let (pk, vk) = S::Snark::circuit_specific_setup(circuit, &mut rng).unwrap();
+    let proof = S::Snark::prove(&pk, circuit, &mut rng).unwrap();
 
     let mut fp_vars = Vec::new();
     for _ in 0..public_input_size {
@@ -73,38 +216,23 @@ pub fn generate_verify_constraints(
     let input_booleans: Vec<Vec<Boolean<_>>> =
         fp_vars.iter().map(|i| i.to_bits_le().unwrap()).collect();
 
-    let inputs = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::InputVar::new(input_booleans);
-
-    let proof = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::new_proof_unchecked(
+    let inputs = BooleanInputVar::new(input_booleans);
+
+    let proof = S::VerifierGadget::new_proof_unchecked(
         ns!(cs, "alloc_proof"),
         || Ok(proof),
         AllocationMode::Witness,
     )
     .unwrap();
 
-    let vk = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::new_verification_key_unchecked(
-        ns!(cs, "alloc_vk"), || Ok(vk), AllocationMode::Witness
+    let vk = S::VerifierGadget::new_verification_key_unchecked(
+        ns!(cs, "alloc_vk"),
+        || Ok(vk),
+        AllocationMode::Witness,
     )
     .unwrap();
 
-    let res = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::verify(&vk, &inputs, &proof)
-    .unwrap();
+    let res = S::VerifierGadget::verify(&vk, &inputs, &proof).unwrap();
 
     cs.finalize();
 
@@ -114,42 +242,15 @@ pub fn generate_verify_constraints(
         .map(|f| var_to_index(f, 0))
         .collect::<Vec<usize>>();
 
-    let proof_indices: Vec<usize> = vec![
-        var_to_index(&proof.a.x, num_instance_variables),
-        var_to_index(&proof.a.y, num_instance_variables),
-        var_to_index(&proof.b.x.c0, num_instance_variables),
-        var_to_index(&proof.b.x.c1, num_instance_variables),
-        var_to_index(&proof.b.y.c0, num_instance_variables),
-        var_to_index(&proof.b.y.c1, num_instance_variables),
-        var_to_index(&proof.c.x, num_instance_variables),
-        var_to_index(&proof.c.y, num_instance_variables),
-    ];
-
-    let mut vk_indices: Vec<usize> = vec![
-        var_to_index(&vk.h_g2.x.c0, num_instance_variables),
-        var_to_index(&vk.h_g2.x.c1, num_instance_variables),
-        var_to_index(&vk.h_g2.y.c0, num_instance_variables),
-        var_to_index(&vk.h_g2.y.c1, num_instance_variables),
-        var_to_index(&vk.g_alpha_g1.x, num_instance_variables),
-        var_to_index(&vk.g_alpha_g1.y, num_instance_variables),
-        var_to_index(&vk.h_beta_g2.x.c0, num_instance_variables),
-        var_to_index(&vk.h_beta_g2.x.c1, num_instance_variables),
-        var_to_index(&vk.h_beta_g2.y.c0, num_instance_variables),
-        var_to_index(&vk.h_beta_g2.y.c1, num_instance_variables),
-        var_to_index(&vk.g_gamma_g1.x, num_instance_variables),
-        var_to_index(&vk.g_gamma_g1.y, num_instance_variables),
-        var_to_index(&vk.h_gamma_g2.x.c0, num_instance_variables),
-        var_to_index(&vk.h_gamma_g2.x.c1, num_instance_variables),
-        var_to_index(&vk.h_gamma_g2.y.c0, num_instance_variables),
-        var_to_index(&vk.h_gamma_g2.y.c1, num_instance_variables),
-    ];
-
-    vk_indices.extend(vk.query.iter().flat_map(|q| {
-        vec![
-            var_to_index(&q.x, num_instance_variables),
-            var_to_index(&q.y, num_instance_variables),
-        ]
-    }));
+    let proof_indices: Vec<usize> = S::proof_variables(&proof)
+        .into_iter()
+        .map(|f| var_to_index(f, num_instance_variables))
+        .collect();
+
+    let vk_indices: Vec<usize> = S::verification_key_variables(&vk)
+        .into_iter()
+        .map(|f| var_to_index(f, num_instance_variables))
+        .collect();
 
     let out_index = match &res {
         Boolean::Is(x) => x
@@ -182,9 +283,18 @@ pub fn generate_verify_constraints(
     )
 }
 
-pub fn generate_verify_witness<T: Field>(inputs: &[T], proof: &[T], vk: &[T]) -> Vec<T> {
+/// The number of variables of the verifier of `S` for `public_input_size` inputs, including the constant `1`
+pub fn variable_count<S: RecursiveSnark>(public_input_size: usize) -> usize {
+    generate_verify_constraints::<S>(public_input_size).5
+}
+
+pub fn generate_verify_witness<S: RecursiveSnark, T: Field>(
+    inputs: &[T],
+    proof: &[T],
+    vk: &[T],
+) -> Vec<T> {
     assert_eq!(proof.len(), 8);
-    assert_eq!(vk.len(), 18 + (2 * inputs.len()));
+    assert_eq!(vk.len(), S::verification_key_size(inputs.len()));
 
     let cs_sys = ConstraintSystem::<BW6Fr>::new();
     let cs = ConstraintSystemRef::new(cs_sys);
@@ -201,59 +311,23 @@ pub fn generate_verify_witness<T: Field>(inputs: &[T], proof: &[T], vk: &[T]) ->
         .map(|i| i.to_bits_le().unwrap())
         .collect();
 
-    let inputs = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::InputVar::new(input_booleans);
-
-    let proof = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::new_proof_unchecked(
+    let inputs = BooleanInputVar::new(input_booleans);
+
+    let proof = S::VerifierGadget::new_proof_unchecked(
         ns!(cs, "alloc_proof"),
-        || {
-            Ok(Proof {
-                a: new_g1(&proof[0..2]),
-                b: new_g2(&proof[2..6]),
-                c: new_g1(&proof[6..8]),
-            })
-        },
+        || Ok(S::proof(proof)),
         AllocationMode::Witness,
     )
     .unwrap();
 
-    let vk = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::new_verification_key_unchecked(
+    let vk = S::VerifierGadget::new_verification_key_unchecked(
         ns!(cs, "alloc_vk"),
-        || {
-            Ok(VerifyingKey {
-                h_g2: new_g2(&vk[0..4]),
-                g_alpha_g1: new_g1(&vk[4..6]),
-                h_beta_g2: new_g2(&vk[6..10]),
-                g_gamma_g1: new_g1(&vk[10..12]),
-                h_gamma_g2: new_g2(&vk[12..16]),
-                query: (16..vk.len())
-                    .collect::<Vec<_>>()
-                    .chunks(2)
-                    .map(|c| new_g1(&vk[c[0]..c[1] + 1]))
-                    .collect(),
-            })
-        },
+        || Ok(S::verification_key(vk)),
         AllocationMode::Witness,
     )
     .unwrap();
 
-    let _ = <VerifierGadget as SNARKGadget<
-        <BLS12PairingEngine as PairingEngine>::Fr,
-        <BLS12PairingEngine as PairingEngine>::Fq,
-        GM17Snark,
-    >>::verify(&vk, &inputs, &proof)
-    .unwrap();
+    let _ = S::VerifierGadget::verify(&vk, &inputs, &proof).unwrap();
 
     cs.finalize();
 
@@ -268,6 +342,16 @@ pub fn generate_verify_witness<T: Field>(inputs: &[T], proof: &[T], vk: &[T]) ->
         .collect()
 }
 
+#[inline]
+fn g1_variables(g: &G1Var) -> Vec<&FpVar<BLS12Fq>> {
+    vec![&g.x, &g.y]
+}
+
+#[inline]
+fn g2_variables(g: &G2Var) -> Vec<&FpVar<BLS12Fq>> {
+    vec![&g.x.c0, &g.x.c1, &g.y.c0, &g.y.c1]
+}
+
 #[inline]
 fn var_to_index<F: ark_ff::PrimeField>(var: &FpVar<F>, offset: usize) -> usize {
     match var {
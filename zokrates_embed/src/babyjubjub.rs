@@ -0,0 +1,488 @@
+//! Point addition and scalar multiplication on the BabyJubJub twisted Edwards curve
+//! `a * u^2 + v^2 = 1 + d * u^2 * v^2`, which is defined over the scalar field of BN254
+//!
+//! The addition formulas are complete, as `a` is a square and `d` is not, so that sums involving
+//! the point at infinity `(0, 1)` or equal points need no special case. The points given as
+//! inputs are not checked to be on the curve, and computing the witness fails if adding them
+//! requires a division by zero.
+//!
+//! The scalars are given as 256 bits in big endian order, which are not checked to be bits.
+//! Multiplying a variable point uses windows of 2 bits over a table of the first 4 multiples of
+//! the point. Multiplying the generator uses windows of 3 bits over constant tables, from which
+//! selecting a point only takes the products of the bits of the window.
+
+use crate::native::{ConstraintSystem, LinearCombination};
+use crate::R1CS;
+use zokrates_field::Field;
+
+/// The number of bits of a scalar
+pub const SCALAR_SIZE: usize = 256;
+
+const A: u32 = 168700;
+const D: u32 = 168696;
+const GENERATOR: (&str, &str) = (
+    "16540640123574156134436876038791482806971768689494387082833631921987005038935",
+    "20819045374670962167435360035096875258406992893633759881276124905556507972311",
+);
+
+/// The number of bits of a window of the multiplication of a variable point
+const WINDOW_SIZE: usize = 2;
+/// The number of bits of a window of the multiplication of the generator
+const FIXED_BASE_WINDOW_SIZE: usize = 3;
+
+/// The number of variables of an addition, including the constant `1`
+pub fn add_variable_count() -> usize {
+    1 + 4 + 6
+}
+
+/// The number of variables of the multiplication of a variable point, including the constant `1`
+pub fn scalar_mult_variable_count() -> usize {
+    let window_count = SCALAR_SIZE / WINDOW_SIZE;
+    // the table takes 2 additions, and selecting from it takes a product of the bits and 3
+    // products for each coordinate
+    let table = 2 * 6;
+    let select = 1 + 2 * 3;
+    // each window except the first one doubles the accumulator twice and adds the selected point
+    let windows = select + (window_count - 1) * (WINDOW_SIZE * 6 + select + 6);
+    1 + SCALAR_SIZE + 2 + table + windows
+}
+
+/// The number of variables of the multiplication of the generator, including the constant `1`
+pub fn fixed_base_scalar_mult_variable_count() -> usize {
+    // selecting from the table of a window of `n` bits takes the `2^n - n - 1` products of at
+    // least two of its bits, and each window except the first one adds the selected point
+    let windows: usize = (0..SCALAR_SIZE)
+        .step_by(FIXED_BASE_WINDOW_SIZE)
+        .map(|start| {
+            let n = std::cmp::min(FIXED_BASE_WINDOW_SIZE, SCALAR_SIZE - start);
+            (1 << n) - n - 1
+        })
+        .sum();
+    let additions = (SCALAR_SIZE + FIXED_BASE_WINDOW_SIZE - 1) / FIXED_BASE_WINDOW_SIZE - 1;
+    1 + SCALAR_SIZE + windows + additions * 6
+}
+
+/// A point in affine coordinates
+#[derive(Clone)]
+struct Point<T> {
+    u: LinearCombination<T>,
+    v: LinearCombination<T>,
+}
+
+impl<T: Field> Point<T> {
+    fn infinity() -> Self {
+        Point {
+            u: LinearCombination::zero(),
+            v: LinearCombination::one(),
+        }
+    }
+
+    fn generator() -> Self {
+        Point {
+            u: LinearCombination::constant(T::try_from_dec_str(GENERATOR.0).unwrap()),
+            v: LinearCombination::constant(T::try_from_dec_str(GENERATOR.1).unwrap()),
+        }
+    }
+
+    fn alloc(cs: &mut ConstraintSystem<T>, values: Option<&[T]>) -> Self {
+        let mut coordinate =
+            |i: usize| LinearCombination::variable(cs.alloc(values.map(|v| v[i].clone())));
+        Point {
+            u: coordinate(0),
+            v: coordinate(1),
+        }
+    }
+
+    fn into_vec(self) -> Vec<Vec<(usize, T)>> {
+        vec![self.u.into_vec(), self.v.into_vec()]
+    }
+}
+
+/// The inverse of `b`, which only fails for points which are not on the curve
+fn inverse<T: Field>(b: &T) -> Result<T, String> {
+    b.inverse_mul()
+        .ok_or_else(|| "Cannot add points which are not on the curve".to_string())
+}
+
+/// Allocate a variable equal to `a / b`, which does not require a constraint if `b` is constant
+fn div<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    a: LinearCombination<T>,
+    b: LinearCombination<T>,
+) -> Result<LinearCombination<T>, String> {
+    match b.as_constant() {
+        Some(b) => Ok(a.scale(&inverse(&b)?)),
+        None => {
+            let value = match cs.eval(&a).zip(cs.eval(&b)) {
+                Some((a, b)) => Some(a * inverse(&b)?),
+                None => None,
+            };
+            let c = LinearCombination::variable(cs.alloc(value));
+            cs.enforce(b, c.clone(), a);
+            Ok(c)
+        }
+    }
+}
+
+/// The sum of two points. Adding constant points does not require any constraint.
+fn add<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    p: &Point<T>,
+    q: &Point<T>,
+) -> Result<Point<T>, String> {
+    // u = (u1 * v2 + v1 * u2) / (1 + d * u1 * u2 * v1 * v2)
+    // v = (v1 * v2 - a * u1 * u2) / (1 - d * u1 * u2 * v1 * v2)
+    // where v1 * v2 - a * u1 * u2 == (v1 - a * u1) * (u2 + v2) - v1 * u2 + a * u1 * v2
+    let a = T::from(A);
+    let beta = cs.mul(&p.u, &q.v);
+    let gamma = cs.mul(&p.v, &q.u);
+    let delta = cs.mul(&(p.v.clone() - p.u.scale(&a)), &(q.u.clone() + q.v.clone()));
+    let d_tau = cs.mul(&beta, &gamma).scale(&T::from(D));
+
+    Ok(Point {
+        u: div(
+            cs,
+            beta.clone() + gamma.clone(),
+            LinearCombination::one() + d_tau.clone(),
+        )?,
+        v: div(
+            cs,
+            delta + beta.scale(&a) - gamma,
+            LinearCombination::one() - d_tau,
+        )?,
+    })
+}
+
+/// Select `table[2 * hi + lo]` from a table of 4 points
+fn select<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    table: &[Point<T>],
+    hi: &LinearCombination<T>,
+    lo: &LinearCombination<T>,
+) -> Point<T> {
+    let hi_lo = cs.mul(hi, lo);
+    let mut coordinate = |t: [&LinearCombination<T>; 4]| {
+        // t0 + lo * (t1 - t0) + hi * (t2 - t0) + hi * lo * (t3 - t2 - t1 + t0)
+        let lo_term = cs.mul(lo, &(t[1].clone() - t[0].clone()));
+        let hi_term = cs.mul(hi, &(t[2].clone() - t[0].clone()));
+        let hi_lo_term = cs.mul(
+            &hi_lo,
+            &(t[3].clone() - t[2].clone() - t[1].clone() + t[0].clone()),
+        );
+        t[0].clone() + lo_term + hi_term + hi_lo_term
+    };
+    Point {
+        u: coordinate([&table[0].u, &table[1].u, &table[2].u, &table[3].u]),
+        v: coordinate([&table[0].v, &table[1].v, &table[2].v, &table[3].v]),
+    }
+}
+
+/// Select `table[sum(2^i * bits[i])]` from a table of constant points, where `bits` are in little
+/// endian order. The coordinates are linear combinations of the products of the bits, whose
+/// coefficients are computed from the table.
+fn select_constant<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    table: &[Point<T>],
+    bits: &[LinearCombination<T>],
+) -> Point<T> {
+    assert_eq!(table.len(), 1 << bits.len());
+
+    // the product of the bits in each subset of the bits, with the empty product being `1`
+    let mut products = vec![LinearCombination::one()];
+    for mask in 1..table.len() {
+        let top = (usize::BITS - 1 - mask.leading_zeros()) as usize;
+        let product = cs.mul(&products[mask ^ (1 << top)], &bits[top]);
+        products.push(product);
+    }
+
+    let coordinate = |values: Vec<T>| {
+        // invert `table[mask] == sum(coefficients[subset])` over the subsets of each `mask`
+        let mut coefficients = values;
+        for i in 0..bits.len() {
+            for mask in 0..coefficients.len() {
+                if mask & (1 << i) != 0 {
+                    coefficients[mask] =
+                        coefficients[mask].clone() - coefficients[mask ^ (1 << i)].clone();
+                }
+            }
+        }
+        coefficients
+            .iter()
+            .zip(&products)
+            .fold(LinearCombination::zero(), |acc, (c, product)| {
+                acc + product.scale(c)
+            })
+    };
+    let constant = |lc: &LinearCombination<T>| {
+        lc.as_constant()
+            .expect("the table should only contain constant points")
+    };
+
+    Point {
+        u: coordinate(table.iter().map(|p| constant(&p.u)).collect()),
+        v: coordinate(table.iter().map(|p| constant(&p.v)).collect()),
+    }
+}
+
+/// Allocate the bits of a scalar, in big endian order
+fn alloc_scalar<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+) -> Vec<LinearCombination<T>> {
+    (0..SCALAR_SIZE)
+        .map(|i| LinearCombination::variable(cs.alloc(values.map(|v| v[i].clone()))))
+        .collect()
+}
+
+fn scalar_mult<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+) -> Result<Point<T>, String> {
+    let bits = alloc_scalar(cs, values);
+    let point = Point::alloc(cs, values.map(|v| &v[SCALAR_SIZE..]));
+
+    let double = add(cs, &point, &point)?;
+    let triple = add(cs, &double, &point)?;
+    let table = vec![Point::infinity(), point, double, triple];
+
+    // the most significant window initialises the accumulator
+    let mut windows = bits.chunks(WINDOW_SIZE);
+    let first = windows.next().unwrap();
+    let mut acc = select(cs, &table, &first[0], &first[1]);
+
+    for window in windows {
+        for _ in 0..WINDOW_SIZE {
+            acc = add(cs, &acc, &acc)?;
+        }
+        let selected = select(cs, &table, &window[0], &window[1]);
+        acc = add(cs, &acc, &selected)?;
+    }
+
+    Ok(acc)
+}
+
+fn fixed_base_scalar_mult<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+) -> Result<Point<T>, String> {
+    let bits = alloc_scalar(cs, values);
+
+    // the windows start from the least significant bit, and the multiples of the generator in
+    // the tables are constant so adding them does not require any constraint
+    let mut base = Point::generator();
+    let mut acc: Option<Point<T>> = None;
+
+    for start in (0..SCALAR_SIZE).step_by(FIXED_BASE_WINDOW_SIZE) {
+        let end = std::cmp::min(start + FIXED_BASE_WINDOW_SIZE, SCALAR_SIZE);
+        let window: Vec<_> = (start..end)
+            .map(|i| bits[SCALAR_SIZE - 1 - i].clone())
+            .collect();
+
+        let mut table = vec![Point::infinity()];
+        for i in 1..1 << window.len() {
+            let next = add(cs, &table[i - 1], &base)?;
+            table.push(next);
+        }
+        base = add(cs, &table[table.len() - 1], &base)?;
+
+        let selected = select_constant(cs, &table, &window);
+        acc = Some(match acc {
+            Some(acc) => add(cs, &acc, &selected)?,
+            None => selected,
+        });
+    }
+
+    Ok(acc.unwrap())
+}
+
+fn add_points<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+) -> Result<Point<T>, String> {
+    let p = Point::alloc(cs, values);
+    let q = Point::alloc(cs, values.map(|v| &v[2..]));
+    add(cs, &p, &q)
+}
+
+/// Generate the constraints of an addition. The inputs are the coordinates of the two points
+/// in the variables `1..=4`, and the outputs are the coordinates of their sum.
+pub fn generate_edwards_add_constraints<T: Field>() -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let output = add_points(&mut cs, None)
+        .expect("the constant points should be on the curve")
+        .into_vec();
+    (cs.into_r1cs(), output)
+}
+
+/// Compute the values of all variables of the addition of the points `inputs`, starting with
+/// the constant `1`
+pub fn generate_edwards_add_witness<T: Field>(inputs: &[T]) -> Result<Vec<T>, String> {
+    assert_eq!(inputs.len(), 4);
+    let mut cs = ConstraintSystem::witness();
+    add_points(&mut cs, Some(inputs))?;
+    Ok(cs.into_witness())
+}
+
+/// Generate the constraints of the multiplication of a point by a scalar. The inputs are the
+/// bits of the scalar followed by the coordinates of the point in the variables
+/// `1..=SCALAR_SIZE + 2`, and the outputs are the coordinates of the product.
+pub fn generate_edwards_scalar_mult_constraints<T: Field>() -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let output = scalar_mult(&mut cs, None)
+        .expect("the constant points should be on the curve")
+        .into_vec();
+    (cs.into_r1cs(), output)
+}
+
+/// Compute the values of all variables of the multiplication of a point by a scalar, starting
+/// with the constant `1`
+pub fn generate_edwards_scalar_mult_witness<T: Field>(inputs: &[T]) -> Result<Vec<T>, String> {
+    assert_eq!(inputs.len(), SCALAR_SIZE + 2);
+    let mut cs = ConstraintSystem::witness();
+    scalar_mult(&mut cs, Some(inputs))?;
+    Ok(cs.into_witness())
+}
+
+/// Generate the constraints of the multiplication of the generator by a scalar. The inputs are
+/// the bits of the scalar in the variables `1..=SCALAR_SIZE`, and the outputs are the
+/// coordinates of the product.
+pub fn generate_edwards_fixed_base_scalar_mult_constraints<T: Field>(
+) -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let output = fixed_base_scalar_mult(&mut cs, None)
+        .expect("the constant points should be on the curve")
+        .into_vec();
+    (cs.into_r1cs(), output)
+}
+
+/// Compute the values of all variables of the multiplication of the generator by a scalar,
+/// starting with the constant `1`
+pub fn generate_edwards_fixed_base_scalar_mult_witness<T: Field>(
+    inputs: &[T],
+) -> Result<Vec<T>, String> {
+    assert_eq!(inputs.len(), SCALAR_SIZE);
+    let mut cs = ConstraintSystem::witness();
+    fixed_base_scalar_mult(&mut cs, Some(inputs))?;
+    Ok(cs.into_witness())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::tests::is_satisfied;
+    use zokrates_field::Bn128Field;
+
+    fn field(value: &str) -> Bn128Field {
+        Bn128Field::try_from_dec_str(value).unwrap()
+    }
+
+    fn eval(lc: &[(usize, Bn128Field)], witness: &[Bn128Field]) -> Bn128Field {
+        lc.iter().fold(Bn128Field::from(0), |acc, (i, c)| {
+            acc + witness[*i].clone() * c
+        })
+    }
+
+    /// The bits of `value` in big endian order
+    fn scalar(value: &str) -> Vec<Bn128Field> {
+        let bits = field(value).to_bits_be();
+        (bits.len()..SCALAR_SIZE)
+            .map(|_| false)
+            .chain(bits)
+            .map(Bn128Field::from)
+            .collect()
+    }
+
+    fn generator() -> Vec<Bn128Field> {
+        vec![field(GENERATOR.0), field(GENERATOR.1)]
+    }
+
+    // 2 * G
+    const DOUBLE: (&str, &str) = (
+        "17324563846726889236817837922625232543153115346355010501047597319863650987830",
+        "20022170825455209233733649024450576091402881793145646502279487074566492066831",
+    );
+
+    // a public key and its secret key
+    const PUBLIC_KEY: (&str, &str) = (
+        "14897476871502190904409029696666322856887678969656209656241038339251270171395",
+        "16668832459046858928951622951481252834155254151733002984053501254009901876174",
+    );
+    const SECRET_KEY: &str =
+        "1997011358982923168928344992199991480689546837621580239342656433234255379025";
+
+    fn check(
+        (r1cs, outputs): (R1CS<Bn128Field>, Vec<Vec<(usize, Bn128Field)>>),
+        variable_count: usize,
+        witness: Vec<Bn128Field>,
+        expected: (&str, &str),
+    ) {
+        assert_eq!(r1cs.aux_count + 1, variable_count);
+        assert_eq!(witness.len(), variable_count);
+        assert!(is_satisfied(&r1cs, &witness));
+        assert_eq!(eval(&outputs[0], &witness), field(expected.0));
+        assert_eq!(eval(&outputs[1], &witness), field(expected.1));
+    }
+
+    #[test]
+    fn add() {
+        let (r1cs, outputs) = generate_edwards_add_constraints();
+
+        // doubling, and adding the point at infinity
+        let inputs = vec![generator(), generator()].concat();
+        let witness = generate_edwards_add_witness(&inputs).unwrap();
+        check(
+            (r1cs.clone(), outputs.clone()),
+            add_variable_count(),
+            witness,
+            DOUBLE,
+        );
+
+        let inputs = vec![generator(), vec![field("0"), field("1")]].concat();
+        let witness = generate_edwards_add_witness(&inputs).unwrap();
+        check((r1cs, outputs), add_variable_count(), witness, GENERATOR);
+    }
+
+    #[test]
+    fn add_off_curve() {
+        // `d * u1 * u2 * v1 * v2 == -1` makes the denominator of `u` zero
+        let v = Bn128Field::from(0) - Bn128Field::from(D).inverse_mul().unwrap();
+        let inputs = vec![field("1"), field("1"), field("1"), v];
+        assert!(generate_edwards_add_witness(&inputs).is_err());
+    }
+
+    #[test]
+    fn scalar_mult() {
+        let inputs = vec![scalar("2"), generator()].concat();
+        let witness = generate_edwards_scalar_mult_witness(&inputs).unwrap();
+        check(
+            generate_edwards_scalar_mult_constraints(),
+            scalar_mult_variable_count(),
+            witness,
+            DOUBLE,
+        );
+
+        let inputs = vec![scalar(SECRET_KEY), generator()].concat();
+        let witness = generate_edwards_scalar_mult_witness(&inputs).unwrap();
+        check(
+            generate_edwards_scalar_mult_constraints(),
+            scalar_mult_variable_count(),
+            witness,
+            PUBLIC_KEY,
+        );
+    }
+
+    #[test]
+    fn fixed_base_scalar_mult() {
+        let (r1cs, outputs) = generate_edwards_fixed_base_scalar_mult_constraints();
+
+        for (value, expected) in vec![("2", DOUBLE), (SECRET_KEY, PUBLIC_KEY), ("1", GENERATOR)] {
+            let witness = generate_edwards_fixed_base_scalar_mult_witness(&scalar(value)).unwrap();
+            check(
+                (r1cs.clone(), outputs.clone()),
+                fixed_base_scalar_mult_variable_count(),
+                witness,
+                expected,
+            );
+        }
+    }
+}
@@ -1,18 +1,25 @@
+//! The SHA-256 compression function gadget of `sapling-crypto`, used as a reference for the
+//! native implementation in `sha256`
+
 extern crate sapling_crypto_ce as sapling_crypto;
 use sapling_crypto::bellman;
 
-use crate::{Constraint, Witness, R1CS};
+use crate::{Constraint, R1CS};
 use bellman::{
     pairing::{ff::Field, Engine},
     ConstraintSystem, Index, LinearCombination, SynthesisError, Variable,
 };
-use sapling_crypto::bellman::pairing::ff::{PrimeField, PrimeFieldRepr};
 use sapling_crypto::circuit::{
     boolean::{AllocatedBit, Boolean},
     sha256::sha256_compression_function,
     uint32::UInt32,
 };
 
+#[derive(Debug)]
+pub struct Witness<T> {
+    pub values: Vec<T>,
+}
+
 fn sha256_round<E: Engine, CS: ConstraintSystem<E>>(
     mut cs: CS,
     input: &[Option<E::Fr>],
@@ -253,35 +260,6 @@ fn var_to_index(v: Variable) -> usize {
     }
 }
 
-pub fn from_bellman<T: zokrates_field::Field, E: Engine>(c: Constraint<E::Fr>) -> Constraint<T> {
-    Constraint {
-        a: c.a
-            .into_iter()
-            .map(|(index, fq)| {
-                let mut res: Vec<u8> = vec![];
-                fq.into_repr().write_le(&mut res).unwrap();
-                (index, T::from_byte_vector(res))
-            })
-            .collect(),
-        b: c.b
-            .into_iter()
-            .map(|(index, fq)| {
-                let mut res: Vec<u8> = vec![];
-                fq.into_repr().write_le(&mut res).unwrap();
-                (index, T::from_byte_vector(res))
-            })
-            .collect(),
-        c: c.c
-            .into_iter()
-            .map(|(index, fq)| {
-                let mut res: Vec<u8> = vec![];
-                fq.into_repr().write_le(&mut res).unwrap();
-                (index, T::from_byte_vector(res))
-            })
-            .collect(),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
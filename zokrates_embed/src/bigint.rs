@@ -0,0 +1,708 @@
+//! Arithmetic modulo a prime `p` which is not the modulus of the native field
+//!
+//! Integers are represented by limbs of `LIMB_SIZE` bits in little endian order. A product
+//! `a * b == c mod p` is checked by allocating the quotient `q` such that
+//! `a * b + k * p == q * p + c` for some constant `k`, and checking this equality of integers
+//! limb by limb while propagating the carries.
+
+use crate::native::{ConstraintSystem, LinearCombination};
+use crate::R1CS;
+use num_bigint::{BigInt, BigUint, Sign};
+use zokrates_field::Field;
+
+pub const LIMB_SIZE: usize = 64;
+
+/// The number of bits of a carry, which may be negative
+const CARRY_SIZE: usize = 72;
+
+fn limb(value: &BigUint, i: usize) -> BigUint {
+    (value >> (i * LIMB_SIZE)) % (BigUint::from(1u32) << LIMB_SIZE)
+}
+
+/// The value of words of `LIMB_SIZE` bits in big endian order
+pub fn from_words(words: &[u64]) -> BigUint {
+    words
+        .iter()
+        .fold(BigUint::from(0u32), |acc, w| (acc << LIMB_SIZE) + *w)
+}
+
+/// The value of a field element as a signed integer in `[-(r - 1) / 2, (r - 1) / 2]`
+fn to_signed<T: Field>(value: T) -> BigInt {
+    let value = value.to_biguint();
+    let max = T::max_value().to_biguint();
+    match value > &max / 2u32 {
+        true => BigInt::from_biguint(Sign::Minus, max - value + 1u32),
+        false => BigInt::from_biguint(Sign::Plus, value),
+    }
+}
+
+/// The number of variables allocated by `Integer::alloc`
+pub fn alloc_variable_count(limb_count: usize) -> usize {
+    limb_count * (LIMB_SIZE + 1)
+}
+
+/// The number of variables allocated by `Integer::is_less_than`
+pub fn is_less_than_variable_count(limb_count: usize) -> usize {
+    limb_count * (LIMB_SIZE + 1)
+}
+
+/// An integer whose value is known to be in `[0, max]`
+#[derive(Debug, Clone)]
+pub struct Integer<T> {
+    limbs: Vec<LinearCombination<T>>,
+    /// A bound on the absolute value of each limb
+    limb_bound: BigUint,
+    max: BigUint,
+}
+
+impl<T: Field> Integer<T> {
+    /// A constant integer of `limb_count` limbs. The most significant limb holds all bits above
+    /// the other limbs, so the value can be larger than `2^(limb_count * LIMB_SIZE)`.
+    pub fn constant(value: &BigUint, limb_count: usize) -> Self {
+        let limbs: Vec<_> = (0..limb_count)
+            .map(|i| match i == limb_count - 1 {
+                true => value >> (i * LIMB_SIZE),
+                false => limb(value, i),
+            })
+            .collect();
+
+        Integer {
+            limb_bound: limbs.iter().max().unwrap().clone(),
+            limbs: limbs
+                .into_iter()
+                .map(|l| LinearCombination::constant(T::try_from(l).unwrap()))
+                .collect(),
+            max: value.clone(),
+        }
+    }
+
+    /// An integer made of limbs which are already known to be of `LIMB_SIZE` bits, in little
+    /// endian order
+    pub fn from_limbs(limbs: Vec<LinearCombination<T>>) -> Self {
+        Integer {
+            limb_bound: (BigUint::from(1u32) << LIMB_SIZE) - 1u32,
+            max: (BigUint::from(1u32) << (limbs.len() * LIMB_SIZE)) - 1u32,
+            limbs,
+        }
+    }
+
+    /// Allocate an integer of `limb_count` limbs, returning it along with its bits in little
+    /// endian order
+    pub fn alloc(
+        cs: &mut ConstraintSystem<T>,
+        value: Option<BigUint>,
+        limb_count: usize,
+    ) -> (Self, Vec<LinearCombination<T>>) {
+        let (limbs, bits): (Vec<_>, Vec<_>) = (0..limb_count)
+            .map(|i| {
+                let l = value.as_ref().map(|v| T::try_from(limb(v, i)).unwrap());
+                let l = LinearCombination::variable(cs.alloc(l));
+                let bits = cs.decompose(l.clone(), LIMB_SIZE);
+                (l, bits)
+            })
+            .unzip();
+
+        (
+            Self::from_limbs(limbs),
+            bits.into_iter().flatten().collect(),
+        )
+    }
+
+    pub fn limbs(&self) -> &[LinearCombination<T>] {
+        &self.limbs
+    }
+
+    /// The value of this integer, if the values are being computed
+    pub fn value(&self, cs: &ConstraintSystem<T>) -> Option<BigUint> {
+        self.limbs
+            .iter()
+            .enumerate()
+            .map(|(i, l)| cs.eval(l).map(|l| to_signed(l) << (i * LIMB_SIZE)))
+            .sum::<Option<BigInt>>()
+            .map(|v| v.to_biguint().expect("integers should not be negative"))
+    }
+
+    /// This integer if `bit` is `1`, and zero otherwise
+    pub fn mul_bit(&self, cs: &mut ConstraintSystem<T>, bit: &LinearCombination<T>) -> Self {
+        Integer {
+            limbs: self.limbs.iter().map(|l| cs.mul(bit, l)).collect(),
+            limb_bound: self.limb_bound.clone(),
+            max: self.max.clone(),
+        }
+    }
+
+    /// A bit which is `1` if this integer is strictly less than `bound`, computed as the
+    /// complement of the carry of `self + 2^(limb_count * LIMB_SIZE) - bound`. The limbs of this
+    /// integer must be of `LIMB_SIZE` bits.
+    pub fn is_less_than(
+        &self,
+        cs: &mut ConstraintSystem<T>,
+        bound: &BigUint,
+    ) -> LinearCombination<T> {
+        assert!(self.limb_bound.bits() <= LIMB_SIZE);
+        let complement = (BigUint::from(1u32) << (self.limbs.len() * LIMB_SIZE)) - bound;
+        let shift = T::from(2).pow(LIMB_SIZE);
+
+        self.limbs
+            .iter()
+            .enumerate()
+            .fold(LinearCombination::zero(), |carry, (i, l)| {
+                let sum = l.clone()
+                    + carry
+                    + LinearCombination::constant(T::try_from(limb(&complement, i)).unwrap());
+                let carry = cs.alloc_bit(
+                    cs.eval(&sum)
+                        .map(|s| s.to_biguint() >> LIMB_SIZE == BigUint::from(1u32)),
+                );
+                cs.decompose(sum - carry.scale(&shift), LIMB_SIZE);
+                carry
+            })
+            .scale(&T::from(-1))
+            + LinearCombination::one()
+    }
+
+    pub fn add(&self, other: &Self) -> Self {
+        assert_eq!(self.limbs.len(), other.limbs.len());
+        Integer {
+            limbs: self
+                .limbs
+                .iter()
+                .zip(&other.limbs)
+                .map(|(a, b)| a.clone() + b.clone())
+                .collect(),
+            limb_bound: &self.limb_bound + &other.limb_bound,
+            max: &self.max + &other.max,
+        }
+    }
+}
+
+/// A prime modulus of at most `limb_count * LIMB_SIZE` bits. The operands of products must be
+/// less than `2^(limb_count * LIMB_SIZE + 4)`.
+#[derive(Debug, Clone)]
+pub struct Modulus {
+    value: BigUint,
+    limb_count: usize,
+}
+
+impl Modulus {
+    pub fn new(value: BigUint, limb_count: usize) -> Self {
+        assert!(value.bits() <= limb_count * LIMB_SIZE);
+        Modulus { value, limb_count }
+    }
+
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    pub fn limb_count(&self) -> usize {
+        self.limb_count
+    }
+
+    /// The number of bits of the quotient of a product by `p`
+    fn quotient_size(&self) -> usize {
+        2 * (self.limb_count * LIMB_SIZE + 4) - (self.value.bits() - 1)
+    }
+
+    fn quotient_limb_count(&self) -> usize {
+        (self.quotient_size() + LIMB_SIZE - 1) / LIMB_SIZE
+    }
+
+    /// The number of variables allocated by `enforce_mul` when `product_count` products of limbs
+    /// of `a` and `b` are not products by constants
+    fn enforce_mul_cost(
+        &self,
+        a_limb_count: usize,
+        b_limb_count: usize,
+        product_count: usize,
+    ) -> usize {
+        let column_count = std::cmp::max(
+            a_limb_count + b_limb_count - 1,
+            self.quotient_limb_count() + self.limb_count - 1,
+        );
+        product_count
+            + self.quotient_size()
+            + self.quotient_limb_count()
+            + (column_count - 1) * (CARRY_SIZE + 1)
+    }
+
+    /// The number of variables allocated by `enforce_mul` when the operands are variables of
+    /// `limb_count` limbs
+    pub fn enforce_mul_variable_count(&self) -> usize {
+        self.enforce_mul_cost(
+            self.limb_count,
+            self.limb_count,
+            self.limb_count * self.limb_count,
+        )
+    }
+
+    /// The number of variables allocated by `mul` when the operands are variables of
+    /// `limb_count` limbs
+    pub fn mul_variable_count(&self) -> usize {
+        alloc_variable_count(self.limb_count) + self.enforce_mul_variable_count()
+    }
+
+    /// The number of variables allocated by `normalize` for an integer of `limb_count` limbs
+    pub fn normalize_variable_count(&self, limb_count: usize) -> usize {
+        alloc_variable_count(self.limb_count)
+            + self.enforce_mul_cost(limb_count, self.limb_count, 0)
+            + is_less_than_variable_count(self.limb_count)
+    }
+
+    /// Reduce `value` modulo `p`
+    pub fn reduce(&self, value: &BigUint) -> BigUint {
+        value % &self.value
+    }
+
+    /// The inverse of `value` modulo `p`, or `0` if `value` is a multiple of `p`
+    pub fn invert(&self, value: &BigUint) -> BigUint {
+        value.modpow(&(&self.value - 2u32), &self.value)
+    }
+
+    /// The opposite of `value` modulo `p`
+    pub fn negate(&self, value: &BigUint) -> BigUint {
+        self.reduce(&(&self.value - self.reduce(value)))
+    }
+
+    /// The smallest multiple of `p` which is at least `value`
+    fn multiple_above(&self, value: &BigUint) -> BigUint {
+        (value + &self.value - 1u32) / &self.value * &self.value
+    }
+
+    /// Allocate the reduction of `value` modulo `p`
+    pub fn alloc<T: Field>(
+        &self,
+        cs: &mut ConstraintSystem<T>,
+        value: Option<BigUint>,
+    ) -> (Integer<T>, Vec<LinearCombination<T>>) {
+        Integer::alloc(cs, value.map(|v| self.reduce(&v)), self.limb_count)
+    }
+
+    /// Compute `a - b` as `a + k * p - b` for a constant `k` such that the result is not negative
+    pub fn sub<T: Field>(&self, a: &Integer<T>, b: &Integer<T>) -> Integer<T> {
+        let kp = Integer::<T>::constant(&self.multiple_above(&b.max), b.limbs.len());
+        let difference = a.add(&kp);
+
+        Integer {
+            limbs: difference
+                .limbs
+                .into_iter()
+                .zip(&b.limbs)
+                .map(|(d, b)| d - b.clone())
+                .collect(),
+            limb_bound: difference.limb_bound + &b.limb_bound,
+            max: difference.max,
+        }
+    }
+
+    /// Enforce `a * b == c mod p`
+    pub fn enforce_mul<T: Field>(
+        &self,
+        cs: &mut ConstraintSystem<T>,
+        a: &Integer<T>,
+        b: &Integer<T>,
+        c: &Integer<T>,
+    ) {
+        assert!(CARRY_SIZE + LIMB_SIZE < T::get_required_bits());
+
+        // `k * p >= c` so that the quotient of `a * b + k * p - c` by `p` is not negative
+        let kp = Integer::<T>::constant(&self.multiple_above(&c.max), c.limbs.len());
+        let q_max = (&a.max * &b.max + &kp.max) / &self.value;
+        assert!(q_max.bits() <= self.quotient_size());
+
+        let q_value = a
+            .value(cs)
+            .zip(b.value(cs))
+            .zip(c.value(cs))
+            .map(|((a, b), c)| (a * b + &kp.max - c) / &self.value);
+        let q: Vec<_> = (0..self.quotient_limb_count())
+            .map(|i| {
+                let size = std::cmp::min(LIMB_SIZE, self.quotient_size() - i * LIMB_SIZE);
+                let l = q_value.as_ref().map(|q| T::try_from(limb(q, i)).unwrap());
+                let l = LinearCombination::variable(cs.alloc(l));
+                cs.decompose(l.clone(), size);
+                l
+            })
+            .collect();
+
+        // the linear combination of each limb of `a * b + k * p - q * p - c`, along with a bound
+        // on its absolute value
+        let column_count = std::cmp::max(
+            a.limbs.len() + b.limbs.len() - 1,
+            q.len() + self.limb_count - 1,
+        );
+        assert!(c.limbs.len() <= column_count);
+        let mut columns = vec![(LinearCombination::zero(), BigUint::from(0u32)); column_count];
+
+        for (i, a_i) in a.limbs.iter().enumerate() {
+            for (j, b_j) in b.limbs.iter().enumerate() {
+                columns[i + j].0 = columns[i + j].0.clone() + cs.mul(a_i, b_j);
+                columns[i + j].1 += &a.limb_bound * &b.limb_bound;
+            }
+        }
+
+        for (i, (kp_i, c_i)) in kp.limbs.iter().zip(&c.limbs).enumerate() {
+            columns[i].0 = columns[i].0.clone() + kp_i.clone() - c_i.clone();
+            columns[i].1 += &kp.limb_bound + &c.limb_bound;
+        }
+
+        for (i, q_i) in q.iter().enumerate() {
+            for j in 0..self.limb_count {
+                let p_j = limb(&self.value, j);
+                columns[i + j].0 =
+                    columns[i + j].0.clone() - q_i.scale(&T::try_from(p_j.clone()).unwrap());
+                columns[i + j].1 += ((BigUint::from(1u32) << LIMB_SIZE) - 1u32) * p_j;
+            }
+        }
+
+        // each column added to the carry of the previous one must be a multiple of
+        // `2^LIMB_SIZE`, and the last one must be zero
+        let shift = T::from(2).pow(LIMB_SIZE);
+        let carry_offset = BigUint::from(1u32) << (CARRY_SIZE - 1);
+        let mut carry = LinearCombination::zero();
+        let mut carry_bound = BigUint::from(0u32);
+
+        for (i, (column, bound)) in columns.into_iter().enumerate() {
+            let sum = column + carry;
+            let bound = bound + carry_bound;
+            assert!(bound < &carry_offset << LIMB_SIZE);
+
+            if i == column_count - 1 {
+                cs.enforce(sum, LinearCombination::one(), LinearCombination::zero());
+                break;
+            }
+
+            let value = cs.eval(&sum).map(|s| s * shift.inverse_mul().unwrap());
+            carry = LinearCombination::variable(cs.alloc(value));
+            carry_bound = bound >> LIMB_SIZE;
+            cs.enforce(
+                carry.clone(),
+                LinearCombination::constant(shift.clone()),
+                sum,
+            );
+            // the carry is in `[-2^(CARRY_SIZE - 1), 2^(CARRY_SIZE - 1))`
+            cs.decompose(
+                carry.clone()
+                    + LinearCombination::constant(T::try_from(carry_offset.clone()).unwrap()),
+                CARRY_SIZE,
+            );
+        }
+    }
+
+    /// Allocate `a * b mod p`
+    pub fn mul<T: Field>(
+        &self,
+        cs: &mut ConstraintSystem<T>,
+        a: &Integer<T>,
+        b: &Integer<T>,
+    ) -> Integer<T> {
+        let value = a.value(cs).zip(b.value(cs)).map(|(a, b)| a * b);
+        let (c, _) = self.alloc(cs, value);
+        self.enforce_mul(cs, a, b, &c);
+        c
+    }
+
+    /// Allocate `a / b mod p`. If `b` is a multiple of `p`, the constraints are only satisfied
+    /// if `a` is too.
+    pub fn div<T: Field>(
+        &self,
+        cs: &mut ConstraintSystem<T>,
+        a: &Integer<T>,
+        b: &Integer<T>,
+    ) -> Integer<T> {
+        let value = a
+            .value(cs)
+            .zip(b.value(cs))
+            .map(|(a, b)| a * self.invert(&b));
+        let (c, _) = self.alloc(cs, value);
+        self.enforce_mul(cs, b, &c, a);
+        c
+    }
+
+    /// Allocate the inverse of `a` modulo `p`, enforcing that `a` is not a multiple of `p`
+    pub fn inverse<T: Field>(&self, cs: &mut ConstraintSystem<T>, a: &Integer<T>) -> Integer<T> {
+        self.div(cs, &self.constant(&BigUint::from(1u32)), a)
+    }
+
+    /// A constant integer with as many limbs as `p`
+    pub fn constant<T: Field>(&self, value: &BigUint) -> Integer<T> {
+        Integer::constant(value, self.limb_count)
+    }
+
+    /// Enforce that `a` is less than `p`, where the limbs of `a` are of `LIMB_SIZE` bits
+    pub fn enforce_reduced<T: Field>(&self, cs: &mut ConstraintSystem<T>, a: &Integer<T>) {
+        let is_reduced = a.is_less_than(cs, &self.value);
+        cs.enforce(
+            is_reduced,
+            LinearCombination::one(),
+            LinearCombination::one(),
+        );
+    }
+
+    /// Allocate the reduction of `a` modulo `p`, enforcing that it is less than `p`
+    pub fn normalize<T: Field>(&self, cs: &mut ConstraintSystem<T>, a: &Integer<T>) -> Integer<T> {
+        let (c, _) = self.alloc(cs, a.value(cs));
+        self.enforce_mul(cs, a, &self.constant(&BigUint::from(1u32)), &c);
+        self.enforce_reduced(cs, &c);
+        c
+    }
+}
+
+/// Read the inputs as integers of `limb_count` words of `LIMB_SIZE` bits in big endian order
+fn inputs<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+    limb_counts: &[usize],
+) -> Vec<Integer<T>> {
+    // the inputs are not checked to be words of `LIMB_SIZE` bits, they are expected to be checked
+    // by the caller
+    let mut index = 0;
+    limb_counts
+        .iter()
+        .map(|limb_count| {
+            let limbs = (0..*limb_count)
+                .map(|_| {
+                    index += 1;
+                    LinearCombination::variable(cs.alloc(values.map(|v| v[index - 1].clone())))
+                })
+                .collect::<Vec<_>>();
+            Integer::from_limbs(limbs.into_iter().rev().collect())
+        })
+        .collect()
+}
+
+/// The words of `LIMB_SIZE` bits of an integer in big endian order
+fn outputs<T: Field>(a: Integer<T>) -> Vec<Vec<(usize, T)>> {
+    a.limbs
+        .into_iter()
+        .rev()
+        .map(LinearCombination::into_vec)
+        .collect()
+}
+
+fn mul_mod<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    modulus: &[u64],
+    values: Option<&[T]>,
+) -> Integer<T> {
+    let p = Modulus::new(from_words(modulus), modulus.len());
+    let inputs = inputs(cs, values, &[p.limb_count, p.limb_count]);
+    let c = p.mul(cs, &inputs[0], &inputs[1]);
+    p.enforce_reduced(cs, &c);
+    c
+}
+
+fn reduce<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    modulus: &[u64],
+    input_limb_count: usize,
+    values: Option<&[T]>,
+) -> Integer<T> {
+    let p = Modulus::new(from_words(modulus), modulus.len());
+    let inputs = inputs(cs, values, &[input_limb_count]);
+    p.normalize(cs, &inputs[0])
+}
+
+/// The number of variables of the product of two integers modulo `modulus`, given as words of
+/// `LIMB_SIZE` bits in big endian order, including the constant `1`
+pub fn mul_mod_variable_count(modulus: &[u64]) -> usize {
+    let p = Modulus::new(from_words(modulus), modulus.len());
+    1 + 2 * p.limb_count + p.mul_variable_count() + is_less_than_variable_count(p.limb_count)
+}
+
+/// Generate the constraints of the product of two integers modulo `modulus`. The inputs are the
+/// words of both integers, which are the variables `1..=2 * modulus.len()`, and the outputs are
+/// the words of the product, which is less than `modulus`.
+pub fn generate_mul_mod_constraints<T: Field>(modulus: &[u64]) -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let c = mul_mod(&mut cs, modulus, None);
+    (cs.into_r1cs(), outputs(c))
+}
+
+/// Compute the values of all variables of the product of `inputs` modulo `modulus`, starting
+/// with the constant `1`
+pub fn generate_mul_mod_witness<T: Field>(modulus: &[u64], inputs: &[T]) -> Vec<T> {
+    assert_eq!(inputs.len(), 2 * modulus.len());
+    let mut cs = ConstraintSystem::witness();
+    mul_mod(&mut cs, modulus, Some(inputs));
+    cs.into_witness()
+}
+
+/// The number of variables of the reduction of an integer of `input_limb_count` words modulo
+/// `modulus`, including the constant `1`
+pub fn reduce_variable_count(modulus: &[u64], input_limb_count: usize) -> usize {
+    let p = Modulus::new(from_words(modulus), modulus.len());
+    1 + input_limb_count + p.normalize_variable_count(input_limb_count)
+}
+
+/// Generate the constraints of the reduction of an integer of `input_limb_count` words modulo
+/// `modulus`, where `input_limb_count` is at most twice the length of `modulus`. The inputs are
+/// the variables `1..=input_limb_count`, and the outputs are the words of the result.
+pub fn generate_reduce_constraints<T: Field>(
+    modulus: &[u64],
+    input_limb_count: usize,
+) -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    assert!(input_limb_count <= 2 * modulus.len());
+    let mut cs = ConstraintSystem::constraints();
+    let c = reduce(&mut cs, modulus, input_limb_count, None);
+    (cs.into_r1cs(), outputs(c))
+}
+
+/// Compute the values of all variables of the reduction of `inputs` modulo `modulus`, starting
+/// with the constant `1`
+pub fn generate_reduce_witness<T: Field>(modulus: &[u64], inputs: &[T]) -> Vec<T> {
+    assert!(inputs.len() <= 2 * modulus.len());
+    let mut cs = ConstraintSystem::witness();
+    reduce(&mut cs, modulus, inputs.len(), Some(inputs));
+    cs.into_witness()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::tests::is_satisfied;
+    use std::convert::TryFrom;
+    use zokrates_field::Bn128Field;
+
+    fn secp256k1_p() -> Modulus {
+        Modulus::new(
+            BigUint::parse_bytes(
+                b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+                16,
+            )
+            .unwrap(),
+            4,
+        )
+    }
+
+    #[test]
+    fn mul() {
+        let p = secp256k1_p();
+        let mut cs = ConstraintSystem::<Bn128Field>::witness();
+
+        let a_value = p.value() - 2u32;
+        let b_value = BigUint::parse_bytes(b"123456789abcdef0123456789abcdef", 16).unwrap();
+
+        let (a, _) = Integer::alloc(&mut cs, Some(a_value.clone()), 4);
+        let (b, _) = Integer::alloc(&mut cs, Some(b_value.clone()), 4);
+        let count = cs.variable_count();
+        let c = p.mul(&mut cs, &a, &b);
+
+        assert_eq!(cs.variable_count() - count, p.mul_variable_count());
+        assert_eq!(c.value(&cs), Some(p.reduce(&(a_value * &b_value))));
+
+        // `b - a` is negative and needs to be shifted by a multiple of `p`
+        let d = p.mul(&mut cs, &p.sub(&b, &a), &c);
+        let d_value = p.reduce(&((b_value + 2u32) * c.value(&cs).unwrap()));
+        assert_eq!(d.value(&cs), Some(d_value));
+
+        let witness = cs.into_witness();
+        let r1cs = {
+            let mut cs = ConstraintSystem::<Bn128Field>::constraints();
+            let (a, _) = Integer::alloc(&mut cs, None, 4);
+            let (b, _) = Integer::alloc(&mut cs, None, 4);
+            let c = p.mul(&mut cs, &a, &b);
+            p.mul(&mut cs, &p.sub(&b, &a), &c);
+            cs.into_r1cs()
+        };
+        assert!(is_satisfied(&r1cs, &witness));
+    }
+
+    #[test]
+    fn wrong_product() {
+        let p = secp256k1_p();
+
+        let gadget = |cs: &mut ConstraintSystem<Bn128Field>, values: Option<[u32; 3]>| {
+            let (a, _) = p.alloc(cs, values.map(|v| BigUint::from(v[0])));
+            let (b, _) = p.alloc(cs, values.map(|v| BigUint::from(v[1])));
+            let (c, _) = p.alloc(cs, values.map(|v| BigUint::from(v[2])));
+            p.enforce_mul(cs, &a, &b, &c);
+        };
+
+        let mut cs = ConstraintSystem::constraints();
+        gadget(&mut cs, None);
+        let r1cs = cs.into_r1cs();
+
+        let mut cs = ConstraintSystem::witness();
+        gadget(&mut cs, Some([3, 5, 15]));
+        assert!(is_satisfied(&r1cs, &cs.into_witness()));
+
+        let mut cs = ConstraintSystem::witness();
+        gadget(&mut cs, Some([3, 5, 16]));
+        assert!(!is_satisfied(&r1cs, &cs.into_witness()));
+    }
+
+    #[test]
+    fn inverse() {
+        let p = secp256k1_p();
+
+        let gadget = |cs: &mut ConstraintSystem<Bn128Field>, value: Option<u32>| {
+            let (a, _) = p.alloc(cs, value.map(BigUint::from));
+            p.inverse(cs, &a).value(cs)
+        };
+
+        let mut cs = ConstraintSystem::constraints();
+        gadget(&mut cs, None);
+        let r1cs = cs.into_r1cs();
+
+        let mut cs = ConstraintSystem::witness();
+        let inverse = gadget(&mut cs, Some(42)).unwrap();
+        assert_eq!(p.reduce(&(inverse * 42u32)), BigUint::from(1u32));
+        assert!(is_satisfied(&r1cs, &cs.into_witness()));
+
+        // zero has no inverse
+        let mut cs = ConstraintSystem::witness();
+        gadget(&mut cs, Some(0));
+        assert!(!is_satisfied(&r1cs, &cs.into_witness()));
+    }
+
+    #[test]
+    fn embeds() {
+        // the base field of BN254
+        let modulus = [
+            0x30644e72e131a029,
+            0xb85045b68181585d,
+            0x97816a916871ca8d,
+            0x3c208c16d87cfd47,
+        ];
+        let p = from_words(&modulus);
+
+        let to_words = |value: &BigUint| -> Vec<Bn128Field> {
+            (0..4)
+                .rev()
+                .map(|i| Bn128Field::try_from(limb(value, i)).unwrap())
+                .collect()
+        };
+        let eval = |lc: &[(usize, Bn128Field)], witness: &[Bn128Field]| {
+            lc.iter().fold(Bn128Field::from(0), |acc, (i, c)| {
+                acc + witness[*i].clone() * c
+            })
+        };
+
+        // the product of `p - 1` and `2^256 - 1`
+        let a = &p - 1u32;
+        let b = (BigUint::from(1u32) << 256) - 1u32;
+
+        let (r1cs, outputs) = generate_mul_mod_constraints::<Bn128Field>(&modulus);
+        assert_eq!(r1cs.aux_count + 1, mul_mod_variable_count(&modulus));
+
+        let inputs: Vec<_> = to_words(&a).into_iter().chain(to_words(&b)).collect();
+        let witness = generate_mul_mod_witness(&modulus, &inputs);
+        assert!(is_satisfied(&r1cs, &witness));
+        let result: Vec<_> = outputs.iter().map(|o| eval(o, &witness)).collect();
+        assert_eq!(result, to_words(&((&a * &b) % &p)));
+
+        // the reduction of the same product, as an integer of 8 words
+        let (r1cs, outputs) = generate_reduce_constraints::<Bn128Field>(&modulus, 8);
+        assert_eq!(r1cs.aux_count + 1, reduce_variable_count(&modulus, 8));
+
+        let product = &a * &b;
+        let inputs: Vec<_> = to_words(&(&product >> 256))
+            .into_iter()
+            .chain(to_words(&(&product % (BigUint::from(1u32) << 256))))
+            .collect();
+        let witness = generate_reduce_witness(&modulus, &inputs);
+        assert!(is_satisfied(&r1cs, &witness));
+        let result: Vec<_> = outputs.iter().map(|o| eval(o, &witness)).collect();
+        assert_eq!(result, to_words(&(&product % &p)));
+    }
+}
@@ -0,0 +1,265 @@
+//! The compression function of BLAKE2s (https://tools.ietf.org/html/rfc7693), as
+//! `blake2s_compression` in `stdlib/hashes/blake2/blake2s_p.zok`
+//!
+//! Words are represented by their bits in big endian order like `u32_to_bits`.
+
+use crate::native::{pack, ConstraintSystem, LinearCombination};
+use crate::R1CS;
+use zokrates_field::Field;
+
+pub const WORD_SIZE: usize = 32;
+/// The number of input bits: the state `h`, the message block `m`, the offset counter `t` and
+/// the final block flag
+pub const INPUT_SIZE: usize = (8 + 16 + 2) * WORD_SIZE + 1;
+/// The number of output bits: the new state `h`
+pub const OUTPUT_SIZE: usize = 8 * WORD_SIZE;
+
+/// Initialization Vector, section 2.6.
+const IV: [u32; 8] = [
+    0x6A09E667, 0xBB67AE85, 0x3C6EF372, 0xA54FF53A, 0x510E527F, 0x9B05688C, 0x1F83D9AB, 0x5BE0CD19,
+];
+
+/// Message Schedule SIGMA, section 2.7.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The number of variables of the compression function, including the constant `1`
+pub fn variable_count() -> usize {
+    // each mixing function adds 3 words twice (34 bits) and 2 words twice (33 bits), and xors 4
+    // words. In the first round, `v[15]` is a constant so the first xor of the fourth mixing
+    // function is free. Finally, each word of the output is computed with 2 xors.
+    let mixing = 2 * 34 + 2 * 33 + 4 * WORD_SIZE;
+    1 + INPUT_SIZE + 10 * 8 * mixing - WORD_SIZE + 8 * 2 * WORD_SIZE
+}
+
+/// A word, with its bits in little endian order so that bit `i` is `word[i]`
+type Word<T> = Vec<LinearCombination<T>>;
+
+fn constant<T: Field>(value: u32) -> Word<T> {
+    (0..WORD_SIZE)
+        .map(|i| LinearCombination::constant(T::from((value >> i) & 1 == 1)))
+        .collect()
+}
+
+fn xor<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    a: &[LinearCombination<T>],
+    b: &[LinearCombination<T>],
+) -> Word<T> {
+    a.iter().zip(b).map(|(a, b)| cs.xor(a, b)).collect()
+}
+
+fn rotr<T: Field>(word: &[LinearCombination<T>], n: usize) -> Word<T> {
+    (0..WORD_SIZE)
+        .map(|i| word[(i + n) % WORD_SIZE].clone())
+        .collect()
+}
+
+/// Add words modulo `2^32` by decomposing their sum into bits
+fn add<T: Field>(cs: &mut ConstraintSystem<T>, words: &[&Word<T>]) -> Word<T> {
+    let sum = words
+        .iter()
+        .fold(LinearCombination::zero(), |acc, word| acc + pack(word));
+
+    // the sum of `n` words fits in `32 + log2(n)` bits
+    let carry_size = (usize::BITS - (words.len() - 1).leading_zeros()) as usize;
+
+    let mut bits = cs.decompose(sum, WORD_SIZE + carry_size);
+    bits.truncate(WORD_SIZE);
+    bits
+}
+
+#[allow(clippy::too_many_arguments)]
+fn mixing_g<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    v: &mut [Word<T>],
+    a: usize,
+    b: usize,
+    c: usize,
+    d: usize,
+    x: &Word<T>,
+    y: &Word<T>,
+) {
+    v[a] = add(cs, &[&v[a], &v[b], x]);
+    v[d] = rotr(&xor(cs, &v[d], &v[a]), 16);
+    v[c] = add(cs, &[&v[c], &v[d]]);
+    v[b] = rotr(&xor(cs, &v[b], &v[c]), 12);
+    v[a] = add(cs, &[&v[a], &v[b], y]);
+    v[d] = rotr(&xor(cs, &v[d], &v[a]), 8);
+    v[c] = add(cs, &[&v[c], &v[d]]);
+    v[b] = rotr(&xor(cs, &v[b], &v[c]), 7);
+}
+
+/// Apply the compression function to the inputs made of the variables `1..=INPUT_SIZE`,
+/// returning the bits of the new state
+fn compression<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+) -> Vec<LinearCombination<T>> {
+    // the input bits are not checked to be booleans, they are expected to be checked by the caller
+    let bits: Vec<_> = (0..INPUT_SIZE)
+        .map(|i| LinearCombination::variable(cs.alloc(values.map(|v| v[i].clone()))))
+        .collect();
+
+    let mut words: Vec<Word<T>> = bits[..INPUT_SIZE - 1]
+        .chunks(WORD_SIZE)
+        .map(|word| word.iter().rev().cloned().collect())
+        .collect();
+    let last = bits[INPUT_SIZE - 1].clone();
+
+    let t: Vec<_> = words.split_off(24);
+    let m: Vec<_> = words.split_off(8);
+    let h = words;
+
+    let mut v: Vec<_> = h
+        .iter()
+        .cloned()
+        .chain(IV.iter().map(|iv| constant(*iv)))
+        .collect();
+
+    v[12] = xor(cs, &v[12], &t[0]);
+    v[13] = xor(cs, &v[13], &t[1]);
+    v[14] = v[14].iter().map(|bit| cs.xor(bit, &last)).collect();
+
+    for s in SIGMA.iter() {
+        mixing_g(cs, &mut v, 0, 4, 8, 12, &m[s[0]], &m[s[1]]);
+        mixing_g(cs, &mut v, 1, 5, 9, 13, &m[s[2]], &m[s[3]]);
+        mixing_g(cs, &mut v, 2, 6, 10, 14, &m[s[4]], &m[s[5]]);
+        mixing_g(cs, &mut v, 3, 7, 11, 15, &m[s[6]], &m[s[7]]);
+        mixing_g(cs, &mut v, 0, 5, 10, 15, &m[s[8]], &m[s[9]]);
+        mixing_g(cs, &mut v, 1, 6, 11, 12, &m[s[10]], &m[s[11]]);
+        mixing_g(cs, &mut v, 2, 7, 8, 13, &m[s[12]], &m[s[13]]);
+        mixing_g(cs, &mut v, 3, 4, 9, 14, &m[s[14]], &m[s[15]]);
+    }
+
+    (0..8)
+        .flat_map(|i| {
+            let word = xor(cs, &h[i], &v[i]);
+            let word = xor(cs, &word, &v[i + 8]);
+            word.into_iter().rev()
+        })
+        .collect()
+}
+
+/// Generate the constraints of the compression function. The input bits are the variables
+/// `1..=INPUT_SIZE`, and the output bits are returned as linear combinations of the variables.
+pub fn generate_blake2s_round_constraints<T: Field>() -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let outputs = compression(&mut cs, None)
+        .into_iter()
+        .map(LinearCombination::into_vec)
+        .collect();
+    (cs.into_r1cs(), outputs)
+}
+
+/// Compute the values of all variables of the compression function of `inputs`, starting with
+/// the constant `1`
+pub fn generate_blake2s_round_witness<T: Field>(inputs: &[T]) -> Vec<T> {
+    assert_eq!(inputs.len(), INPUT_SIZE);
+    let mut cs = ConstraintSystem::witness();
+    compression(&mut cs, Some(inputs));
+    cs.into_witness()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::tests::is_satisfied;
+    use zokrates_field::Bn128Field;
+
+    /// A straightforward implementation of the compression function over `u32` words
+    fn blake2s_compression(mut h: [u32; 8], m: [u32; 16], t: [u32; 2], last: bool) -> [u32; 8] {
+        let mut v = [0u32; 16];
+        v[..8].copy_from_slice(&h);
+        v[8..].copy_from_slice(&IV);
+
+        v[12] ^= t[0];
+        v[13] ^= t[1];
+        if last {
+            v[14] = !v[14];
+        }
+
+        let g = |v: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize, x: u32, y: u32| {
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+            v[d] = (v[d] ^ v[a]).rotate_right(16);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(12);
+            v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+            v[d] = (v[d] ^ v[a]).rotate_right(8);
+            v[c] = v[c].wrapping_add(v[d]);
+            v[b] = (v[b] ^ v[c]).rotate_right(7);
+        };
+
+        for s in SIGMA.iter() {
+            g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+            g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+            g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+            g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+            g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+            g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+            g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+            g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+        }
+
+        for (i, word) in h.iter_mut().enumerate() {
+            *word ^= v[i] ^ v[i + 8];
+        }
+        h
+    }
+
+    fn to_bits(words: &[u32]) -> Vec<Bn128Field> {
+        words
+            .iter()
+            .flat_map(|word| (0..WORD_SIZE).rev().map(move |i| (word >> i) & 1 == 1))
+            .map(Bn128Field::from)
+            .collect()
+    }
+
+    #[test]
+    fn matches_reference() {
+        let (r1cs, outputs) = generate_blake2s_round_constraints::<Bn128Field>();
+        assert_eq!(r1cs.aux_count + 1, variable_count());
+        assert_eq!(outputs.len(), OUTPUT_SIZE);
+
+        // hash of "abc", see appendix B
+        let mut h = IV;
+        h[0] ^= 0x01010020;
+        let mut m = [0u32; 16];
+        m[0] = 0x00636261;
+
+        for (t, last) in vec![([3, 0], true), ([0xffffffff, 1], false)] {
+            let inputs: Vec<_> = to_bits(&h)
+                .into_iter()
+                .chain(to_bits(&m))
+                .chain(to_bits(&t))
+                .chain(std::iter::once(Bn128Field::from(last)))
+                .collect();
+
+            let witness = generate_blake2s_round_witness(&inputs);
+            assert!(is_satisfied(&r1cs, &witness));
+
+            let result: Vec<_> = outputs
+                .iter()
+                .map(|bit| {
+                    bit.iter().fold(Bn128Field::from(0), |acc, (index, c)| {
+                        acc + witness[*index].clone() * c
+                    })
+                })
+                .collect();
+
+            assert_eq!(result, to_bits(&blake2s_compression(h, m, t, last)));
+        }
+
+        assert_eq!(blake2s_compression(h, m, [3, 0], true)[0], 0x8C5E8C50);
+    }
+}
@@ -0,0 +1,238 @@
+//! The Keccak-f[1600] permutation (https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.202.pdf)
+//!
+//! The state is made of 25 lanes of 64 bits, each lane being represented by its bits in big
+//! endian order like `u64_to_bits`.
+
+use crate::native::{ConstraintSystem, LinearCombination};
+use crate::R1CS;
+use zokrates_field::Field;
+
+pub const LANE_COUNT: usize = 25;
+pub const LANE_SIZE: usize = 64;
+pub const STATE_SIZE: usize = LANE_COUNT * LANE_SIZE;
+
+const ROUNDS: usize = 24;
+
+const RHO: [usize; 24] = [
+    1, 3, 6, 10, 15, 21, 28, 36, 45, 55, 2, 14, 27, 41, 56, 8, 25, 43, 62, 18, 39, 61, 20, 44,
+];
+
+const PI: [usize; 24] = [
+    10, 7, 11, 17, 18, 3, 5, 16, 8, 21, 24, 4, 15, 23, 19, 13, 12, 2, 20, 14, 22, 9, 6, 1,
+];
+
+const RC: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// The number of variables of the permutation, including the constant `1`
+pub fn variable_count() -> usize {
+    // each round allocates 2 variables per bit of the state in theta, and 2 more in chi
+    1 + STATE_SIZE + ROUNDS * 4 * STATE_SIZE
+}
+
+/// A lane, with its bits in little endian order so that bit `z` is `lane[z]`
+type Lane<T> = Vec<LinearCombination<T>>;
+
+fn xor_lanes<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    a: &[LinearCombination<T>],
+    b: &[LinearCombination<T>],
+) -> Lane<T> {
+    a.iter().zip(b).map(|(a, b)| cs.xor(a, b)).collect()
+}
+
+fn rotl<T: Field>(lane: &[LinearCombination<T>], n: usize) -> Lane<T> {
+    (0..LANE_SIZE)
+        .map(|z| lane[(z + LANE_SIZE - n) % LANE_SIZE].clone())
+        .collect()
+}
+
+fn round<T: Field>(cs: &mut ConstraintSystem<T>, mut st: Vec<Lane<T>>, rc: u64) -> Vec<Lane<T>> {
+    // theta
+    let bc: Vec<Lane<T>> = (0..5)
+        .map(|i| {
+            (5..LANE_COUNT)
+                .step_by(5)
+                .fold(st[i].clone(), |acc, j| xor_lanes(cs, &acc, &st[i + j]))
+        })
+        .collect();
+
+    for i in 0..5 {
+        let t = xor_lanes(cs, &bc[(i + 4) % 5], &rotl(&bc[(i + 1) % 5], 1));
+        for j in 0..5 {
+            st[j * 5 + i] = xor_lanes(cs, &st[j * 5 + i], &t);
+        }
+    }
+
+    // rho pi
+    let mut t = st[1].clone();
+    for (j, rho) in PI.iter().zip(RHO.iter()) {
+        let rotated = rotl(&t, *rho);
+        t = std::mem::replace(&mut st[*j], rotated);
+    }
+
+    // chi
+    for i in 0..5 {
+        let row: Vec<_> = st[i * 5..i * 5 + 5].to_vec();
+        for j in 0..5 {
+            st[i * 5 + j] = (0..LANE_SIZE)
+                .map(|z| {
+                    // !b & c
+                    let not_b = LinearCombination::one() - row[(j + 1) % 5][z].clone();
+                    let t = cs.mul(&not_b, &row[(j + 2) % 5][z]);
+                    cs.xor(&row[j][z], &t)
+                })
+                .collect();
+        }
+    }
+
+    // iota, xoring with a constant bit is free
+    st[0] = st[0]
+        .iter()
+        .enumerate()
+        .map(|(z, bit)| match (rc >> z) & 1 {
+            1 => LinearCombination::one() - bit.clone(),
+            _ => bit.clone(),
+        })
+        .collect();
+
+    st
+}
+
+/// Apply the permutation to a state made of the variables `1..=STATE_SIZE`, returning the final
+/// state in the same order
+fn permutation<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+) -> Vec<LinearCombination<T>> {
+    // the input bits are not checked to be booleans, they are expected to be checked by the caller
+    let bits: Vec<_> = (0..STATE_SIZE)
+        .map(|i| LinearCombination::variable(cs.alloc(values.map(|v| v[i].clone()))))
+        .collect();
+
+    let st = bits
+        .chunks(LANE_SIZE)
+        .map(|lane| lane.iter().rev().cloned().collect())
+        .collect();
+
+    let st = RC.iter().fold(st, |st, rc| round(cs, st, *rc));
+
+    st.into_iter()
+        .flat_map(|lane| lane.into_iter().rev())
+        .collect()
+}
+
+/// Generate the constraints of the permutation. The input bits are the variables
+/// `1..=STATE_SIZE`, and the output bits are returned as linear combinations of the variables.
+pub fn generate_keccak_f1600_constraints<T: Field>() -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let outputs = permutation(&mut cs, None)
+        .into_iter()
+        .map(LinearCombination::into_vec)
+        .collect();
+    (cs.into_r1cs(), outputs)
+}
+
+/// Compute the values of all variables of the permutation of the bits of `state`, starting with
+/// the constant `1`
+pub fn generate_keccak_f1600_witness<T: Field>(state: &[T]) -> Vec<T> {
+    assert_eq!(state.len(), STATE_SIZE);
+    let mut cs = ConstraintSystem::witness();
+    permutation(&mut cs, Some(state));
+    cs.into_witness()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::tests::is_satisfied;
+    use zokrates_field::Bn128Field;
+
+    /// A straightforward implementation of the permutation over `u64` lanes
+    fn keccak_f1600(mut st: [u64; LANE_COUNT]) -> [u64; LANE_COUNT] {
+        for rc in RC.iter() {
+            let mut bc = [0u64; 5];
+            for (i, c) in bc.iter_mut().enumerate() {
+                *c = st[i] ^ st[i + 5] ^ st[i + 10] ^ st[i + 15] ^ st[i + 20];
+            }
+            for i in 0..5 {
+                let t = bc[(i + 4) % 5] ^ bc[(i + 1) % 5].rotate_left(1);
+                for j in 0..5 {
+                    st[j * 5 + i] ^= t;
+                }
+            }
+
+            let mut t = st[1];
+            for (j, rho) in PI.iter().zip(RHO.iter()) {
+                t = std::mem::replace(&mut st[*j], t.rotate_left(*rho as u32));
+            }
+
+            for i in 0..5 {
+                bc.copy_from_slice(&st[i * 5..i * 5 + 5]);
+                for j in 0..5 {
+                    st[i * 5 + j] ^= !bc[(j + 1) % 5] & bc[(j + 2) % 5];
+                }
+            }
+
+            st[0] ^= rc;
+        }
+        st
+    }
+
+    fn to_bits(st: &[u64]) -> Vec<Bn128Field> {
+        st.iter()
+            .flat_map(|lane| (0..LANE_SIZE).rev().map(move |z| (lane >> z) & 1 == 1))
+            .map(Bn128Field::from)
+            .collect()
+    }
+
+    #[test]
+    fn matches_reference() {
+        let (r1cs, outputs) = generate_keccak_f1600_constraints::<Bn128Field>();
+        assert_eq!(r1cs.aux_count + 1, variable_count());
+        assert_eq!(outputs.len(), STATE_SIZE);
+
+        for st in vec![[0u64; LANE_COUNT], [0x0123456789abcdef; LANE_COUNT]] {
+            let witness = generate_keccak_f1600_witness(&to_bits(&st));
+            assert!(is_satisfied(&r1cs, &witness));
+
+            let result: Vec<_> = outputs
+                .iter()
+                .map(|bit| {
+                    bit.iter().fold(Bn128Field::from(0), |acc, (index, c)| {
+                        acc + witness[*index].clone() * c
+                    })
+                })
+                .collect();
+
+            assert_eq!(result, to_bits(&keccak_f1600(st)));
+        }
+
+        // the first lane of the permutation of the zero state
+        assert_eq!(keccak_f1600([0u64; LANE_COUNT])[0], 0xf1258f7940e1dde7);
+    }
+}
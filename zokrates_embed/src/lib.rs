@@ -1,5 +1,14 @@
 pub mod ark;
-pub mod bellman;
+pub mod babyjubjub;
+#[cfg(test)]
+mod bellman;
+pub mod bigint;
+pub mod blake2s;
+pub mod keccak;
+pub mod native;
+pub mod poseidon;
+pub mod secp256k1;
+pub mod sha256;
 
 #[derive(Debug, Clone)]
 #[allow(clippy::upper_case_acronyms)]
@@ -17,11 +26,6 @@ impl<T> Default for R1CS<T> {
     }
 }
 
-#[derive(Debug)]
-pub struct Witness<T> {
-    pub values: Vec<T>,
-}
-
 #[derive(Default, Debug, PartialEq, Clone)]
 pub struct Constraint<T> {
     pub a: Vec<(usize, T)>,
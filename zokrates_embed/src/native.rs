@@ -0,0 +1,319 @@
+//! A constraint system to write gadgets directly over a `zokrates_field::Field`, which makes them
+//! available on every curve and backend. The same code generates the constraints and, when the
+//! inputs are known, the values of all variables.
+
+use crate::{Constraint, R1CS};
+use std::collections::BTreeMap;
+use std::ops::{Add, Sub};
+use zokrates_field::Field;
+
+/// A linear combination of variables. Variable `0` is the constant `1`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearCombination<T>(BTreeMap<usize, T>);
+
+impl<T: Field> LinearCombination<T> {
+    pub fn zero() -> Self {
+        LinearCombination(BTreeMap::new())
+    }
+
+    pub fn one() -> Self {
+        Self::constant(T::one())
+    }
+
+    pub fn constant(value: T) -> Self {
+        Self::zero().add_term(0, value)
+    }
+
+    pub fn variable(index: usize) -> Self {
+        Self::zero().add_term(index, T::one())
+    }
+
+    fn add_term(mut self, index: usize, coefficient: T) -> Self {
+        let c = self.0.remove(&index).unwrap_or_else(T::zero) + coefficient;
+        if !c.is_zero() {
+            self.0.insert(index, c);
+        }
+        self
+    }
+
+    pub fn scale(&self, coefficient: &T) -> Self {
+        match coefficient.is_zero() {
+            true => Self::zero(),
+            false => LinearCombination(
+                self.0
+                    .iter()
+                    .map(|(index, c)| (*index, c.clone() * coefficient))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// The value of the constant term, if this linear combination is constant
+    pub fn as_constant(&self) -> Option<T> {
+        match self.0.keys().all(|index| *index == 0) {
+            true => Some(self.0.get(&0).cloned().unwrap_or_else(T::zero)),
+            false => None,
+        }
+    }
+
+    pub fn into_vec(self) -> Vec<(usize, T)> {
+        self.0.into_iter().collect()
+    }
+}
+
+impl<T: Field> Add for LinearCombination<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        other
+            .0
+            .into_iter()
+            .fold(self, |acc, (index, c)| acc.add_term(index, c))
+    }
+}
+
+impl<T: Field> Sub for LinearCombination<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self + other.scale(&(T::zero() - T::one()))
+    }
+}
+
+/// The linear combination `sum(2^i * bits[i])` of bits in little endian order
+pub fn pack<T: Field>(bits: &[LinearCombination<T>]) -> LinearCombination<T> {
+    bits.iter()
+        .enumerate()
+        .fold(LinearCombination::zero(), |acc, (i, bit)| {
+            acc + bit.scale(&T::from(2).pow(i))
+        })
+}
+
+/// A constraint system which records the constraints of a gadget, along with the values of its
+/// variables if they are being computed
+pub struct ConstraintSystem<T> {
+    r1cs: R1CS<T>,
+    /// The values of the variables, starting with the constant `1`
+    values: Option<Vec<T>>,
+}
+
+impl<T: Field> ConstraintSystem<T> {
+    /// A constraint system which only records the constraints
+    pub fn constraints() -> Self {
+        ConstraintSystem {
+            r1cs: R1CS::default(),
+            values: None,
+        }
+    }
+
+    /// A constraint system which also computes the values of the variables
+    pub fn witness() -> Self {
+        ConstraintSystem {
+            r1cs: R1CS::default(),
+            values: Some(vec![T::one()]),
+        }
+    }
+
+    /// The number of variables, including the constant `1`
+    pub fn variable_count(&self) -> usize {
+        self.r1cs.aux_count + 1
+    }
+
+    /// The value of a linear combination, if the values are being computed
+    pub fn eval(&self, lc: &LinearCombination<T>) -> Option<T> {
+        self.values.as_ref().map(|values| {
+            lc.0.iter().fold(T::zero(), |acc, (index, c)| {
+                acc + values[*index].clone() * c
+            })
+        })
+    }
+
+    /// Allocate a new variable, returning its index
+    pub fn alloc(&mut self, value: Option<T>) -> usize {
+        self.r1cs.aux_count += 1;
+        if let Some(values) = self.values.as_mut() {
+            values.push(value.expect("the value of a variable should be known"));
+        }
+        self.r1cs.aux_count
+    }
+
+    /// Enforce `a * b == c`
+    pub fn enforce(
+        &mut self,
+        a: LinearCombination<T>,
+        b: LinearCombination<T>,
+        c: LinearCombination<T>,
+    ) {
+        self.r1cs.constraints.push(Constraint {
+            a: a.into_vec(),
+            b: b.into_vec(),
+            c: c.into_vec(),
+        });
+    }
+
+    /// Allocate a variable equal to `a * b`
+    pub fn mul(
+        &mut self,
+        a: &LinearCombination<T>,
+        b: &LinearCombination<T>,
+    ) -> LinearCombination<T> {
+        // products by constants do not require a constraint
+        match (a.as_constant(), b.as_constant()) {
+            (Some(a), _) => b.scale(&a),
+            (_, Some(b)) => a.scale(&b),
+            (None, None) => {
+                let value = self.eval(a).zip(self.eval(b)).map(|(a, b)| a * b);
+                let c = LinearCombination::variable(self.alloc(value));
+                self.enforce(a.clone(), b.clone(), c.clone());
+                c
+            }
+        }
+    }
+
+    /// Allocate a variable constrained to be `0` or `1`
+    pub fn alloc_bit(&mut self, value: Option<bool>) -> LinearCombination<T> {
+        let bit = LinearCombination::variable(self.alloc(value.map(T::from)));
+        self.enforce(bit.clone(), bit.clone(), bit.clone());
+        bit
+    }
+
+    /// Allocate the `bit_count` bits of the value of `lc`, in little endian order, enforcing that
+    /// they add up to `lc`
+    pub fn decompose(
+        &mut self,
+        lc: LinearCombination<T>,
+        bit_count: usize,
+    ) -> Vec<LinearCombination<T>> {
+        let value = self.eval(&lc).map(|v| v.to_bits_be());
+
+        let bits: Vec<_> = (0..bit_count)
+            .map(|i| {
+                let bit = value.as_ref().map(|v| v[v.len() - 1 - i]);
+                self.alloc_bit(bit)
+            })
+            .collect();
+
+        self.enforce(lc, LinearCombination::one(), pack(&bits));
+        bits
+    }
+
+    /// Allocate a variable equal to `a XOR b`, where `a` and `b` are bits
+    pub fn xor(
+        &mut self,
+        a: &LinearCombination<T>,
+        b: &LinearCombination<T>,
+    ) -> LinearCombination<T> {
+        // a ^ b == a + b - 2ab
+        match (a.as_constant(), b.as_constant()) {
+            (Some(_), _) | (_, Some(_)) => {
+                let ab = self.mul(a, b);
+                a.clone() + b.clone() - ab.scale(&T::from(2))
+            }
+            // allocate the result so that chained xors do not grow the linear combinations
+            (None, None) => {
+                let value = self
+                    .eval(a)
+                    .zip(self.eval(b))
+                    .map(|(a, b)| a.clone() + b.clone() - a * b * T::from(2));
+                let c = LinearCombination::variable(self.alloc(value));
+                self.enforce(
+                    a.scale(&T::from(2)),
+                    b.clone(),
+                    a.clone() + b.clone() - c.clone(),
+                );
+                c
+            }
+        }
+    }
+
+    pub fn into_r1cs(self) -> R1CS<T> {
+        self.r1cs
+    }
+
+    /// The values of all variables, starting with the constant `1`
+    pub fn into_witness(self) -> Vec<T> {
+        self.values
+            .expect("the values of the variables should be computed")
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use zokrates_field::Bn128Field;
+
+    /// Check that the values of the variables satisfy all constraints
+    pub fn is_satisfied<T: Field>(r1cs: &R1CS<T>, witness: &[T]) -> bool {
+        let eval = |lc: &[(usize, T)]| {
+            lc.iter().fold(T::zero(), |acc, (index, c)| {
+                acc + witness[*index].clone() * c
+            })
+        };
+
+        witness.len() == r1cs.aux_count + 1
+            && r1cs
+                .constraints
+                .iter()
+                .all(|c| eval(&c.a) * eval(&c.b) == eval(&c.c))
+    }
+
+    #[test]
+    fn linear_combinations() {
+        let a = LinearCombination::<Bn128Field>::variable(1) + LinearCombination::one();
+        let b = LinearCombination::variable(1).scale(&Bn128Field::from(2));
+
+        assert_eq!(
+            (a.clone() - b).into_vec(),
+            vec![(0, Bn128Field::from(1)), (1, Bn128Field::from(-1))]
+        );
+        assert_eq!((a.clone() - a).into_vec(), vec![]);
+    }
+
+    #[test]
+    fn mul() {
+        let mut cs = ConstraintSystem::<Bn128Field>::witness();
+
+        let x = LinearCombination::variable(cs.alloc(Some(3.into())));
+        let y = cs.mul(&x, &(x.clone() + LinearCombination::one()));
+        // products by constants are free
+        let z = cs.mul(&y, &LinearCombination::constant(2.into()));
+
+        assert_eq!(cs.eval(&z), Some(24.into()));
+        assert_eq!(cs.variable_count(), 3);
+
+        let witness = cs.values.clone().unwrap();
+        assert!(is_satisfied(&cs.into_r1cs(), &witness));
+    }
+
+    #[test]
+    fn decompose() {
+        let mut cs = ConstraintSystem::<Bn128Field>::witness();
+
+        let x = LinearCombination::variable(cs.alloc(Some(6.into())));
+        let bits = cs.decompose(x + LinearCombination::one(), 4);
+
+        assert_eq!(
+            bits.iter().map(|b| cs.eval(b).unwrap()).collect::<Vec<_>>(),
+            vec![1.into(), 1.into(), 1.into(), 0.into()]
+        );
+
+        let witness = cs.values.clone().unwrap();
+        assert!(is_satisfied(&cs.into_r1cs(), &witness));
+    }
+
+    #[test]
+    fn xor() {
+        for (a, b) in vec![(false, false), (false, true), (true, false), (true, true)] {
+            let mut cs = ConstraintSystem::<Bn128Field>::witness();
+
+            let x = cs.alloc_bit(Some(a));
+            let y = cs.alloc_bit(Some(b));
+            let z = cs.xor(&x, &y);
+
+            assert_eq!(cs.eval(&z), Some(Bn128Field::from(a ^ b)));
+
+            let witness = cs.values.clone().unwrap();
+            assert!(is_satisfied(&cs.into_r1cs(), &witness));
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! The Poseidon permutation (https://eprint.iacr.org/2019/458.pdf) with the parameters of
+//! `stdlib/hashes/poseidon/poseidon.zok`, defined over the scalar field of BN128
+
+mod constants;
+
+use self::constants::{POSEIDON_C, POSEIDON_M};
+use crate::native::{ConstraintSystem, LinearCombination};
+use crate::R1CS;
+use zokrates_field::Field;
+
+pub const MIN_WIDTH: usize = 2;
+pub const MAX_WIDTH: usize = 7;
+
+const FULL_ROUNDS: usize = 8;
+/// The number of partial rounds for each width
+const PARTIAL_ROUNDS: [usize; MAX_WIDTH - MIN_WIDTH + 1] = [56, 57, 56, 60, 60, 63];
+
+fn check_width(width: usize) {
+    assert!(
+        (MIN_WIDTH..=MAX_WIDTH).contains(&width),
+        "the width of the Poseidon permutation should be between {} and {}, found {}",
+        MIN_WIDTH,
+        MAX_WIDTH,
+        width
+    );
+}
+
+/// The number of variables of the permutation of the given width, including the constant `1`
+pub fn variable_count(width: usize) -> usize {
+    check_width(width);
+    // each sbox allocates `x^2`, `x^4` and `x^5`
+    let sbox_count = FULL_ROUNDS * width + PARTIAL_ROUNDS[width - MIN_WIDTH];
+    1 + width + 3 * sbox_count
+}
+
+fn sbox<T: Field>(cs: &mut ConstraintSystem<T>, x: &LinearCombination<T>) -> LinearCombination<T> {
+    let x2 = cs.mul(x, x);
+    let x4 = cs.mul(&x2, &x2);
+    cs.mul(&x4, x)
+}
+
+/// Apply the permutation to a state made of the variables `1..=width`, returning the final state
+fn permutation<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    width: usize,
+    values: Option<&[T]>,
+) -> Vec<LinearCombination<T>> {
+    check_width(width);
+
+    let partial_rounds = PARTIAL_ROUNDS[width - MIN_WIDTH];
+    let c: Vec<T> = POSEIDON_C[width - MIN_WIDTH]
+        .iter()
+        .map(|c| T::try_from_dec_str(c).unwrap())
+        .collect();
+    let m: Vec<Vec<T>> = POSEIDON_M[width - MIN_WIDTH]
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|e| T::try_from_dec_str(e).unwrap())
+                .collect()
+        })
+        .collect();
+
+    let mut state: Vec<_> = (0..width)
+        .map(|i| LinearCombination::variable(cs.alloc(values.map(|v| v[i].clone()))))
+        .collect();
+
+    for r in 0..FULL_ROUNDS + partial_rounds {
+        let is_full_round = r < FULL_ROUNDS / 2 || r >= FULL_ROUNDS / 2 + partial_rounds;
+
+        // add the round constants, then apply the sbox to the whole state in full rounds, and
+        // to its first element in partial rounds
+        state = state
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| {
+                let e = e + LinearCombination::constant(c[r * width + i].clone());
+                match i == 0 || is_full_round {
+                    true => sbox(cs, &e),
+                    false => e,
+                }
+            })
+            .collect();
+
+        // mix the state
+        state = m
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .zip(&state)
+                    .fold(LinearCombination::zero(), |acc, (m, e)| acc + e.scale(m))
+            })
+            .collect();
+    }
+
+    state
+}
+
+/// Generate the constraints of the permutation of the given width. The inputs are the variables
+/// `1..=width` and the outputs are returned as linear combinations of the variables.
+pub fn generate_poseidon_permutation_constraints<T: Field>(
+    width: usize,
+) -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let outputs = permutation(&mut cs, width, None)
+        .into_iter()
+        .map(LinearCombination::into_vec)
+        .collect();
+    (cs.into_r1cs(), outputs)
+}
+
+/// Compute the values of all variables of the permutation of `state`, starting with the constant
+/// `1`
+pub fn generate_poseidon_permutation_witness<T: Field>(state: &[T]) -> Vec<T> {
+    let mut cs = ConstraintSystem::witness();
+    permutation(&mut cs, state.len(), Some(state));
+    cs.into_witness()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::tests::is_satisfied;
+    use zokrates_field::Bn128Field;
+
+    #[test]
+    fn variable_counts() {
+        for width in MIN_WIDTH..=MAX_WIDTH {
+            let (r1cs, outputs) = generate_poseidon_permutation_constraints::<Bn128Field>(width);
+            assert_eq!(r1cs.aux_count + 1, variable_count(width));
+            assert_eq!(outputs.len(), width);
+        }
+    }
+
+    #[test]
+    fn witness() {
+        let state = vec![Bn128Field::from(0), Bn128Field::from(1)];
+        let witness = generate_poseidon_permutation_witness(&state);
+        let (r1cs, outputs) = generate_poseidon_permutation_constraints(2);
+
+        assert!(is_satisfied(&r1cs, &witness));
+
+        // the first element of the permuted state is the hash of the other elements, see
+        // `stdlib/hashes/poseidon/poseidon.zok`
+        let hash = outputs[0]
+            .iter()
+            .fold(Bn128Field::from(0), |acc, (index, c)| {
+                acc + witness[*index].clone() * c
+            });
+        assert_eq!(
+            hash,
+            Bn128Field::try_from_dec_str(
+                "18586133768512220936620570745912940619677854269274689475585506675881198879027"
+            )
+            .unwrap()
+        );
+    }
+}
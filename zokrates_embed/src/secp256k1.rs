@@ -0,0 +1,418 @@
+//! ECDSA signature verification over secp256k1, using the non-native arithmetic of `bigint`
+//!
+//! The scalar multiplications `u1 * G + u2 * Q` are computed together with a double-and-add
+//! over the bits of `u1` and `u2`, starting from an arbitrary point `H` so that the incomplete
+//! addition formulas never meet the point at infinity in practice. The offset `2^255 * H` is
+//! subtracted at the end. Each addition enforces that its operands have different `x`
+//! coordinates, so that the prover cannot choose the result when they do not.
+
+use crate::bigint::{is_less_than_variable_count, Integer, Modulus};
+use crate::native::{ConstraintSystem, LinearCombination};
+use crate::R1CS;
+use num_bigint::BigUint;
+use zokrates_field::Field;
+
+/// The number of limbs of the integers modulo `P` and `N`
+const LIMB_COUNT: usize = 4;
+
+/// The number of inputs: the hash `z`, the signature `(r, s)` and the public key `(qx, qy)`, each
+/// as 4 words of 64 bits in big endian order
+pub const INPUT_SIZE: usize = 5 * LIMB_COUNT;
+
+/// The prime of the base field
+const P: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+/// The order of the group
+const N: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+const G: (&str, &str) = (
+    "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+);
+/// The first point with an even `y` whose `x` is at least the sha256 hash of
+/// "ZoKrates secp256k1 offset"
+const H: (&str, &str) = (
+    "9ef159d8f2de0f2d0cbb87809f58f9a69db8d73f4c45e8f374a12aefd77dfbd0",
+    "8e35f4e3c00c070696e12b79a335a5135cf8c2e7ee0695abbb3383c612c1cbc2",
+);
+/// `2^255 * H`, which is subtracted from the result of the double-and-add
+const D: (&str, &str) = (
+    "0fbc9d81009c1959908a65c37031783b626ea9be2729fa6277c14b2d848a2c41",
+    "8733fc89441101ada2169d71ad5d40941c6e72673fc2f2fb51c1759de206002b",
+);
+
+fn parse(value: &str) -> BigUint {
+    BigUint::parse_bytes(value.as_bytes(), 16).unwrap()
+}
+
+/// The number of variables of the verification, including the constant `1`
+pub fn variable_count() -> usize {
+    let p = Modulus::new(parse(P), LIMB_COUNT);
+    let n = Modulus::new(parse(N), LIMB_COUNT);
+    // checking that the public key is on the curve takes 2 products and a check, inverting `s`
+    // and computing `u1` and `u2` take a product each, and `G + Q` takes 4 products
+    let setup = 2 * p.mul_variable_count()
+        + p.enforce_mul_variable_count()
+        + 3 * n.mul_variable_count()
+        + 4 * p.mul_variable_count();
+    // each step takes 4 products for the addition and 25 variables to select the point to add
+    // and whether to add it, and each step except the first one starts with a doubling which
+    // takes 4 products
+    let steps = 256 * (4 * p.mul_variable_count() + 25) + 255 * 4 * p.mul_variable_count();
+    // subtracting the offset takes 3 products, as `y` is not needed, and checking that `x` is
+    // reduced modulo `p`. Comparing `x` modulo `n` to `r` takes 8 variables for the limbs and 4
+    // more to combine them with the range check of `r`.
+    let result = 3 * p.mul_variable_count()
+        + is_less_than_variable_count(LIMB_COUNT)
+        + n.normalize_variable_count(LIMB_COUNT)
+        + is_less_than_variable_count(LIMB_COUNT)
+        + 12;
+    1 + INPUT_SIZE + setup + steps + result
+}
+
+/// A point in affine coordinates
+#[derive(Clone)]
+struct Point<T> {
+    x: Integer<T>,
+    y: Integer<T>,
+}
+
+impl<T: Field> Point<T> {
+    fn constant((x, y): (&str, &str)) -> Self {
+        Point {
+            x: Integer::constant(&parse(x), LIMB_COUNT),
+            y: Integer::constant(&parse(y), LIMB_COUNT),
+        }
+    }
+}
+
+/// Allocate `x3 = lambda^2 - a.x - b_x`, the `x` coordinate of a sum of points or of a doubling
+fn sum_x<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    p: &Modulus,
+    lambda: &Integer<T>,
+    a: &Point<T>,
+    b_x: &Integer<T>,
+) -> Integer<T> {
+    let value = lambda
+        .value(cs)
+        .zip(a.x.value(cs))
+        .zip(b_x.value(cs))
+        .map(|((lambda, a_x), b_x)| &lambda * &lambda + p.negate(&a_x) + p.negate(&b_x));
+    let (x, _) = p.alloc(cs, value);
+    p.enforce_mul(cs, lambda, lambda, &x.add(&a.x).add(b_x));
+    x
+}
+
+/// Allocate the sum of `a` and the point of `x` coordinate `b_x` on the line of slope `lambda`
+/// through `a`
+fn sum<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    p: &Modulus,
+    lambda: &Integer<T>,
+    a: &Point<T>,
+    b_x: &Integer<T>,
+) -> Point<T> {
+    let x = sum_x(cs, p, lambda, a, b_x);
+
+    let value = lambda
+        .value(cs)
+        .zip(a.x.value(cs))
+        .zip(a.y.value(cs))
+        .zip(x.value(cs))
+        .map(|(((lambda, a_x), a_y), x)| lambda * (a_x + p.negate(&x)) + p.negate(&a_y));
+    let (y, _) = p.alloc(cs, value);
+    p.enforce_mul(cs, lambda, &p.sub(&a.x, &x), &y.add(&a.y));
+
+    Point { x, y }
+}
+
+fn double<T: Field>(cs: &mut ConstraintSystem<T>, p: &Modulus, a: &Point<T>) -> Point<T> {
+    let xx = p.mul(cs, &a.x, &a.x);
+    let lambda = p.div(cs, &xx.add(&xx).add(&xx), &a.y.add(&a.y));
+    sum(cs, p, &lambda, a, &a.x)
+}
+
+/// The slope of the line through `a` and `b`, enforcing that they have different `x`
+/// coordinates. Otherwise any slope would satisfy `lambda * 0 == 0` for `a == b`, so the sum
+/// could be chosen freely by the prover.
+fn slope<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    p: &Modulus,
+    a: &Point<T>,
+    b: &Point<T>,
+) -> Integer<T> {
+    let inverse = p.inverse(cs, &p.sub(&b.x, &a.x));
+    p.mul(cs, &p.sub(&b.y, &a.y), &inverse)
+}
+
+/// The sum of two points, which must have different `x` coordinates
+fn add<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    p: &Modulus,
+    a: &Point<T>,
+    b: &Point<T>,
+) -> Point<T> {
+    let lambda = slope(cs, p, a, b);
+    sum(cs, p, &lambda, a, &b.x)
+}
+
+/// A bit which is `1` if `lc` is zero
+fn is_zero<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    lc: LinearCombination<T>,
+) -> LinearCombination<T> {
+    let value = cs.eval(&lc);
+    let inverse = LinearCombination::variable(
+        cs.alloc(
+            value
+                .clone()
+                .map(|v| v.inverse_mul().unwrap_or_else(T::zero)),
+        ),
+    );
+    let is_zero = LinearCombination::variable(cs.alloc(value.map(|v| T::from(v == T::zero()))));
+
+    cs.enforce(
+        lc.clone(),
+        inverse,
+        LinearCombination::one() - is_zero.clone(),
+    );
+    cs.enforce(lc, is_zero.clone(), LinearCombination::zero());
+    is_zero
+}
+
+fn verify<T: Field>(cs: &mut ConstraintSystem<T>, values: Option<&[T]>) -> LinearCombination<T> {
+    // the inputs are not checked to be words of 64 bits, they are expected to be checked by the
+    // caller
+    let inputs: Vec<_> = (0..INPUT_SIZE)
+        .map(|i| LinearCombination::variable(cs.alloc(values.map(|v| v[i].clone()))))
+        .collect();
+    let mut integers = inputs
+        .chunks(LIMB_COUNT)
+        .map(|words| Integer::from_limbs(words.iter().rev().cloned().collect()));
+    let mut next = || integers.next().unwrap();
+    let (z, r, s, q_x, q_y) = (next(), next(), next(), next(), next());
+
+    let p = Modulus::new(parse(P), LIMB_COUNT);
+    let n = Modulus::new(parse(N), LIMB_COUNT);
+
+    // the public key is on the curve `y^2 = x^3 + 7`
+    let xx = p.mul(cs, &q_x, &q_x);
+    let xxx = p.mul(cs, &xx, &q_x);
+    p.enforce_mul(cs, &q_y, &q_y, &xxx.add(&p.constant(&BigUint::from(7u32))));
+
+    // `u1 = z / s` and `u2 = r / s`, whose bits are needed for the scalar multiplications
+    let w = n.inverse(cs, &s);
+    let scalar = |cs: &mut ConstraintSystem<T>, a: &Integer<T>| {
+        let (u, bits) = n.alloc(cs, a.value(cs).zip(w.value(cs)).map(|(a, w)| a * w));
+        n.enforce_mul(cs, a, &w, &u);
+        bits
+    };
+    let u1 = scalar(cs, &z);
+    let u2 = scalar(cs, &r);
+
+    let g = Point::constant(G);
+    let q = Point { x: q_x, y: q_y };
+    let gq = add(cs, &p, &g, &q);
+
+    let g_minus_q = Point {
+        x: p.sub(&g.x, &q.x),
+        y: p.sub(&g.y, &q.y),
+    };
+    let gq_minus_g = Point {
+        x: p.sub(&gq.x, &g.x),
+        y: p.sub(&gq.y, &g.y),
+    };
+
+    let mut acc = Point::constant(H);
+
+    for i in (0..256).rev() {
+        if i < 255 {
+            acc = double(cs, &p, &acc);
+        }
+
+        // the point to add is `Q + b1 * (G - Q) + b1 * b2 * (G + Q - G)`, which is `G`, `Q` or
+        // `G + Q` unless both bits are zero
+        let (b1, b2) = (&u1[i], &u2[i]);
+        let b1_b2 = cs.mul(b1, b2);
+        let select = |cs: &mut ConstraintSystem<T>,
+                      q: &Integer<T>,
+                      g_minus_q: &Integer<T>,
+                      gq_minus_g: &Integer<T>| {
+            q.add(&g_minus_q.mul_bit(cs, b1))
+                .add(&gq_minus_g.mul_bit(cs, &b1_b2))
+        };
+        let point = Point {
+            x: select(cs, &q.x, &g_minus_q.x, &gq_minus_g.x),
+            y: select(cs, &q.y, &g_minus_q.y, &gq_minus_g.y),
+        };
+
+        let sum = add(cs, &p, &acc, &point);
+
+        let any = b1.clone() + b2.clone() - b1_b2;
+        acc = Point {
+            x: acc.x.add(&p.sub(&sum.x, &acc.x).mul_bit(cs, &any)),
+            y: acc.y.add(&p.sub(&sum.y, &acc.y).mul_bit(cs, &any)),
+        };
+    }
+
+    // subtract the offset
+    let d = Point::<T>::constant(D);
+    let minus_d = Point {
+        x: d.x,
+        y: p.constant(&p.negate(&parse(D.1))),
+    };
+    let lambda = slope(cs, &p, &acc, &minus_d);
+    let x = sum_x(cs, &p, &lambda, &acc, &minus_d.x);
+
+    p.enforce_reduced(cs, &x);
+
+    // reduce `x` modulo `n`. As `x` is reduced modulo `p`, this subtracts `n` at most once.
+    let x_mod_n = n.normalize(cs, &x);
+
+    let r_is_reduced = r.is_less_than(cs, n.value());
+
+    x_mod_n
+        .limbs()
+        .iter()
+        .zip(r.limbs())
+        .fold(r_is_reduced, |acc, (x, r)| {
+            let is_zero = is_zero(cs, x.clone() - r.clone());
+            cs.mul(&acc, &is_zero)
+        })
+}
+
+/// Generate the constraints of the verification. The inputs are the variables `1..=INPUT_SIZE`,
+/// and the output is `1` if the signature is valid and `0` otherwise.
+pub fn generate_ecdsa_secp256k1_verify_constraints<T: Field>() -> (R1CS<T>, Vec<(usize, T)>) {
+    let mut cs = ConstraintSystem::constraints();
+    let output = verify(&mut cs, None).into_vec();
+    (cs.into_r1cs(), output)
+}
+
+/// Compute the values of all variables of the verification of `inputs`, starting with the
+/// constant `1`
+pub fn generate_ecdsa_secp256k1_verify_witness<T: Field>(inputs: &[T]) -> Vec<T> {
+    assert_eq!(inputs.len(), INPUT_SIZE);
+    let mut cs = ConstraintSystem::witness();
+    verify(&mut cs, Some(inputs));
+    cs.into_witness()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::tests::is_satisfied;
+    use std::convert::TryFrom;
+    use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field};
+
+    struct Signature {
+        z: &'static str,
+        r: &'static str,
+        s: &'static str,
+        q: (&'static str, &'static str),
+    }
+
+    /// The example transaction of EIP-155, signed with the private key 0x4646...46
+    const EIP_155: Signature = Signature {
+        z: "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53",
+        r: "28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276",
+        s: "67cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        q: (
+            "4bc2a31265153f07e70e0bab08724e6b85e217f8cd628ceb62974247bb493382",
+            "ce28cab79ad7119ee1ad3ebcdb98a16805211530ecc6cfefa1b88e6dff99232a",
+        ),
+    };
+
+    /// A signature of sha256("zokrates") with the private key sha256("zokrates secp256k1 test key 1")
+    /// and the nonce sha256("zokrates secp256k1 test nonce 1")
+    const FIRST: Signature = Signature {
+        z: "4587a26623dcac8c3020ea45386040468a73a5d555b4426f38660eb4a0b92632",
+        r: "8154114351e7d8ddc6fbb68ff469d1ec62902306de483831a72206816b44de03",
+        s: "c85c09babdb2e87c911a55b26a944e221792ef7b61d40f0fdf9a805b3d7a7f56",
+        q: (
+            "2575d40364078bc75b0ac26d9b6418f82e1b27a8bbdcc6d28fdb785894bea70e",
+            "09b1b660c9341f897cb76a64add328fe9b47349ad3cfb30fe825709c77ba404d",
+        ),
+    };
+
+    /// A signature of sha256("secp256k1") with the private key
+    /// sha256("zokrates secp256k1 test key 2") and the nonce sha256("zokrates secp256k1 test nonce 2")
+    const SECOND: Signature = Signature {
+        z: "383b27532153f353fa4cc689239f7365dfe924ebcf67807eb6916307a4e2701e",
+        r: "233440646f0ed17714c719975e7f65cfd63a018e7eb63efa3386541692d9ce1a",
+        s: "4c58454f3b31e28f77f066cc52bccbc42d988a524d644590f392a50a75f29131",
+        q: (
+            "4bcea19b15df824c8b9e408e5f17f6d99a01c682844fee39d4be859b3cbba0b5",
+            "6bab6d8cd1850946c64e73cdd2e1d42227c13c9015c87a90da7baeb489d1ec46",
+        ),
+    };
+
+    fn to_words<T: Field>(value: &BigUint) -> Vec<T> {
+        (0..LIMB_COUNT)
+            .rev()
+            .map(|i| {
+                let word = (value >> (64 * i)) % (BigUint::from(1u32) << 64);
+                T::try_from(word).unwrap()
+            })
+            .collect()
+    }
+
+    fn inputs<T: Field>(z: &BigUint, r: &BigUint, s: &BigUint, q: (&str, &str)) -> Vec<T> {
+        vec![z, r, s, &parse(q.0), &parse(q.1)]
+            .into_iter()
+            .flat_map(to_words)
+            .collect()
+    }
+
+    fn eval<T: Field>(lc: &[(usize, T)], witness: &[T]) -> T {
+        lc.iter()
+            .fold(T::zero(), |acc, (i, c)| acc + witness[*i].clone() * c)
+    }
+
+    fn verify_signatures<T: Field>() {
+        let (r1cs, output) = generate_ecdsa_secp256k1_verify_constraints::<T>();
+        assert_eq!(r1cs.aux_count + 1, variable_count());
+
+        let signatures = [EIP_155, FIRST, SECOND];
+
+        for (i, signature) in signatures.iter().enumerate() {
+            let (z, r, s) = (parse(signature.z), parse(signature.r), parse(signature.s));
+            let q = signature.q;
+
+            let cases = vec![
+                (inputs(&z, &r, &s, q), 1),
+                // a different hash
+                (inputs(&(&z + 1u32), &r, &s, q), 0),
+                // `r` is not reduced modulo `n`
+                (inputs(&z, &parse(N), &s, q), 0),
+                // the key of another signature
+                (inputs(&z, &r, &s, signatures[(i + 1) % 3].q), 0),
+            ];
+
+            for (inputs, expected) in cases {
+                let witness = generate_ecdsa_secp256k1_verify_witness(&inputs);
+                assert!(is_satisfied(&r1cs, &witness));
+                assert_eq!(eval(&output, &witness), T::from(expected));
+            }
+        }
+    }
+
+    #[test]
+    fn bn128() {
+        verify_signatures::<Bn128Field>();
+    }
+
+    #[test]
+    fn bls12_381() {
+        verify_signatures::<Bls12_381Field>();
+    }
+
+    #[test]
+    fn bls12_377() {
+        verify_signatures::<Bls12_377Field>();
+    }
+
+    #[test]
+    fn bw6_761() {
+        verify_signatures::<Bw6_761Field>();
+    }
+}
@@ -0,0 +1,291 @@
+//! The compression function of SHA-256 (https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf)
+//!
+//! Words are represented by their bits in big endian order like `u32_to_bits`. The function
+//! allocates as many variables as the `sapling-crypto` gadget used with `bellman` and has at most
+//! as many constraints, but does not check the inputs to be booleans.
+
+use crate::native::{pack, ConstraintSystem, LinearCombination};
+use crate::R1CS;
+use zokrates_field::Field;
+
+pub const WORD_SIZE: usize = 32;
+/// The number of input bits: the message block followed by the current hash
+pub const INPUT_SIZE: usize = (16 + 8) * WORD_SIZE;
+/// The number of output bits: the new hash
+pub const OUTPUT_SIZE: usize = 8 * WORD_SIZE;
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The number of variables of the compression function, including the constant `1`
+pub fn variable_count() -> usize {
+    // each word of the message schedule takes 61 bits for `s0`, 54 for `s1` and 34 for the sum
+    let schedule = 48 * (61 + 54 + 34);
+    // each round takes 2 xors per bit for `S1` and `S0`, 1 variable per bit for `ch` and 2 for
+    // `maj`. The new `a` and `e` are sums of 35 bits, except in the last round where they are
+    // added to the current hash directly.
+    let rounds = 64 * (2 + 1 + 2 + 2) * WORD_SIZE + 63 * 2 * 35;
+    // adding the current hash takes 33 bits per word, and 35 for `a` and `e`
+    let output = 6 * 33 + 2 * 35;
+    1 + INPUT_SIZE + schedule + rounds + output
+}
+
+/// A word, with its bits in little endian order so that bit `i` is `word[i]`
+type Word<T> = Vec<LinearCombination<T>>;
+
+fn xor<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    a: &[LinearCombination<T>],
+    b: &[LinearCombination<T>],
+) -> Word<T> {
+    a.iter().zip(b).map(|(a, b)| cs.xor(a, b)).collect()
+}
+
+fn rotr<T: Field>(word: &[LinearCombination<T>], n: usize) -> Word<T> {
+    (0..WORD_SIZE)
+        .map(|i| word[(i + n) % WORD_SIZE].clone())
+        .collect()
+}
+
+fn shr<T: Field>(word: &[LinearCombination<T>], n: usize) -> Word<T> {
+    (0..WORD_SIZE)
+        .map(|i| match i + n < WORD_SIZE {
+            true => word[i + n].clone(),
+            false => LinearCombination::zero(),
+        })
+        .collect()
+}
+
+/// Add words and a constant modulo `2^32` by decomposing their sum into bits
+fn add<T: Field>(cs: &mut ConstraintSystem<T>, words: &[&Word<T>], constant: u32) -> Word<T> {
+    let sum = words.iter().fold(
+        LinearCombination::constant(T::from(constant)),
+        |acc, word| acc + pack(word),
+    );
+
+    // the sum fits in as many bits as its maximum value
+    let max = words.len() as u64 * u32::MAX as u64 + constant as u64;
+    let bit_count = (u64::BITS - max.leading_zeros()) as usize;
+
+    let mut bits = cs.decompose(sum, bit_count);
+    bits.truncate(WORD_SIZE);
+    bits
+}
+
+/// Apply the compression function to the inputs made of the variables `1..=INPUT_SIZE`,
+/// returning the bits of the new hash
+fn compression<T: Field>(
+    cs: &mut ConstraintSystem<T>,
+    values: Option<&[T]>,
+) -> Vec<LinearCombination<T>> {
+    // the input bits are not checked to be booleans, they are expected to be checked by the caller
+    let bits: Vec<_> = (0..INPUT_SIZE)
+        .map(|i| LinearCombination::variable(cs.alloc(values.map(|v| v[i].clone()))))
+        .collect();
+
+    let mut w: Vec<Word<T>> = bits
+        .chunks(WORD_SIZE)
+        .map(|word| word.iter().rev().cloned().collect())
+        .collect();
+    let current_hash = w.split_off(16);
+
+    // message schedule
+    for t in 16..64 {
+        let s0 = xor(cs, &rotr(&w[t - 15], 7), &rotr(&w[t - 15], 18));
+        let s0 = xor(cs, &s0, &shr(&w[t - 15], 3));
+        let s1 = xor(cs, &rotr(&w[t - 2], 17), &rotr(&w[t - 2], 19));
+        let s1 = xor(cs, &s1, &shr(&w[t - 2], 10));
+        let word = add(cs, &[&w[t - 16], &s0, &w[t - 7], &s1], 0);
+        w.push(word);
+    }
+
+    let mut state = current_hash.clone();
+
+    for (t, k) in K.iter().enumerate() {
+        let (a, b, c, d, e, f, g, h) = (
+            &state[0], &state[1], &state[2], &state[3], &state[4], &state[5], &state[6], &state[7],
+        );
+
+        let s1 = xor(cs, &rotr(e, 6), &rotr(e, 11));
+        let s1 = xor(cs, &s1, &rotr(e, 25));
+        // ch = (e & f) ^ (!e & g) = e * (f - g) + g
+        let ch: Word<T> = (0..WORD_SIZE)
+            .map(|i| cs.mul(&e[i], &(f[i].clone() - g[i].clone())) + g[i].clone())
+            .collect();
+        let s0 = xor(cs, &rotr(a, 2), &rotr(a, 13));
+        let s0 = xor(cs, &s0, &rotr(a, 22));
+        // maj = (a & b) ^ (a & c) ^ (b & c) = a * (b + c - 2bc) + bc
+        let maj: Word<T> = (0..WORD_SIZE)
+            .map(|i| {
+                let bc = cs.mul(&b[i], &c[i]);
+                let b_xor_c = b[i].clone() + c[i].clone() - bc.scale(&T::from(2));
+                cs.mul(&a[i], &b_xor_c) + bc
+            })
+            .collect();
+
+        let mut new_e = vec![d, h, &s1, &ch, &w[t]];
+        let mut new_a = vec![h, &s1, &ch, &w[t], &s0, &maj];
+
+        if t == K.len() - 1 {
+            // the new `a` and `e` are added to the current hash without being decomposed first
+            new_a.push(&current_hash[0]);
+            new_e.push(&current_hash[4]);
+
+            let new_hash = vec![
+                (new_a, *k),
+                (vec![a, &current_hash[1]], 0),
+                (vec![b, &current_hash[2]], 0),
+                (vec![c, &current_hash[3]], 0),
+                (new_e, *k),
+                (vec![e, &current_hash[5]], 0),
+                (vec![f, &current_hash[6]], 0),
+                (vec![g, &current_hash[7]], 0),
+            ];
+
+            return new_hash
+                .into_iter()
+                .flat_map(|(words, constant)| add(cs, &words, constant).into_iter().rev())
+                .collect();
+        }
+
+        state = vec![
+            add(cs, &new_a, *k),
+            a.clone(),
+            b.clone(),
+            c.clone(),
+            add(cs, &new_e, *k),
+            e.clone(),
+            f.clone(),
+            g.clone(),
+        ];
+    }
+
+    unreachable!()
+}
+
+/// Generate the constraints of the compression function. The input bits are the variables
+/// `1..=INPUT_SIZE`, and the output bits are returned as linear combinations of the variables.
+pub fn generate_sha256_round_constraints<T: Field>() -> (R1CS<T>, Vec<Vec<(usize, T)>>) {
+    let mut cs = ConstraintSystem::constraints();
+    let outputs = compression(&mut cs, None)
+        .into_iter()
+        .map(LinearCombination::into_vec)
+        .collect();
+    (cs.into_r1cs(), outputs)
+}
+
+/// Compute the values of all variables of the compression function of `inputs`, starting with
+/// the constant `1`
+pub fn generate_sha256_round_witness<T: Field>(inputs: &[T]) -> Vec<T> {
+    assert_eq!(inputs.len(), INPUT_SIZE);
+    let mut cs = ConstraintSystem::witness();
+    compression(&mut cs, Some(inputs));
+    cs.into_witness()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::native::tests::is_satisfied;
+    use zokrates_field::Bn128Field;
+
+    const IV: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    /// A straightforward implementation of the compression function over `u32` words
+    fn sha256_compression(m: [u32; 16], h: [u32; 8]) -> [u32; 8] {
+        let mut w = [0u32; 64];
+        w[..16].copy_from_slice(&m);
+        for t in 16..64 {
+            let s0 = w[t - 15].rotate_right(7) ^ w[t - 15].rotate_right(18) ^ (w[t - 15] >> 3);
+            let s1 = w[t - 2].rotate_right(17) ^ w[t - 2].rotate_right(19) ^ (w[t - 2] >> 10);
+            w[t] = w[t - 16]
+                .wrapping_add(s0)
+                .wrapping_add(w[t - 7])
+                .wrapping_add(s1);
+        }
+
+        let mut v = h;
+        for (k, w) in K.iter().zip(w.iter()) {
+            let [a, b, c, d, e, f, g, h] = v;
+            let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+            let ch = (e & f) ^ (!e & g);
+            let t1 = h
+                .wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(*w);
+            let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+            let maj = (a & b) ^ (a & c) ^ (b & c);
+            let t2 = s0.wrapping_add(maj);
+            v = [t1.wrapping_add(t2), a, b, c, d.wrapping_add(t1), e, f, g];
+        }
+
+        let mut res = h;
+        for (r, v) in res.iter_mut().zip(v.iter()) {
+            *r = r.wrapping_add(*v);
+        }
+        res
+    }
+
+    fn to_bits(words: &[u32]) -> Vec<Bn128Field> {
+        words
+            .iter()
+            .flat_map(|word| (0..WORD_SIZE).rev().map(move |i| (word >> i) & 1 == 1))
+            .map(Bn128Field::from)
+            .collect()
+    }
+
+    #[test]
+    fn matches_reference() {
+        let (r1cs, outputs) = generate_sha256_round_constraints::<Bn128Field>();
+        assert_eq!(r1cs.aux_count + 1, variable_count());
+        assert_eq!(outputs.len(), OUTPUT_SIZE);
+
+        // the padded message "abc"
+        let mut abc = [0u32; 16];
+        abc[0] = 0x61626380;
+        abc[15] = 0x18;
+
+        for (m, h) in vec![(abc, IV), ([0xffffffff; 16], [0xffffffff; 8])] {
+            let inputs: Vec<_> = to_bits(&m).into_iter().chain(to_bits(&h)).collect();
+
+            let witness = generate_sha256_round_witness(&inputs);
+            assert!(is_satisfied(&r1cs, &witness));
+
+            let result: Vec<_> = outputs
+                .iter()
+                .map(|bit| {
+                    bit.iter().fold(Bn128Field::from(0), |acc, (index, c)| {
+                        acc + witness[*index].clone() * c
+                    })
+                })
+                .collect();
+
+            assert_eq!(result, to_bits(&sha256_compression(m, h)));
+        }
+
+        assert_eq!(sha256_compression(abc, IV)[0], 0xba7816bf);
+    }
+
+    #[test]
+    fn no_more_constraints_than_bellman() {
+        use crate::bellman::generate_sha256_round_constraints as generate_bellman_constraints;
+        use sapling_crypto_ce::bellman::pairing::bn256::Bn256;
+
+        let (r1cs, _) = generate_sha256_round_constraints::<Bn128Field>();
+        let (bellman_r1cs, ..) = generate_bellman_constraints::<Bn256>();
+
+        assert!(r1cs.constraints.len() <= bellman_r1cs.constraints.len());
+    }
+}
@@ -1,21 +1,68 @@
-use std::fs::read_to_string;
+use std::collections::HashMap;
+use std::fs::{read_dir, read_to_string};
 use std::io;
 
 use std::path::Path;
 use std::path::{Component, PathBuf};
-use zokrates_common::Resolver;
+use zokrates_common::{suggest, Resolver};
 
 #[derive(Debug, Default)]
 pub struct FileSystemResolver<'a> {
     stdlib_root_path: Option<&'a str>,
+    dependencies: HashMap<String, PathBuf>,
 }
 
 impl<'a> FileSystemResolver<'a> {
     pub fn with_stdlib_root(stdlib_root_path: &'a str) -> Self {
         FileSystemResolver {
             stdlib_root_path: Some(stdlib_root_path),
+            dependencies: HashMap::new(),
         }
     }
+
+    /// Make the dependencies available to imports: `dep_name/path` resolves to `path` inside the
+    /// directory of the dependency called `dep_name`.
+    /// Fails if a dependency is named like a top level module or directory of the standard
+    /// library, which it would otherwise hide.
+    pub fn with_dependencies(mut self, dependencies: HashMap<String, PathBuf>) -> io::Result<Self> {
+        if let Some(stdlib_root_path) = self.stdlib_root_path {
+            let stdlib_root = Path::new(stdlib_root_path);
+
+            let mut names: Vec<_> = dependencies.keys().collect();
+            names.sort();
+
+            if let Some(name) = names.into_iter().find(|name| {
+                stdlib_root.join(name).exists()
+                    || stdlib_root.join(name).with_extension("zok").is_file()
+            }) {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "Dependency `{}` has the same name as a module of the standard library",
+                        name
+                    ),
+                ));
+            }
+        }
+
+        self.dependencies = dependencies;
+        Ok(self)
+    }
+}
+
+/// The module in the directory of `path` whose name is the closest to that of `path`, if any
+fn similar_module(path: &Path) -> Option<String> {
+    let name = path.file_stem()?.to_str()?;
+
+    let modules: Vec<String> = read_dir(path.parent()?)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().map(|e| e == "zok").unwrap_or(false))
+        .filter_map(|path| path.file_stem()?.to_str().map(String::from))
+        .collect();
+
+    suggest(name, modules.iter().map(String::as_str)).map(String::from)
 }
 
 impl<'a> Resolver<io::Error> for FileSystemResolver<'a> {
@@ -27,8 +74,10 @@ impl<'a> Resolver<io::Error> for FileSystemResolver<'a> {
         let source = Path::new(&import_location);
 
         // paths starting with `./` or `../` are interpreted relative to the current file
+        // paths `dep/abc/def` are interpreted relative to the root of the dependency `dep`
         // other paths `abc/def` are interpreted relative to the standard library root path
-        let base = match source.components().next() {
+        let mut components = source.components();
+        let (base, relative_location) = match components.next() {
             Some(Component::CurDir) | Some(Component::ParentDir) => {
                 if !current_location.is_file() {
                     return Err(io::Error::new(
@@ -36,18 +85,29 @@ impl<'a> Resolver<io::Error> for FileSystemResolver<'a> {
                         format!("{} was expected to be a file", current_location.display()),
                     ));
                 }
-                current_location.parent().unwrap().into()
+                (current_location.parent().unwrap().into(), source)
             }
-            _ => PathBuf::from(self.stdlib_root_path.unwrap_or("")),
+            Some(Component::Normal(name)) => {
+                match name.to_str().and_then(|name| self.dependencies.get(name)) {
+                    Some(dependency_root) => (dependency_root.clone(), components.as_path()),
+                    None => (PathBuf::from(self.stdlib_root_path.unwrap_or("")), source),
+                }
+            }
+            _ => (PathBuf::from(self.stdlib_root_path.unwrap_or("")), source),
         };
 
-        let path_owned = base.join(import_location.clone()).with_extension("zok");
+        let path_owned = base.join(relative_location).with_extension("zok");
 
         if !path_owned.is_file() {
-            return Err(io::Error::new(
-                io::ErrorKind::Other,
-                format!("No file found at {}", import_location.display()),
-            ));
+            let message = match similar_module(&path_owned) {
+                Some(module) => format!(
+                    "No file found at {}, did you mean {}?",
+                    import_location.display(),
+                    import_location.with_file_name(module).display()
+                ),
+                None => format!("No file found at {}", import_location.display()),
+            };
+            return Err(io::Error::new(io::ErrorKind::Other, message));
         }
 
         let source = read_to_string(&path_owned)?;
@@ -202,4 +262,82 @@ mod tests {
         let result = fs_resolver.resolve("/path/to/source.zok".into(), "bar.zok".into());
         assert!(result.is_err());
     }
+
+    #[test]
+    fn suggest_similar_module() {
+        let stdlib_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(stdlib_dir.path().join("hashes")).unwrap();
+        File::create(stdlib_dir.path().join("hashes").join("pedersen.zok")).unwrap();
+
+        let fs_resolver = FileSystemResolver::with_stdlib_root(stdlib_dir.path().to_str().unwrap());
+        let error = fs_resolver
+            .resolve("main.zok".into(), "hashes/pedersn".into())
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "No file found at hashes/pedersn, did you mean hashes/pedersen?"
+        );
+    }
+
+    #[test]
+    fn resolve_dependency() {
+        use std::io::Write;
+
+        // create a stdlib folder and a dependency folder with a code file of the same name
+        let stdlib_dir = tempfile::tempdir().unwrap();
+        let mut file = File::create(stdlib_dir.path().join("bar.zok")).unwrap();
+        writeln!(file, "<stdlib code>").unwrap();
+
+        let dependency_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dependency_dir.path().join("utils")).unwrap();
+        let mut file = File::create(dependency_dir.path().join("utils").join("bar.zok")).unwrap();
+        writeln!(file, "<dependency code>").unwrap();
+
+        let mut dependencies = HashMap::new();
+        dependencies.insert(String::from("dep"), dependency_dir.path().to_path_buf());
+
+        let fs_resolver = FileSystemResolver::with_stdlib_root(stdlib_dir.path().to_str().unwrap())
+            .with_dependencies(dependencies)
+            .unwrap();
+
+        let (source, location) = fs_resolver
+            .resolve("main.zok".into(), "dep/utils/bar".into())
+            .unwrap();
+        assert_eq!(source, String::from("<dependency code>\n"));
+        assert_eq!(
+            location,
+            dependency_dir.path().join("utils").join("bar.zok")
+        );
+
+        // paths which do not start with the name of a dependency still resolve to the stdlib
+        let (source, _) = fs_resolver
+            .resolve("main.zok".into(), "bar".into())
+            .unwrap();
+        assert_eq!(source, String::from("<stdlib code>\n"));
+    }
+
+    #[test]
+    fn dependency_shadowing_stdlib() {
+        let stdlib_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(stdlib_dir.path().join("hashes")).unwrap();
+        File::create(stdlib_dir.path().join("bar.zok")).unwrap();
+
+        let dependency_dir = tempfile::tempdir().unwrap();
+
+        for name in &["hashes", "bar"] {
+            let mut dependencies = HashMap::new();
+            dependencies.insert(String::from(*name), dependency_dir.path().to_path_buf());
+
+            let error = FileSystemResolver::with_stdlib_root(stdlib_dir.path().to_str().unwrap())
+                .with_dependencies(dependencies)
+                .unwrap_err();
+            assert_eq!(
+                error.to_string(),
+                format!(
+                    "Dependency `{}` has the same name as a module of the standard library",
+                    name
+                )
+            );
+        }
+    }
 }
@@ -41,10 +41,15 @@ pub struct ComputationResult {
 #[inline]
 fn deserialize_program(value: &[u8]) -> Result<ir::Prog<Bn128Field>, JsValue> {
     let prog = ir::ProgEnum::deserialize(value).map_err(|err| JsValue::from_str(&err))?;
-    match prog {
-        ir::ProgEnum::Bn128Program(p) => Ok(p.collect()),
-        _ => Err(JsValue::from_str("Unsupported binary")),
-    }
+    let read_error = prog.read_error();
+    let prog = match prog {
+        ir::ProgEnum::Bn128Program(p) => p.collect(),
+        _ => return Err(JsValue::from_str("Unsupported binary")),
+    };
+    read_error
+        .check()
+        .map(|_| prog)
+        .map_err(|err| JsValue::from_str(&err))
 }
 
 #[inline]
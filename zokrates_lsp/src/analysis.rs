@@ -0,0 +1,747 @@
+//! Module containing the `Analysis` of the open documents
+//!
+//! Each request is answered from scratch: the document is indexed, and the modules it imports are
+//! loaded through a `FileSystemResolver`, except for the ones which are open in the editor, which
+//! are read from memory so that unsaved changes are taken into account.
+
+use crate::index::{Definition, ImportedSymbol, Index, Span, SymbolKind, Target};
+use crate::text;
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionTextEdit, Diagnostic, DiagnosticSeverity, Hover,
+    HoverContents, Location, MarkupContent, MarkupKind, Position, Range, TextEdit, Url,
+};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use zokrates_common::Resolver;
+use zokrates_core::compile::{check, CompileConfig, CompileError, CompileErrorInner};
+use zokrates_field::{Bls12_377Field, Bls12_381Field, Bn128Field, Bw6_761Field, Field};
+use zokrates_fs_resolver::FileSystemResolver;
+
+/// The number of imports followed when resolving a symbol, to stay clear of cycles
+const MAX_DEPTH: usize = 16;
+
+/// The identifier inserted at the cursor to make incomplete code parse during completion
+const PLACEHOLDER: &str = "__zokrates_completion";
+
+/// Normalise a path so that paths to the same file compare equal
+pub fn normalize(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+#[derive(Debug, Clone)]
+struct Module {
+    path: PathBuf,
+    source: String,
+    index: Index,
+}
+
+impl Module {
+    fn location(&self, span: Span) -> Option<Location> {
+        Some(Location::new(
+            Url::from_file_path(&self.path).ok()?,
+            text::range(&self.source, span),
+        ))
+    }
+}
+
+pub struct Analysis<'a> {
+    stdlib: &'a str,
+    documents: &'a HashMap<PathBuf, String>,
+}
+
+impl<'a> Resolver<io::Error> for Analysis<'a> {
+    fn resolve(
+        &self,
+        current_location: PathBuf,
+        import_location: PathBuf,
+    ) -> Result<(String, PathBuf), io::Error> {
+        let (source, path) = FileSystemResolver::with_stdlib_root(self.stdlib)
+            .resolve(current_location, import_location)?;
+        let path = normalize(&path);
+
+        match self.documents.get(&path) {
+            Some(document) => Ok((document.clone(), path)),
+            None => Ok((source, path)),
+        }
+    }
+}
+
+impl<'a> Analysis<'a> {
+    pub fn new(stdlib: &'a str, documents: &'a HashMap<PathBuf, String>) -> Self {
+        Analysis { stdlib, documents }
+    }
+
+    fn source(&self, path: &Path) -> Option<String> {
+        self.documents
+            .get(path)
+            .cloned()
+            .or_else(|| fs::read_to_string(path).ok())
+    }
+
+    fn module(&self, path: PathBuf, source: String, depth: usize) -> Option<Module> {
+        let index = Index::build(&source, &mut |import| {
+            self.resolve_symbol(&path, import, depth + 1)
+                .map(|(module, id)| module.index.definitions[id].clone())
+        })
+        .ok()?;
+
+        Some(Module {
+            path,
+            source,
+            index,
+        })
+    }
+
+    fn current(&self, path: &Path) -> Option<Module> {
+        self.module(path.to_path_buf(), self.source(path)?, 0)
+    }
+
+    fn import(&self, from: &Path, source: &str, depth: usize) -> Option<Module> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+
+        let (source, path) = Resolver::resolve(self, from.to_path_buf(), source.into()).ok()?;
+        self.module(path, source, depth)
+    }
+
+    /// The module and the definition an imported symbol originates from
+    fn resolve_symbol(
+        &self,
+        from: &Path,
+        import: &ImportedSymbol,
+        depth: usize,
+    ) -> Option<(Module, usize)> {
+        let module = self.import(from, &import.source, depth)?;
+        let id = module.index.symbol(&import.symbol)?;
+
+        match module.index.definitions[id].import.clone() {
+            Some(import) => self.resolve_symbol(&module.path, &import, depth + 1),
+            None => Some((module, id)),
+        }
+    }
+
+    /// The module and the definition `id` of `module` originates from
+    fn origin(&self, module: &Module, id: usize) -> Option<(Module, usize)> {
+        match &module.index.definitions[id].import {
+            Some(import) => self.resolve_symbol(&module.path, import, 0),
+            None => Some((module.clone(), id)),
+        }
+    }
+
+    /// The module and the struct definition a member target points into
+    fn parent(&self, module: &Module, parent: usize) -> Option<(Module, usize)> {
+        self.origin(module, parent)
+            .filter(|(m, id)| m.index.definitions[*id].kind == SymbolKind::Struct)
+    }
+
+    /// Where `target` is defined, as a module and a target in that module
+    fn definition_of(&self, module: &Module, target: Target) -> Option<(Module, Target)> {
+        match target {
+            Target::Definition(id) => self
+                .origin(module, id)
+                .map(|(m, id)| (m, Target::Definition(id))),
+            Target::Member { parent, name } => self
+                .parent(module, parent)
+                .map(|(m, parent)| (m, Target::Member { parent, name })),
+        }
+    }
+
+    pub fn diagnostics(&self, path: &Path) -> Vec<Diagnostic> {
+        let source = match self.source(path) {
+            Some(source) => source,
+            None => return vec![],
+        };
+
+        let curve = zokrates_pest_ast::generate_ast(&source)
+            .ok()
+            .and_then(|f| f.pragma)
+            .map(|p| p.curve.name);
+
+        let errors = match curve.as_deref() {
+            Some(name) if name == Bls12_381Field::name() => {
+                self.check::<Bls12_381Field>(source, path)
+            }
+            Some(name) if name == Bls12_377Field::name() => {
+                self.check::<Bls12_377Field>(source, path)
+            }
+            Some(name) if name == Bw6_761Field::name() => self.check::<Bw6_761Field>(source, path),
+            _ => self.check::<Bn128Field>(source, path),
+        };
+
+        errors.iter().map(|e| diagnostic(path, e)).collect()
+    }
+
+    fn check<T: Field>(&self, source: String, path: &Path) -> Vec<CompileError> {
+        let config = CompileConfig::default();
+
+        match check::<T, _>(source, path.to_path_buf(), Some(self), &config) {
+            Ok(()) => vec![],
+            Err(e) => e.0,
+        }
+    }
+
+    pub fn hover(&self, path: &Path, offset: usize) -> Option<Hover> {
+        let module = self.current(path)?;
+        let (span, target) = module.index.target_at(offset)?;
+
+        let detail = match self.definition_of(&module, target.clone()) {
+            Some((m, Target::Definition(id))) => m.index.definitions[id].detail.clone(),
+            Some((m, Target::Member { parent, name })) => m.index.definitions[parent]
+                .fields
+                .iter()
+                .find(|f| f.name == name)?
+                .detail
+                .clone(),
+            // the origin of the symbol cannot be found, fall back to what is known locally
+            None => match target {
+                Target::Definition(id) => module.index.definitions[id].detail.clone(),
+                Target::Member { .. } => return None,
+            },
+        };
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: format!("```zokrates\n{}\n```", detail),
+            }),
+            range: Some(text::range(&module.source, span)),
+        })
+    }
+
+    pub fn definition(&self, path: &Path, offset: usize) -> Option<Location> {
+        let module = self.current(path)?;
+
+        if let Some(source) = module.index.import_at(offset) {
+            let imported = self.import(path, source, 0)?;
+            return imported.location((0, 0));
+        }
+
+        let (_, target) = module.index.target_at(offset)?;
+
+        match self.definition_of(&module, target.clone()) {
+            Some((m, Target::Definition(id))) => m.location(m.index.definitions[id].span),
+            Some((m, Target::Member { parent, name })) => {
+                let field = m.index.definitions[parent]
+                    .fields
+                    .iter()
+                    .find(|f| f.name == name)?;
+                m.location(field.span)
+            }
+            None => match target {
+                Target::Definition(id) => module.location(module.index.definitions[id].span),
+                Target::Member { .. } => None,
+            },
+        }
+    }
+
+    /// The references to the symbol at `offset`, in this document and in the other open
+    /// documents for module level symbols
+    pub fn references(&self, path: &Path, offset: usize, declaration: bool) -> Vec<Location> {
+        let module = match self.current(path) {
+            Some(module) => module,
+            None => return vec![],
+        };
+
+        let (_, target) = match module.index.target_at(offset) {
+            Some(target) => target,
+            None => return vec![],
+        };
+
+        let (origin, target) = match self.definition_of(&module, target) {
+            Some(origin) => origin,
+            None => return vec![],
+        };
+
+        let is_local = match &target {
+            Target::Definition(id) => !origin.index.definitions[*id].kind.is_module_level(),
+            Target::Member { .. } => false,
+        };
+
+        let mut spans: Vec<(Module, Span)> = vec![];
+
+        if declaration {
+            let span = match &target {
+                Target::Definition(id) => Some(origin.index.definitions[*id].span),
+                Target::Member { parent, name } => origin.index.definitions[*parent]
+                    .fields
+                    .iter()
+                    .find(|f| &f.name == name)
+                    .map(|f| f.span),
+            };
+            spans.extend(span.map(|span| (origin.clone(), span)));
+        }
+
+        let modules: Vec<Module> = if is_local {
+            vec![module]
+        } else {
+            let mut paths: Vec<_> = self.documents.keys().cloned().collect();
+            paths.push(origin.path.clone());
+            paths.push(path.to_path_buf());
+            paths.sort();
+            paths.dedup();
+            paths.into_iter().filter_map(|p| self.current(&p)).collect()
+        };
+
+        for m in modules {
+            let found = self.references_in(&m, &origin, &target);
+            spans.extend(found.into_iter().map(|span| (m.clone(), span)));
+        }
+
+        spans
+            .into_iter()
+            .filter_map(|(m, span)| m.location(span))
+            .collect()
+    }
+
+    /// The spans in `module` which refer to `target` in `origin`, including the imports of it
+    fn references_in(&self, module: &Module, origin: &Module, target: &Target) -> Vec<Span> {
+        let path = normalize(&module.path);
+        let origin_path = normalize(&origin.path);
+
+        // where each definition of the module originates from
+        let origins: Vec<_> = module
+            .index
+            .definitions
+            .iter()
+            .enumerate()
+            .map(|(id, d)| match &d.import {
+                Some(_) => self
+                    .origin(module, id)
+                    .map(|(m, id)| (normalize(&m.path), id)),
+                None => Some((path.clone(), id)),
+            })
+            .collect();
+
+        let is_target =
+            |id: usize, target: usize| origins[id] == Some((origin_path.clone(), target));
+
+        let mut spans: Vec<_> = module
+            .index
+            .references
+            .iter()
+            .filter(|r| match (&r.target, target) {
+                (Target::Definition(id), Target::Definition(target)) => is_target(*id, *target),
+                (
+                    Target::Member { parent, name },
+                    Target::Member {
+                        parent: target_parent,
+                        name: target_name,
+                    },
+                ) => name == target_name && is_target(*parent, *target_parent),
+                _ => false,
+            })
+            .map(|r| r.span)
+            .collect();
+
+        // imports of the target are references too
+        if let Target::Definition(target) = target {
+            spans.extend(
+                module
+                    .index
+                    .definitions
+                    .iter()
+                    .enumerate()
+                    .filter(|(id, d)| d.import.is_some() && is_target(*id, *target))
+                    .map(|(_, d)| d.span),
+            );
+        }
+
+        spans
+    }
+
+    pub fn completion(&self, path: &Path, offset: usize) -> Vec<CompletionItem> {
+        let source = match self.source(path) {
+            Some(source) => source,
+            None => return vec![],
+        };
+
+        let line = &source[text::line_start(&source, offset)..offset];
+
+        if let Some(prefix) = import_source_prefix(line) {
+            let start = text::position(&source, offset - prefix.len());
+            let end = text::position(&source, offset);
+            return self.stdlib_modules(Range::new(start, end));
+        }
+
+        if let Some(import) = import_symbols_source(line) {
+            return self
+                .import(path, import, 0)
+                .map(|m| {
+                    m.index
+                        .definitions
+                        .iter()
+                        .filter(|d| d.kind.is_module_level())
+                        .map(completion_item)
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+
+        // make the code parse by putting an identifier in place of the word being typed
+        let start = text::word_start(&source, offset);
+        let patched = format!("{}{}{}", &source[..start], PLACEHOLDER, &source[offset..]);
+
+        let module = match self
+            .module(path.to_path_buf(), patched, 0)
+            .or_else(|| self.current(path))
+        {
+            Some(module) => module,
+            None => return vec![],
+        };
+
+        if source[..start].ends_with('.') {
+            let parent = module
+                .index
+                .references
+                .iter()
+                .find_map(|r| match &r.target {
+                    Target::Member { parent, .. } if r.span.0 == start => Some(*parent),
+                    _ => None,
+                });
+
+            return parent
+                .and_then(|parent| self.parent(&module, parent))
+                .map(|(m, id)| {
+                    m.index.definitions[id]
+                        .fields
+                        .iter()
+                        .map(|f| CompletionItem {
+                            label: f.name.clone(),
+                            kind: Some(CompletionItemKind::FIELD),
+                            detail: Some(f.detail.clone()),
+                            ..CompletionItem::default()
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+        }
+
+        module
+            .index
+            .visible_at(start)
+            .into_iter()
+            .filter(|d| d.name != PLACEHOLDER)
+            .map(completion_item)
+            .collect()
+    }
+
+    /// The modules of the standard library, replacing the import source in `range`
+    fn stdlib_modules(&self, range: Range) -> Vec<CompletionItem> {
+        let root = Path::new(self.stdlib);
+        let mut modules = vec![];
+        collect_modules(root, root, &mut modules);
+        modules.sort();
+
+        modules
+            .into_iter()
+            .map(|module| CompletionItem {
+                label: module.clone(),
+                kind: Some(CompletionItemKind::MODULE),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, module))),
+                ..CompletionItem::default()
+            })
+            .collect()
+    }
+}
+
+fn collect_modules(root: &Path, dir: &Path, modules: &mut Vec<String>) {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for path in entries.filter_map(|e| e.ok()).map(|e| e.path()) {
+        if path.is_dir() {
+            collect_modules(root, &path, modules);
+        } else if path.extension().map(|e| e == "zok").unwrap_or(false) {
+            if let Ok(relative) = path.with_extension("").strip_prefix(root) {
+                modules.push(
+                    relative
+                        .components()
+                        .map(|c| c.as_os_str().to_string_lossy())
+                        .collect::<Vec<_>>()
+                        .join("/"),
+                );
+            }
+        }
+    }
+}
+
+/// The part of the import source typed so far, if `line` ends inside an import source
+fn import_source_prefix(line: &str) -> Option<&str> {
+    let line = line.trim_start();
+
+    if !line.starts_with("from") && !line.starts_with("import") {
+        return None;
+    }
+
+    match line.matches('"').count() {
+        1 => line.rsplit('"').next(),
+        _ => None,
+    }
+}
+
+/// The source of the import, if `line` ends in the symbol list of a `from` import
+fn import_symbols_source(line: &str) -> Option<&str> {
+    let line = line.trim_start().strip_prefix("from")?.trim_start();
+    let line = line.strip_prefix('"')?;
+    let end = line.find('"')?;
+    let (source, rest) = line.split_at(end);
+
+    match rest[1..].trim_start().starts_with("import") {
+        true => Some(source),
+        false => None,
+    }
+}
+
+fn completion_item(definition: &Definition) -> CompletionItem {
+    CompletionItem {
+        label: definition.name.clone(),
+        kind: Some(match definition.kind {
+            SymbolKind::Function => CompletionItemKind::FUNCTION,
+            SymbolKind::Struct => CompletionItemKind::STRUCT,
+            SymbolKind::Constant => CompletionItemKind::CONSTANT,
+            SymbolKind::Type => CompletionItemKind::CLASS,
+            SymbolKind::Import => CompletionItemKind::REFERENCE,
+            SymbolKind::Variable | SymbolKind::Parameter => CompletionItemKind::VARIABLE,
+            SymbolKind::Generic => CompletionItemKind::TYPE_PARAMETER,
+        }),
+        detail: Some(definition.detail.clone()),
+        ..CompletionItem::default()
+    }
+}
+
+fn diagnostic(path: &Path, error: &CompileError) -> Diagnostic {
+    // positions reported by the compiler start at 1
+    let position = |line: usize, col: usize| {
+        Position::new(line.saturating_sub(1) as u32, col.saturating_sub(1) as u32)
+    };
+
+    let (range, message) = match error.value() {
+        CompileErrorInner::ParserError(e) => {
+            let (line, col) = e.line_col();
+            let start = position(line, col);
+            (Range::new(start, start), e.message())
+        }
+        CompileErrorInner::SemanticError(e) => (
+            e.pos()
+                .map(|(start, end)| {
+                    Range::new(position(start.line, start.col), position(end.line, end.col))
+                })
+                .unwrap_or_default(),
+            e.message().to_string(),
+        ),
+        CompileErrorInner::ImportError(e) => (
+            e.pos()
+                .map(|(start, end)| {
+                    Range::new(position(start.line, start.col), position(end.line, end.col))
+                })
+                .unwrap_or_default(),
+            e.message().to_string(),
+        ),
+        e => (Range::default(), e.to_string().trim().to_string()),
+    };
+
+    // errors found in imported modules are reported at the top of the document
+    let (range, message) = match normalize(error.file()) == normalize(path) {
+        true => (range, message),
+        false => (
+            Range::default(),
+            format!("{}: {}", error.file().display(), message),
+        ),
+    };
+
+    Diagnostic {
+        range,
+        severity: Some(DiagnosticSeverity::ERROR),
+        source: Some("zokrates".into()),
+        message,
+        ..Diagnostic::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+
+    fn write(dir: &Path, name: &str, source: &str) -> PathBuf {
+        let path = dir.join(name);
+        File::create(&path)
+            .unwrap()
+            .write_all(source.as_bytes())
+            .unwrap();
+        normalize(&path)
+    }
+
+    fn at(source: &str, pattern: &str) -> usize {
+        source.find(pattern).unwrap()
+    }
+
+    const LIB: &str = r#"
+struct Point {
+    field x
+    field y
+}
+
+def double(field a) -> field:
+    return 2 * a
+"#;
+
+    const MAIN: &str = r#"
+from "./lib" import Point, double
+
+def main(Point p) -> field:
+    field a = double(p.x)
+    return a + p.y
+"#;
+
+    #[test]
+    fn definition_across_imports() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = write(dir.path(), "lib.zok", LIB);
+        let main = write(dir.path(), "main.zok", MAIN);
+
+        let documents = HashMap::new();
+        let analysis = Analysis::new("", &documents);
+
+        let location = analysis.definition(&main, at(MAIN, "double(p")).unwrap();
+        assert_eq!(location.uri, Url::from_file_path(&lib).unwrap());
+        assert_eq!(
+            location.range,
+            text::range(LIB, (at(LIB, "double"), at(LIB, "double") + 6))
+        );
+
+        // members of imported structs
+        let location = analysis.definition(&main, at(MAIN, "y\n")).unwrap();
+        assert_eq!(location.uri, Url::from_file_path(&lib).unwrap());
+        assert_eq!(location.range.start, text::position(LIB, at(LIB, "y\n")));
+
+        // import sources
+        let location = analysis.definition(&main, at(MAIN, "lib")).unwrap();
+        assert_eq!(location.uri, Url::from_file_path(&lib).unwrap());
+    }
+
+    #[test]
+    fn hover() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "lib.zok", LIB);
+        let main = write(dir.path(), "main.zok", MAIN);
+
+        let documents = HashMap::new();
+        let analysis = Analysis::new("", &documents);
+
+        let hover = |offset| match analysis.hover(&main, offset).unwrap().contents {
+            HoverContents::Markup(m) => m.value,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(
+            hover(at(MAIN, "double(p")),
+            "```zokrates\ndef double(field a) -> (field)\n```"
+        );
+        assert_eq!(hover(at(MAIN, "x)")), "```zokrates\nfield x\n```");
+        assert_eq!(hover(at(MAIN, "a + p")), "```zokrates\nfield a\n```");
+    }
+
+    #[test]
+    fn references_across_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = write(dir.path(), "lib.zok", LIB);
+        let main = write(dir.path(), "main.zok", MAIN);
+
+        let mut documents = HashMap::new();
+        documents.insert(main.clone(), MAIN.to_string());
+        let analysis = Analysis::new("", &documents);
+
+        // from the definition of `double` in the library
+        let locations = analysis.references(&lib, at(LIB, "double"), true);
+        // the definition, the import and the call
+        assert_eq!(locations.len(), 3);
+
+        let locations = analysis.references(&main, at(MAIN, "x)"), false);
+        assert_eq!(locations.len(), 1);
+        assert_eq!(locations[0].uri, Url::from_file_path(&main).unwrap());
+    }
+
+    #[test]
+    fn open_documents_are_read_from_memory() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = write(dir.path(), "lib.zok", LIB);
+        let main = write(dir.path(), "main.zok", MAIN);
+
+        // `double` is renamed in the editor, without being saved
+        let mut documents = HashMap::new();
+        documents.insert(lib, LIB.replace("double", "twice"));
+        let analysis = Analysis::new("", &documents);
+
+        let diagnostics = analysis.diagnostics(&main);
+        assert!(diagnostics
+            .iter()
+            .any(|d| d.message.contains("Could not find symbol double")));
+    }
+
+    #[test]
+    fn diagnostics() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = "def main() -> field:\n    return a\n";
+        let main = write(dir.path(), "main.zok", source);
+
+        let documents = HashMap::new();
+        let analysis = Analysis::new("", &documents);
+
+        let diagnostics = analysis.diagnostics(&main);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start, Position::new(1, 11));
+
+        let source = "def main() -> field:\n    return 1 +\n";
+        let main = write(dir.path(), "main.zok", source);
+        let diagnostics = analysis.diagnostics(&main);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].range.start.line, 1);
+    }
+
+    #[test]
+    fn completion() {
+        let dir = tempfile::tempdir().unwrap();
+        write(dir.path(), "lib.zok", LIB);
+        let stdlib = dir.path().to_str().unwrap();
+
+        // members
+        let source = "from \"./lib\" import Point\n\ndef main(Point p) -> field:\n    return p.\n";
+        let main = write(dir.path(), "main.zok", source);
+        let documents = HashMap::new();
+        let analysis = Analysis::new(stdlib, &documents);
+
+        let labels = |items: Vec<CompletionItem>| {
+            let mut labels: Vec<_> = items.into_iter().map(|i| i.label).collect();
+            labels.sort();
+            labels
+        };
+
+        let items = analysis.completion(&main, at(source, "p.\n") + 2);
+        assert_eq!(labels(items), vec!["x", "y"]);
+
+        // symbols in scope
+        let source = "from \"./lib\" import Point\n\ndef main(Point p) -> field:\n    return \n";
+        let main = write(dir.path(), "main.zok", source);
+        let items = analysis.completion(&main, source.len() - 1);
+        assert_eq!(labels(items), vec!["Point", "main", "p"]);
+
+        // symbols of an imported module
+        let source = "from \"./lib\" import \n";
+        let main = write(dir.path(), "main.zok", source);
+        let items = analysis.completion(&main, source.len() - 1);
+        assert_eq!(labels(items), vec!["Point", "double"]);
+
+        // modules of the standard library
+        let source = "from \"li\n";
+        let main = write(dir.path(), "main.zok", source);
+        let items = analysis.completion(&main, source.len() - 1);
+        assert_eq!(labels(items), vec!["lib", "main"]);
+    }
+}
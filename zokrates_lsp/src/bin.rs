@@ -0,0 +1,55 @@
+//! A language server for ZoKrates, communicating over stdio
+
+#[macro_use]
+extern crate lazy_static;
+
+mod analysis;
+mod index;
+mod server;
+mod text;
+
+use clap::{App, Arg};
+use lsp_server::Connection;
+use server::Server;
+
+lazy_static! {
+    static ref DEFAULT_STDLIB_PATH: String = dirs::home_dir()
+        .map(|p| p.join(".zokrates/stdlib"))
+        .unwrap()
+        .into_os_string()
+        .into_string()
+        .unwrap();
+}
+
+fn main() {
+    // stdout is reserved for the protocol, logs go to stderr
+    env_logger::init();
+
+    let matches = App::new("ZoKrates language server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Provides diagnostics, hover, go to definition, completion and references for ZoKrates programs over stdio")
+        .arg(
+            Arg::with_name("stdlib-path")
+                .long("stdlib-path")
+                .help("Path to the standard library")
+                .value_name("PATH")
+                .takes_value(true)
+                .required(false)
+                .env("ZOKRATES_STDLIB")
+                .default_value(DEFAULT_STDLIB_PATH.as_str()),
+        )
+        .get_matches();
+
+    let stdlib = matches.value_of("stdlib-path").unwrap().to_string();
+
+    let (connection, io_threads) = Connection::stdio();
+
+    let result = Server::new(connection, stdlib)
+        .run()
+        .and_then(|_| io_threads.join().map_err(|e| e.into()));
+
+    if let Err(e) = result {
+        log::error!("{}", e);
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,981 @@
+//! Module containing the `Index` of the symbols of a single module
+//!
+//! The index is built from the pest AST, so that it keeps the exact location of every
+//! identifier. It records each definition along with the part of the source where it is visible,
+//! and resolves each identifier to the definition it refers to, following the scoping rules of
+//! the language: module symbols are visible in the whole module, generics and parameters in their
+//! declaration, and variables from their definition to the end of the enclosing block.
+
+use std::collections::HashMap;
+use zokrates_pest_ast as pest;
+
+/// A range of byte offsets in the source
+pub type Span = (usize, usize);
+
+/// The number of type aliases followed when looking for a struct, to stay clear of cycles
+const MAX_ALIASES: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SymbolKind {
+    Function,
+    Struct,
+    Constant,
+    Type,
+    Import,
+    Variable,
+    Parameter,
+    Generic,
+}
+
+impl SymbolKind {
+    /// Whether symbols of this kind are declared at the module level
+    pub fn is_module_level(&self) -> bool {
+        !matches!(
+            self,
+            SymbolKind::Variable | SymbolKind::Parameter | SymbolKind::Generic
+        )
+    }
+}
+
+/// A type as written in the source, reduced to its base type and its number of dimensions
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ty {
+    pub base: String,
+    pub dimensions: usize,
+}
+
+impl Ty {
+    fn new<S: Into<String>>(base: S, dimensions: usize) -> Self {
+        Ty {
+            base: base.into(),
+            dimensions,
+        }
+    }
+
+    fn from_pest(ty: &pest::Type) -> Self {
+        match ty {
+            pest::Type::Basic(ty) => Ty::new(basic_type_span(ty).as_str(), 0),
+            pest::Type::Struct(ty) => Ty::new(&ty.id.value, 0),
+            pest::Type::Array(ty) => {
+                let base = match &ty.ty {
+                    pest::BasicOrStructType::Basic(ty) => basic_type_span(ty).as_str(),
+                    pest::BasicOrStructType::Struct(ty) => ty.id.value.as_str(),
+                };
+                Ty::new(base, ty.dimensions.len())
+            }
+        }
+    }
+
+    fn element(self) -> Self {
+        Ty::new(self.base, self.dimensions.saturating_sub(1))
+    }
+
+    fn array(self) -> Self {
+        Ty::new(self.base, self.dimensions + 1)
+    }
+}
+
+/// A symbol imported from another module
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSymbol {
+    pub source: String,
+    pub symbol: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub name: String,
+    pub span: Span,
+    pub ty: Ty,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Definition {
+    pub name: String,
+    pub kind: SymbolKind,
+    /// The span of the identifier in the definition
+    pub span: Span,
+    /// A one line description of the symbol, shown on hover
+    pub detail: String,
+    /// The part of the source where the symbol is visible
+    pub scope: Span,
+    /// The type of variables, parameters and constants, or the aliased type of type definitions
+    pub ty: Option<Ty>,
+    /// The return types of functions
+    pub returns: Vec<Ty>,
+    /// The fields of structs
+    pub fields: Vec<Field>,
+    /// The origin of imported symbols
+    pub import: Option<ImportedSymbol>,
+}
+
+impl Definition {
+    fn new(id: &pest::IdentifierExpression, kind: SymbolKind, detail: String) -> Self {
+        Definition {
+            name: id.value.clone(),
+            kind,
+            span: span(&id.span),
+            detail,
+            scope: (0, 0),
+            ty: None,
+            returns: vec![],
+            fields: vec![],
+            import: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Target {
+    /// A definition of this module, by index
+    Definition(usize),
+    /// A member of the struct defined or imported by the module level definition `parent`
+    Member { parent: usize, name: String },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub span: Span,
+    pub target: Target,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Index {
+    pub definitions: Vec<Definition>,
+    pub references: Vec<Reference>,
+    /// The import sources, with the span of the string between the quotes
+    pub imports: Vec<(Span, String)>,
+}
+
+fn contains((start, end): Span, offset: usize) -> bool {
+    start <= offset && offset <= end
+}
+
+impl Index {
+    /// Build the index of `source`, using `lookup` to find the definitions of imported symbols
+    pub fn build(
+        source: &str,
+        lookup: &mut dyn FnMut(&ImportedSymbol) -> Option<Definition>,
+    ) -> Result<Self, pest::Error> {
+        let file = pest::generate_ast(source)?;
+
+        let mut builder = Builder {
+            index: Index::default(),
+            scopes: vec![Scope {
+                end: source.len(),
+                names: HashMap::new(),
+            }],
+            imported: HashMap::new(),
+            lookup,
+        };
+
+        // module symbols are declared first, so that they are visible in the whole module
+        for declaration in &file.declarations {
+            builder.declare(declaration);
+        }
+
+        for declaration in &file.declarations {
+            builder.visit_declaration(declaration);
+        }
+
+        Ok(builder.index)
+    }
+
+    /// The module level definition of `name`
+    pub fn symbol(&self, name: &str) -> Option<usize> {
+        self.definitions
+            .iter()
+            .rposition(|d| d.kind.is_module_level() && d.name == name)
+    }
+
+    /// The identifier at `offset` and what it refers to, be it a reference or a definition
+    pub fn target_at(&self, offset: usize) -> Option<(Span, Target)> {
+        self.references
+            .iter()
+            .find(|r| contains(r.span, offset))
+            .map(|r| (r.span, r.target.clone()))
+            .or_else(|| {
+                self.definitions
+                    .iter()
+                    .position(|d| contains(d.span, offset))
+                    .map(|id| (self.definitions[id].span, Target::Definition(id)))
+            })
+    }
+
+    /// The import source at `offset`
+    pub fn import_at(&self, offset: usize) -> Option<&str> {
+        self.imports
+            .iter()
+            .find(|(span, _)| contains(*span, offset))
+            .map(|(_, source)| source.as_str())
+    }
+
+    /// The spans of the references to `target`
+    pub fn references_to<'a>(&'a self, target: &'a Target) -> impl Iterator<Item = Span> + 'a {
+        self.references
+            .iter()
+            .filter(move |r| &r.target == target)
+            .map(|r| r.span)
+    }
+
+    /// The definitions visible at `offset`, the innermost one for each name
+    pub fn visible_at(&self, offset: usize) -> Vec<&Definition> {
+        let mut visible: HashMap<&str, &Definition> = HashMap::new();
+
+        // definitions come in source order, so inner definitions override outer ones
+        for definition in self
+            .definitions
+            .iter()
+            .filter(|d| contains(d.scope, offset))
+        {
+            visible.insert(&definition.name, definition);
+        }
+
+        let mut visible: Vec<_> = visible.into_values().collect();
+        visible.sort_by(|a, b| a.name.cmp(&b.name));
+        visible
+    }
+}
+
+fn span(s: &pest::Span) -> Span {
+    (s.start(), s.end())
+}
+
+fn basic_type_span<'a, 'ast>(ty: &'a pest::BasicType<'ast>) -> &'a pest::Span<'ast> {
+    match ty {
+        pest::BasicType::Field(t) => &t.span,
+        pest::BasicType::Boolean(t) => &t.span,
+        pest::BasicType::U8(t) => &t.span,
+        pest::BasicType::U16(t) => &t.span,
+        pest::BasicType::U32(t) => &t.span,
+        pest::BasicType::U64(t) => &t.span,
+    }
+}
+
+fn type_span<'a, 'ast>(ty: &'a pest::Type<'ast>) -> &'a pest::Span<'ast> {
+    match ty {
+        pest::Type::Basic(t) => basic_type_span(t),
+        pest::Type::Array(t) => &t.span,
+        pest::Type::Struct(t) => &t.span,
+    }
+}
+
+fn generics(generics: &[pest::IdentifierExpression]) -> String {
+    match generics.len() {
+        0 => String::new(),
+        _ => format!(
+            "<{}>",
+            generics
+                .iter()
+                .map(|g| g.value.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+struct Scope {
+    end: usize,
+    names: HashMap<String, usize>,
+}
+
+struct Builder<'a> {
+    index: Index,
+    scopes: Vec<Scope>,
+    imported: HashMap<usize, Option<Definition>>,
+    lookup: &'a mut dyn FnMut(&ImportedSymbol) -> Option<Definition>,
+}
+
+impl<'a> Builder<'a> {
+    fn define(&mut self, mut definition: Definition, scope: Span) -> usize {
+        let id = self.index.definitions.len();
+        definition.scope = scope;
+        self.scopes
+            .last_mut()
+            .unwrap()
+            .names
+            .insert(definition.name.clone(), id);
+        self.index.definitions.push(definition);
+        id
+    }
+
+    /// Define a variable visible until the end of the current block
+    fn define_local(&mut self, definition: Definition) -> usize {
+        let scope = (definition.span.0, self.scopes.last().unwrap().end);
+        self.define(definition, scope)
+    }
+
+    fn enter(&mut self, end: usize) {
+        self.scopes.push(Scope {
+            end,
+            names: HashMap::new(),
+        });
+    }
+
+    fn exit(&mut self) {
+        self.scopes.pop();
+    }
+
+    fn refer(&mut self, id: &pest::IdentifierExpression) -> Option<usize> {
+        let definition = self
+            .scopes
+            .iter()
+            .rev()
+            .find_map(|s| s.names.get(&id.value).cloned())?;
+        self.index.references.push(Reference {
+            span: span(&id.span),
+            target: Target::Definition(definition),
+        });
+        Some(definition)
+    }
+
+    /// The definition `id`, looking through imports
+    fn resolved(&mut self, id: usize) -> Option<Definition> {
+        let import = match &self.index.definitions[id].import {
+            None => return Some(self.index.definitions[id].clone()),
+            Some(import) => import.clone(),
+        };
+
+        if !self.imported.contains_key(&id) {
+            let definition = (self.lookup)(&import);
+            self.imported.insert(id, definition);
+        }
+
+        self.imported[&id].clone()
+    }
+
+    /// The struct of type `ty` along with the module level definition it is found through
+    fn struct_of(&mut self, ty: &Ty) -> Option<(usize, Definition)> {
+        if ty.dimensions > 0 {
+            return None;
+        }
+
+        let mut base = ty.base.clone();
+
+        for _ in 0..MAX_ALIASES {
+            let id = *self.scopes[0].names.get(&base)?;
+            let definition = self.resolved(id)?;
+
+            match definition.kind {
+                SymbolKind::Struct => return Some((id, definition)),
+                SymbolKind::Type => match definition.ty {
+                    Some(Ty {
+                        base: aliased,
+                        dimensions: 0,
+                    }) => base = aliased,
+                    _ => return None,
+                },
+                _ => return None,
+            }
+        }
+
+        None
+    }
+
+    fn member(&mut self, ty: Option<Ty>, id: &pest::IdentifierExpression) -> Option<Ty> {
+        let (parent, definition) = self.struct_of(&ty?)?;
+
+        self.index.references.push(Reference {
+            span: span(&id.span),
+            target: Target::Member {
+                parent,
+                name: id.value.clone(),
+            },
+        });
+
+        definition
+            .fields
+            .iter()
+            .find(|f| f.name == id.value)
+            .map(|f| f.ty.clone())
+    }
+
+    fn declare(&mut self, declaration: &pest::SymbolDeclaration) {
+        let module = (0, self.scopes[0].end);
+
+        match declaration {
+            pest::SymbolDeclaration::Import(pest::ImportDirective::Main(import)) => {
+                self.index
+                    .imports
+                    .push((span(&import.source.span), import.source.value.clone()));
+
+                // without an alias, the imported `main` function keeps its name
+                if let Some(alias) = &import.alias {
+                    let mut definition = Definition::new(
+                        alias,
+                        SymbolKind::Import,
+                        import.span.as_str().trim().to_string(),
+                    );
+                    definition.import = Some(ImportedSymbol {
+                        source: import.source.value.clone(),
+                        symbol: "main".into(),
+                    });
+                    self.define(definition, module);
+                }
+            }
+            pest::SymbolDeclaration::Import(pest::ImportDirective::From(import)) => {
+                self.index
+                    .imports
+                    .push((span(&import.source.span), import.source.value.clone()));
+
+                for symbol in &import.symbols {
+                    let mut definition = Definition::new(
+                        symbol.alias.as_ref().unwrap_or(&symbol.id),
+                        SymbolKind::Import,
+                        format!(
+                            "from \"{}\" import {}",
+                            import.source.value,
+                            symbol.span.as_str()
+                        ),
+                    );
+                    definition.import = Some(ImportedSymbol {
+                        source: import.source.value.clone(),
+                        symbol: symbol.id.value.clone(),
+                    });
+                    self.define(definition, module);
+                }
+            }
+            pest::SymbolDeclaration::Constant(c) => {
+                let mut definition = Definition::new(
+                    &c.id,
+                    SymbolKind::Constant,
+                    format!("const {} {}", type_span(&c.ty).as_str(), c.id.value),
+                );
+                definition.ty = Some(Ty::from_pest(&c.ty));
+                self.define(definition, module);
+            }
+            pest::SymbolDeclaration::Struct(s) => {
+                let fields: Vec<_> = s
+                    .fields
+                    .iter()
+                    .map(|f| Field {
+                        name: f.id.value.clone(),
+                        span: span(&f.id.span),
+                        ty: Ty::from_pest(&f.ty),
+                        detail: format!("{} {}", type_span(&f.ty).as_str(), f.id.value),
+                    })
+                    .collect();
+
+                let mut definition = Definition::new(
+                    &s.id,
+                    SymbolKind::Struct,
+                    format!(
+                        "struct {}{} {{ {} }}",
+                        s.id.value,
+                        generics(&s.generics),
+                        fields
+                            .iter()
+                            .map(|f| format!("{};", f.detail))
+                            .collect::<Vec<_>>()
+                            .join(" ")
+                    ),
+                );
+                definition.fields = fields;
+                self.define(definition, module);
+            }
+            pest::SymbolDeclaration::Type(t) => {
+                let mut definition = Definition::new(
+                    &t.id,
+                    SymbolKind::Type,
+                    format!(
+                        "type {}{} = {}",
+                        t.id.value,
+                        generics(&t.generics),
+                        type_span(&t.ty).as_str()
+                    ),
+                );
+                definition.ty = Some(Ty::from_pest(&t.ty));
+                self.define(definition, module);
+            }
+            pest::SymbolDeclaration::Function(f) => {
+                let returns = match f.returns.len() {
+                    0 => String::new(),
+                    _ => format!(
+                        " -> ({})",
+                        f.returns
+                            .iter()
+                            .map(|r| type_span(r).as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                };
+
+                let mut definition = Definition::new(
+                    &f.id,
+                    SymbolKind::Function,
+                    format!(
+                        "def {}{}({}){}",
+                        f.id.value,
+                        generics(&f.generics),
+                        f.parameters
+                            .iter()
+                            .map(|p| p.span.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                        returns
+                    ),
+                );
+                definition.returns = f.returns.iter().map(Ty::from_pest).collect();
+                self.define(definition, module);
+            }
+        }
+    }
+
+    fn visit_generics(&mut self, generics: &[pest::IdentifierExpression], scope: Span) {
+        for g in generics {
+            let definition = Definition::new(g, SymbolKind::Generic, format!("<{}>", g.value));
+            self.define(definition, scope);
+        }
+    }
+
+    fn visit_declaration(&mut self, declaration: &pest::SymbolDeclaration) {
+        match declaration {
+            pest::SymbolDeclaration::Import(..) => {}
+            pest::SymbolDeclaration::Constant(c) => {
+                self.visit_type(&c.ty);
+                self.visit_expression(&c.expression);
+            }
+            pest::SymbolDeclaration::Struct(s) => {
+                self.enter(s.span.end());
+                self.visit_generics(&s.generics, span(&s.span));
+                for field in &s.fields {
+                    self.visit_type(&field.ty);
+                }
+                self.exit();
+            }
+            pest::SymbolDeclaration::Type(t) => {
+                self.enter(t.span.end());
+                self.visit_generics(&t.generics, span(&t.span));
+                self.visit_type(&t.ty);
+                self.exit();
+            }
+            pest::SymbolDeclaration::Function(f) => {
+                self.enter(f.span.end());
+                self.visit_generics(&f.generics, span(&f.span));
+                for parameter in &f.parameters {
+                    self.visit_type(&parameter.ty);
+                    let mut definition = Definition::new(
+                        &parameter.id,
+                        SymbolKind::Parameter,
+                        parameter.span.as_str().to_string(),
+                    );
+                    definition.ty = Some(Ty::from_pest(&parameter.ty));
+                    self.define(definition, span(&f.span));
+                }
+                for ty in &f.returns {
+                    self.visit_type(ty);
+                }
+                for statement in &f.statements {
+                    self.visit_statement(statement);
+                }
+                self.exit();
+            }
+        }
+    }
+
+    fn visit_type(&mut self, ty: &pest::Type) {
+        match ty {
+            pest::Type::Basic(..) => {}
+            pest::Type::Struct(s) => {
+                self.refer(&s.id);
+                if let Some(g) = &s.explicit_generics {
+                    self.visit_explicit_generics(g);
+                }
+            }
+            pest::Type::Array(a) => {
+                if let pest::BasicOrStructType::Struct(s) = &a.ty {
+                    self.refer(&s.id);
+                    if let Some(g) = &s.explicit_generics {
+                        self.visit_explicit_generics(g);
+                    }
+                }
+                for dimension in &a.dimensions {
+                    self.visit_expression(dimension);
+                }
+            }
+        }
+    }
+
+    fn visit_explicit_generics(&mut self, generics: &pest::ExplicitGenerics) {
+        for value in &generics.values {
+            if let pest::ConstantGenericValue::Identifier(id) = value {
+                self.refer(id);
+            }
+        }
+    }
+
+    fn visit_statement(&mut self, statement: &pest::Statement) {
+        match statement {
+            pest::Statement::Return(r) => {
+                for e in &r.expressions {
+                    self.visit_expression(e);
+                }
+            }
+            pest::Statement::Assertion(a) => {
+                self.visit_expression(&a.expression);
+            }
+            pest::Statement::Definition(d) => {
+                // the right hand side is visited first, as it cannot see the variables it defines
+                self.visit_expression(&d.expression);
+
+                for lhs in &d.lhs {
+                    match lhs {
+                        pest::TypedIdentifierOrAssignee::TypedIdentifier(t) => {
+                            self.visit_type(&t.ty);
+                            let mut definition = Definition::new(
+                                &t.identifier,
+                                SymbolKind::Variable,
+                                t.span.as_str().to_string(),
+                            );
+                            definition.ty = Some(Ty::from_pest(&t.ty));
+                            self.define_local(definition);
+                        }
+                        pest::TypedIdentifierOrAssignee::Assignee(a) => {
+                            let mut ty = self
+                                .refer(&a.id)
+                                .and_then(|id| self.index.definitions[id].ty.clone());
+
+                            for access in &a.accesses {
+                                ty = match access {
+                                    pest::AssigneeAccess::Select(s) => {
+                                        self.visit_select(ty, &s.expression)
+                                    }
+                                    pest::AssigneeAccess::Member(m) => self.member(ty, &m.id),
+                                };
+                            }
+                        }
+                    }
+                }
+            }
+            pest::Statement::Iteration(i) => {
+                self.visit_type(&i.ty);
+                self.visit_expression(&i.from);
+                self.visit_expression(&i.to);
+
+                self.enter(i.span.end());
+                let mut definition = Definition::new(
+                    &i.index,
+                    SymbolKind::Variable,
+                    format!("{} {}", type_span(&i.ty).as_str(), i.index.value),
+                );
+                definition.ty = Some(Ty::from_pest(&i.ty));
+                self.define_local(definition);
+                for statement in &i.statements {
+                    self.visit_statement(statement);
+                }
+                self.exit();
+            }
+        }
+    }
+
+    fn visit_select(&mut self, ty: Option<Ty>, e: &pest::RangeOrExpression) -> Option<Ty> {
+        match e {
+            pest::RangeOrExpression::Expression(e) => {
+                self.visit_expression(e);
+                ty.map(Ty::element)
+            }
+            pest::RangeOrExpression::Range(r) => {
+                if let Some(from) = &r.from {
+                    self.visit_expression(&from.0);
+                }
+                if let Some(to) = &r.to {
+                    self.visit_expression(&to.0);
+                }
+                ty
+            }
+        }
+    }
+
+    /// Visit an expression and return its type, if it can be found without type checking
+    fn visit_expression(&mut self, e: &pest::Expression) -> Option<Ty> {
+        match e {
+            pest::Expression::Identifier(id) => self
+                .refer(id)
+                .and_then(|id| self.resolved(id))
+                .and_then(|d| d.ty),
+            pest::Expression::Literal(l) => match l {
+                pest::LiteralExpression::BooleanLiteral(_) => Some(Ty::new("bool", 0)),
+                pest::LiteralExpression::DecimalLiteral(d) => d.suffix.as_ref().map(|s| {
+                    Ty::new(
+                        match s {
+                            pest::DecimalSuffix::U8(_) => "u8",
+                            pest::DecimalSuffix::U16(_) => "u16",
+                            pest::DecimalSuffix::U32(_) => "u32",
+                            pest::DecimalSuffix::U64(_) => "u64",
+                            pest::DecimalSuffix::Field(_) => "field",
+                        },
+                        0,
+                    )
+                }),
+                pest::LiteralExpression::HexLiteral(h) => Some(Ty::new(
+                    match h.value {
+                        pest::HexNumberExpression::U8(_) => "u8",
+                        pest::HexNumberExpression::U16(_) => "u16",
+                        pest::HexNumberExpression::U32(_) => "u32",
+                        pest::HexNumberExpression::U64(_) => "u64",
+                    },
+                    0,
+                )),
+            },
+            pest::Expression::Binary(b) => {
+                let left = self.visit_expression(&b.left);
+                let right = self.visit_expression(&b.right);
+
+                match b.op {
+                    pest::BinaryOperator::Or
+                    | pest::BinaryOperator::And
+                    | pest::BinaryOperator::Eq
+                    | pest::BinaryOperator::NotEq
+                    | pest::BinaryOperator::Lt
+                    | pest::BinaryOperator::Gt
+                    | pest::BinaryOperator::Lte
+                    | pest::BinaryOperator::Gte => Some(Ty::new("bool", 0)),
+                    _ => left.or(right),
+                }
+            }
+            pest::Expression::Unary(u) => self.visit_expression(&u.expression),
+            pest::Expression::Ternary(t) => {
+                self.visit_expression(&t.condition);
+                let consequence = self.visit_expression(&t.consequence);
+                let alternative = self.visit_expression(&t.alternative);
+                consequence.or(alternative)
+            }
+            pest::Expression::IfElse(t) => {
+                self.visit_expression(&t.condition);
+                let consequence = self.visit_expression(&t.consequence);
+                let alternative = self.visit_expression(&t.alternative);
+                consequence.or(alternative)
+            }
+            pest::Expression::Postfix(p) => {
+                // keep track of the function being called, if any
+                let mut function = match &*p.base {
+                    pest::Expression::Identifier(id) => {
+                        self.refer(id).and_then(|id| self.resolved(id))
+                    }
+                    base => {
+                        self.visit_expression(base);
+                        None
+                    }
+                };
+
+                let mut ty = function.as_ref().and_then(|d| d.ty.clone());
+
+                for access in &p.accesses {
+                    ty = match access {
+                        pest::Access::Call(c) => {
+                            if let Some(g) = &c.explicit_generics {
+                                self.visit_explicit_generics(g);
+                            }
+                            for argument in &c.arguments.expressions {
+                                self.visit_expression(argument);
+                            }
+                            function.take().and_then(|f| f.returns.into_iter().next())
+                        }
+                        pest::Access::Select(s) => self.visit_select(ty, &s.expression),
+                        pest::Access::Member(m) => self.member(ty, &m.id),
+                    };
+                }
+
+                ty
+            }
+            pest::Expression::InlineArray(a) => {
+                let mut ty = None;
+
+                for e in &a.expressions {
+                    let element = match e {
+                        pest::SpreadOrExpression::Spread(s) => self.visit_expression(&s.expression),
+                        pest::SpreadOrExpression::Expression(e) => {
+                            self.visit_expression(e).map(Ty::array)
+                        }
+                    };
+                    ty = ty.or(element);
+                }
+
+                ty
+            }
+            pest::Expression::InlineStruct(s) => {
+                self.refer(&s.ty);
+                let ty = Some(Ty::new(&s.ty.value, 0));
+
+                for member in &s.members {
+                    self.member(ty.clone(), &member.id);
+                    self.visit_expression(&member.expression);
+                }
+
+                ty
+            }
+            pest::Expression::ArrayInitializer(a) => {
+                let ty = self.visit_expression(&a.value);
+                self.visit_expression(&a.count);
+                ty.map(Ty::array)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(source: &str) -> Index {
+        Index::build(source, &mut |_| None).unwrap()
+    }
+
+    fn at(source: &str, pattern: &str) -> usize {
+        source.find(pattern).unwrap()
+    }
+
+    fn definition_at(index: &Index, offset: usize) -> &Definition {
+        match index.target_at(offset) {
+            Some((_, Target::Definition(id))) => &index.definitions[id],
+            t => panic!("expected a definition, found {:?}", t),
+        }
+    }
+
+    #[test]
+    fn scopes() {
+        let source = r#"
+const u32 N = 2
+
+def foo<M>(field[M] a) -> field:
+    return a[0]
+
+def main(private field a) -> field:
+    field b = a + 1
+    for u32 i in 0..N do
+        field a = b * 2
+        b = a
+    endfor
+    return foo(b)
+"#;
+        let index = index(source);
+
+        // `N` refers to the constant
+        let n = definition_at(&index, at(source, "N do"));
+        assert_eq!(n.kind, SymbolKind::Constant);
+        assert_eq!(n.detail, "const u32 N");
+
+        // the generic `M` is visible in the parameter type
+        let m = definition_at(&index, at(source, "M] a"));
+        assert_eq!(m.kind, SymbolKind::Generic);
+
+        // `a` in the loop refers to the variable shadowing the parameter
+        let a = definition_at(&index, at(source, "a\n    endfor"));
+        assert_eq!(a.kind, SymbolKind::Variable);
+        assert_eq!(a.detail, "field a");
+
+        // `a` before the loop refers to the parameter
+        let a = definition_at(&index, at(source, "a + 1"));
+        assert_eq!(a.kind, SymbolKind::Parameter);
+        assert_eq!(a.detail, "private field a");
+
+        // `foo` refers to the function
+        let foo = definition_at(&index, at(source, "foo(b)"));
+        assert_eq!(foo.detail, "def foo<M>(field[M] a) -> (field)");
+
+        // `i` is only visible in the loop
+        let return_offset = at(source, "return foo");
+        assert!(index
+            .visible_at(return_offset)
+            .iter()
+            .all(|d| d.name != "i"));
+        assert!(index
+            .visible_at(at(source, "b = a\n"))
+            .iter()
+            .any(|d| d.name == "i"));
+
+        // `b` is referred to in the loop, in the assignment and in the return statement
+        let b = index
+            .definitions
+            .iter()
+            .position(|d| d.name == "b")
+            .unwrap();
+        assert_eq!(index.references_to(&Target::Definition(b)).count(), 3);
+    }
+
+    #[test]
+    fn members() {
+        let source = r#"
+struct Point {
+    field x
+    field y
+}
+
+struct Segment {
+    Point[2] ends
+}
+
+def origin() -> Point:
+    return Point { x: 0, y: 0 }
+
+def main(Segment s) -> field:
+    field a = s.ends[1].y
+    field b = origin().x
+    return a + b
+"#;
+        let index = index(source);
+        let point = index.symbol("Point").unwrap();
+
+        match index.target_at(at(source, "y\n    field b")) {
+            Some((_, Target::Member { parent, name })) => {
+                assert_eq!(parent, point);
+                assert_eq!(name, "y");
+            }
+            t => panic!("expected a member, found {:?}", t),
+        }
+
+        match index.target_at(at(source, "x\n    return")) {
+            Some((_, Target::Member { parent, name })) => {
+                assert_eq!(parent, point);
+                assert_eq!(name, "x");
+            }
+            t => panic!("expected a member, found {:?}", t),
+        }
+
+        // members of inline structs are references too
+        let x = Target::Member {
+            parent: point,
+            name: "x".into(),
+        };
+        assert_eq!(index.references_to(&x).count(), 2);
+    }
+
+    #[test]
+    fn imports() {
+        let source = r#"
+from "hashes/sha256/512bit" import main as sha256, Foo
+import "./bar" as bar
+
+def main():
+    return
+"#;
+        let mut imported = vec![];
+        let index = Index::build(source, &mut |import| {
+            imported.push(import.clone());
+            None
+        })
+        .unwrap();
+
+        let sha256 = &index.definitions[index.symbol("sha256").unwrap()];
+        assert_eq!(sha256.kind, SymbolKind::Import);
+        assert_eq!(
+            sha256.import,
+            Some(ImportedSymbol {
+                source: "hashes/sha256/512bit".into(),
+                symbol: "main".into()
+            })
+        );
+
+        let bar = &index.definitions[index.symbol("bar").unwrap()];
+        assert_eq!(bar.import.as_ref().unwrap().symbol, "main");
+
+        assert_eq!(
+            index.import_at(at(source, "512bit")),
+            Some("hashes/sha256/512bit")
+        );
+
+        // imports are only looked up when needed
+        assert!(imported.is_empty());
+    }
+}
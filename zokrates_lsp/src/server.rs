@@ -0,0 +1,355 @@
+//! Module containing the main loop of the language server
+//!
+//! The whole document is sent by the client on each change. Diagnostics are published when a
+//! document is opened or changed, and for all open documents when one of them is saved, as the
+//! others may import it.
+
+use crate::analysis::{normalize, Analysis};
+use crate::text;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument,
+    Notification as _, PublishDiagnostics,
+};
+use lsp_types::request::{Completion, GotoDefinition, HoverRequest, References, Request as _};
+use lsp_types::{
+    CompletionOptions, CompletionParams, CompletionResponse, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, DidSaveTextDocumentParams,
+    GotoDefinitionParams, GotoDefinitionResponse, HoverParams, HoverProviderCapability, OneOf,
+    PublishDiagnosticsParams, ReferenceParams, ServerCapabilities, TextDocumentPositionParams,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, Url,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::path::PathBuf;
+
+pub fn capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Options(
+            TextDocumentSyncOptions {
+                open_close: Some(true),
+                change: Some(TextDocumentSyncKind::FULL),
+                save: Some(TextDocumentSyncSaveOptions::Supported(true)),
+                ..TextDocumentSyncOptions::default()
+            },
+        )),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        definition_provider: Some(OneOf::Left(true)),
+        references_provider: Some(OneOf::Left(true)),
+        completion_provider: Some(CompletionOptions {
+            trigger_characters: Some(vec![".".into(), "\"".into(), "/".into()]),
+            ..CompletionOptions::default()
+        }),
+        ..ServerCapabilities::default()
+    }
+}
+
+pub struct Server {
+    connection: Connection,
+    stdlib: String,
+    /// The open documents, by normalised path
+    documents: HashMap<PathBuf, String>,
+}
+
+impl Server {
+    pub fn new(connection: Connection, stdlib: String) -> Self {
+        Server {
+            connection,
+            stdlib,
+            documents: HashMap::new(),
+        }
+    }
+
+    pub fn run(mut self) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let capabilities = serde_json::to_value(capabilities())?;
+        self.connection.initialize(capabilities)?;
+
+        log::info!("Initialized with standard library at {}", self.stdlib);
+
+        while let Ok(message) = self.connection.receiver.recv() {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+                    let response = self.on_request(request);
+                    self.connection.sender.send(Message::Response(response))?;
+                }
+                Message::Notification(notification) => self.on_notification(notification)?,
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn analysis(&self) -> Analysis {
+        Analysis::new(&self.stdlib, &self.documents)
+    }
+
+    fn on_request(&self, request: Request) -> Response {
+        log::debug!("Request {}", request.method);
+
+        let id = request.id.clone();
+
+        let result = match request.method.as_str() {
+            HoverRequest::METHOD => self.handle(request.params, |s, p: HoverParams| {
+                let (path, offset) = s.locate(&p.text_document_position_params)?;
+                s.analysis().hover(&path, offset)
+            }),
+            GotoDefinition::METHOD => self.handle(request.params, |s, p: GotoDefinitionParams| {
+                let (path, offset) = s.locate(&p.text_document_position_params)?;
+                s.analysis()
+                    .definition(&path, offset)
+                    .map(GotoDefinitionResponse::Scalar)
+            }),
+            References::METHOD => self.handle(request.params, |s, p: ReferenceParams| {
+                let (path, offset) = s.locate(&p.text_document_position)?;
+                Some(
+                    s.analysis()
+                        .references(&path, offset, p.context.include_declaration),
+                )
+            }),
+            Completion::METHOD => self.handle(request.params, |s, p: CompletionParams| {
+                let (path, offset) = s.locate(&p.text_document_position)?;
+                Some(CompletionResponse::Array(
+                    s.analysis().completion(&path, offset),
+                ))
+            }),
+            method => {
+                return Response::new_err(
+                    id,
+                    ErrorCode::MethodNotFound as i32,
+                    format!("Unsupported request {}", method),
+                )
+            }
+        };
+
+        match result {
+            Ok(result) => Response::new_ok(id, result),
+            Err(e) => Response::new_err(id, ErrorCode::InvalidParams as i32, e.to_string()),
+        }
+    }
+
+    fn handle<P: DeserializeOwned, R: Serialize>(
+        &self,
+        params: serde_json::Value,
+        handler: impl FnOnce(&Self, P) -> R,
+    ) -> Result<serde_json::Value, serde_json::Error> {
+        let params = serde_json::from_value(params)?;
+        serde_json::to_value(handler(self, params))
+    }
+
+    /// The path of the document and the offset of the position in it
+    fn locate(&self, position: &TextDocumentPositionParams) -> Option<(PathBuf, usize)> {
+        let path = normalize(&position.text_document.uri.to_file_path().ok()?);
+        let offset = text::offset(self.documents.get(&path)?, position.position);
+        Some((path, offset))
+    }
+
+    fn on_notification(
+        &mut self,
+        notification: Notification,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        log::debug!("Notification {}", notification.method);
+
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: DidOpenTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.open(&uri, params.text_document.text);
+                self.publish(uri)?;
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: DidChangeTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                // documents are synchronised in full, so the last change holds the whole text
+                if let Some(change) = params.content_changes.into_iter().last() {
+                    self.open(&uri, change.text);
+                }
+                self.publish(uri)?;
+            }
+            DidSaveTextDocument::METHOD => {
+                let _: DidSaveTextDocumentParams = serde_json::from_value(notification.params)?;
+                let paths: Vec<_> = self.documents.keys().cloned().collect();
+                for path in paths {
+                    if let Ok(uri) = Url::from_file_path(path) {
+                        self.publish(uri)?;
+                    }
+                }
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: DidCloseTextDocumentParams =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                if let Ok(path) = uri.to_file_path() {
+                    self.documents.remove(&normalize(&path));
+                }
+                self.send_diagnostics(PublishDiagnosticsParams::new(uri, vec![], None))?;
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn open(&mut self, uri: &Url, text: String) {
+        if let Ok(path) = uri.to_file_path() {
+            self.documents.insert(normalize(&path), text);
+        }
+    }
+
+    fn publish(&self, uri: Url) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let diagnostics = match uri.to_file_path() {
+            Ok(path) => self.analysis().diagnostics(&normalize(&path)),
+            Err(()) => vec![],
+        };
+
+        self.send_diagnostics(PublishDiagnosticsParams::new(uri, diagnostics, None))
+    }
+
+    fn send_diagnostics(
+        &self,
+        params: PublishDiagnosticsParams,
+    ) -> Result<(), Box<dyn Error + Sync + Send>> {
+        let notification = Notification::new(PublishDiagnostics::METHOD.to_string(), params);
+        self.connection
+            .sender
+            .send(Message::Notification(notification))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lsp_server::RequestId;
+    use lsp_types::notification::{Exit, Initialized};
+    use lsp_types::request::{Initialize, Shutdown};
+    use lsp_types::{HoverContents, Position, TextDocumentIdentifier, TextDocumentItem};
+    use std::fs::File;
+    use std::io::Write;
+    use std::thread;
+
+    fn request<P: Serialize>(id: i32, method: &str, params: P) -> Message {
+        Message::Request(Request::new(RequestId::from(id), method.into(), params))
+    }
+
+    fn notification<P: Serialize>(method: &str, params: P) -> Message {
+        Message::Notification(Notification::new(method.into(), params))
+    }
+
+    fn response(client: &Connection) -> Response {
+        loop {
+            match client.receiver.recv().unwrap() {
+                Message::Response(response) => return response,
+                _ => continue,
+            }
+        }
+    }
+
+    fn diagnostics(client: &Connection) -> PublishDiagnosticsParams {
+        loop {
+            match client.receiver.recv().unwrap() {
+                Message::Notification(n) if n.method == PublishDiagnostics::METHOD => {
+                    return serde_json::from_value(n.params).unwrap()
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    #[test]
+    fn session() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.zok");
+        let source = "def main(field a) -> field:\n    return a\n";
+        File::create(&path)
+            .unwrap()
+            .write_all(source.as_bytes())
+            .unwrap();
+        let uri = Url::from_file_path(normalize(&path)).unwrap();
+
+        let (server, client) = Connection::memory();
+        let server = thread::spawn(move || Server::new(server, String::new()).run().unwrap());
+
+        client
+            .sender
+            .send(request(
+                1,
+                Initialize::METHOD,
+                serde_json::json!({ "capabilities": {} }),
+            ))
+            .unwrap();
+        assert!(response(&client).error.is_none());
+        client
+            .sender
+            .send(notification(Initialized::METHOD, serde_json::json!({})))
+            .unwrap();
+
+        client
+            .sender
+            .send(notification(
+                DidOpenTextDocument::METHOD,
+                DidOpenTextDocumentParams {
+                    text_document: TextDocumentItem::new(
+                        uri.clone(),
+                        "zokrates".into(),
+                        0,
+                        source.into(),
+                    ),
+                },
+            ))
+            .unwrap();
+        assert!(diagnostics(&client).diagnostics.is_empty());
+
+        // hover the `a` in the return statement
+        client
+            .sender
+            .send(request(
+                2,
+                HoverRequest::METHOD,
+                TextDocumentPositionParams::new(
+                    TextDocumentIdentifier::new(uri.clone()),
+                    Position::new(1, 11),
+                ),
+            ))
+            .unwrap();
+        let hover: lsp_types::Hover =
+            serde_json::from_value(response(&client).result.unwrap()).unwrap();
+        match hover.contents {
+            HoverContents::Markup(m) => assert_eq!(m.value, "```zokrates\nfield a\n```"),
+            _ => panic!("expected markup"),
+        }
+
+        // introduce an error
+        client
+            .sender
+            .send(notification(
+                DidChangeTextDocument::METHOD,
+                serde_json::json!({
+                    "textDocument": { "uri": uri, "version": 1 },
+                    "contentChanges": [{ "text": "def main(field a) -> field:\n    return b\n" }]
+                }),
+            ))
+            .unwrap();
+        let published = diagnostics(&client);
+        assert_eq!(published.uri, uri);
+        assert_eq!(published.diagnostics.len(), 1);
+        assert_eq!(published.diagnostics[0].range.start, Position::new(1, 11));
+
+        client
+            .sender
+            .send(request(3, Shutdown::METHOD, ()))
+            .unwrap();
+        assert!(response(&client).error.is_none());
+        client.sender.send(notification(Exit::METHOD, ())).unwrap();
+
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+//! Conversions between byte offsets in a source and LSP positions
+//!
+//! LSP positions are zero-based lines and columns, where columns count UTF-16 code units.
+
+use lsp_types::{Position, Range};
+
+pub fn position(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line = before.matches('\n').count();
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    let character = text[line_start..offset].encode_utf16().count();
+
+    Position::new(line as u32, character as u32)
+}
+
+pub fn range(text: &str, (start, end): (usize, usize)) -> Range {
+    Range::new(position(text, start), position(text, end))
+}
+
+pub fn offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(i) => line_start += i + 1,
+            None => return text.len(),
+        }
+    }
+
+    let mut units = 0;
+
+    for (i, c) in text[line_start..].char_indices() {
+        if units >= position.character as usize || c == '\n' {
+            return line_start + i;
+        }
+        units += c.len_utf16();
+    }
+
+    text.len()
+}
+
+/// The byte offset of the start of the line containing `offset`
+pub fn line_start(text: &str, offset: usize) -> usize {
+    text[..offset].rfind('\n').map(|i| i + 1).unwrap_or(0)
+}
+
+/// The byte offset of the start of the identifier ending at `offset`, if any
+pub fn word_start(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_ascii_alphanumeric() || *c == '_')
+        .last()
+        .map(|(i, _)| i)
+        .unwrap_or(offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let text = "def main():\n    field a = 1 // é\n    return\n";
+
+        for offset in 0..=text.len() {
+            if !text.is_char_boundary(offset) {
+                continue;
+            }
+            assert_eq!(offset, super::offset(text, position(text, offset)));
+        }
+
+        assert_eq!(position(text, 16), Position::new(1, 4));
+        assert_eq!(
+            offset(text, Position::new(2, 4)),
+            text.find("return").unwrap()
+        );
+        // positions past the end of a line are clamped to the end of that line
+        assert_eq!(offset(text, Position::new(0, 42)), 11);
+    }
+
+    #[test]
+    fn words() {
+        let text = "foo.bar_1";
+        assert_eq!(word_start(text, text.len()), 4);
+        assert_eq!(word_start(text, 4), 4);
+        assert_eq!(word_start(text, 3), 0);
+        assert_eq!(line_start("a\nbc", 3), 2);
+    }
+}
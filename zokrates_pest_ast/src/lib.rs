@@ -1,5 +1,5 @@
 use from_pest::FromPest;
-use pest::error::Error as PestError;
+use pest::error::{Error as PestError, LineColLocation};
 use pest::iterators::Pairs;
 use std::fmt;
 use zokrates_parser::parse;
@@ -9,10 +9,10 @@ extern crate lazy_static;
 
 pub use ast::{
     Access, Arguments, ArrayAccess, ArrayInitializerExpression, ArrayType, AssertionStatement,
-    Assignee, AssigneeAccess, BasicOrStructType, BasicType, BinaryExpression, BinaryOperator,
-    CallAccess, ConstantDefinition, ConstantGenericValue, DecimalLiteralExpression, DecimalNumber,
-    DecimalSuffix, DefinitionStatement, ExplicitGenerics, Expression, FieldType, File,
-    FromExpression, FunctionDefinition, HexLiteralExpression, HexNumberExpression,
+    Assignee, AssigneeAccess, Attribute, BasicOrStructType, BasicType, BinaryExpression,
+    BinaryOperator, CallAccess, ConstantDefinition, ConstantGenericValue, DecimalLiteralExpression,
+    DecimalNumber, DecimalSuffix, DefinitionStatement, ExplicitGenerics, Expression, FieldType,
+    File, FromExpression, FunctionDefinition, HexLiteralExpression, HexNumberExpression,
     IdentifierExpression, IfElseExpression, ImportDirective, ImportSymbol, InlineArrayExpression,
     InlineStructExpression, InlineStructMember, IterationStatement, LiteralExpression, Parameter,
     PostfixExpression, Range, RangeOrExpression, ReturnStatement, Span, Spread, SpreadOrExpression,
@@ -173,6 +173,7 @@ mod ast {
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::function_definition))]
     pub struct FunctionDefinition<'ast> {
+        pub attributes: Vec<Attribute<'ast>>,
         pub id: IdentifierExpression<'ast>,
         pub generics: Vec<IdentifierExpression<'ast>>,
         pub parameters: Vec<Parameter<'ast>>,
@@ -182,6 +183,15 @@ mod ast {
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::attribute))]
+    pub struct Attribute<'ast> {
+        pub id: IdentifierExpression<'ast>,
+        pub argument: Option<AnyString<'ast>>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::const_definition))]
     pub struct ConstantDefinition<'ast> {
@@ -1061,6 +1071,20 @@ impl<'ast> From<Pairs<'ast, Rule>> for Prog<'ast> {
 #[derive(PartialEq, Clone, Debug)]
 pub struct Error(PestError<Rule>);
 
+impl Error {
+    /// The line and column where the error starts, both starting at 1
+    pub fn line_col(&self) -> (usize, usize) {
+        match self.0.line_col {
+            LineColLocation::Pos(pos) | LineColLocation::Span(pos, _) => pos,
+        }
+    }
+
+    /// The error message, without the location and the source excerpt
+    pub fn message(&self) -> String {
+        self.0.variant.message().to_string()
+    }
+}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.0)
@@ -1135,6 +1159,7 @@ mod tests {
                         span: Span::new(source, 0, 29).unwrap()
                     })),
                     SymbolDeclaration::Function(FunctionDefinition {
+                        attributes: vec![],
                         generics: vec![],
                         id: IdentifierExpression {
                             value: String::from("main"),
@@ -1196,6 +1221,7 @@ mod tests {
                         span: Span::new(source, 0, 29).unwrap()
                     })),
                     SymbolDeclaration::Function(FunctionDefinition {
+                        attributes: vec![],
                         generics: vec![],
                         id: IdentifierExpression {
                             value: String::from("main"),
@@ -1281,6 +1307,7 @@ mod tests {
                         span: Span::new(source, 0, 29).unwrap()
                     })),
                     SymbolDeclaration::Function(FunctionDefinition {
+                        attributes: vec![],
                         generics: vec![],
                         id: IdentifierExpression {
                             value: String::from("main"),
@@ -1341,6 +1368,7 @@ mod tests {
             Ok(File {
                 pragma: None,
                 declarations: vec![SymbolDeclaration::Function(FunctionDefinition {
+                    attributes: vec![],
                     generics: vec![],
                     id: IdentifierExpression {
                         value: String::from("main"),
@@ -1379,6 +1407,7 @@ mod tests {
             Ok(File {
                 pragma: None,
                 declarations: vec![SymbolDeclaration::Function(FunctionDefinition {
+                    attributes: vec![],
                     generics: vec![],
                     id: IdentifierExpression {
                         value: String::from("main"),
@@ -172,6 +172,7 @@ fn compile_and_run<T: Field>(t: Tests) {
     };
 
     let interpreter = zokrates_core::ir::Interpreter::default();
+    let bytecode = bin.clone().lower();
 
     for test in t.tests.into_iter() {
         let with_abi = test.abi.unwrap_or(false);
@@ -190,6 +191,13 @@ fn compile_and_run<T: Field>(t: Tests) {
 
         let output = interpreter.execute(bin.clone(), &input);
 
+        assert_eq!(
+            bytecode.execute(&input),
+            output,
+            "bytecode execution of {} diverged from the interpreter",
+            entry_point.display()
+        );
+
         if let Err(e) = compare(output, test.output) {
             let mut code = File::open(&entry_point).unwrap();
             let mut s = String::new();